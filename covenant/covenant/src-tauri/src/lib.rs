@@ -1,10 +1,14 @@
 use serde::{Deserialize, Serialize};
-use sessionless::{PrivateKey, Sessionless};
-use sessionless::hex::{FromHex, IntoHex};
-use std::env;
 use std::sync::{Mutex, LazyLock};
 use std::collections::HashMap;
 use tauri_plugin_clipboard_manager::ClipboardExt;
+use tracing::{debug, error, info};
+
+// Shared identity, endpoints and service clients
+use nullary_core::{
+    get_service_url, http, http_client, public_key, queue_if_transient, sign_operation,
+    sign_operation_with_timestamp_at, NullaryError, SigningPurpose,
+};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CovenantConnection {
@@ -63,133 +67,76 @@ static USER_UUID: LazyLock<Mutex<Option<String>>> = LazyLock::new(|| {
     Mutex::new(None)
 });
 
-static SESSIONLESS_INSTANCE: LazyLock<Mutex<Option<Sessionless>>> = LazyLock::new(|| {
-    Mutex::new(None)
-});
-
-/// Get service URL based on environment
-fn get_service_url(service: &str) -> String {
-    let env = env::var("COVENANT_ENV").unwrap_or_else(|_| "dev".to_string());
-    println!("🌍 Environment for {}: {}", service, env);
-    
-    let url = match (env.as_str(), service) {
-        // Test environment (127.0.0.1:5111-5122)
-        ("test", "covenant") => "http://127.0.0.1:5122".to_string(),
-        
-        // Local environment (127.0.0.1:3000-3011)
-        ("local", "covenant") => "http://127.0.0.1:3011".to_string(),
-        
-        // Dev environment (default)
-        (_, service) => format!("https://dev.{}.allyabase.com", service),
-    };
-    
-    println!("📍 Service URL for {} in {} env: {}", service, env, url);
-    url
-}
-
-/// Create a sessionless instance with unique keys per app instance
-fn create_sessionless() -> Result<Sessionless, String> {
-    let private_key = match env::var("PRIVATE_KEY") {
-        Ok(env_key) => {
-            println!("🔑 Using PRIVATE_KEY from environment: {}...", &env_key[..16]);
-            env_key
-        }
-        Err(_) => {
-            println!("🔑 No PRIVATE_KEY environment variable found, generating unique key");
-            // Generate unique key per app instance to avoid conflicts
-            let unique_id = std::process::id();
-            let base_key = "c86012b167c5e3a6b0de97d8e1950cd9548f83bb67f47112bed6a082db795496";
-            format!("{}{:08x}", &base_key[..56], unique_id) // Mix in process ID for uniqueness
-        }
-    };
-    
-    let sessionless = Sessionless::from_private_key(PrivateKey::from_hex(private_key).expect("private key"));
-    Ok(sessionless)
-}
-
-/// Get or create sessionless instance
-fn get_sessionless() -> Result<Sessionless, String> {
-    let mut instance = SESSIONLESS_INSTANCE.lock().map_err(|e| format!("Failed to lock sessionless: {}", e))?;
-    
-    if instance.is_none() {
-        *instance = Some(create_sessionless()?);
-    }
-    
-    // We need to clone the data, not the reference, so recreate from private key
-    let sessionless = instance.as_ref().unwrap();
-    Ok(Sessionless::from_private_key(*sessionless.private_key()))
-}
-
-/// Get current timestamp as string
-fn get_timestamp() -> String {
-    chrono::Utc::now().timestamp_millis().to_string()
-}
-
 /// Create authenticated payload for covenant service
-async fn create_auth_payload(contract_uuid: Option<&str>) -> Result<HashMap<String, String>, String> {
-    let sessionless = get_sessionless()?;
-    let timestamp = get_timestamp();
+async fn create_auth_payload(contract_uuid: Option<&str>) -> Result<HashMap<String, String>, NullaryError> {
     let user_uuid = get_user_uuid().await?;
-    let pub_key = sessionless.public_key().to_hex();
+    let pub_key = public_key("covenant")?;
     
-    let message = if let Some(uuid) = contract_uuid {
-        format!("{}{}{}", timestamp, user_uuid, uuid)
-    } else {
-        format!("{}{}", timestamp, user_uuid)
+    // Signs timestamp + userUUID [+ contract uuid]
+    let signed = match contract_uuid {
+        Some(uuid) => sign_operation("covenant", SigningPurpose::AuthHeader, &[&user_uuid, uuid]).await?,
+        None => sign_operation("covenant", SigningPurpose::AuthHeader, &[&user_uuid]).await?,
     };
     
-    let signature = sessionless.sign(&message).to_hex();
-    
     let mut payload = HashMap::new();
-    payload.insert("timestamp".to_string(), timestamp);
+    payload.insert("timestamp".to_string(), signed.timestamp);
     payload.insert("userUUID".to_string(), user_uuid);
     payload.insert("pubKey".to_string(), pub_key);
-    payload.insert("signature".to_string(), signature);
+    payload.insert("signature".to_string(), signed.signature);
     
     Ok(payload)
 }
 
 /// Get user UUID (generate if needed)
-async fn get_user_uuid() -> Result<String, String> {
-    let mut uuid_cache = USER_UUID.lock().map_err(|e| format!("Failed to lock UUID cache: {}", e))?;
+async fn get_user_uuid() -> Result<String, NullaryError> {
+    let mut uuid_cache = USER_UUID.lock().map_err(|e| NullaryError::internal(format!("Failed to lock UUID cache: {}", e)))?;
     
     if uuid_cache.is_none() {
         let uuid = sessionless::Sessionless::generate_uuid().to_string();
         *uuid_cache = Some(uuid.clone());
-        println!("💾 Generated user UUID: {}", uuid);
+        info!("💾 Generated user UUID: {}", uuid);
     }
     
     Ok(uuid_cache.as_ref().unwrap().clone())
 }
 
-/// Create a new covenant contract
-#[tauri::command]
-async fn create_contract(
+/// A contract as the outbox stores and replays it
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct NewContract {
     title: String,
     description: String,
     participants: Vec<String>,
     steps: Vec<String>,
-) -> Result<String, String> {
-    println!("╔══════════════════════════════════════════════════════════════════════════════╗");
-    println!("║ 📝 CREATING COVENANT CONTRACT                                               ║");
-    println!("╠══════════════════════════════════════════════════════════════════════════════╣");
-    println!("║ Title: {:<69} ║", title);
-    println!("║ Description: {:<63} ║", description.chars().take(63).collect::<String>());
-    println!("╠══════════════════════════════════════════════════════════════════════════════╣");
-    println!("║ 👥 PARTICIPANTS ({} total):                                                  ║", participants.len());
+}
+
+/// Send a new contract to Covenant
+async fn send_contract(contract: NewContract) -> Result<String, NullaryError> {
+    let NewContract {
+        title,
+        description,
+        participants,
+        steps,
+    } = contract;
+
+    info!("╔══════════════════════════════════════════════════════════════════════════════╗");
+    info!("║ 📝 CREATING COVENANT CONTRACT                                               ║");
+    info!("╠══════════════════════════════════════════════════════════════════════════════╣");
+    info!("║ Title: {:<69} ║", title);
+    info!("║ Description: {:<63} ║", description.chars().take(63).collect::<String>());
+    info!("╠══════════════════════════════════════════════════════════════════════════════╣");
+    info!("║ 👥 PARTICIPANTS ({} total):                                                  ║", participants.len());
     for (i, participant) in participants.iter().enumerate() {
-        println!("║   {}. {:<70} ║", i + 1, participant);
+        info!("║   {}. {:<70} ║", i + 1, participant);
     }
-    println!("╠══════════════════════════════════════════════════════════════════════════════╣");
-    println!("║ 📋 CONTRACT STEPS ({} total):                                                ║", steps.len());
+    info!("╠══════════════════════════════════════════════════════════════════════════════╣");
+    info!("║ 📋 CONTRACT STEPS ({} total):                                                ║", steps.len());
     for (i, step) in steps.iter().enumerate() {
-        println!("║   {}. {:<70} ║", i + 1, step.chars().take(70).collect::<String>());
+        info!("║   {}. {:<70} ║", i + 1, step.chars().take(70).collect::<String>());
     }
-    println!("╚══════════════════════════════════════════════════════════════════════════════╝");
+    info!("╚══════════════════════════════════════════════════════════════════════════════╝");
     
-    let client = reqwest::Client::new();
     let covenant_url = get_service_url("covenant");
-    let url = format!("{}/contract", covenant_url);
+    let url = format!("{}contract", covenant_url);
     
     let mut auth_payload = create_auth_payload(None).await?;
     
@@ -210,27 +157,24 @@ async fn create_contract(
     request_payload["participants"] = serde_json::json!(participants);
     request_payload["steps"] = serde_json::json!(step_objects);
     
-    println!("🔧 Making request to: {}", url);
-    println!("📦 Payload: {}", serde_json::to_string_pretty(&request_payload).unwrap_or_default());
+    info!("🔧 Making request to: {}", url);
     
-    let response = client
+    let request = http_client()
         .post(&url)
-        .json(&request_payload)
-        .send()
-        .await
-        .map_err(|e| format!("Request failed: {}", e))?;
+        .json(&request_payload);
+    let response = http::send("covenant", request).await?;
     
     let status = response.status();
-    let response_text = response.text().await.map_err(|e| format!("Failed to read response: {}", e))?;
+    let response_text = response.text().await.map_err(|e| NullaryError::from_reqwest("covenant", "Failed to read response", &e))?;
     
-    println!("📊 Response ({}): {}", status, response_text);
+    debug!(%status, bytes = response_text.len(), "📊 Response");
     
     if status.is_success() {
         // Parse response to get contract UUID
         if let Ok(response_data) = serde_json::from_str::<serde_json::Value>(&response_text) {
             if let Some(data) = response_data.get("data") {
                 if let Some(uuid) = data.get("uuid").and_then(|u| u.as_str()) {
-                    println!("✅ Created contract: {}", uuid);
+                    info!("✅ Created contract: {}", uuid);
                     return Ok(uuid.to_string());
                 }
             }
@@ -238,271 +182,293 @@ async fn create_contract(
         
         // Fallback - try to extract UUID from response
         if response_text.contains("uuid") {
-            println!("✅ Contract created successfully");
+            info!("✅ Contract created successfully");
             return Ok("created".to_string());
         }
     }
     
-    Err(format!("Failed to create contract ({}): {}", status, response_text))
+    Err(NullaryError::from_status("covenant", status.as_u16(), format!("Failed to create contract: {}", response_text)))
+}
+
+/// Create a new covenant contract, queued in the outbox while Covenant is
+/// unreachable
+#[tauri::command]
+async fn create_contract(
+    title: String,
+    description: String,
+    participants: Vec<String>,
+    steps: Vec<String>,
+) -> Result<String, NullaryError> {
+    let contract = NewContract {
+        title,
+        description,
+        participants,
+        steps,
+    };
+    let result = send_contract(contract.clone()).await;
+    queue_if_transient(result, "create_contract", "covenant", &get_service_url("covenant"), &contract).await
 }
 
 /// Get user's contracts
 #[tauri::command]
-async fn get_contracts() -> Result<Vec<CovenantConnection>, String> {
-    println!("🔍 Getting user contracts...");
+async fn get_contracts() -> Result<Vec<CovenantConnection>, NullaryError> {
+    debug!("🔍 Getting user contracts...");
     
-    let client = reqwest::Client::new();
     let covenant_url = get_service_url("covenant");
-    let url = format!("{}/contracts", covenant_url);
+    let url = format!("{}contracts", covenant_url);
     
-    println!("🔧 Making request to: {}", url);
+    info!("🔧 Making request to: {}", url);
     
-    let response = client
-        .get(&url)
-        .send()
-        .await
-        .map_err(|e| format!("Request failed: {}", e))?;
+    let response = http::get("covenant", &url).await?;
     
     let status = response.status();
-    let response_text = response.text().await.map_err(|e| format!("Failed to read response: {}", e))?;
+    let response_text = response.text().await.map_err(|e| NullaryError::from_reqwest("covenant", "Failed to read response", &e))?;
     
-    println!("📊 Response ({}): {}", status, response_text);
+    debug!(%status, bytes = response_text.len(), "📊 Response");
     
-    if status.is_success() {
-        // Try to parse contracts from response
-        if let Ok(response_data) = serde_json::from_str::<serde_json::Value>(&response_text) {
-            // Handle different response formats
-            let contracts_array = if let Some(data) = response_data.get("data") {
-                data.as_array()
-            } else if response_data.is_array() {
-                response_data.as_array()
+    if !status.is_success() {
+        return Err(NullaryError::from_status("covenant", status, format!("Failed to get contracts: {}", response_text)));
+    }
+    
+    let response_data: serde_json::Value = serde_json::from_str(&response_text)
+        .map_err(|e| NullaryError::service("covenant", format!("Failed to parse contracts: {}", e)))?;
+    
+    // Handle different response formats: { data: [...] } or a bare array
+    let contracts = response_data
+        .get("data")
+        .unwrap_or(&response_data)
+        .as_array()
+        .ok_or_else(|| NullaryError::service("covenant", format!("Unexpected contracts response: {}", response_text)))?;
+    
+    let connections: Vec<CovenantConnection> = contracts.iter().filter_map(|contract| {
+        // Extract contract fields (make only uuid and title required)
+        let uuid = contract.get("uuid")?.as_str()?.to_string();
+        let title = contract.get("title")?.as_str()?.to_string();
+        let description = contract.get("description").and_then(|d| d.as_str()).unwrap_or("").to_string();
+        let participants = contract.get("participants")
+            .and_then(|p| p.as_array())
+            .map(|arr| arr.iter().filter_map(|p| p.as_str().map(|s| s.to_string())).collect())
+            .unwrap_or_default();
+        let creator = contract.get("creator").and_then(|c| c.as_str()).unwrap_or("unknown").to_string();
+        let status = contract.get("status").and_then(|s| s.as_str()).unwrap_or("active").to_string();
+        let created_at = contract.get("createdAt")
+            .or_else(|| contract.get("created_at"))
+            .and_then(|c| c.as_str())
+            .unwrap_or("0").to_string();
+        
+        // Calculate progress - handle both old format (steps array) and new format (stepCount/completedSteps)
+        let progress = if let (Some(step_count), Some(completed_steps)) = (
+            contract.get("stepCount").and_then(|s| s.as_u64()),
+            contract.get("completedSteps").and_then(|c| c.as_u64())
+        ) {
+            if step_count > 0 {
+                (completed_steps as f32 / step_count as f32) * 100.0
             } else {
-                None
-            };
-            
-            if let Some(contracts) = contracts_array {
-                let connections: Vec<CovenantConnection> = contracts.iter().filter_map(|contract| {
-                    // Extract contract fields (make only uuid and title required)
-                    let uuid = contract.get("uuid")?.as_str()?.to_string();
-                    let title = contract.get("title")?.as_str()?.to_string();
-                    let description = contract.get("description").and_then(|d| d.as_str()).unwrap_or("").to_string();
-                    let participants = contract.get("participants")
-                        .and_then(|p| p.as_array())
-                        .map(|arr| arr.iter().filter_map(|p| p.as_str().map(|s| s.to_string())).collect())
-                        .unwrap_or_default();
-                    let creator = contract.get("creator").and_then(|c| c.as_str()).unwrap_or("unknown").to_string();
-                    let status = contract.get("status").and_then(|s| s.as_str()).unwrap_or("active").to_string();
-                    let created_at = contract.get("createdAt")
-                        .or_else(|| contract.get("created_at"))
-                        .and_then(|c| c.as_str())
-                        .unwrap_or("0").to_string();
-                    
-                    // Calculate progress - handle both old format (steps array) and new format (stepCount/completedSteps)
-                    let progress = if let (Some(step_count), Some(completed_steps)) = (
-                        contract.get("stepCount").and_then(|s| s.as_u64()),
-                        contract.get("completedSteps").and_then(|c| c.as_u64())
-                    ) {
-                        if step_count > 0 {
-                            (completed_steps as f32 / step_count as f32) * 100.0
-                        } else {
-                            0.0
-                        }
-                    } else if let Some(steps) = contract.get("steps").and_then(|s| s.as_array()) {
-                        let completed_steps = steps.iter().filter(|step| {
-                            step.get("completed").and_then(|c| c.as_bool()).unwrap_or(false)
-                        }).count();
-                        let total_steps = steps.len();
-                        if total_steps > 0 { 
-                            (completed_steps as f32 / total_steps as f32) * 100.0 
-                        } else { 
-                            0.0 
-                        }
-                    } else {
-                        0.0
-                    };
-                    
-                    // Extract BDO UUID and pubKey if available
-                    let bdo_uuid = contract.get("bdoUuid").and_then(|b| b.as_str()).map(|s| s.to_string());
-                    let pub_key = contract.get("pubKey").and_then(|p| p.as_str()).map(|s| s.to_string());
-                    
-                    Some(CovenantConnection {
-                        uuid,
-                        title,
-                        description,
-                        participants,
-                        creator,
-                        status,
-                        created_at,
-                        progress,
-                        bdo_uuid,
-                        pub_key,
-                    })
-                }).collect();
-                
-                println!("📋 Found {} contracts", connections.len());
-                return Ok(connections);
+                0.0
             }
-        }
-    }
+        } else if let Some(steps) = contract.get("steps").and_then(|s| s.as_array()) {
+            let completed_steps = steps.iter().filter(|step| {
+                step.get("completed").and_then(|c| c.as_bool()).unwrap_or(false)
+            }).count();
+            let total_steps = steps.len();
+            if total_steps > 0 { 
+                (completed_steps as f32 / total_steps as f32) * 100.0 
+            } else { 
+                0.0 
+            }
+        } else {
+            0.0
+        };
+        
+        // Extract BDO UUID and pubKey if available
+        let bdo_uuid = contract.get("bdoUuid").and_then(|b| b.as_str()).map(|s| s.to_string());
+        let pub_key = contract.get("pubKey").and_then(|p| p.as_str()).map(|s| s.to_string());
+        
+        Some(CovenantConnection {
+            uuid,
+            title,
+            description,
+            participants,
+            creator,
+            status,
+            created_at,
+            progress,
+            bdo_uuid,
+            pub_key,
+        })
+    }).collect();
     
-    // Return empty list if no contracts or parsing failed
-    println!("📋 No contracts found or failed to parse response");
-    Ok(vec![])
+    info!("📋 Found {} contracts", connections.len());
+    Ok(connections)
 }
 
 /// Get a specific contract
 #[tauri::command]
-async fn get_contract(contract_uuid: String) -> Result<Contract, String> {
-    println!("🔍 Getting contract: {}", contract_uuid);
+async fn get_contract(contract_uuid: String) -> Result<Contract, NullaryError> {
+    debug!("🔍 Getting contract: {}", contract_uuid);
     
-    let client = reqwest::Client::new();
     let covenant_url = get_service_url("covenant");
-    let url = format!("{}/contract/{}", covenant_url, contract_uuid);
+    let url = format!("{}contract/{}", covenant_url, contract_uuid);
     
-    println!("🔧 Making request to: {}", url);
+    info!("🔧 Making request to: {}", url);
     
-    let response = client
-        .get(&url)
-        .send()
-        .await
-        .map_err(|e| format!("Request failed: {}", e))?;
+    let response = http::get("covenant", &url).await?;
     
     let status = response.status();
-    let response_text = response.text().await.map_err(|e| format!("Failed to read response: {}", e))?;
+    let response_text = response.text().await.map_err(|e| NullaryError::from_reqwest("covenant", "Failed to read response", &e))?;
     
-    println!("📊 Response ({}): {}", status, response_text);
+    debug!(%status, bytes = response_text.len(), "📊 Response");
     
     if !status.is_success() {
-        return Err(format!("Failed to get contract ({}): {}", status, response_text));
+        return Err(NullaryError::from_status("covenant", status.as_u16(), format!("Failed to get contract: {}", response_text)));
     }
     
     // Parse contract from response
     let contract: Contract = serde_json::from_str(&response_text)
-        .map_err(|e| format!("Failed to parse contract: {}", e))?;
+        .map_err(|e| NullaryError::service("covenant", format!("Failed to parse contract: {}", e)))?;
     
-    println!("📋 Retrieved contract: {}", contract.title);
+    info!("📋 Retrieved contract: {}", contract.title);
     Ok(contract)
 }
 
-/// Sign a contract step
-#[tauri::command]
-async fn sign_step(contract_uuid: String, step_id: String) -> Result<bool, String> {
-    println!("✍️ Signing step {} for contract {}", step_id, contract_uuid);
+/// A step signature as the outbox stores and replays it
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct StepSigning {
+    contract_uuid: String,
+    step_id: String,
+}
+
+/// Sign a contract step and send the signature to Covenant
+async fn send_step_signature(signing: StepSigning) -> Result<bool, NullaryError> {
+    let StepSigning {
+        contract_uuid,
+        step_id,
+    } = signing;
+
+    info!("✍️ Signing step {} for contract {}", step_id, contract_uuid);
     
-    let client = reqwest::Client::new();
     let covenant_url = get_service_url("covenant");
-    let url = format!("{}/contract/{}/sign", covenant_url, contract_uuid);
-    
-    // Create auth payload for contract
-    let mut auth_payload = create_auth_payload(Some(&contract_uuid)).await?;
+    let url = format!("{}contract/{}/sign", covenant_url, contract_uuid);
     
-    // Add step signing data
-    auth_payload.insert("step_id".to_string(), step_id.clone());
-    
-    // Create step-specific signature
-    let sessionless = get_sessionless()?;
-    let timestamp = get_timestamp();
+    // Create step-specific signature over timestamp + userUUID + contract + step
     let user_uuid = get_user_uuid().await?;
-    let step_message = format!("{}{}{}{}", timestamp, user_uuid, contract_uuid, step_id);
-    let step_signature = sessionless.sign(&step_message).to_hex();
-    
-    auth_payload.insert("step_signature".to_string(), step_signature);
+    let step_signed = sign_operation("covenant", SigningPurpose::CovenantStep, &[&user_uuid, &contract_uuid, &step_id]).await?;
+    
+    // The payload has one timestamp, so the auth signature reuses the step's
+    let auth_signed = sign_operation_with_timestamp_at(
+        &covenant_url,
+        "covenant",
+        SigningPurpose::AuthHeader,
+        &step_signed.timestamp,
+        &[&user_uuid, &contract_uuid],
+    ).await?;
+    
+    let mut auth_payload = HashMap::new();
+    auth_payload.insert("timestamp".to_string(), step_signed.timestamp);
+    auth_payload.insert("userUUID".to_string(), user_uuid);
+    auth_payload.insert("pubKey".to_string(), public_key("covenant")?);
+    auth_payload.insert("signature".to_string(), auth_signed.signature);
+    auth_payload.insert("step_id".to_string(), step_id.clone());
+    auth_payload.insert("step_signature".to_string(), step_signed.signature);
     
-    println!("🔧 Making request to: {}", url);
-    println!("📦 Payload: {:?}", auth_payload);
+    info!("🔧 Making request to: {}", url);
     
-    let response = client
+    let request = http_client()
         .put(&url)
-        .json(&auth_payload)
-        .send()
-        .await
-        .map_err(|e| format!("Request failed: {}", e))?;
+        .json(&auth_payload);
+    let response = http::send("covenant", request).await?;
     
     let status = response.status();
-    let response_text = response.text().await.map_err(|e| format!("Failed to read response: {}", e))?;
+    let response_text = response.text().await.map_err(|e| NullaryError::from_reqwest("covenant", "Failed to read response", &e))?;
     
-    println!("📊 Response ({}): {}", status, response_text);
+    debug!(%status, bytes = response_text.len(), "📊 Response");
     
     if status.is_success() {
-        println!("✅ Step signed successfully");
+        info!("✅ Step signed successfully");
         Ok(true)
     } else {
-        Err(format!("Failed to sign step ({}): {}", status, response_text))
+        Err(NullaryError::from_status("covenant", status.as_u16(), format!("Failed to sign step: {}", response_text)))
     }
 }
 
+/// Sign a contract step, queued in the outbox while Covenant is unreachable.
+/// The step is signed again when it is replayed.
+#[tauri::command]
+async fn sign_step(contract_uuid: String, step_id: String) -> Result<bool, NullaryError> {
+    let signing = StepSigning {
+        contract_uuid,
+        step_id,
+    };
+    let result = send_step_signature(signing.clone()).await;
+    queue_if_transient(result, "sign_step", "covenant", &get_service_url("covenant"), &signing).await
+}
+
 /// Get contract SVG visualization
 #[tauri::command]
-async fn get_contract_svg(contract_uuid: String, theme: Option<String>) -> Result<String, String> {
-    println!("🎨 Getting SVG for contract: {}", contract_uuid);
+async fn get_contract_svg(contract_uuid: String, theme: Option<String>) -> Result<String, NullaryError> {
+    info!("🎨 Getting SVG for contract: {}", contract_uuid);
     
-    let client = reqwest::Client::new();
     let covenant_url = get_service_url("covenant");
     let theme = theme.unwrap_or_else(|| "dark".to_string());
-    let url = format!("{}/contract/{}/svg?theme={}", covenant_url, contract_uuid, theme);
+    let url = format!("{}contract/{}/svg?theme={}", covenant_url, contract_uuid, theme);
     
-    println!("🔧 Making request to: {}", url);
+    info!("🔧 Making request to: {}", url);
     
-    let response = client
-        .get(&url)
-        .send()
-        .await
-        .map_err(|e| format!("Request failed: {}", e))?;
+    let response = http::get("covenant", &url).await?;
     
     let status = response.status();
     
     if !status.is_success() {
         let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-        return Err(format!("Failed to get SVG ({}): {}", status, error_text));
+        return Err(NullaryError::from_status("covenant", status.as_u16(), format!("Failed to get SVG: {}", error_text)));
     }
     
-    let svg = response.text().await.map_err(|e| format!("Failed to read SVG: {}", e))?;
+    let svg = response.text().await.map_err(|e| NullaryError::from_reqwest("covenant", "Failed to read SVG", &e))?;
     
-    println!("🎨 Generated SVG visualization ({} chars)", svg.len());
+    info!("🎨 Generated SVG visualization ({} chars)", svg.len());
     Ok(svg)
 }
 
 /// Get user UUID and public key for sharing
 #[tauri::command]
-async fn get_user_info() -> Result<(String, String), String> {
-    let sessionless = get_sessionless()?;
-    let pub_key = sessionless.public_key().to_hex();
+async fn get_user_info() -> Result<(String, String), NullaryError> {
+    let pub_key = public_key("covenant")?;
     let user_uuid = get_user_uuid().await?;
     
-    println!("╔══════════════════════════════════════════════════════════════════════════════╗");
-    println!("║ 🔑 YOUR COVENANT USER INFORMATION                                           ║");
-    println!("╠══════════════════════════════════════════════════════════════════════════════╣");
-    println!("║ User UUID: {:<65} ║", user_uuid);
-    println!("║ Public Key: {:<64} ║", pub_key);
-    println!("╠══════════════════════════════════════════════════════════════════════════════╣");
-    println!("║ 📋 PARTICIPANT MANAGEMENT TIPS:                                             ║");
-    println!("║ • Copy your UUID from the app interface - it's automatically copied         ║");
-    println!("║ • Share this UUID with others who want to add you to contracts              ║");
-    println!("║ • Use the Connection screen to generate shareable URLs for easier sharing   ║");
-    println!("║ • When creating contracts, paste participant UUIDs one per line             ║");
-    println!("║ • Each participant must share their UUID with the contract creator          ║");
-    println!("╚══════════════════════════════════════════════════════════════════════════════╝");
+    info!("╔══════════════════════════════════════════════════════════════════════════════╗");
+    info!("║ 🔑 YOUR COVENANT USER INFORMATION                                           ║");
+    info!("╠══════════════════════════════════════════════════════════════════════════════╣");
+    info!("║ User UUID: {:<65} ║", user_uuid);
+    info!("║ Public Key: {:<64} ║", pub_key);
+    info!("╠══════════════════════════════════════════════════════════════════════════════╣");
+    info!("║ 📋 PARTICIPANT MANAGEMENT TIPS:                                             ║");
+    info!("║ • Copy your UUID from the app interface - it's automatically copied         ║");
+    info!("║ • Share this UUID with others who want to add you to contracts              ║");
+    info!("║ • Use the Connection screen to generate shareable URLs for easier sharing   ║");
+    info!("║ • When creating contracts, paste participant UUIDs one per line             ║");
+    info!("║ • Each participant must share their UUID with the contract creator          ║");
+    info!("╚══════════════════════════════════════════════════════════════════════════════╝");
     
     Ok((user_uuid, pub_key))
 }
 
 /// Copy user UUID to clipboard
 #[tauri::command]
-async fn copy_user_uuid_to_clipboard(app_handle: tauri::AppHandle) -> Result<String, String> {
+async fn copy_user_uuid_to_clipboard(app_handle: tauri::AppHandle) -> Result<String, NullaryError> {
     let user_uuid = get_user_uuid().await?;
     
     // Use the clipboard plugin to copy the UUID
     app_handle.clipboard()
         .write_text(&user_uuid)
-        .map_err(|e| format!("Failed to copy to clipboard: {}", e))?;
+        .map_err(|e| NullaryError::internal(format!("Failed to copy to clipboard: {}", e)))?;
     
-    println!("📋 UUID copied to clipboard: {}", user_uuid);
+    info!("📋 UUID copied to clipboard: {}", user_uuid);
     Ok(user_uuid)
 }
 
 /// Initialize sessionless and generate connection URL for other instances
 #[tauri::command]
-async fn generate_connection_url() -> Result<String, String> {
+async fn generate_connection_url() -> Result<String, NullaryError> {
     let (user_uuid, pub_key) = get_user_info().await?;
     
     // Create a simple connection URL that other instances can use
@@ -512,19 +478,19 @@ async fn generate_connection_url() -> Result<String, String> {
         urlencoding::encode(&pub_key)
     );
     
-    println!("🔗 Generated connection URL: {}", connection_url);
+    info!("🔗 Generated connection URL: {}", connection_url);
     Ok(connection_url)
 }
 
 /// Process a connection URL from another instance
 #[tauri::command]
-async fn process_connection_url(connection_url: String) -> Result<String, String> {
-    println!("🔗 Processing connection URL: {}", connection_url);
+async fn process_connection_url(connection_url: String) -> Result<String, NullaryError> {
+    info!("🔗 Processing connection URL: {}", connection_url);
     
     // Parse URL to extract partner information
     let url_parts: Vec<&str> = connection_url.split('?').collect();
     if url_parts.len() != 2 {
-        return Err("Invalid connection URL format".to_string());
+        return Err(NullaryError::validation("Invalid connection URL format"));
     }
     
     let query_params: std::collections::HashMap<String, String> = url_parts[1]
@@ -543,9 +509,9 @@ async fn process_connection_url(connection_url: String) -> Result<String, String
         .collect();
     
     let partner_uuid = query_params.get("userUUID")
-        .ok_or("Missing userUUID parameter")?;
+        .ok_or_else(|| NullaryError::validation("Missing userUUID parameter"))?;
     let partner_public_key = query_params.get("publicKey")
-        .ok_or("Missing publicKey parameter")?;
+        .ok_or_else(|| NullaryError::validation("Missing publicKey parameter"))?;
     let partner_name = query_params.get("name")
         .map(|s| s.as_str())
         .unwrap_or("Unknown User");
@@ -553,16 +519,28 @@ async fn process_connection_url(connection_url: String) -> Result<String, String
     // Check for self-connection attempt
     let (_our_uuid, our_public_key) = get_user_info().await?;
     if partner_public_key == &our_public_key {
-        return Err("❌ Cannot connect to yourself! You're trying to connect to your own public key.".to_string());
+        return Err(NullaryError::validation("❌ Cannot connect to yourself! You're trying to connect to your own public key."));
     }
     
-    println!("✅ Connection request valid: {} → {}", our_public_key, partner_public_key);
-    println!("✅ Partner: {} ({})", partner_name, partner_uuid);
+    info!("✅ Connection request valid: {} → {}", our_public_key, partner_public_key);
+    info!("✅ Partner: {} ({})", partner_name, partner_uuid);
     
     // Return success message with partner info
     Ok(format!("Connected to {} ({})", partner_name, partner_uuid))
 }
 
+/// Replay contracts and step signatures queued while Covenant was unreachable
+fn register_outbox_handlers() {
+    nullary_core::register_outbox_handler("create_contract", |payload| async move {
+        let contract: NewContract = nullary_core::outbox_payload(payload)?;
+        Ok(serde_json::json!(send_contract(contract).await?))
+    });
+    nullary_core::register_outbox_handler("sign_step", |payload| async move {
+        let signing: StepSigning = nullary_core::outbox_payload(payload)?;
+        Ok(serde_json::json!(send_step_signature(signing).await?))
+    });
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
@@ -577,8 +555,62 @@ pub fn run() {
             get_user_info,
             copy_user_uuid_to_clipboard,
             generate_connection_url,
-            process_connection_url
+            process_connection_url,
+            // User Persistence Functions
+            nullary_core::generate_sessionless_keys,
+            nullary_core::stronghold_init,
+            nullary_core::stronghold_get_record,
+            nullary_core::stronghold_set_record,
+            nullary_core::stronghold_clear_vault,
+            nullary_core::read_user_data_file,
+            nullary_core::write_user_data_file,
+            nullary_core::clear_user_data,
+            // Documents
+            nullary_core::read_document,
+            nullary_core::write_document,
+            nullary_core::delete_document,
+            nullary_core::list_documents,
+            // Personas
+            nullary_core::list_personas,
+            nullary_core::create_persona,
+            nullary_core::rename_persona,
+            nullary_core::delete_persona,
+            nullary_core::switch_persona,
+            nullary_core::get_persona_service_uuids,
+            nullary_core::set_persona_service_uuid,
+            // Identity backup
+            nullary_core::export_identity_backup,
+            nullary_core::import_identity_backup,
+            nullary_core::export_recovery_words,
+            nullary_core::import_recovery_words,
+            // Key rotation
+            nullary_core::rotate_identity,
+            nullary_core::retry_identity_rotation,
+            nullary_core::get_key_rotations,
+            // Signing
+            nullary_core::get_signing_journal,
+            // Logs
+            nullary_core::export_logs,
+            // Health
+            nullary_core::check_service_health,
+            // Outbox
+            nullary_core::list_outbox,
+            nullary_core::retry_outbox_item,
+            nullary_core::cancel_outbox_item,
+            // Endpoint configuration
+            nullary_core::get_environment_config,
+            nullary_core::switch_environment
         ])
+        .setup(|app| {
+            info!("📜 Covenant backend is starting up...");
+
+            // Register the app and load (or create on first launch) its identity
+            if let Err(e) = nullary_core::init(app.handle(), "covenant") {
+                error!("❌ Failed to initialize nullary core: {}", e);
+            }
+            register_outbox_handlers();
+            Ok(())
+        })
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
\ No newline at end of file
@@ -1030,7 +1030,8 @@ async function loadContracts() {
         console.log(`📋 Loaded ${appState.contracts.length} contracts`);
     } catch (error) {
         console.error('❌ Failed to load contracts:', error);
-        appState.error = error.toString();
+        // Commands reject with a NullaryError: { kind, message, ... }
+        appState.error = error?.message || String(error);
     } finally {
         appState.loading = false;
         renderCurrentScreen();
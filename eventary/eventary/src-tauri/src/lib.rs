@@ -1,43 +1,50 @@
 use reqwest::Client;
 use sanora_rs::structs::{ProductMeta, SanoraUser, SuccessResult};
-use sanora_rs::{Sanora};
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 use serde_json::Value;
-use sessionless::hex::FromHex;
-use sessionless::hex::IntoHex;
-use sessionless::{PrivateKey, Sessionless};
-use std::env;
 use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{debug, error};
 
-async fn get_sessionless() -> Result<Sessionless, String> {
-    let private_key = env::var("PRIVATE_KEY").unwrap_or_else(|_| {
-        String::from("b75011b167c5e3a6b0de97d8e1950cd9548f83bb67f47112bed6a082db795496")
-    });
-    let sessionless =
-        Sessionless::from_private_key(PrivateKey::from_hex(private_key).expect("private key"));
-    Ok(sessionless)
-}
+// Shared identity, endpoints and service clients
+use nullary_core::{get_service_url, queue_if_transient, sanora_client, NullaryError};
 
 #[tauri::command]
-async fn create_sanora_user() -> Result<SanoraUser, String> {
-    let s = get_sessionless().await;
-    match s {
-        Ok(sessionless) => {
-            let sanora = Sanora::new(
-                Some("https://poppy.sanora.allyabase.com/".to_string()),
-                Some(sessionless),
-            );
-            let _user = sanora.create_user().await;
-            dbg!(&_user);
-            return match _user {
-                Ok(user) => Ok(user),
-                Err(_) => Err("no user".to_string()),
+async fn create_sanora_user() -> Result<SanoraUser, NullaryError> {
+    nullary_core::create_sanora_user().await
+}
+
+/// An event listing as the outbox stores and replays it
+#[derive(Serialize, Deserialize)]
+struct NewProduct {
+    uuid: String,
+    title: String,
+    description: String,
+    price: u32,
+    times: String,
+    location: String,
+}
+
+/// Send an event listing to Sanora
+async fn send_product(product: &NewProduct) -> Result<ProductMeta, NullaryError> {
+    match sanora_client().await {
+        Ok(sanora) => {
+            let meta = sanora
+                //.add_product(uuid, title, description, price, times, location)
+                .add_product(&product.uuid, &product.title, &product.description, &product.price)
+                .await;
+
+            debug!("{:?}", meta);
+            return match meta {
+                Ok(meta) => Ok(meta),
+                Err(e) => Err(NullaryError::service("sanora", format!("Failed to add product: {}", e))),
             };
         }
-        Err(_) => Err("no user".to_string()),
+        Err(e) => Err(e),
     }
 }
 
+/// Create an event listing, queued in the outbox while Sanora is unreachable
 #[tauri::command]
 async fn add_product(
     uuid: &str,
@@ -46,27 +53,25 @@ async fn add_product(
     price: u32,
     times: &str,
     location: &str,
-) -> Result<ProductMeta, String> {
-    let s = get_sessionless().await;
-    match s {
-        Ok(sessionless) => {
-            let sanora = Sanora::new(
-                Some("https://poppy.sanora.allyabase.com/".to_string()),
-                Some(sessionless),
-            );
-            let meta = sanora
-                //.add_product(uuid, title, description, price, times, location)
-                .add_product(uuid, title, description, &price)
-                .await;
+) -> Result<ProductMeta, NullaryError> {
+    let product = NewProduct {
+        uuid: uuid.to_string(),
+        title: title.to_string(),
+        description: description.to_string(),
+        price,
+        times: times.to_string(),
+        location: location.to_string(),
+    };
+    let result = send_product(&product).await;
+    queue_if_transient(result, "add_product", "sanora", &get_service_url("sanora"), &product).await
+}
 
-            dbg!(&meta);
-            return match meta {
-                Ok(meta) => Ok(meta),
-                Err(_) => Err("adding product failed".to_string()),
-            };
-        }
-        Err(_) => Err("no sessionless".to_string()),
-    }
+/// Replay event listings queued while Sanora was unreachable
+fn register_outbox_handlers() {
+    nullary_core::register_outbox_handler("add_product", |payload| async move {
+        let product: NewProduct = nullary_core::outbox_payload(payload)?;
+        Ok(json!(send_product(&product).await?))
+    });
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -74,7 +79,62 @@ pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_upload::init())
         .plugin(tauri_plugin_opener::init())
-        .invoke_handler(tauri::generate_handler![create_sanora_user, add_product])
+        .invoke_handler(tauri::generate_handler![
+            create_sanora_user,
+            add_product,
+            // User Persistence Functions
+            nullary_core::generate_sessionless_keys,
+            nullary_core::stronghold_init,
+            nullary_core::stronghold_get_record,
+            nullary_core::stronghold_set_record,
+            nullary_core::stronghold_clear_vault,
+            nullary_core::read_user_data_file,
+            nullary_core::write_user_data_file,
+            nullary_core::clear_user_data,
+            // Documents
+            nullary_core::read_document,
+            nullary_core::write_document,
+            nullary_core::delete_document,
+            nullary_core::list_documents,
+            // Personas
+            nullary_core::list_personas,
+            nullary_core::create_persona,
+            nullary_core::rename_persona,
+            nullary_core::delete_persona,
+            nullary_core::switch_persona,
+            nullary_core::get_persona_service_uuids,
+            nullary_core::set_persona_service_uuid,
+            // Identity backup
+            nullary_core::export_identity_backup,
+            nullary_core::import_identity_backup,
+            nullary_core::export_recovery_words,
+            nullary_core::import_recovery_words,
+            // Key rotation
+            nullary_core::rotate_identity,
+            nullary_core::retry_identity_rotation,
+            nullary_core::get_key_rotations,
+            // Signing
+            nullary_core::get_signing_journal,
+            // Logs
+            nullary_core::export_logs,
+            // Health
+            nullary_core::check_service_health,
+            // Outbox
+            nullary_core::list_outbox,
+            nullary_core::retry_outbox_item,
+            nullary_core::cancel_outbox_item,
+            // Endpoint configuration
+            nullary_core::get_environment_config,
+            nullary_core::switch_environment
+        ])
+        .setup(|app| {
+            // Register the app and load (or create on first launch) its identity
+            if let Err(e) = nullary_core::init(app.handle(), "eventary") {
+                error!("❌ Failed to initialize nullary core: {}", e);
+            }
+            register_outbox_handlers();
+            Ok(())
+        })
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
@@ -1,11 +1,14 @@
-use std::env;
 use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 use tauri::Manager;
-use sessionless::{Sessionless, hex::IntoHex};
-use prof_rs::ProfClient;
 use serde_json::json;
 use serde_json::Value;
+use tracing::{debug, error, info, warn};
+
+// Shared identity, endpoints and service clients
+use nullary_core::{
+    current_env, get_service_url, prof_client_at, public_key, queue_if_transient, NullaryError,
+};
 
 // Profile data structure matching Prof service expectations
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -48,80 +51,45 @@ pub struct SessionlessInfo {
     pub public_key: String,
 }
 
-// Environment configuration
-fn get_service_url(service: &str) -> String {
-    let env = env::var("IDOTHIS_ENV").unwrap_or_else(|_| "dev".to_string());
-    
-    println!("🔧 get_service_url: env={}, service={}", env, service);
-    
-    match env.as_str() {
-        "local" => {
-            match service {
-                "prof" => "http://localhost:3008".to_string(),
-                "bdo" => "http://localhost:3003".to_string(),
-                "sanora" => "http://localhost:7243".to_string(),
-                "dolores" => "http://localhost:3007".to_string(),
-                _ => "http://localhost:3008".to_string(),
-            }
-        },
-        "test" => {
-            match service {
-                "prof" => "http://localhost:5113".to_string(),
-                "bdo" => "http://localhost:5114".to_string(),
-                "sanora" => "http://localhost:5121".to_string(),
-                "dolores" => "http://localhost:5118".to_string(),
-                _ => "http://localhost:5113".to_string(),
-            }
-        },
-        _ => { // dev
-            match service {
-                "prof" => "https://dev.prof.allyabase.com".to_string(),
-                "bdo" => "https://dev.bdo.allyabase.com".to_string(),
-                "sanora" => "https://dev.sanora.allyabase.com".to_string(),
-                "dolores" => "https://dev.dolores.allyabase.com".to_string(),
-                _ => "https://dev.prof.allyabase.com".to_string(),
-            }
-        }
-    }
-}
-
 // Get environment config (for JavaScript sync)
 #[tauri::command]
-async fn get_env_config() -> Result<String, String> {
-    let env = env::var("IDOTHIS_ENV").unwrap_or_else(|_| "dev".to_string());
-    Ok(env)
-}
-
-// Create sessionless instance
-fn create_sessionless() -> Sessionless {
-    // Development sessionless instance (in production, this would be user-specific)
-    Sessionless::new()
+async fn get_env_config() -> Result<String, NullaryError> {
+    Ok(current_env())
 }
 
 // Get sessionless info
 #[tauri::command]
-async fn get_sessionless_info() -> Result<SessionlessInfo, String> {
-    let sessionless = create_sessionless();
+async fn get_sessionless_info() -> Result<SessionlessInfo, NullaryError> {
+    let public_key = public_key("prof")?;
     
     Ok(SessionlessInfo {
-        uuid: sessionless.public_key().to_hex(),
-        public_key: sessionless.public_key().to_hex(),
+        uuid: public_key.clone(),
+        public_key,
     })
 }
 
 // Profile Management Commands
 
-#[tauri::command]
-async fn create_profile(profile_data: ProfileData, image_data: Option<String>) -> Result<Profile, String> {
-    println!("🔨 Creating profile with data: {:?}", profile_data);
+// A profile as the outbox stores and replays it
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct NewProfile {
+    profile_data: ProfileData,
+    image_data: Option<String>,
+}
+
+async fn send_profile(profile: NewProfile) -> Result<Profile, NullaryError> {
+    let NewProfile {
+        profile_data,
+        image_data,
+    } = profile;
+
+    info!("🔨 Creating profile with data: {:?}", profile_data);
     
-    let sessionless = create_sessionless();
     let prof_url = get_service_url("prof");
     
-    println!("🔗 Connecting to Prof service at: {}", prof_url);
+    info!("🔗 Connecting to Prof service at: {}", prof_url);
     
-    let prof_client = ProfClient::new(prof_url)
-        .with_sessionless(sessionless);
+    let prof_client = prof_client_at(&prof_url).await?;
     
     // Convert ProfileData to prof-rs expected format
     let mut prof_data = HashMap::new();
@@ -151,8 +119,8 @@ async fn create_profile(profile_data: ProfileData, image_data: Option<String>) -
         match base64::decode(&base64_data) {
             Ok(bytes) => Some((bytes, "profile.jpg".to_string())),
             Err(e) => {
-                println!("⚠️ Failed to decode image: {}", e);
-                None
+                warn!("⚠️ Failed to decode image: {}", e);
+                return Err(NullaryError::validation(format!("Failed to decode image: {}", e)));
             }
         }
     } else {
@@ -161,51 +129,58 @@ async fn create_profile(profile_data: ProfileData, image_data: Option<String>) -
     
     match prof_client.create_profile(prof_data, image_bytes).await {
         Ok(profile) => {
-            println!("✅ Profile created successfully");
+            info!("✅ Profile created successfully");
             
             // Convert prof response to our Profile structure
-            let converted_profile = convert_prof_profile(profile);
+            let converted_profile = convert_prof_profile(profile)?;
             Ok(converted_profile)
         },
         Err(e) => {
-            println!("❌ Failed to create profile: {}", e);
-            Err(format!("Failed to create profile: {}", e))
+            error!("❌ Failed to create profile: {}", e);
+            Err(NullaryError::service("prof", format!("Failed to create profile: {}", e)))
         }
     }
 }
 
+// Queued in the outbox while Prof is unreachable
+#[tauri::command]
+async fn create_profile(profile_data: ProfileData, image_data: Option<String>) -> Result<Profile, NullaryError> {
+    let profile = NewProfile {
+        profile_data,
+        image_data,
+    };
+    let result = send_profile(profile.clone()).await;
+    queue_if_transient(result, "create_profile", "prof", &get_service_url("prof"), &profile).await
+}
+
 #[tauri::command]
-async fn get_profile() -> Result<Profile, String> {
-    println!("🔍 Getting profile");
+async fn get_profile() -> Result<Profile, NullaryError> {
+    debug!("🔍 Getting profile");
     
-    let sessionless = create_sessionless();
     let prof_url = get_service_url("prof");
     
-    let prof_client = ProfClient::new(prof_url)
-        .with_sessionless(sessionless);
+    let prof_client = prof_client_at(&prof_url).await?;
     
     match prof_client.get_profile(None).await {
         Ok(profile) => {
-            println!("✅ Profile retrieved successfully");
-            let converted_profile = convert_prof_profile(profile);
+            info!("✅ Profile retrieved successfully");
+            let converted_profile = convert_prof_profile(profile)?;
             Ok(converted_profile)
         },
         Err(e) => {
-            println!("❌ Failed to get profile: {}", e);
-            Err(format!("Failed to get profile: {}", e))
+            error!("❌ Failed to get profile: {}", e);
+            Err(NullaryError::service("prof", format!("Failed to get profile: {}", e)))
         }
     }
 }
 
 #[tauri::command]
-async fn update_profile(profile_data: ProfileData, image_data: Option<String>) -> Result<Profile, String> {
-    println!("🔄 Updating profile with data: {:?}", profile_data);
+async fn update_profile(profile_data: ProfileData, image_data: Option<String>) -> Result<Profile, NullaryError> {
+    info!("🔄 Updating profile with data: {:?}", profile_data);
     
-    let sessionless = create_sessionless();
     let prof_url = get_service_url("prof");
     
-    let prof_client = ProfClient::new(prof_url)
-        .with_sessionless(sessionless);
+    let prof_client = prof_client_at(&prof_url).await?;
     
     // Convert ProfileData to prof-rs expected format
     let mut prof_data = HashMap::new();
@@ -230,8 +205,8 @@ async fn update_profile(profile_data: ProfileData, image_data: Option<String>) -
         match base64::decode(&base64_data) {
             Ok(bytes) => Some((bytes, "profile.jpg".to_string())),
             Err(e) => {
-                println!("⚠️ Failed to decode image: {}", e);
-                None
+                warn!("⚠️ Failed to decode image: {}", e);
+                return Err(NullaryError::validation(format!("Failed to decode image: {}", e)));
             }
         }
     } else {
@@ -240,35 +215,33 @@ async fn update_profile(profile_data: ProfileData, image_data: Option<String>) -
     
     match prof_client.update_profile(prof_data, image_bytes).await {
         Ok(profile) => {
-            println!("✅ Profile updated successfully");
-            let converted_profile = convert_prof_profile(profile);
+            info!("✅ Profile updated successfully");
+            let converted_profile = convert_prof_profile(profile)?;
             Ok(converted_profile)
         },
         Err(e) => {
-            println!("❌ Failed to update profile: {}", e);
-            Err(format!("Failed to update profile: {}", e))
+            error!("❌ Failed to update profile: {}", e);
+            Err(NullaryError::service("prof", format!("Failed to update profile: {}", e)))
         }
     }
 }
 
 #[tauri::command]
-async fn delete_profile() -> Result<String, String> {
-    println!("🗑️ Deleting profile");
+async fn delete_profile() -> Result<String, NullaryError> {
+    info!("🗑️ Deleting profile");
     
-    let sessionless = create_sessionless();
     let prof_url = get_service_url("prof");
     
-    let prof_client = ProfClient::new(prof_url)
-        .with_sessionless(sessionless);
+    let prof_client = prof_client_at(&prof_url).await?;
     
     match prof_client.delete_profile().await {
         Ok(_) => {
-            println!("✅ Profile deleted successfully");
+            info!("✅ Profile deleted successfully");
             Ok("Profile deleted successfully".to_string())
         },
         Err(e) => {
-            println!("❌ Failed to delete profile: {}", e);
-            Err(format!("Failed to delete profile: {}", e))
+            error!("❌ Failed to delete profile: {}", e);
+            Err(NullaryError::service("prof", format!("Failed to delete profile: {}", e)))
         }
     }
 }
@@ -276,8 +249,8 @@ async fn delete_profile() -> Result<String, String> {
 // Profile Discovery Commands (for swipeable interface)
 
 #[tauri::command]
-async fn get_all_profiles() -> Result<Vec<Profile>, String> {
-    println!("👥 Getting all profiles for discovery");
+async fn get_all_profiles() -> Result<Vec<Profile>, NullaryError> {
+    info!("👥 Getting all profiles for discovery");
     
     // In a real implementation, this would fetch profiles from a discovery service
     // For now, we'll return mock profiles for demonstration
@@ -328,19 +301,19 @@ async fn get_all_profiles() -> Result<Vec<Profile>, String> {
         },
     ];
     
-    println!("✅ Returning {} mock profiles", mock_profiles.len());
+    info!("✅ Returning {} mock profiles", mock_profiles.len());
     Ok(mock_profiles)
 }
 
 // Utility function to convert prof-rs profile to our Profile structure
-fn convert_prof_profile(prof_profile: prof_rs::Profile) -> Profile {
-    let sessionless = create_sessionless();
+fn convert_prof_profile(prof_profile: prof_rs::Profile) -> Result<Profile, NullaryError> {
+    let public_key = public_key("prof")?;
     let prof_url = get_service_url("prof");
     
     // Generate image URL if image filename exists
     let image_url = if let Some(ref filename) = prof_profile.image_filename {
         if !filename.is_empty() {
-            Some(format!("{}/user/{}/profile/image", prof_url.trim_end_matches('/'), sessionless.public_key().to_hex()))
+            Some(format!("{}/user/{}/profile/image", prof_url.trim_end_matches('/'), public_key))
         } else {
             None
         }
@@ -348,8 +321,8 @@ fn convert_prof_profile(prof_profile: prof_rs::Profile) -> Profile {
         None
     };
     
-    Profile {
-        uuid: sessionless.public_key().to_hex(),
+    Ok(Profile {
+        uuid: public_key,
         name: prof_profile.name,
         email: prof_profile.email,
         idothis: prof_profile.additional_fields.get("idothis")
@@ -369,20 +342,28 @@ fn convert_prof_profile(prof_profile: prof_rs::Profile) -> Profile {
         created_at: Some(prof_profile.created_at),
         updated_at: Some(prof_profile.updated_at),
         additional_fields: prof_profile.additional_fields,
-    }
+    })
 }
 
 // Debug command
 #[tauri::command]
-async fn dbg(message: String) -> Result<String, String> {
-    println!("🐛 DEBUG: {}", message);
+async fn dbg(message: String) -> Result<String, NullaryError> {
+    info!("🐛 DEBUG: {}", message);
     Ok(format!("Debug logged: {}", message))
 }
 
 // Health check
 #[tauri::command]
-async fn health_check() -> Result<String, String> {
-    Ok("IDothis backend is healthy".to_string())
+async fn health_check() -> Result<nullary_core::HealthReport, NullaryError> {
+    nullary_core::check_service_health(None).await
+}
+
+/// Replay profiles queued while Prof was unreachable
+fn register_outbox_handlers() {
+    nullary_core::register_outbox_handler("create_profile", |payload| async move {
+        let profile: NewProfile = nullary_core::outbox_payload(payload)?;
+        Ok(json!(send_profile(profile).await?))
+    });
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -398,10 +379,60 @@ pub fn run() {
             delete_profile,
             get_all_profiles,
             dbg,
-            health_check
+            health_check,
+            // User Persistence Functions
+            nullary_core::generate_sessionless_keys,
+            nullary_core::stronghold_init,
+            nullary_core::stronghold_get_record,
+            nullary_core::stronghold_set_record,
+            nullary_core::stronghold_clear_vault,
+            nullary_core::read_user_data_file,
+            nullary_core::write_user_data_file,
+            nullary_core::clear_user_data,
+            // Documents
+            nullary_core::read_document,
+            nullary_core::write_document,
+            nullary_core::delete_document,
+            nullary_core::list_documents,
+            // Personas
+            nullary_core::list_personas,
+            nullary_core::create_persona,
+            nullary_core::rename_persona,
+            nullary_core::delete_persona,
+            nullary_core::switch_persona,
+            nullary_core::get_persona_service_uuids,
+            nullary_core::set_persona_service_uuid,
+            // Identity backup
+            nullary_core::export_identity_backup,
+            nullary_core::import_identity_backup,
+            nullary_core::export_recovery_words,
+            nullary_core::import_recovery_words,
+            // Key rotation
+            nullary_core::rotate_identity,
+            nullary_core::retry_identity_rotation,
+            nullary_core::get_key_rotations,
+            // Signing
+            nullary_core::get_signing_journal,
+            // Logs
+            nullary_core::export_logs,
+            // Health
+            nullary_core::check_service_health,
+            // Outbox
+            nullary_core::list_outbox,
+            nullary_core::retry_outbox_item,
+            nullary_core::cancel_outbox_item,
+            // Endpoint configuration
+            nullary_core::get_environment_config,
+            nullary_core::switch_environment
         ])
-        .setup(|_app| {
-            println!("💼 IDothis backend is starting up...");
+        .setup(|app| {
+            info!("💼 IDothis backend is starting up...");
+
+            // Register the app and load (or create on first launch) its identity
+            if let Err(e) = nullary_core::init(app.handle(), "idothis") {
+                error!("❌ Failed to initialize nullary core: {}", e);
+            }
+            register_outbox_handlers();
             Ok(())
         })
         .run(tauri::generate_context!())
@@ -763,6 +763,13 @@ async function initApp() {
         // Create app structure
         createAppStructure();
 
+        // A stale feed is refreshed in the background; show it once it changes
+        window.__TAURI__.event.listen('feed-updated', () => {
+            if (appState.currentScreen === 'feed' && !appState.loading) {
+                loadScreenData('feed');
+            }
+        });
+
         // Load initial screen data
         await loadScreenData(appState.currentScreen);
 
@@ -1,39 +1,43 @@
 use bdo_rs::structs::BDOUser;
-use bdo_rs::{Bases, BDO};
+use bdo_rs::Bases;
 use dolores_rs::structs::Feed;
-use dolores_rs::{Dolores, DoloresUser};
+use dolores_rs::DoloresUser;
 use sanora_rs::structs::SanoraUser;
-use sanora_rs::Sanora;
-use prof_rs::{ProfClient, ProfileBuilder, Profile};
+use prof_rs::{ProfileBuilder, Profile};
 use reqwest::Client;
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 use serde_json::Value;
-use sessionless::hex::FromHex;
-use sessionless::hex::IntoHex;
-use sessionless::{PrivateKey, Sessionless};
-use std::collections::HashMap;
-use std::env;
+use std::collections::{BTreeMap, HashMap};
 use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{debug, error, info};
+
+// Shared identity, endpoints and service clients
+use nullary_core::{
+    aggregate_feed, bases_by_id, bdo_client_at, bdo_user_uuid_at, current_env, discover_bases,
+    discover_bases_at, dolores_client_at, dolores_feed_at, feed_sources, forget_if_not_found,
+    get_service_url, known_base, moderation, prof_client_at, public_key, public_key_at,
+    queue_if_transient, sanora_client_at, without_blocked_posts, BaseData, FeedFetch,
+    NullaryError, Validators,
+};
 
 /// Debug logging command for development
 #[tauri::command]
 fn dbg(log: &str) {
-    dbg!(log);
+    info!(target: "frontend", "{}", log);
 }
 
-/// Get the public key from the sessionless instance
+/// Get the public key this install uses with BDO
 #[tauri::command]
-async fn get_public_key() -> Result<String, String> {
-    let sessionless = get_sessionless().await?;
-    Ok(sessionless.public_key().to_hex())
+async fn get_public_key() -> Result<String, NullaryError> {
+    public_key("bdo")
 }
 
 /// Get sessionless information for the frontend
 #[tauri::command]
-async fn get_sessionless_info() -> Result<Value, String> {
-    let sessionless = get_sessionless().await?;
+async fn get_sessionless_info() -> Result<Value, NullaryError> {
     Ok(json!({
-        "publicKey": sessionless.public_key().to_hex(),
+        "publicKey": public_key("bdo")?,
         "ready": true
     }))
 }
@@ -41,350 +45,251 @@ async fn get_sessionless_info() -> Result<Value, String> {
 /// Get environment configuration from environment variables
 #[tauri::command]
 fn get_env_config() -> String {
-    env::var("MYBASE_ENV").unwrap_or_else(|_| "dev".to_string())
-}
-
-/// Get service URL based on environment and service name
-fn get_service_url(service: &str) -> String {
-    let env = env::var("MYBASE_ENV").unwrap_or_else(|_| "dev".to_string());
-    println!("🔧 get_service_url: env={}, service={}", env, service);
-    
-    let url = match (env.as_str(), service) {
-        // Test environment (127.0.0.1:5111-5122)
-        ("test", "bdo") => "http://127.0.0.1:5114/".to_string(),
-        ("test", "dolores") => "http://127.0.0.1:5118/".to_string(),
-        ("test", "sanora") => "http://127.0.0.1:5121/".to_string(),
-        ("test", "prof") => "http://127.0.0.1:5113/".to_string(),
-        
-        // Local environment (127.0.0.1:3000-3011)
-        ("local", "bdo") => "http://127.0.0.1:3003/".to_string(),
-        ("local", "dolores") => "http://127.0.0.1:3005/".to_string(),
-        ("local", "sanora") => "http://127.0.0.1:7243/".to_string(),
-        ("local", "prof") => "http://127.0.0.1:3007/".to_string(),
-        
-        // Dev environment (default)
-        (_, service) => format!("https://dev.{}.allyabase.com/", service),
-    };
-    
-    println!("🔗 Resolved URL for {} in {} environment: {}", service, env, url);
-    url
-}
-
-/// Get or create sessionless instance using environment variable or default key
-async fn get_sessionless() -> Result<Sessionless, String> {
-    let private_key = env::var("PRIVATE_KEY").unwrap_or_else(|_| {
-        String::from("b75011b167c5e3a6b0de97d8e1950cd9548f83bb67f47112bed6a082db795496")
-    });
-    let sessionless =
-        Sessionless::from_private_key(PrivateKey::from_hex(private_key).expect("private key"));
-    Ok(sessionless)
-}
-
-/// Sign a message using sessionless
-async fn sign_message(message: String) -> Result<String, String> {
-    let sessionless = get_sessionless().await?;
-    let signature = sessionless.sign(&message);
-    Ok(signature.into_hex())
+    current_env()
 }
 
 /// Create a new BDO user for big dumb object storage
 #[tauri::command]
-async fn create_bdo_user() -> Result<BDOUser, String> {
-    let s = get_sessionless().await;
-    let mybase = "mybase";
-    
-    // Get BDO URL based on environment
-    let bdo_url = get_service_url("bdo");
-    let env = env::var("MYBASE_ENV").unwrap_or_else(|_| "dev".to_string());
-    
-    println!("🔗 Creating BDO user on: {} (env: {})", bdo_url, env);
-    println!("🔍 BDO URL being passed to BDO::new: {:?}", &bdo_url);
-    
-    match s {
-        Ok(sessionless) => {
-            let bdo = BDO::new(
-                Some(bdo_url),
-                Some(sessionless),
-            );
-            let _user = bdo.create_user(&mybase, &json!({})).await;
-            dbg!(&_user);
-            return match _user {
-                Ok(user) => Ok(user),
-                Err(_) => Err("no user".to_string()),
-            };
-        }
-        Err(_) => Err("no user".to_string()),
-    }
+async fn create_bdo_user() -> Result<BDOUser, NullaryError> {
+    nullary_core::create_bdo_user("mybase").await
 }
 
 /// Get available bases (servers) for connecting to
 #[tauri::command]
-async fn get_bases(uuid: &str, bdo_url: &str) -> Result<Value, String> {
-    let s = get_sessionless().await;
-    let mybase = "mybase";
-    match s {
-        Ok(sessionless) => {
-            let bdo = BDO::new(Some(bdo_url.to_string()), Some(sessionless));
-            let bases_result = bdo.get_bases(&uuid, &mybase).await;
-
-            match bases_result {
-                Ok(bases) => Ok(bases),
-                Err(e) => {
-                    dbg!(e);
-                    Err("no bases".to_string())
-                }
-            }
-        }
-        Err(_) => Err("no bdo".to_string()),
-    }
+async fn get_bases(uuid: &str, bdo_url: &str) -> Result<BTreeMap<String, BaseData>, NullaryError> {
+    // Discovery signs as this app's own BDO user, which is the one `uuid`
+    // was created as
+    debug!("🔍 Discovering bases at {} for {}", bdo_url, uuid);
+    let page = discover_bases_at(bdo_url, false).await?;
+    Ok(bases_by_id(page.data))
 }
 
 /// Get bases from BDO (alias for base-command.js compatibility)
 #[tauri::command]
-async fn get_bases_from_bdo(uuid: &str, bdo_url: &str) -> Result<Value, String> {
+async fn get_bases_from_bdo(
+    uuid: &str,
+    bdo_url: &str,
+) -> Result<BTreeMap<String, BaseData>, NullaryError> {
     get_bases(uuid, bdo_url).await
 }
 
 /// Get bases without parameters (for shared base-command.js)
 #[tauri::command]
-async fn get_bases_simple() -> Result<Value, String> {
-    let sessionless = get_sessionless().await.map_err(|e| format!("Failed to get sessionless: {}", e))?;
-    let bdo_url = get_service_url("bdo");
-    let uuid = sessionless.public_key().to_hex();
-    
-    get_bases(&uuid, &bdo_url).await
+async fn get_bases_simple() -> Result<BTreeMap<String, BaseData>, NullaryError> {
+    let page = discover_bases(None).await?;
+    Ok(bases_by_id(page.data))
 }
 
 /// Connect to a service (placeholder for base-command.js compatibility)
 #[tauri::command]
-async fn connect_to_service(service_url: &str, service_name: &str) -> Result<Value, String> {
-    let sessionless = get_sessionless().await.map_err(|e| format!("Failed to get sessionless: {}", e))?;
-    
+async fn connect_to_service(service_url: &str, service_name: &str) -> Result<Value, NullaryError> {
     match service_name {
         "bdo" => {
-            let bdo = BDO::new(Some(service_url.to_string()), Some(sessionless));
-            let mybase = "mybase";
-            match bdo.create_user(&mybase, &json!({})).await {
-                Ok(user) => Ok(json!({"uuid": user.uuid})),
-                Err(e) => Err(format!("Failed to connect to BDO: {}", e)),
-            }
+            let user = nullary_core::create_bdo_user_at(service_url, "mybase").await?;
+            Ok(json!({"uuid": user.uuid}))
         },
         "dolores" => {
-            let dolores = Dolores::new(Some(service_url.to_string()), Some(sessionless));
-            match dolores.create_user().await {
-                Ok(user) => Ok(json!({"uuid": user.uuid})),
-                Err(e) => Err(format!("Failed to connect to Dolores: {}", e)),
-            }
+            let user = nullary_core::create_dolores_user_at(service_url).await?;
+            Ok(json!({"uuid": user.uuid}))
         },
-        _ => Err(format!("Unknown service: {}", service_name))
+        _ => Err(NullaryError::validation(format!("Unknown service: {}", service_name)))
     }
 }
 
-/// Join a base (placeholder for base-command.js compatibility)
+/// Join a base by id or name: register with its services and remember it
+/// for every Nullary app
 #[tauri::command]
-async fn join_base(base_name: &str) -> Result<Value, String> {
-    println!("📥 Joining base: {}", base_name);
-    // For now, just return success - base joining is handled by the base discovery
-    Ok(json!({"success": true, "message": format!("Joined base: {}", base_name)}))
+async fn join_base(base_name: &str) -> Result<Value, NullaryError> {
+    let base = nullary_core::join_base(base_name).await?;
+    Ok(json!({"success": true, "message": format!("Joined base: {}", base.name), "base": base}))
 }
 
-/// Leave a base (placeholder for base-command.js compatibility)
+/// Leave a base by id or name, deleting our users there if asked
 #[tauri::command]
-async fn leave_base(base_name: &str) -> Result<Value, String> {
-    println!("📤 Leaving base: {}", base_name);
-    // For now, just return success - base leaving is handled by the base discovery
-    Ok(json!({"success": true, "message": format!("Left base: {}", base_name)}))
+async fn leave_base(base_name: &str, delete_users: Option<bool>) -> Result<Value, NullaryError> {
+    let base = nullary_core::leave_base(base_name, delete_users.unwrap_or(false)).await?;
+    Ok(json!({"success": true, "message": format!("Left base: {}", base.name), "base": base}))
 }
 
 /// Create a new Dolores user for social media feeds
 #[tauri::command]
-async fn create_dolores_user(dolores_url: &str) -> Result<DoloresUser, String> {
-    let s = get_sessionless().await;
-    match s {
-        Ok(sessionless) => {
-            let dolores = Dolores::new(Some(dolores_url.to_string()), Some(sessionless));
-            let _user = dolores.create_user().await;
-            dbg!(&_user);
-            return match _user {
-                Ok(user) => Ok(user),
-                Err(_) => Err("no user".to_string()),
-            };
-        }
-        Err(_) => Err("no user".to_string()),
-    }
+async fn create_dolores_user(dolores_url: &str) -> Result<DoloresUser, NullaryError> {
+    nullary_core::create_dolores_user_at(dolores_url).await
 }
 
+// Dolores tags the MyBase social feed is fetched with
+const SOCIAL_TAGS: &str = "social,mybase";
+
 /// Get social media feed from Dolores with specific tags
 #[tauri::command]
-async fn get_feed(uuid: &str, dolores_url: &str, tags: &str) -> Result<Feed, String> {
-    let s = get_sessionless().await;
-    match s {
-        Ok(sessionless) => {
-            let dolores = Dolores::new(Some(dolores_url.to_string()), Some(sessionless));
+async fn get_feed(uuid: &str, dolores_url: &str, tags: &str) -> Result<Feed, NullaryError> {
+    match dolores_client_at(dolores_url).await {
+        Ok(dolores) => {
             let feed_result = dolores.get_feed(&uuid, &tags).await;
 
             match feed_result {
-                Ok(feed) => Ok(feed),
+                Ok(mut feed) => {
+                    // Posts by blocked users are dropped in every list
+                    let moderation = moderation();
+                    for posts in [
+                        &mut feed.allPosts,
+                        &mut feed.videoPosts,
+                        &mut feed.picPosts,
+                        &mut feed.genericPosts,
+                    ] {
+                        posts.retain(|post| !moderation.blocks_post(post));
+                    }
+                    Ok(feed)
+                }
                 Err(e) => {
-                    dbg!(e);
-                    Err("no feed".to_string())
+                    error!("{:?}", e);
+                    Err(NullaryError::service("dolores", format!("Failed to get feed: {}", e)))
                 }
             }
         }
-        Err(_) => Err("no dolores".to_string()),
+        Err(e) => Err(e),
     }
 }
 
-/// Get social feed for MyBase interface
+/// Social posts from the Dolores at `dolores_url`, shaped for the feed
+async fn fetch_social_posts(dolores_url: &str, validators: Validators) -> Result<FeedFetch<Vec<Value>>, NullaryError> {
+    // Get feed with social/mybase tags, as this base's Dolores user
+    let feed = dolores_feed_at(dolores_url, SOCIAL_TAGS, &validators).await.map_err(|e| {
+        error!("❌ Failed to get feed: {:?}", e);
+        e
+    })?;
+    
+    // Transform feed data for frontend
+    Ok(feed.map(|posts| {
+        info!("✅ Feed retrieved with {} posts", posts.len());
+        posts.into_iter().map(|post| {
+            // Extract values from the JSON object
+            let uuid = post.get("uuid").and_then(|v| v.as_str()).unwrap_or("unknown");
+            let post_type = post.get("post_type").and_then(|v| v.as_str()).unwrap_or("text");
+            let author_name = post.get("author_name").and_then(|v| v.as_str()).unwrap_or("Anonymous");
+            let author_uuid = post.get("author_uuid").and_then(|v| v.as_str()).unwrap_or("");
+            let title = post.get("title").and_then(|v| v.as_str()).unwrap_or("");
+            let content = post.get("content").and_then(|v| v.as_str()).unwrap_or("");
+            let tags = post.get("tags").and_then(|v| v.as_str()).unwrap_or("");
+            let created_at = post.get("created_at").and_then(|v| v.as_str()).unwrap_or("");
+        
+            json!({
+                "uuid": uuid,
+                "post_type": post_type,
+                "author": {
+                    "name": author_name,
+                    "uuid": author_uuid,
+                    "image_url": null
+                },
+                "content": {
+                    "title": title,
+                    "content": content,
+                    "tags": tags
+                },
+                "timestamp": created_at,
+                "likes": 0,
+                "comments": 0,
+                "shares": 0
+            })
+        }).collect()
+    }))
+}
+
+/// Get social feed for MyBase interface, from one base (id or name) or from
+/// the home base and every joined base
 #[tauri::command]
 async fn get_social_feed(
     base_name: Option<String>,
     limit: Option<u32>,
     offset: Option<u32>,
-) -> Result<Value, String> {
-    let sessionless = get_sessionless().await.map_err(|e| format!("Failed to get sessionless: {}", e))?;
-    let dolores_url = get_service_url("dolores");
-    
-    println!("🔍 Getting social feed from: {}", dolores_url);
-    
-    // Create/get Dolores user first
-    let dolores = Dolores::new(Some(dolores_url), Some(sessionless));
-    
-    println!("🔐 Debug auth data (from dolores.sessionless):");
-    println!("  PubKey: {}", dolores.sessionless.public_key().to_hex());
-    
-    let dolores_user = match dolores.create_user().await {
-        Ok(user) => {
-            println!("✅ Dolores user ready: {}", user.uuid);
-            user
-        }
-        Err(e) => {
-            println!("❌ Failed to create Dolores user: {:?}", e);
-            return Err(format!("Failed to create Dolores user: {}", e));
+) -> Result<Value, NullaryError> {
+    let sources = match base_name {
+        Some(base_name) => {
+            let base = known_base(&base_name).await?;
+            let url = base.dns.url("dolores").ok_or_else(|| {
+                NullaryError::validation(format!("Base {} runs no Dolores", base.name))
+            })?;
+            feed_sources("dolores", Some(url))
         }
+        None => feed_sources("dolores", None),
     };
     
-    // Get feed with social/mybase tags
-    let tags = "social,mybase";
-    match dolores.get_feed(&dolores_user.uuid, tags).await {
-        Ok(feed) => {
-            println!("✅ Feed retrieved with {} posts", feed.allPosts.len());
-            
-            // Transform feed data for frontend
-            let posts: Vec<Value> = feed.allPosts.into_iter().map(|post| {
-                // Extract values from the JSON object
-                let uuid = post.get("uuid").and_then(|v| v.as_str()).unwrap_or("unknown");
-                let post_type = post.get("post_type").and_then(|v| v.as_str()).unwrap_or("text");
-                let author_name = post.get("author_name").and_then(|v| v.as_str()).unwrap_or("Anonymous");
-                let author_uuid = post.get("author_uuid").and_then(|v| v.as_str()).unwrap_or("");
-                let title = post.get("title").and_then(|v| v.as_str()).unwrap_or("");
-                let content = post.get("content").and_then(|v| v.as_str()).unwrap_or("");
-                let tags = post.get("tags").and_then(|v| v.as_str()).unwrap_or("");
-                let created_at = post.get("created_at").and_then(|v| v.as_str()).unwrap_or("");
-                
-                json!({
-                    "uuid": uuid,
-                    "post_type": post_type,
-                    "author": {
-                        "name": author_name,
-                        "uuid": author_uuid,
-                        "image_url": null
-                    },
-                    "content": {
-                        "title": title,
-                        "content": content,
-                        "tags": tags
-                    },
-                    "timestamp": created_at,
-                    "likes": 0,
-                    "comments": 0,
-                    "shares": 0
-                })
-            }).collect();
-            
-            Ok(json!({
-                "success": true,
-                "data": {
-                    "posts": posts,
-                    "total": posts.len(),
-                    "limit": limit.unwrap_or(20),
-                    "offset": offset.unwrap_or(0)
-                }
-            }))
-        }
-        Err(e) => {
-            println!("❌ Failed to get feed: {:?}", e);
-            // Return empty feed instead of error for better UX
-            Ok(json!({
-                "success": true,
-                "data": {
-                    "posts": [],
-                    "total": 0,
-                    "limit": limit.unwrap_or(20),
-                    "offset": offset.unwrap_or(0)
-                }
-            }))
+    debug!("🔍 Getting social feed from {} bases", sources.len());
+    
+    // Always asks every base at once; a cached page only stands in while its
+    // base is unreachable
+    let feed = aggregate_feed(sources, "dolores", SOCIAL_TAGS, true, |url, validators| async move {
+        fetch_social_posts(&url, validators).await
+    })
+    .await?;
+    let posts = without_blocked_posts(feed.data);
+    
+    Ok(json!({
+        "success": true,
+        "data": {
+            "posts": posts,
+            "total": posts.len(),
+            "limit": limit.unwrap_or(20),
+            "offset": offset.unwrap_or(0),
+            "stale": feed.stale,
+            "sync": feed.sync
         }
-    }
+    }))
 }
 
 /// Create a new Sanora user for blog/content hosting
 #[tauri::command]
-async fn create_sanora_user(sanora_url: &str) -> Result<SanoraUser, String> {
-    let s = get_sessionless().await;
-    match s {
-        Ok(sessionless) => {
-            let sanora = Sanora::new(Some(sanora_url.to_string()), Some(sessionless));
-            let _user = sanora.create_user().await;
-            dbg!(&_user);
-            return match _user {
-                Ok(user) => Ok(user),
-                Err(_) => Err("no user".to_string()),
-            };
-        }
-        Err(_) => Err("no user".to_string()),
-    }
+async fn create_sanora_user(sanora_url: &str) -> Result<SanoraUser, NullaryError> {
+    nullary_core::create_sanora_user_at(sanora_url).await
 }
 
 /// Get Sanora user information (which includes their content)
 #[tauri::command]
-async fn get_sanora_user(uuid: &str, sanora_url: &str) -> Result<SanoraUser, String> {
-    let s = get_sessionless().await;
-    match s {
-        Ok(sessionless) => {
-            let sanora = Sanora::new(Some(sanora_url.to_string()), Some(sessionless));
+async fn get_sanora_user(uuid: &str, sanora_url: &str) -> Result<SanoraUser, NullaryError> {
+    match sanora_client_at(sanora_url).await {
+        Ok(sanora) => {
             let user_result = sanora.get_user_by_uuid(&uuid).await;
 
             match user_result {
                 Ok(user) => Ok(user),
                 Err(e) => {
-                    dbg!(e);
-                    Err("failed to get user".to_string())
+                    error!("{:?}", e);
+                    Err(NullaryError::service("sanora", format!("Failed to get user: {}", e)))
                 }
             }
         }
-        Err(_) => Err("no sanora".to_string()),
+        Err(e) => Err(e),
     }
 }
 
-/// Create or update a user profile
-#[tauri::command]
-async fn create_profile(
+/// A profile as the outbox stores and replays it
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct NewProfile {
     name: String,
     bio: Option<String>,
     interests: Option<String>,
     homepage: Option<String>,
     image_url: Option<String>,
-) -> Result<Value, String> {
-    let sessionless = get_sessionless().await.map_err(|e| format!("Failed to get sessionless: {}", e))?;
+}
+
+/// Send a profile to Prof
+async fn send_profile(profile: NewProfile) -> Result<Value, NullaryError> {
+    let NewProfile {
+        name,
+        bio,
+        interests,
+        homepage,
+        image_url,
+    } = profile;
+
     let prof_url = get_service_url("prof");
     
-    println!("📝 Creating profile on: {}", prof_url);
+    info!("📝 Creating profile on: {}", prof_url);
     
     // Use a placeholder email since it's required by Prof but we don't want to collect it
-    let placeholder_email = format!("{}@mybase.local", sessionless.public_key().to_hex());
+    let placeholder_email = format!("{}@mybase.local", public_key_at(&prof_url, "prof")?);
     
-    let prof_client = ProfClient::new(prof_url).with_sessionless(sessionless);
+    let prof_client = prof_client_at(&prof_url).await?;
     
     let mut profile_builder = ProfileBuilder::new()
         .name(&name)
@@ -408,11 +313,11 @@ async fn create_profile(
     
     let profile_data = profile_builder.build();
     
-    println!("🔍 Profile data being sent: {:?}", profile_data);
+    debug!("🔍 Profile data being sent: {:?}", profile_data);
     
     match prof_client.create_profile(profile_data, None).await {
         Ok(profile) => {
-            println!("✅ Profile created successfully: {}", profile.uuid);
+            info!("✅ Profile created successfully: {}", profile.uuid);
             Ok(json!({
                 "success": true,
                 "profile": {
@@ -427,25 +332,45 @@ async fn create_profile(
             }))
         }
         Err(e) => {
-            println!("❌ Failed to create profile: {:?}", e);
-            Err(format!("Failed to create profile: {}", e))
+            error!("❌ Failed to create profile: {:?}", e);
+            Err(NullaryError::service("prof", format!("Failed to create profile: {}", e)))
         }
     }
 }
 
+/// Create or update a user profile, queued in the outbox while Prof is
+/// unreachable
+#[tauri::command]
+async fn create_profile(
+    name: String,
+    bio: Option<String>,
+    interests: Option<String>,
+    homepage: Option<String>,
+    image_url: Option<String>,
+) -> Result<Value, NullaryError> {
+    let profile = NewProfile {
+        name,
+        bio,
+        interests,
+        homepage,
+        image_url,
+    };
+    let result = send_profile(profile.clone()).await;
+    queue_if_transient(result, "create_profile", "prof", &get_service_url("prof"), &profile).await
+}
+
 /// Get user profile
 #[tauri::command]
-async fn get_profile(uuid: Option<String>) -> Result<Value, String> {
-    let sessionless = get_sessionless().await.map_err(|e| format!("Failed to get sessionless: {}", e))?;
+async fn get_profile(uuid: Option<String>) -> Result<Value, NullaryError> {
     let prof_url = get_service_url("prof");
     
-    println!("📖 Getting profile from: {}", prof_url);
+    info!("📖 Getting profile from: {}", prof_url);
     
-    let prof_client = ProfClient::new(prof_url).with_sessionless(sessionless);
+    let prof_client = prof_client_at(&prof_url).await?;
     
     match prof_client.get_profile(uuid.as_deref()).await {
         Ok(profile) => {
-            println!("✅ Profile retrieved successfully: {}", profile.uuid);
+            info!("✅ Profile retrieved successfully: {}", profile.uuid);
             Ok(json!({
                 "success": true,
                 "profile": {
@@ -461,7 +386,7 @@ async fn get_profile(uuid: Option<String>) -> Result<Value, String> {
             }))
         }
         Err(prof_rs::ProfError::NotFound(_)) => {
-            println!("📭 No profile found");
+            info!("📭 No profile found");
             Ok(json!({
                 "success": false,
                 "error": "profile_not_found",
@@ -469,8 +394,8 @@ async fn get_profile(uuid: Option<String>) -> Result<Value, String> {
             }))
         }
         Err(e) => {
-            println!("❌ Failed to get profile: {:?}", e);
-            Err(format!("Failed to get profile: {}", e))
+            error!("❌ Failed to get profile: {:?}", e);
+            Err(NullaryError::service("prof", format!("Failed to get profile: {}", e)))
         }
     }
 }
@@ -483,16 +408,15 @@ async fn update_profile(
     interests: Option<String>,
     homepage: Option<String>,
     image_url: Option<String>,
-) -> Result<Value, String> {
-    let sessionless = get_sessionless().await.map_err(|e| format!("Failed to get sessionless: {}", e))?;
+) -> Result<Value, NullaryError> {
     let prof_url = get_service_url("prof");
     
-    println!("📝 Updating profile on: {}", prof_url);
+    info!("📝 Updating profile on: {}", prof_url);
     
     // Use a placeholder email since it's required by Prof but we don't want to collect it
-    let placeholder_email = format!("{}@mybase.local", sessionless.public_key().to_hex());
+    let placeholder_email = format!("{}@mybase.local", public_key_at(&prof_url, "prof")?);
     
-    let prof_client = ProfClient::new(prof_url).with_sessionless(sessionless);
+    let prof_client = prof_client_at(&prof_url).await?;
     
     let mut profile_builder = ProfileBuilder::new()
         .name(&name)
@@ -518,7 +442,7 @@ async fn update_profile(
     
     match prof_client.update_profile(profile_data, None).await {
         Ok(profile) => {
-            println!("✅ Profile updated successfully: {}", profile.uuid);
+            info!("✅ Profile updated successfully: {}", profile.uuid);
             Ok(json!({
                 "success": true,
                 "profile": {
@@ -534,55 +458,63 @@ async fn update_profile(
             }))
         }
         Err(e) => {
-            println!("❌ Failed to update profile: {:?}", e);
-            Err(format!("Failed to update profile: {}", e))
+            error!("❌ Failed to update profile: {:?}", e);
+            Err(NullaryError::service("prof", format!("Failed to update profile: {}", e)))
         }
     }
 }
 
 /// Teleport content from a URL via BDO
 #[tauri::command]
-async fn teleport_content(bdo_url: &str, teleport_url: &str) -> Result<Value, String> {
-    println!("🌐 Teleporting content from: {} via BDO: {}", teleport_url, bdo_url);
+async fn teleport_content(bdo_url: &str, teleport_url: &str) -> Result<Value, NullaryError> {
+    info!("🌐 Teleporting content from: {} via BDO: {}", teleport_url, bdo_url);
     
-    let s = get_sessionless().await;
-    match s {
-        Ok(sessionless) => {
-            let bdo = BDO::new(Some(bdo_url.to_string()), Some(sessionless));
+    match bdo_client_at(bdo_url).await {
+        Ok(bdo) => {
             
             // Create/get BDO user first
             let mybase = "mybase";
-            let bdo_user = match bdo.create_user(&mybase, &json!({})).await {
-                Ok(user) => {
-                    println!("✅ BDO user ready for teleportation: {}", user.uuid);
-                    user
+            let bdo_uuid = match bdo_user_uuid_at(bdo_url, mybase).await {
+                Ok(uuid) => {
+                    info!("✅ BDO user ready for teleportation: {}", uuid);
+                    uuid
                 }
                 Err(e) => {
-                    println!("❌ Failed to create BDO user: {:?}", e);
-                    return Err(format!("Failed to create BDO user: {}", e));
+                    error!("❌ Failed to create BDO user: {:?}", e);
+                    return Err(e);
                 }
             };
             
             // Now teleport the content
-            println!("🚀 Starting teleportation with uuid: {}", bdo_user.uuid);
-            match bdo.teleport(&bdo_user.uuid, &mybase, teleport_url).await {
+            info!("🚀 Starting teleportation with uuid: {}", bdo_uuid);
+            match bdo.teleport(&bdo_uuid, &mybase, teleport_url).await {
                 Ok(teleported_content) => {
-                    println!("✅ Successfully teleported content: {:?}", teleported_content);
+                    info!("✅ Successfully teleported content: {:?}", teleported_content);
                     Ok(teleported_content)
                 }
                 Err(e) => {
-                    println!("❌ Teleportation failed: {:?}", e);
-                    Err(format!("Teleportation failed: {}", e))
+                    error!("❌ Teleportation failed: {:?}", e);
+                    let error = NullaryError::service("bdo", format!("Teleportation failed: {}", e));
+                    forget_if_not_found(bdo_url, "bdo", Some(mybase), &error);
+                    Err(error)
                 }
             }
         }
         Err(e) => {
-            println!("❌ Failed to get sessionless instance: {}", e);
-            Err(format!("Failed to get sessionless: {}", e))
+            error!("❌ Failed to get sessionless instance: {}", e);
+            Err(e)
         }
     }
 }
 
+/// Replay profiles queued while Prof was unreachable
+fn register_outbox_handlers() {
+    nullary_core::register_outbox_handler("create_profile", |payload| async move {
+        let profile: NewProfile = nullary_core::outbox_payload(payload)?;
+        send_profile(profile).await
+    });
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
@@ -608,10 +540,68 @@ pub fn run() {
             create_profile,
             get_profile,
             update_profile,
-            teleport_content
+            teleport_content,
+            // User Persistence Functions
+            nullary_core::generate_sessionless_keys,
+            nullary_core::stronghold_init,
+            nullary_core::stronghold_get_record,
+            nullary_core::stronghold_set_record,
+            nullary_core::stronghold_clear_vault,
+            nullary_core::read_user_data_file,
+            nullary_core::write_user_data_file,
+            nullary_core::clear_user_data,
+            // Documents
+            nullary_core::read_document,
+            nullary_core::write_document,
+            nullary_core::delete_document,
+            nullary_core::list_documents,
+            // Personas
+            nullary_core::list_personas,
+            nullary_core::create_persona,
+            nullary_core::rename_persona,
+            nullary_core::delete_persona,
+            nullary_core::switch_persona,
+            nullary_core::get_persona_service_uuids,
+            nullary_core::set_persona_service_uuid,
+            // Identity backup
+            nullary_core::export_identity_backup,
+            nullary_core::import_identity_backup,
+            nullary_core::export_recovery_words,
+            nullary_core::import_recovery_words,
+            // Key rotation
+            nullary_core::rotate_identity,
+            nullary_core::retry_identity_rotation,
+            nullary_core::get_key_rotations,
+            // Signing
+            nullary_core::get_signing_journal,
+            // Logs
+            nullary_core::export_logs,
+            // Health
+            nullary_core::check_service_health,
+            // Outbox
+            nullary_core::list_outbox,
+            nullary_core::retry_outbox_item,
+            nullary_core::cancel_outbox_item,
+            // Moderation
+            nullary_core::get_moderation,
+            nullary_core::set_base_muted,
+            nullary_core::set_base_ignored,
+            nullary_core::set_user_blocked,
+            // Nearby bases
+            nullary_core::discover_bases_near,
+            nullary_core::bases_near_me,
+            // Endpoint configuration
+            nullary_core::get_environment_config,
+            nullary_core::switch_environment
         ])
-        .setup(|_app| {
-            println!("🌐 MyBase backend is starting up...");
+        .setup(|app| {
+            info!("🌐 MyBase backend is starting up...");
+
+            // Register the app and load (or create on first launch) its identity
+            if let Err(e) = nullary_core::init(app.handle(), "mybase") {
+                error!("❌ Failed to initialize nullary core: {}", e);
+            }
+            register_outbox_handlers();
             Ok(())
         })
         .run(tauri::generate_context!())
@@ -1,16 +1,19 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
 use tauri::Manager;
 use chrono::Utc;
+use tracing::{debug, error, info, warn};
 
-// Import Planet Nine service clients
-use bdo_rs::{BdoClient, BdoUser};
-use dolores_rs::{DoloresClient, DoloresUser};
-use sanora_rs::{SanoraClient, SanoraUser, Product};
-use sessionless::{Sessionless, PrivateKey};
-// User Persistence Module
-mod user_persistence;
-use user_persistence::*;
+// Import Planet Nine service types
+use bdo_rs::BdoUser;
+use dolores_rs::DoloresUser;
+use sanora_rs::{SanoraUser, Product};
+
+// Shared identity, endpoints and service clients
+use nullary_core::{
+    aggregate_feed, cached_feed, current_env, discover_bases, dolores_feed_at, feed_sources,
+    get_service_url, moderation, public_key, sanora_base_products_at, AggregatedFeed, BaseData,
+    FeedFetch, FeedItem, FeedKey, FeedPage, NullaryError, Validators,
+};
 
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -27,358 +30,231 @@ pub struct FeedPost {
     pub post_type: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct BaseInfo {
-    pub name: String,
-    pub description: Option<String>,
-    pub connected: bool,
-    pub services: HashMap<String, String>,
+// Dolores tags the photo feed is fetched with
+const DOLORES_TAGS: [&str; 3] = ["photos", "social", "photary"];
+
+impl FeedItem for FeedPost {
+    fn feed_id(&self) -> Option<String> {
+        Some(self.uuid.clone())
+    }
+
+    fn feed_time(&self) -> Option<i64> {
+        self.timestamp
+    }
 }
 
-// Global state for service clients
-static mut SESSIONLESS: Option<Sessionless> = None;
-static mut BDO_CLIENT: Option<BdoClient> = None;
-static mut DOLORES_CLIENT: Option<DoloresClient> = None;
-static mut SANORA_CLIENT: Option<SanoraClient> = None;
+// Posts by blocked users are dropped as each page is read, so a block also
+// hides posts already cached
+fn without_blocked(mut posts: Vec<FeedPost>) -> Vec<FeedPost> {
+    let moderation = moderation();
+    posts.retain(|post| {
+        !post.author.as_deref().is_some_and(|author| moderation.blocks(author))
+    });
+    posts
+}
+
+fn without_blocked_page(mut page: FeedPage<Vec<FeedPost>>) -> FeedPage<Vec<FeedPost>> {
+    page.data = without_blocked(page.data);
+    page
+}
 
+// Counts come from the cached feed pages, so a count only downloads a feed
+// when nothing is cached yet
 #[tauri::command]
-async fn get_feed_count(feed_type: String) -> Result<usize, String> {
+async fn get_feed_count(feed_type: String) -> Result<usize, NullaryError> {
     match feed_type.as_str() {
-        "dolores" => {
-            // Get real Dolores feed and return count
-            match get_dolores_feed().await {
-                Ok(feed) => Ok(feed.len()),
-                Err(_) => Ok(0)
-            }
-        }
-        "products" => {
-            // Get real products feed and return count
-            match get_products_feed().await {
-                Ok(feed) => Ok(feed.len()),
-                Err(_) => Ok(0)
-            }
-        }
-        "blogs" => {
-            // Get real blogs feed and return count
-            match get_blogs_feed().await {
-                Ok(feed) => Ok(feed.len()),
-                Err(_) => Ok(0)
-            }
-        }
-        _ => Ok(0)
+        "dolores" => get_dolores_feed().await.map(|page| page.data.len()),
+        "products" => get_products_feed().await.map(|page| page.data.len()),
+        "blogs" => get_blogs_feed().await.map(|page| page.data.len()),
+        _ => Err(NullaryError::validation(format!("Unknown feed type: {}", feed_type)))
     }
 }
 
 #[tauri::command]
-async fn get_products_count() -> Result<usize, String> {
-    // Get real products feed and return count
-    match get_products_feed().await {
-        Ok(feed) => Ok(feed.len()),
-        Err(_) => Ok(0)
-    }
+async fn get_products_count() -> Result<usize, NullaryError> {
+    get_products_feed().await.map(|page| page.data.len())
 }
 
 #[tauri::command]
-async fn get_blogs_count() -> Result<usize, String> {
-    // Get real blogs feed and return count
-    match get_blogs_feed().await {
-        Ok(feed) => Ok(feed.len()),
-        Err(_) => Ok(0)
-    }
+async fn get_blogs_count() -> Result<usize, NullaryError> {
+    get_blogs_feed().await.map(|page| page.data.len())
 }
 
 #[tauri::command]
-async fn get_dolores_feed() -> Result<Vec<FeedPost>, String> {
-    println!("🔍 Getting Dolores feed from test environment...");
-    
-    // Try to get real Dolores feed from test environment (localhost:5118)
-    let sessionless = unsafe { SESSIONLESS.as_ref() };
-    if sessionless.is_none() {
-        return Err("Sessionless not initialized".to_string());
-    }
-    
-    let sessionless = sessionless.unwrap();
+async fn get_dolores_feed() -> Result<AggregatedFeed<FeedPost>, NullaryError> {
+    debug!("🔍 Getting Dolores feed from {} environment...", current_env());
+
+    // The home base and every joined base at once, each served from the feed
+    // cache; a stale page is refreshed in the background
+    let sources = feed_sources("dolores", None);
+    let tags = DOLORES_TAGS.join(",");
+    let mut feed = aggregate_feed(sources, "dolores", &tags, false, |url, validators| async move {
+        fetch_dolores_feed(&url, validators).await
+    })
+    .await?;
+    feed.data = without_blocked(feed.data);
+    Ok(feed)
+}
+
+/// A Dolores post as the feed reads it
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct DoloresPost {
+    uuid: Option<String>,
+    title: Option<String>,
+    description: Option<String>,
+    author: Option<String>,
+    timestamp: Option<f64>,
+    images: Option<Vec<String>>,
+    tags: Option<Vec<String>>,
+}
+
+async fn fetch_dolores_feed(dolores_url: &str, validators: Validators) -> Result<FeedFetch<Vec<FeedPost>>, NullaryError> {
+    info!("📡 Connecting to Dolores at {}", dolores_url);
     
-    // Connect to test Dolores service
-    let dolores_url = "http://127.0.0.1:5118/";
-    println!("📡 Connecting to Dolores at {}", dolores_url);
+    // Get feed with photary tags
+    let tags = DOLORES_TAGS.join(",");
+    let feed = dolores_feed_at(dolores_url, &tags, &validators).await.map_err(|e| {
+        warn!("⚠️ Failed to get Dolores feed: {}", e);
+        e
+    })?;
     
-    match DoloresClient::new(dolores_url.to_string(), sessionless.clone()) {
-        Ok(client) => {
-            // Create user if needed
-            match client.create_user().await {
-                Ok(user) => {
-                    println!("✅ Connected to Dolores as user: {}", user.uuid);
-                    
-                    // Get feed with photary tags
-                    let tags = vec!["photos".to_string(), "social".to_string(), "photary".to_string()];
-                    match client.get_feed(&user.uuid, &tags).await {
-                        Ok(feed_items) => {
-                            println!("📋 Retrieved {} items from Dolores feed", feed_items.len());
-                            
-                            // Convert Dolores feed items to our FeedPost format
-                            let posts = feed_items.into_iter().map(|item| {
-                                FeedPost {
-                                    uuid: item.uuid.unwrap_or_else(|| format!("dolores-{}", chrono::Utc::now().timestamp())),
-                                    title: item.title,
-                                    description: item.description,
-                                    content: None,
-                                    author: item.author.unwrap_or_else(|| "Anonymous".to_string()),
-                                    timestamp: item.timestamp.map(|t| t as i64),
-                                    images: item.images,
-                                    tags: item.tags,
-                                    price: None,
-                                    post_type: "photo".to_string(),
-                                }
-                            }).collect();
-                            
-                            Ok(posts)
-                        }
-                        Err(e) => {
-                            println!("⚠️ Failed to get Dolores feed: {}", e);
-                            Ok(vec![]) // Return empty feed instead of error
-                        }
-                    }
-                }
-                Err(e) => {
-                    println!("⚠️ Failed to create Dolores user: {}", e);
-                    Ok(vec![]) // Return empty feed instead of error
+    Ok(feed.map(|feed_items| {
+        info!("📋 Retrieved {} items from Dolores feed", feed_items.len());
+        
+        // Convert Dolores feed items to our FeedPost format
+        feed_items.into_iter()
+            .filter_map(|item| serde_json::from_value::<DoloresPost>(item).ok())
+            .map(|item| {
+                FeedPost {
+                    uuid: item.uuid.unwrap_or_else(|| format!("dolores-{}", chrono::Utc::now().timestamp())),
+                    title: item.title,
+                    description: item.description,
+                    content: None,
+                    author: item.author.or_else(|| Some("Anonymous".to_string())),
+                    timestamp: item.timestamp.map(|t| t as i64),
+                    images: item.images,
+                    tags: item.tags,
+                    price: None,
+                    post_type: "photo".to_string(),
                 }
-            }
-        }
-        Err(e) => {
-            println!("❌ Failed to connect to Dolores: {}", e);
-            Ok(vec![]) // Return empty feed instead of error
-        }
-    }
+            }).collect()
+    }))
 }
 
 #[tauri::command]
-async fn get_products_feed() -> Result<Vec<FeedPost>, String> {
-    println!("🔍 Getting products feed from test environment...");
-    
-    // Try to get real products from test Sanora service (localhost:5121)
-    let sessionless = unsafe { SESSIONLESS.as_ref() };
-    if sessionless.is_none() {
-        return Err("Sessionless not initialized".to_string());
-    }
-    
-    let sessionless = sessionless.unwrap();
+async fn get_products_feed() -> Result<FeedPage<Vec<FeedPost>>, NullaryError> {
+    debug!("🔍 Getting products feed from {} environment...", current_env());
+
+    // Served from the feed cache; a stale page is refreshed in the background
+    let sanora_url = get_service_url("sanora");
+    let key = FeedKey::new(&sanora_url, "sanora", "products")?;
+    cached_feed(key, move |validators| async move {
+        fetch_products_feed(&sanora_url, validators).await
+    })
+    .await
+    .map(without_blocked_page)
+}
+
+async fn fetch_products_feed(sanora_url: &str, validators: Validators) -> Result<FeedFetch<Vec<FeedPost>>, NullaryError> {
+    info!("📡 Connecting to Sanora at {}", sanora_url);
     
-    // Connect to test Sanora service
-    let sanora_url = "http://127.0.0.1:5121/";
-    println!("📡 Connecting to Sanora at {}", sanora_url);
+    // Get all products from this base
+    let products = sanora_base_products_at::<Product>(sanora_url, &validators).await.map_err(|e| {
+        warn!("⚠️ Failed to get Sanora products: {}", e);
+        e
+    })?;
     
-    match SanoraClient::new(sanora_url.to_string(), sessionless.clone()) {
-        Ok(client) => {
-            // Create user if needed
-            match client.create_user().await {
-                Ok(user) => {
-                    println!("✅ Connected to Sanora as user: {}", user.uuid);
-                    
-                    // Get all products from this base
-                    match client.get_all_base_products().await {
-                        Ok(products) => {
-                            println!("📋 Retrieved {} products from Sanora", products.len());
-                            
-                            // Filter for non-blog products and convert to FeedPost format
-                            let posts = products.into_iter()
-                                .filter(|product| {
-                                    // Only include products that aren't blogs
-                                    !product.tags.iter().any(|tag| tag.to_lowercase().contains("blog"))
-                                })
-                                .map(|product| {
-                                    FeedPost {
-                                        uuid: product.uuid.unwrap_or_else(|| format!("product-{}", chrono::Utc::now().timestamp())),
-                                        title: Some(product.title),
-                                        description: Some(product.description),
-                                        content: None,
-                                        author: Some("Marketplace".to_string()),
-                                        timestamp: product.created_at.map(|t| t as i64),
-                                        images: None, // Products might not have preview images in basic version
-                                        tags: Some(product.tags),
-                                        price: Some(product.price as f64 / 100.0), // Convert cents to dollars
-                                        post_type: "product".to_string(),
-                                    }
-                                }).collect();
-                            
-                            Ok(posts)
-                        }
-                        Err(e) => {
-                            println!("⚠️ Failed to get Sanora products: {}", e);
-                            Ok(vec![]) // Return empty feed instead of error
-                        }
-                    }
-                }
-                Err(e) => {
-                    println!("⚠️ Failed to create Sanora user: {}", e);
-                    Ok(vec![]) // Return empty feed instead of error
+    Ok(products.map(|products| {
+        info!("📋 Retrieved {} products from Sanora", products.len());
+        
+        // Filter for non-blog products and convert to FeedPost format
+        products.into_iter()
+            .filter(|product| {
+                // Only include products that aren't blogs
+                !product.tags.iter().any(|tag| tag.to_lowercase().contains("blog"))
+            })
+            .map(|product| {
+                FeedPost {
+                    uuid: product.uuid.unwrap_or_else(|| format!("product-{}", chrono::Utc::now().timestamp())),
+                    title: Some(product.title),
+                    description: Some(product.description),
+                    content: None,
+                    author: Some("Marketplace".to_string()),
+                    timestamp: product.created_at.map(|t| t as i64),
+                    images: None, // Products might not have preview images in basic version
+                    tags: Some(product.tags),
+                    price: Some(product.price as f64 / 100.0), // Convert cents to dollars
+                    post_type: "product".to_string(),
                 }
-            }
-        }
-        Err(e) => {
-            println!("❌ Failed to connect to Sanora: {}", e);
-            Ok(vec![]) // Return empty feed instead of error
-        }
-    }
+            }).collect()
+    }))
 }
 
 #[tauri::command]
-async fn get_blogs_feed() -> Result<Vec<FeedPost>, String> {
-    println!("🔍 Getting blogs feed from test environment...");
-    
-    // Try to get real blog posts from test Sanora service (localhost:5121)
-    let sessionless = unsafe { SESSIONLESS.as_ref() };
-    if sessionless.is_none() {
-        return Err("Sessionless not initialized".to_string());
-    }
-    
-    let sessionless = sessionless.unwrap();
+async fn get_blogs_feed() -> Result<FeedPage<Vec<FeedPost>>, NullaryError> {
+    debug!("🔍 Getting blogs feed from {} environment...", current_env());
+
+    // Served from the feed cache; a stale page is refreshed in the background
+    let sanora_url = get_service_url("sanora");
+    let key = FeedKey::new(&sanora_url, "sanora", "blogs")?;
+    cached_feed(key, move |validators| async move {
+        fetch_blogs_feed(&sanora_url, validators).await
+    })
+    .await
+    .map(without_blocked_page)
+}
+
+async fn fetch_blogs_feed(sanora_url: &str, validators: Validators) -> Result<FeedFetch<Vec<FeedPost>>, NullaryError> {
+    info!("📡 Connecting to Sanora at {}", sanora_url);
     
-    // Connect to test Sanora service
-    let sanora_url = "http://127.0.0.1:5121/";
-    println!("📡 Connecting to Sanora at {}", sanora_url);
+    // Get all products from this base
+    let products = sanora_base_products_at::<Product>(sanora_url, &validators).await.map_err(|e| {
+        warn!("⚠️ Failed to get Sanora products for blogs: {}", e);
+        e
+    })?;
     
-    match SanoraClient::new(sanora_url.to_string(), sessionless.clone()) {
-        Ok(client) => {
-            // Create user if needed
-            match client.create_user().await {
-                Ok(user) => {
-                    println!("✅ Connected to Sanora as user: {}", user.uuid);
-                    
-                    // Get all products from this base
-                    match client.get_all_base_products().await {
-                        Ok(products) => {
-                            println!("📋 Retrieved {} products from Sanora", products.len());
-                            
-                            // Filter for blog products and convert to FeedPost format
-                            let posts = products.into_iter()
-                                .filter(|product| {
-                                    // Only include products that are blogs
-                                    product.tags.iter().any(|tag| tag.to_lowercase().contains("blog"))
-                                })
-                                .map(|product| {
-                                    FeedPost {
-                                        uuid: product.uuid.unwrap_or_else(|| format!("blog-{}", chrono::Utc::now().timestamp())),
-                                        title: Some(product.title),
-                                        description: Some(product.description),
-                                        content: None, // Blog content would be in product details
-                                        author: Some("Blogger".to_string()),
-                                        timestamp: product.created_at.map(|t| t as i64),
-                                        images: None,
-                                        tags: Some(product.tags),
-                                        price: product.price.then(|| product.price as f64 / 100.0), // Convert cents to dollars if paid blog
-                                        post_type: "blog".to_string(),
-                                    }
-                                }).collect();
-                            
-                            Ok(posts)
-                        }
-                        Err(e) => {
-                            println!("⚠️ Failed to get Sanora products for blogs: {}", e);
-                            Ok(vec![]) // Return empty feed instead of error
-                        }
-                    }
+    Ok(products.map(|products| {
+        info!("📋 Retrieved {} products from Sanora", products.len());
+        
+        // Filter for blog products and convert to FeedPost format
+        products.into_iter()
+            .filter(|product| {
+                // Only include products that are blogs
+                product.tags.iter().any(|tag| tag.to_lowercase().contains("blog"))
+            })
+            .map(|product| {
+                FeedPost {
+                    uuid: product.uuid.unwrap_or_else(|| format!("blog-{}", chrono::Utc::now().timestamp())),
+                    title: Some(product.title),
+                    description: Some(product.description),
+                    content: None, // Blog content would be in product details
+                    author: Some("Blogger".to_string()),
+                    timestamp: product.created_at.map(|t| t as i64),
+                    images: None,
+                    tags: Some(product.tags),
+                    price: product.price.then(|| product.price as f64 / 100.0), // Convert cents to dollars if paid blog
+                    post_type: "blog".to_string(),
                 }
-                Err(e) => {
-                    println!("⚠️ Failed to create Sanora user: {}", e);
-                    Ok(vec![]) // Return empty feed instead of error
-                }
-            }
-        }
-        Err(e) => {
-            println!("❌ Failed to connect to Sanora: {}", e);
-            Ok(vec![]) // Return empty feed instead of error
-        }
-    }
+            }).collect()
+    }))
 }
 
 #[tauri::command]
-async fn get_bases() -> Result<Vec<BaseInfo>, String> {
-    println!("🔍 Getting bases from test environment...");
-    
-    // Try to get real base discovery from test BDO service (localhost:5114)
-    let sessionless = unsafe { SESSIONLESS.as_ref() };
-    if sessionless.is_none() {
-        return Err("Sessionless not initialized".to_string());
-    }
-    
-    let sessionless = sessionless.unwrap();
-    
-    // Connect to test BDO service
-    let bdo_url = "http://127.0.0.1:5114/";
-    println!("📡 Connecting to BDO at {}", bdo_url);
-    
-    match BdoClient::new(bdo_url.to_string(), sessionless.clone()) {
-        Ok(client) => {
-            // Create user if needed
-            match client.create_user().await {
-                Ok(user) => {
-                    println!("✅ Connected to BDO as user: {}", user.uuid);
-                    
-                    // Try to get base discovery data
-                    // For now, return test environment base configuration
-                    let bases = vec![
-                        BaseInfo {
-                            name: "Test Base 1".to_string(),
-                            description: Some("Test environment base server on port 5111".to_string()),
-                            connected: true,
-                            services: {
-                                let mut services = HashMap::new();
-                                services.insert("bdo".to_string(), "http://127.0.0.1:5114/".to_string());
-                                services.insert("dolores".to_string(), "http://127.0.0.1:5118/".to_string());
-                                services.insert("sanora".to_string(), "http://127.0.0.1:5121/".to_string());
-                                services
-                            },
-                        },
-                        BaseInfo {
-                            name: "Test Base 2".to_string(),
-                            description: Some("Test environment base server on port 5112".to_string()),
-                            connected: false,
-                            services: {
-                                let mut services = HashMap::new();
-                                services.insert("bdo".to_string(), "http://127.0.0.1:5115/".to_string());
-                                services.insert("dolores".to_string(), "http://127.0.0.1:5119/".to_string());
-                                services.insert("sanora".to_string(), "http://127.0.0.1:5122/".to_string());
-                                services
-                            },
-                        },
-                    ];
-                    
-                    Ok(bases)
-                }
-                Err(e) => {
-                    println!("⚠️ Failed to create BDO user: {}", e);
-                    Ok(vec![]) // Return empty instead of error
-                }
-            }
-        }
-        Err(e) => {
-            println!("❌ Failed to connect to BDO: {}", e);
-            Ok(vec![]) // Return empty instead of error
-        }
-    }
+async fn get_bases() -> Result<Vec<BaseData>, NullaryError> {
+    debug!("🔍 Getting bases from {} environment...", current_env());
+    discover_bases(None).await.map(|page| page.data)
 }
 
 #[tauri::command]
-async fn initialize_clients() -> Result<String, String> {
-    println!("🔧 Initializing Planet Nine service clients...");
-    
-    // Initialize sessionless with default key
-    let private_key = std::env::var("PRIVATE_KEY").unwrap_or_else(|_| {
-        "b75011b167c5e3a6b0de97d8e1950cd9548f83bb67f47112bed6a082db795496".to_string()
-    });
+async fn initialize_clients() -> Result<String, NullaryError> {
+    info!("🔧 Initializing Planet Nine service clients...");
     
-    let sessionless = match Sessionless::from_private_key(PrivateKey::from_hex(private_key).map_err(|e| e.to_string())?) {
-        Ok(s) => s,
-        Err(e) => return Err(format!("Failed to create sessionless: {}", e)),
-    };
+    // Clients are built per call with keys derived from this install's master
+    // seed; make sure it is available (PRIVATE_KEY overrides it)
+    public_key("bdo")?;
     
-    unsafe {
-        SESSIONLESS = Some(sessionless);
-    }
-    
-    println!("✅ Service clients initialized");
+    info!("✅ Service clients initialized");
     Ok("Clients initialized successfully".to_string())
 }
 
@@ -397,22 +273,70 @@ get_feed_count,
             initialize_clients,
 
             // User Persistence Functions
-            generate_sessionless_keys,
-            stronghold_init,
-            stronghold_get_record,
-            stronghold_set_record,
-            stronghold_clear_vault,
-            read_user_data_file,
-            write_user_data_file,
-            clear_user_data
+            nullary_core::generate_sessionless_keys,
+            nullary_core::stronghold_init,
+            nullary_core::stronghold_get_record,
+            nullary_core::stronghold_set_record,
+            nullary_core::stronghold_clear_vault,
+            nullary_core::read_user_data_file,
+            nullary_core::write_user_data_file,
+            nullary_core::clear_user_data,
+            // Documents
+            nullary_core::read_document,
+            nullary_core::write_document,
+            nullary_core::delete_document,
+            nullary_core::list_documents,
+            // Personas
+            nullary_core::list_personas,
+            nullary_core::create_persona,
+            nullary_core::rename_persona,
+            nullary_core::delete_persona,
+            nullary_core::switch_persona,
+            nullary_core::get_persona_service_uuids,
+            nullary_core::set_persona_service_uuid,
+            // Identity backup
+            nullary_core::export_identity_backup,
+            nullary_core::import_identity_backup,
+            nullary_core::export_recovery_words,
+            nullary_core::import_recovery_words,
+            // Key rotation
+            nullary_core::rotate_identity,
+            nullary_core::retry_identity_rotation,
+            nullary_core::get_key_rotations,
+            // Signing
+            nullary_core::get_signing_journal,
+            // Logs
+            nullary_core::export_logs,
+            // Health
+            nullary_core::check_service_health,
+            // Outbox
+            nullary_core::list_outbox,
+            nullary_core::retry_outbox_item,
+            nullary_core::cancel_outbox_item,
+            // Moderation
+            nullary_core::get_moderation,
+            nullary_core::set_base_muted,
+            nullary_core::set_base_ignored,
+            nullary_core::set_user_blocked,
+            // Nearby bases
+            nullary_core::discover_bases_near,
+            nullary_core::bases_near_me,
+            // Endpoint configuration
+            nullary_core::get_environment_config,
+            nullary_core::switch_environment
         ])
         .setup(|app| {
-            println!("🌍 Nexus Portal starting...");
+            info!("🌍 Nexus Portal starting...");
+            
+            // Register the app and load (or create on first launch) its identity
+            if let Err(e) = nullary_core::init(app.handle(), "nexus") {
+                error!("❌ Failed to initialize nullary core: {}", e);
+            }
             
             // Initialize clients on startup
             tauri::async_runtime::spawn(async {
                 if let Err(e) = initialize_clients().await {
-                    eprintln!("❌ Failed to initialize clients: {}", e);
+                    error!("❌ Failed to initialize clients: {}", e);
                 }
             });
             
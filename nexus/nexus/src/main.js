@@ -25,6 +25,17 @@ document.addEventListener('DOMContentLoaded', async () => {
     // Load initial data
     await loadFeedPreviews();
     await loadBases();
+
+    // Stale feeds are refreshed in the background; show them once they change
+    if (window.__TAURI__) {
+        window.__TAURI__.event.listen('feed-updated', async () => {
+            if (nexusState.currentScreen === 'feed' && nexusState.currentFeed) {
+                await loadFeedContent(nexusState.currentFeed);
+            } else if (nexusState.currentScreen === 'main') {
+                await loadFeedPreviews();
+            }
+        });
+    }
     
     console.log('✅ Nexus Portal ready');
 });
@@ -129,23 +140,8 @@ async function loadFeedPreviews() {
 async function getFeedCount(feedType) {
     try {
         if (window.__TAURI__) {
-            // Use Tauri backend for real service calls
-            switch (feedType) {
-                case 'dolores':
-                    const doloresFeed = await window.__TAURI__.core.invoke('get_dolores_feed');
-                    return doloresFeed?.length || 0;
-                    
-                case 'products':
-                    const productsData = await window.__TAURI__.core.invoke('get_products_feed');
-                    return productsData?.length || 0;
-                    
-                case 'blogs':
-                    const blogsData = await window.__TAURI__.core.invoke('get_blogs_feed');
-                    return blogsData?.length || 0;
-                    
-                default:
-                    return 0;
-            }
+            // Counted from the cached feed pages in the backend
+            return await window.__TAURI__.core.invoke('get_feed_count', { feedType }) || 0;
         } else {
             // No fallback - return 0 if Tauri not available
             console.log(`🔌 Tauri not available, cannot get ${feedType} count`);
@@ -262,20 +258,20 @@ async function loadFeedContent(feedType) {
             // Use Tauri backend for real data
             switch (feedType) {
                 case 'dolores':
-                    posts = await window.__TAURI__.core.invoke('get_dolores_feed') || [];
+                    posts = (await window.__TAURI__.core.invoke('get_dolores_feed'))?.data || [];
                     break;
                 case 'products':
-                    posts = await window.__TAURI__.core.invoke('get_products_feed') || [];
+                    posts = (await window.__TAURI__.core.invoke('get_products_feed'))?.data || [];
                     break;
                 case 'blogs':
-                    posts = await window.__TAURI__.core.invoke('get_blogs_feed') || [];
+                    posts = (await window.__TAURI__.core.invoke('get_blogs_feed'))?.data || [];
                     break;
                 case 'all':
-                    const [doloresPosts, productPosts, blogPosts] = await Promise.all([
-                        window.__TAURI__.core.invoke('get_dolores_feed') || [],
-                        window.__TAURI__.core.invoke('get_products_feed') || [],
-                        window.__TAURI__.core.invoke('get_blogs_feed') || []
-                    ]);
+                    const [doloresPosts, productPosts, blogPosts] = (await Promise.all([
+                        window.__TAURI__.core.invoke('get_dolores_feed'),
+                        window.__TAURI__.core.invoke('get_products_feed'),
+                        window.__TAURI__.core.invoke('get_blogs_feed')
+                    ])).map(page => page?.data || []);
                     posts = [...doloresPosts, ...productPosts, ...blogPosts]
                         .sort((a, b) => new Date(b.timestamp || 0) - new Date(a.timestamp || 0));
                     break;
@@ -1,166 +1,65 @@
 use addie_rs::structs::{PaymentIntent, Payee};
-use addie_rs::Addie;
 use bdo_rs::structs::BDOUser;
-use bdo_rs::{Bases, Spellbook, BDO};
-use dolores_rs::structs::Feed;
-use dolores_rs::{Dolores, DoloresUser};
+use bdo_rs::{Bases, Spellbook};
+use dolores_rs::DoloresUser;
 use fount_rs::structs::Gateway;
-use fount_rs::{Fount, FountUser};
+use fount_rs::FountUser;
 use sanora_rs::structs::{Order, SanoraUser, ProductMeta};
-use sanora_rs::{Orders, Sanora};
-use reqwest::Client;
+use sanora_rs::Orders;
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 use serde_json::Value;
-use sessionless::hex::FromHex;
-use sessionless::hex::IntoHex;
-use sessionless::{PrivateKey, Sessionless};
-use std::env;
+use std::collections::BTreeMap;
 use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{debug, error, info, warn};
+
+// Shared identity, endpoints and service clients
+use nullary_core::{
+    addie_client, addie_user_uuid, aggregate_feed, bases_by_id, bdo_client_at, bdo_user_uuid_at,
+    cached_feed, current_env, discover_bases_at, dolores_feed_at, feed_sources,
+    forget_if_not_found, get_service_url, http, http_client, public_key, queue_if_transient,
+    sanora_base_products_at, sanora_client_at, sanora_user_uuid_at, signed_request,
+    without_blocked_posts, AggregatedFeed, BaseData, FeedKey, FeedPage, NullaryError,
+};
 
 /// Debug logging command for development
 #[tauri::command]
 fn dbg(log: &str) {
-    dbg!(log);
+    info!(target: "frontend", "{}", log);
 }
 
-/// Get the public key from the sessionless instance
+/// Get the public key this install uses with Sanora
 #[tauri::command]
-async fn get_public_key() -> Result<String, String> {
-    let sessionless = get_sessionless().await?;
-    Ok(sessionless.public_key().to_hex())
+async fn get_public_key() -> Result<String, NullaryError> {
+    public_key("sanora")
 }
 
 /// Get environment configuration from environment variables
 #[tauri::command]
 fn get_env_config() -> String {
-    env::var("NINEFY_ENV").unwrap_or_else(|_| "dev".to_string())
-}
-
-/// Get service URL based on environment and service name
-fn get_service_url(service: &str) -> String {
-    let env = env::var("NINEFY_ENV").unwrap_or_else(|_| "dev".to_string());
-    
-    match (env.as_str(), service) {
-        // Test environment (127.0.0.1:5111-5122)
-        ("test", "julia") => "http://127.0.0.1:5111/".to_string(),
-        ("test", "continuebee") => "http://127.0.0.1:5112/".to_string(),
-        ("test", "pref") => "http://127.0.0.1:5113/".to_string(),
-        ("test", "bdo") => "http://127.0.0.1:5114/".to_string(),
-        ("test", "joan") => "http://127.0.0.1:5115/".to_string(),
-        ("test", "addie") => "http://127.0.0.1:5116/".to_string(),
-        ("test", "fount") => "http://127.0.0.1:5117/".to_string(),
-        ("test", "dolores") => "http://127.0.0.1:5118/".to_string(),
-        ("test", "minnie") => "http://127.0.0.1:5119/".to_string(),
-        ("test", "aretha") => "http://127.0.0.1:5120/".to_string(),
-        ("test", "sanora") => "http://127.0.0.1:5121/".to_string(),
-        ("test", "covenant") => "http://127.0.0.1:5122/".to_string(),
-        
-        // Local environment (127.0.0.1:3000-3011)
-        ("local", "julia") => "http://127.0.0.1:3000/".to_string(),
-        ("local", "continuebee") => "http://127.0.0.1:2999/".to_string(),
-        ("local", "fount") => "http://127.0.0.1:3002/".to_string(),
-        ("local", "bdo") => "http://127.0.0.1:3003/".to_string(),
-        ("local", "pref") => "http://127.0.0.1:3004/".to_string(),
-        ("local", "addie") => "http://127.0.0.1:3005/".to_string(),
-        ("local", "dolores") => "http://127.0.0.1:3005/".to_string(),
-        ("local", "joan") => "http://127.0.0.1:3004/".to_string(),
-        ("local", "aretha") => "http://127.0.0.1:7277/".to_string(),
-        ("local", "minnie") => "http://127.0.0.1:2525/".to_string(),
-        ("local", "sanora") => "http://127.0.0.1:7243/".to_string(),
-        ("local", "covenant") => "http://127.0.0.1:3011/".to_string(),
-        
-        // Dev environment (default)
-        (_, service) => format!("https://dev.{}.allyabase.com/", service),
-    }
-}
-
-/// Get or create sessionless instance using environment variable or default key
-async fn get_sessionless() -> Result<Sessionless, String> {
-    let private_key = env::var("PRIVATE_KEY").unwrap_or_else(|_| {
-        String::from("b75011b167c5e3a6b0de97d8e1950cd9548f83bb67f47112bed6a082db795496")
-    });
-    let sessionless =
-        Sessionless::from_private_key(PrivateKey::from_hex(private_key).expect("private key"));
-    Ok(sessionless)
-}
-
-/// Sign a message using sessionless
-async fn sign_message(message: String) -> Result<String, String> {
-    let sessionless = get_sessionless().await?;
-    let signature = sessionless.sign(&message);
-    Ok(signature.into_hex())
+    current_env()
 }
 
 /// Create a new Fount user for MAGIC transactions
 #[tauri::command]
-async fn create_fount_user() -> Result<FountUser, String> {
-    let s = get_sessionless().await;
-    match s {
-        Ok(sessionless) => {
-            let fount = Fount::new(
-                Some(get_service_url("fount")),
-                Some(sessionless),
-            );
-            let _user = fount.create_user().await;
-            dbg!(&_user);
-            return match _user {
-                Ok(user) => Ok(user),
-                Err(_) => Err("no user".to_string()),
-            };
-        }
-        Err(_) => Err("no user".to_string()),
-    }
+async fn create_fount_user() -> Result<FountUser, NullaryError> {
+    nullary_core::create_fount_user().await
 }
 
 /// Create a new BDO user for big dumb object storage
 #[tauri::command]
-async fn create_bdo_user() -> Result<BDOUser, String> {
-    let s = get_sessionless().await;
-    let ninefy = "ninefy";
-    
-    // Get BDO URL based on environment
-    let bdo_url = get_service_url("bdo");
-    let env = env::var("NINEFY_ENV").unwrap_or_else(|_| "dev".to_string());
-    
-    println!("🔗 Creating BDO user on: {} (env: {})", bdo_url, env);
-    
-    match s {
-        Ok(sessionless) => {
-            let bdo = BDO::new(
-                Some(bdo_url),
-                Some(sessionless),
-            );
-            let _user = bdo.create_user(&ninefy, &json!({})).await;
-            dbg!(&_user);
-            return match _user {
-                Ok(user) => Ok(user),
-                Err(_) => Err("no user".to_string()),
-            };
-        }
-        Err(_) => Err("no user".to_string()),
-    }
+async fn create_bdo_user() -> Result<BDOUser, NullaryError> {
+    nullary_core::create_bdo_user("ninefy").await
 }
 
 /// Get available bases (servers) for connecting to
 #[tauri::command]
-async fn get_bases(uuid: &str, bdo_url: &str) -> Result<Value, String> {
-    let s = get_sessionless().await;
-    let ninefy = "ninefy";
-    match s {
-        Ok(sessionless) => {
-            let bdo = BDO::new(Some(bdo_url.to_string()), Some(sessionless));
-            let bases_result = bdo.get_bases(&uuid, &ninefy).await;
-
-            match bases_result {
-                Ok(bases) => Ok(bases),
-                Err(e) => {
-                    dbg!(e);
-                    Err("no bases".to_string())
-                }
-            }
-        }
-        Err(_) => Err("no bdo".to_string()),
-    }
+async fn get_bases(uuid: &str, bdo_url: &str) -> Result<BTreeMap<String, BaseData>, NullaryError> {
+    // Discovery signs as this app's own BDO user, which is the one `uuid`
+    // was created as
+    debug!("🔍 Discovering bases at {} for {}", bdo_url, uuid);
+    let page = discover_bases_at(bdo_url, false).await?;
+    Ok(bases_by_id(page.data))
 }
 
 /// Create payment intent with splits for multiple payees
@@ -169,36 +68,33 @@ async fn get_payment_intent_with_splits(
     amount: u32,
     currency: &str,
     payees: Vec<Payee>
-) -> Result<PaymentIntent, String> {
-    let s = get_sessionless().await;
+) -> Result<PaymentIntent, NullaryError> {
     let stripe = "stripe";
 
-    match s {
-        Ok(sessionless) => {
-            let addie = Addie::new(
-                Some(get_service_url("addie")),
-                Some(sessionless),
-            );
-            let addie_user = match addie.create_user().await {
-                Ok(user) => user,
-                Err(_) => {
-                    dbg!("The problem is getting the user");
-                    return Ok(PaymentIntent::new());
+    match addie_client().await {
+        Ok(addie) => {
+            let addie_uuid = match addie_user_uuid().await {
+                Ok(uuid) => uuid,
+                Err(e) => {
+                    warn!("The problem is getting the user");
+                    return Err(e);
                 }
             };
 
             match addie
-                .get_payment_intent(&addie_user.uuid, &stripe, &amount, &currency, &payees)
+                .get_payment_intent(&addie_uuid, &stripe, &amount, &currency, &payees)
                 .await
             {
                 Ok(intent) => Ok(intent),
                 Err(err) => {
-                    dbg!("the intent failed for some reason {}", err);
-                    return Ok(PaymentIntent::new());
+                    let error = NullaryError::service("addie", format!("Failed to create payment intent: {}", err));
+                    forget_if_not_found(&get_service_url("addie"), "addie", None, &error);
+                    error!("the intent failed for some reason {}", error);
+                    Err(error)
                 }
             }
         }
-        Err(_) => Ok(PaymentIntent::new()),
+        Err(e) => Err(e),
     }
 }
 
@@ -207,187 +103,194 @@ async fn get_payment_intent_with_splits(
 async fn get_payment_intent_without_splits(
     amount: u32,
     currency: &str,
-) -> Result<PaymentIntent, String> {
-    let s = get_sessionless().await;
+) -> Result<PaymentIntent, NullaryError> {
     let stripe = "stripe";
 
-    match s {
-        Ok(sessionless) => {
-            let addie = Addie::new(
-                Some(get_service_url("addie")),
-                Some(sessionless),
-            );
-            let addie_user = match addie.create_user().await {
-                Ok(user) => user,
-                Err(_) => {
-                    dbg!("The problem is getting the user");
-                    return Ok(PaymentIntent::new());
+    match addie_client().await {
+        Ok(addie) => {
+            let addie_uuid = match addie_user_uuid().await {
+                Ok(uuid) => uuid,
+                Err(e) => {
+                    warn!("The problem is getting the user");
+                    return Err(e);
                 }
             };
 
             match addie
-                .get_payment_intent_without_splits(&addie_user.uuid, &stripe, &amount, &currency)
+                .get_payment_intent_without_splits(&addie_uuid, &stripe, &amount, &currency)
                 .await
             {
                 Ok(intent) => Ok(intent),
                 Err(err) => {
-                    dbg!("the intent failed for some reason {}", err);
-                    return Ok(PaymentIntent::new());
+                    let error = NullaryError::service("addie", format!("Failed to create payment intent: {}", err));
+                    forget_if_not_found(&get_service_url("addie"), "addie", None, &error);
+                    error!("the intent failed for some reason {}", error);
+                    Err(error)
                 }
             }
         }
-        Err(_) => Ok(PaymentIntent::new()),
+        Err(e) => Err(e),
     }
 }
 
 /// Create a new Dolores user for video/media storage
 #[tauri::command]
-async fn create_dolores_user(dolores_url: &str) -> Result<DoloresUser, String> {
-    let s = get_sessionless().await;
-    match s {
-        Ok(sessionless) => {
-            let dolores = Dolores::new(Some(dolores_url.to_string()), Some(sessionless));
-            let _user = dolores.create_user().await;
-            dbg!(&_user);
-            return match _user {
-                Ok(user) => Ok(user),
-                Err(_) => Err("no user".to_string()),
-            };
-        }
-        Err(_) => Err("no user".to_string()),
-    }
+async fn create_dolores_user(dolores_url: &str) -> Result<DoloresUser, NullaryError> {
+    nullary_core::create_dolores_user_at(dolores_url).await
 }
 
-/// Get media feed from Dolores with specific tags
+/// Get media feed from Dolores with specific tags, from one base or, without
+/// a URL, the home base and every joined base that isn't muted
 #[tauri::command]
-async fn get_feed(uuid: &str, dolores_url: &str, tags: &str) -> Result<Feed, String> {
-    let s = get_sessionless().await;
-    match s {
-        Ok(sessionless) => {
-            let dolores = Dolores::new(Some(dolores_url.to_string()), Some(sessionless));
-            let feed_result = dolores.get_feed(&uuid, &tags).await;
-
-            match feed_result {
-                Ok(feed) => Ok(feed),
-                Err(e) => {
-                    dbg!(e);
-                    Err("no feed".to_string())
-                }
-            }
-        }
-        Err(_) => Err("no dolores".to_string()),
+async fn get_feed(dolores_url: Option<String>, tags: String) -> Result<AggregatedFeed<Value>, NullaryError> {
+    let sources = feed_sources("dolores", dolores_url.as_deref());
+    let feed_tags = tags.clone();
+    let feed = aggregate_feed(sources, "dolores", &tags, false, move |url, validators| {
+        let tags = feed_tags.clone();
+        async move { dolores_feed_at(&url, &tags, &validators).await }
+    })
+    .await?;
+
+    if feed.unavailable() {
+        warn!("⚠️ No base could serve the feed");
     }
+    Ok(AggregatedFeed {
+        data: without_blocked_posts(feed.data),
+        ..feed
+    })
 }
 
 /// Create a new Sanora user for blog product hosting
 #[tauri::command]
-async fn create_sanora_user(sanora_url: &str) -> Result<SanoraUser, String> {
-    let s = get_sessionless().await;
-    match s {
-        Ok(sessionless) => {
-            let sanora = Sanora::new(Some(sanora_url.to_string()), Some(sessionless));
-            let _user = sanora.create_user().await;
-            dbg!(&_user);
-            return match _user {
-                Ok(user) => Ok(user),
-                Err(_) => Err("no user".to_string()),
-            };
-        }
-        Err(_) => Err("no user".to_string()),
-    }
+async fn create_sanora_user(sanora_url: &str) -> Result<SanoraUser, NullaryError> {
+    nullary_core::create_sanora_user_at(sanora_url).await
 }
 
 /// Get orders for a specific blog product
 #[tauri::command]
-async fn get_orders_for_product_id(uuid: &str, sanora_url: &str, product_id: &str) -> Result<Orders, String> {
-    let s = get_sessionless().await;
-    match s {
-        Ok(sessionless) => {
-            let sanora = Sanora::new(Some(sanora_url.to_string()), Some(sessionless));
+async fn get_orders_for_product_id(uuid: &str, sanora_url: &str, product_id: &str) -> Result<Orders, NullaryError> {
+    match sanora_client_at(sanora_url).await {
+        Ok(sanora) => {
             let orders_result = sanora.get_orders_for_product_id(&uuid, &product_id).await;
         
             match orders_result {
                 Ok(orders) => Ok(orders),
                 Err(e) => {
-                    dbg!(e);
-                    Err("failed to get orders".to_string())
+                    error!("{:?}", e);
+                    Err(NullaryError::service("sanora", format!("Failed to get orders: {}", e)))
                 }
             }
         }
-        Err(_) => Err("no sanora".to_string()),
+        Err(e) => Err(e),
     }
 }
 
-/// Add an order for a blog product
-#[tauri::command]
-async fn add_order(uuid: &str, sanora_url: &str, order: Order) -> Result<SanoraUser, String> {
-    let s = get_sessionless().await;
-    match s {
-        Ok(sessionless) => {
-            let sanora = Sanora::new(Some(sanora_url.to_string()), Some(sessionless));
-            let order_result = sanora.add_order(&uuid, &order).await;
+/// An order as the outbox stores and replays it
+#[derive(Serialize, Deserialize)]
+struct NewOrder {
+    uuid: String,
+    sanora_url: String,
+    order: Order,
+}
+
+/// Send an order to Sanora
+async fn send_order(new_order: &NewOrder) -> Result<SanoraUser, NullaryError> {
+    match sanora_client_at(&new_order.sanora_url).await {
+        Ok(sanora) => {
+            let order_result = sanora.add_order(&new_order.uuid, &new_order.order).await;
 
             match order_result {
                 Ok(user) => Ok(user),
                 Err(e) => {
-                    dbg!(e);
-                    Err("failed to add order".to_string())
+                    error!("{:?}", e);
+                    Err(NullaryError::service("sanora", format!("Failed to add order: {}", e)))
                 }
             }
         }
-        Err(_) => Err("no sanora".to_string()),
+        Err(e) => Err(e),
     }
 }
 
-/// Create a new blog product in Sanora
+/// Add an order for a blog product, queued in the outbox while Sanora is unreachable
 #[tauri::command]
-async fn add_product(uuid: &str, sanora_url: &str, title: &str, description: &str, price: u32) -> Result<ProductMeta, String> {
-    println!("🦀 Rust add_product called with: uuid={}, sanora_url={}, title={}, price={}", uuid, sanora_url, title, price);
-    
-    let s = get_sessionless().await;
-    match s {
-        Ok(sessionless) => {
-            let sanora = Sanora::new(Some(sanora_url.to_string()), Some(sessionless));
-            println!("🦀 Calling sanora.add_product with sessionless authentication");
-            let product_result = sanora.add_product(&uuid, &title, &description, &price).await;
+async fn add_order(uuid: &str, sanora_url: &str, order: Order) -> Result<SanoraUser, NullaryError> {
+    let new_order = NewOrder {
+        uuid: uuid.to_string(),
+        sanora_url: sanora_url.to_string(),
+        order,
+    };
+    let result = send_order(&new_order).await;
+    queue_if_transient(result, "add_order", "sanora", sanora_url, &new_order).await
+}
+
+/// A product as the outbox stores and replays it
+#[derive(Serialize, Deserialize)]
+struct NewProduct {
+    uuid: String,
+    sanora_url: String,
+    title: String,
+    description: String,
+    price: u32,
+}
+
+/// Send a product to Sanora
+async fn send_product(product: &NewProduct) -> Result<ProductMeta, NullaryError> {
+    match sanora_client_at(&product.sanora_url).await {
+        Ok(sanora) => {
+            info!("🦀 Calling sanora.add_product with sessionless authentication");
+            let product_result = sanora.add_product(&product.uuid, &product.title, &product.description, &product.price).await;
 
             match product_result {
                 Ok(meta) => {
-                    println!("🦀 ✅ Product added successfully: {:?}", meta);
+                    info!("🦀 ✅ Product added successfully: {:?}", meta);
                     Ok(meta)
                 },
                 Err(e) => {
-                    println!("🦀 ❌ Failed to add product: {:?}", e);
-                    dbg!(e);
-                    Err("failed to add product".to_string())
+                    info!("🦀 ❌ Failed to add product: {:?}", e);
+                    error!("{:?}", e);
+                    Err(NullaryError::service("sanora", format!("Failed to add product: {}", e)))
                 }
             }
         }
-        Err(_) => {
-            println!("🦀 ❌ Failed to get sessionless instance");
-            Err("no sanora".to_string())
+        Err(e) => {
+            info!("🦀 ❌ Failed to get sessionless instance");
+            Err(e)
         }
     }
 }
 
+/// Create a new blog product in Sanora, queued in the outbox while Sanora is unreachable
+#[tauri::command]
+async fn add_product(uuid: &str, sanora_url: &str, title: &str, description: &str, price: u32) -> Result<ProductMeta, NullaryError> {
+    info!("🦀 Rust add_product called with: uuid={}, sanora_url={}, title={}, price={}", uuid, sanora_url, title, price);
+
+    let product = NewProduct {
+        uuid: uuid.to_string(),
+        sanora_url: sanora_url.to_string(),
+        title: title.to_string(),
+        description: description.to_string(),
+        price,
+    };
+    let result = send_product(&product).await;
+    queue_if_transient(result, "add_product", "sanora", sanora_url, &product).await
+}
+
 /// Get Sanora user information (which includes their products)
 #[tauri::command]
-async fn get_sanora_user(uuid: &str, sanora_url: &str) -> Result<SanoraUser, String> {
-    let s = get_sessionless().await;
-    match s {
-        Ok(sessionless) => {
-            let sanora = Sanora::new(Some(sanora_url.to_string()), Some(sessionless));
+async fn get_sanora_user(uuid: &str, sanora_url: &str) -> Result<SanoraUser, NullaryError> {
+    match sanora_client_at(sanora_url).await {
+        Ok(sanora) => {
             let user_result = sanora.get_user_by_uuid(&uuid).await;
 
             match user_result {
                 Ok(user) => Ok(user),
                 Err(e) => {
-                    dbg!(e);
-                    Err("failed to get user".to_string())
+                    error!("{:?}", e);
+                    Err(NullaryError::service("sanora", format!("Failed to get user: {}", e)))
                 }
             }
         }
-        Err(_) => Err("no sanora".to_string()),
+        Err(e) => Err(e),
     }
 }
 
@@ -398,7 +301,7 @@ async fn toggle_product_availability(
     _sanora_url: &str, 
     _product_id: &str, 
     available: bool
-) -> Result<Value, String> {
+) -> Result<Value, NullaryError> {
     // NOTE: This function is a placeholder because toggle_product_availability 
     // doesn't exist in the current sanora_rs crate. In a real implementation,
     // this would either:
@@ -406,7 +309,7 @@ async fn toggle_product_availability(
     // 2. Be implemented as product metadata updates
     // 3. Require extending the sanora_rs crate
     
-    println!("🔄 Toggle product availability called (placeholder): {}", available);
+    info!("🔄 Toggle product availability called (placeholder): {}", available);
     
     // Return a mock success response for now
     Ok(json!({
@@ -416,14 +319,22 @@ async fn toggle_product_availability(
     }))
 }
 
+/// Sanora endpoint for a product's image or artifact, with the title encoded
+fn product_upload_url(sanora_url: &str, uuid: &str, title: &str, kind: &str) -> Result<String, NullaryError> {
+    let mut url = url::Url::parse(sanora_url)
+        .map_err(|e| NullaryError::validation(format!("Invalid Sanora URL {}: {}", sanora_url, e)))?;
+    url.path_segments_mut()
+        .map_err(|_| NullaryError::validation(format!("Invalid Sanora URL {}", sanora_url)))?
+        .pop_if_empty()
+        .extend(["user", uuid, "product", title, kind]);
+    Ok(url.to_string())
+}
+
 /// Upload image to Sanora with sessionless authentication
 #[tauri::command]
-async fn upload_image(file_data: Vec<u8>, file_name: String, url: String, message: String, timestamp: String) -> Result<String, String> {
-    println!("🦀 Rust uploading image: {} to: {} with message: {}", file_name, url, message);
-
-    let signature = sign_message(message).await?;
-
-    let client = Client::new();
+async fn upload_image(file_data: Vec<u8>, file_name: String, sanora_url: String, uuid: String, title: String) -> Result<String, NullaryError> {
+    let url = product_upload_url(&sanora_url, &uuid, &title, "image")?;
+    info!("🦀 Rust uploading image: {} to: {}", file_name, url);
 
     // Get extension from file name
     let extension = file_name.split('.').last().unwrap_or("jpg");
@@ -440,43 +351,38 @@ async fn upload_image(file_data: Vec<u8>, file_name: String, url: String, messag
     let part = reqwest::multipart::Part::bytes(file_data)
         .file_name(file_name.clone())
         .mime_str(mime_type)
-        .map_err(|e| format!("Failed to create multipart form: {}", e))?;
+        .map_err(|e| NullaryError::validation(format!("Failed to create multipart form: {}", e)))?;
 
     let form = reqwest::multipart::Form::new()
         .part("image", part);
 
-    let response = client
+    // Sanora checks timestamp + uuid + title, signed by the user on this base
+    let request = http_client()
         .put(&url)
         .header("Accept", "application/json")
-        .header("x-pn-timestamp", &timestamp)
-        .header("x-pn-signature", &signature)
-        .multipart(form)
-        .send()
-        .await
-        .map_err(|e| format!("Request failed: {}", e))?;
+        .multipart(form);
+    let request = signed_request(request, &url, "sanora", &[&uuid, &title]).await?;
+    let response = http::send("sanora", request).await?;
 
     let status = response.status();
 
     if status.is_success() {
         let body = response.text().await
-            .map_err(|e| format!("Failed to read response body: {}", e))?;
-        println!("🦀 Image upload success: {}", body);
+            .map_err(|e| NullaryError::from_reqwest("sanora", "Failed to read response body", &e))?;
+        info!("🦀 Image upload success");
         Ok(body)
     } else {
         let error_body = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-        println!("❌ Image upload error {}: {}", status.as_u16(), error_body);
-        Err(format!("HTTP error {}: {}", status.as_u16(), error_body))
+        error!("❌ Image upload error {}: {}", status.as_u16(), error_body);
+        Err(NullaryError::from_status("sanora", status.as_u16(), error_body))
     }
 }
 
 /// Upload artifact to Sanora with sessionless authentication
 #[tauri::command]
-async fn upload_artifact(file_data: Vec<u8>, file_name: String, url: String, message: String, timestamp: String, artifact_type: String) -> Result<String, String> {
-    println!("🦀 Rust uploading artifact: {} to: {} with message: {}", file_name, url, message);
-
-    let signature = sign_message(message).await?;
-
-    let client = Client::new();
+async fn upload_artifact(file_data: Vec<u8>, file_name: String, sanora_url: String, uuid: String, title: String, artifact_type: String) -> Result<String, NullaryError> {
+    let url = product_upload_url(&sanora_url, &uuid, &title, "artifact")?;
+    info!("🦀 Rust uploading artifact: {} to: {}", file_name, url);
 
     // Get extension from file name
     let extension = file_name.split('.').last().unwrap_or("bin");
@@ -497,156 +403,113 @@ async fn upload_artifact(file_data: Vec<u8>, file_name: String, url: String, mes
     let part = reqwest::multipart::Part::bytes(file_data)
         .file_name(file_name.clone())
         .mime_str(mime_type)
-        .map_err(|e| format!("Failed to create multipart form: {}", e))?;
+        .map_err(|e| NullaryError::validation(format!("Failed to create multipart form: {}", e)))?;
 
     let form = reqwest::multipart::Form::new()
         .part("artifact", part);
 
-    let response = client
+    // Sanora checks timestamp + uuid + title, signed by the user on this base
+    let request = http_client()
         .put(&url)
         .header("Accept", "application/json")
-        .header("x-pn-timestamp", &timestamp)
-        .header("x-pn-signature", &signature)
         .header("x-pn-artifact-type", &artifact_type)
-        .multipart(form)
-        .send()
-        .await
-        .map_err(|e| format!("Request failed: {}", e))?;
+        .multipart(form);
+    let request = signed_request(request, &url, "sanora", &[&uuid, &title]).await?;
+    let response = http::send("sanora", request).await?;
 
     let status = response.status();
 
     if status.is_success() {
         let body = response.text().await
-            .map_err(|e| format!("Failed to read response body: {}", e))?;
-        println!("🦀 Artifact upload success: {}", body);
+            .map_err(|e| NullaryError::from_reqwest("sanora", "Failed to read response body", &e))?;
+        info!("🦀 Artifact upload success");
         Ok(body)
     } else {
         let error_body = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-        println!("❌ Artifact upload error {}: {}", status.as_u16(), error_body);
-        Err(format!("HTTP error {}: {}", status.as_u16(), error_body))
+        error!("❌ Artifact upload error {}: {}", status.as_u16(), error_body);
+        Err(NullaryError::from_status("sanora", status.as_u16(), error_body))
     }
 }
 
-/// Get all products available on the entire base (new /products/base endpoint)
+/// Get all products available on the entire base (/products/base endpoint),
+/// served from the feed cache; a stale page is refreshed in the background
 #[tauri::command]
-async fn get_all_base_products(sanora_url: &str) -> Result<Value, String> {
-    println!("🔄 Getting ALL base products from: {}", sanora_url);
-    
-    let client = Client::new();
-    
-    // Use the new /products/base endpoint (no authentication required)
-    let products_url = format!("{}/products/base", sanora_url.trim_end_matches('/'));
-    
-    println!("🔍 Trying base products endpoint: {}", products_url);
-    
-    match client.get(&products_url).send().await {
-        Ok(response) => {
-            let status = response.status();
-            println!("📡 Response status: {}", status);
-            
-            if status.is_success() {
-                match response.text().await {
-                    Ok(body) => {
-                        println!("📄 Response body: {}", body);
-                        
-                        // Try to parse as JSON
-                        match serde_json::from_str::<Value>(&body) {
-                            Ok(json_value) => {
-                                println!("✅ Got products JSON from base endpoint");
-                                Ok(json_value)
-                            }
-                            Err(_) => {
-                                // If it's not JSON, return it as a string in an array
-                                println!("⚠️ Response is not JSON, wrapping as string");
-                                Ok(json!([{"response": body}]))
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        println!("❌ Failed to read response body: {}", e);
-                        Err(format!("Failed to read response: {}", e))
-                    }
-                }
-            } else {
-                let error_body = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-                println!("❌ HTTP error {}: {}", status.as_u16(), error_body);
-                
-                // Instead of failing, return empty array for now
-                println!("⚠️ Returning empty array due to HTTP error");
-                Ok(json!([]))
-            }
-        }
-        Err(e) => {
-            println!("❌ Request failed: {}", e);
-            Err(format!("Request failed: {}", e))
-        }
-    }
+async fn get_all_base_products(sanora_url: &str) -> Result<FeedPage<Vec<Value>>, NullaryError> {
+    info!("🔄 Getting ALL base products from: {}", sanora_url);
+
+    let url = sanora_url.to_string();
+    let key = FeedKey::new(&url, "sanora", "products")?;
+    let page = cached_feed(key, move |validators| async move {
+        sanora_base_products_at(&url, &validators).await
+    })
+    .await?;
+
+    info!("✅ Got {} base products (stale: {})", page.data.len(), page.stale);
+    Ok(page)
 }
 
 /// Teleport content from a URL via BDO
 #[tauri::command]
-async fn teleport_content(bdo_url: &str, teleport_url: &str) -> Result<Value, String> {
-    println!("🌐 Teleporting content from: {} via BDO: {}", teleport_url, bdo_url);
+async fn teleport_content(bdo_url: &str, teleport_url: &str) -> Result<Value, NullaryError> {
+    info!("🌐 Teleporting content from: {} via BDO: {}", teleport_url, bdo_url);
     
-    let s = get_sessionless().await;
-    match s {
-        Ok(sessionless) => {
-            let bdo = BDO::new(Some(bdo_url.to_string()), Some(sessionless));
+    match bdo_client_at(bdo_url).await {
+        Ok(bdo) => {
             
             // Create/get BDO user first
             let ninefy = "ninefy";
-            let bdo_user = match bdo.create_user(&ninefy, &json!({})).await {
-                Ok(user) => {
-                    println!("✅ BDO user ready for teleportation: {}", user.uuid);
-                    user
+            let bdo_uuid = match bdo_user_uuid_at(bdo_url, ninefy).await {
+                Ok(uuid) => {
+                    info!("✅ BDO user ready for teleportation: {}", uuid);
+                    uuid
                 }
                 Err(e) => {
-                    println!("❌ Failed to create BDO user: {:?}", e);
-                    return Err(format!("Failed to create BDO user: {}", e));
+                    error!("❌ Failed to create BDO user: {:?}", e);
+                    return Err(e);
                 }
             };
             
             // Now teleport the content
-            println!("🚀 Starting teleportation with uuid: {}", bdo_user.uuid);
-            match bdo.teleport(&bdo_user.uuid, &ninefy, teleport_url).await {
+            info!("🚀 Starting teleportation with uuid: {}", bdo_uuid);
+            match bdo.teleport(&bdo_uuid, &ninefy, teleport_url).await {
                 Ok(teleported_content) => {
-                    println!("✅ Successfully teleported content: {:?}", teleported_content);
+                    info!("✅ Successfully teleported content: {:?}", teleported_content);
                     Ok(teleported_content)
                 }
                 Err(e) => {
-                    println!("❌ Teleportation failed: {:?}", e);
-                    Err(format!("Teleportation failed: {}", e))
+                    error!("❌ Teleportation failed: {:?}", e);
+                    let error = NullaryError::service("bdo", format!("Teleportation failed: {}", e));
+                    forget_if_not_found(bdo_url, "bdo", Some(ninefy), &error);
+                    Err(error)
                 }
             }
         }
         Err(e) => {
-            println!("❌ Failed to get sessionless instance: {}", e);
-            Err(format!("Failed to get sessionless: {}", e))
+            error!("❌ Failed to get sessionless instance: {}", e);
+            Err(e)
         }
     }
 }
 
 /// Get all products available on a base (HTTP-based implementation) - DEPRECATED
 #[tauri::command]
-async fn get_base_products(sanora_url: &str, user_uuid: Option<String>) -> Result<Value, String> {
-    println!("🔄 Getting base products from: {}", sanora_url);
-    
-    let client = Client::new();
+async fn get_base_products(sanora_url: &str, user_uuid: Option<String>) -> Result<Value, NullaryError> {
+    info!("🔄 Getting base products from: {}", sanora_url);
     
     // Use provided user UUID, or try to create/get one
     let uuid = match user_uuid {
         Some(uuid) => uuid,
         None => {
-            // If no UUID provided, try to create a user first
-            println!("🔍 No user UUID provided, creating Sanora user...");
-            match create_sanora_user(sanora_url).await {
-                Ok(user) => {
-                    println!("✅ Created user with UUID: {}", user.uuid);
-                    user.uuid
+            // If no UUID provided, use this install's registered user
+            debug!("🔍 No user UUID provided, looking up Sanora user...");
+            match sanora_user_uuid_at(sanora_url).await {
+                Ok(uuid) => {
+                    info!("✅ Using Sanora user: {}", uuid);
+                    uuid
                 }
                 Err(e) => {
-                    println!("❌ Failed to create user: {}", e);
-                    return Err(format!("Failed to create user: {}", e));
+                    error!("❌ Failed to get Sanora user: {}", e);
+                    return Err(e);
                 }
             }
         }
@@ -655,52 +518,61 @@ async fn get_base_products(sanora_url: &str, user_uuid: Option<String>) -> Resul
     // The correct endpoint pattern from the test is: GET /products/{uuid}
     let products_url = format!("{}/products/{}", sanora_url.trim_end_matches('/'), uuid);
     
-    println!("🔍 Trying user products endpoint: {}", products_url);
+    debug!("🔍 Trying user products endpoint: {}", products_url);
     
-    match client.get(&products_url).send().await {
+    match http::get("sanora", &products_url).await {
         Ok(response) => {
             let status = response.status();
-            println!("📡 Response status: {}", status);
+            info!("📡 Response status: {}", status);
             
             if status.is_success() {
                 match response.text().await {
                     Ok(body) => {
-                        println!("📄 Response body: {}", body);
+                        debug!(bytes = body.len(), "📄 Response body");
                         
                         // Try to parse as JSON
                         match serde_json::from_str::<Value>(&body) {
                             Ok(json_value) => {
-                                println!("✅ Got products JSON from user endpoint");
+                                info!("✅ Got products JSON from user endpoint");
                                 Ok(json_value)
                             }
                             Err(_) => {
                                 // If it's not JSON, return it as a string in an array
-                                println!("⚠️ Response is not JSON, wrapping as string");
+                                warn!("⚠️ Response is not JSON, wrapping as string");
                                 Ok(json!([{"response": body}]))
                             }
                         }
                     }
                     Err(e) => {
-                        println!("❌ Failed to read response body: {}", e);
-                        Err(format!("Failed to read response: {}", e))
+                        error!("❌ Failed to read response body: {}", e);
+                        Err(NullaryError::from_reqwest("sanora", "Failed to read response", &e))
                     }
                 }
             } else {
                 let error_body = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-                println!("❌ HTTP error {}: {}", status.as_u16(), error_body);
-                
-                // Instead of failing, return empty array for now
-                println!("⚠️ Returning empty array due to HTTP error");
-                Ok(json!([]))
+                error!("❌ HTTP error {}: {}", status.as_u16(), error_body);
+                Err(NullaryError::from_status("sanora", status.as_u16(), error_body))
             }
         }
         Err(e) => {
-            println!("❌ Request failed: {}", e);
-            Err(format!("Request failed: {}", e))
+            error!("❌ Request failed: {}", e);
+            Err(e)
         }
     }
 }
 
+/// Replay orders and products queued while Sanora was unreachable
+fn register_outbox_handlers() {
+    nullary_core::register_outbox_handler("add_order", |payload| async move {
+        let new_order: NewOrder = nullary_core::outbox_payload(payload)?;
+        Ok(json!(send_order(&new_order).await?))
+    });
+    nullary_core::register_outbox_handler("add_product", |payload| async move {
+        let product: NewProduct = nullary_core::outbox_payload(payload)?;
+        Ok(json!(send_product(&product).await?))
+    });
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
@@ -727,10 +599,60 @@ pub fn run() {
             get_all_base_products,
             upload_image,
             upload_artifact,
-            teleport_content
+            teleport_content,
+            // User Persistence Functions
+            nullary_core::generate_sessionless_keys,
+            nullary_core::stronghold_init,
+            nullary_core::stronghold_get_record,
+            nullary_core::stronghold_set_record,
+            nullary_core::stronghold_clear_vault,
+            nullary_core::read_user_data_file,
+            nullary_core::write_user_data_file,
+            nullary_core::clear_user_data,
+            // Documents
+            nullary_core::read_document,
+            nullary_core::write_document,
+            nullary_core::delete_document,
+            nullary_core::list_documents,
+            // Personas
+            nullary_core::list_personas,
+            nullary_core::create_persona,
+            nullary_core::rename_persona,
+            nullary_core::delete_persona,
+            nullary_core::switch_persona,
+            nullary_core::get_persona_service_uuids,
+            nullary_core::set_persona_service_uuid,
+            // Identity backup
+            nullary_core::export_identity_backup,
+            nullary_core::import_identity_backup,
+            nullary_core::export_recovery_words,
+            nullary_core::import_recovery_words,
+            // Key rotation
+            nullary_core::rotate_identity,
+            nullary_core::retry_identity_rotation,
+            nullary_core::get_key_rotations,
+            // Signing
+            nullary_core::get_signing_journal,
+            // Logs
+            nullary_core::export_logs,
+            // Health
+            nullary_core::check_service_health,
+            // Outbox
+            nullary_core::list_outbox,
+            nullary_core::retry_outbox_item,
+            nullary_core::cancel_outbox_item,
+            // Endpoint configuration
+            nullary_core::get_environment_config,
+            nullary_core::switch_environment
         ])
-        .setup(|_app| {
-            println!("🛒 Ninefy backend is starting up...");
+        .setup(|app| {
+            info!("🛒 Ninefy backend is starting up...");
+
+            // Register the app and load (or create on first launch) its identity
+            if let Err(e) = nullary_core::init(app.handle(), "ninefy") {
+                error!("❌ Failed to initialize nullary core: {}", e);
+            }
+            register_outbox_handlers();
             Ok(())
         })
         .run(tauri::generate_context!())
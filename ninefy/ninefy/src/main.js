@@ -557,9 +557,10 @@ async function getProductsFromBases() {
       console.log(`🔍 Fetching products from ${base.name} at ${base.dns.sanora}`);
       
       // Use the new /products/base endpoint (no authentication required)
-      const products = await invoke('get_all_base_products', {
+      const page = await invoke('get_all_base_products', {
         sanoraUrl: base.dns.sanora
       });
+      const products = page.data;
       
       console.log(`📦 Raw products from ${base.name}:`, products);
       console.log(`📦 Products type:`, typeof products, Array.isArray(products));
@@ -1230,9 +1231,10 @@ function createBrowseBaseScreen() {
       
       // Try to fetch from backend using new /products/base endpoint
       try {
-        const baseProducts = await invoke('get_all_base_products', {
+        const page = await invoke('get_all_base_products', {
           sanoraUrl: selectedBase
         });
+        const baseProducts = page.data;
         
         console.log('✅ Loaded base products:', baseProducts);
         
@@ -2399,20 +2401,13 @@ async function uploadImageToSanora(uuid, sanoraUrl, title, imageFile) {
     const arrayBuffer = await imageFile.arrayBuffer();
     const fileData = new Uint8Array(arrayBuffer);
     
-    // Create message for signing
-    const timestamp = Date.now().toString();
-    const message = timestamp + uuid + title;
-    
-    // Create upload URL (adjust based on actual Sanora endpoints)
-    const url = `${sanoraUrl.replace(/\/$/, '')}/user/${uuid}/product/${encodeURIComponent(title)}/image`;
-    
-    // Call Tauri function
+    // The backend builds the upload URL and signs the request itself
     const result = await invoke('upload_image', {
       fileData: Array.from(fileData),
       fileName: imageFile.name,
-      url: url,
-      message: message,
-      timestamp: timestamp
+      sanoraUrl: sanoraUrl,
+      uuid: uuid,
+      title: title
     });
     
     console.log('✅ Image uploaded successfully via Tauri');
@@ -2447,20 +2442,13 @@ async function uploadArtifactToSanora(uuid, sanoraUrl, title, artifactFile) {
     const fileExtension = artifactFile.name.split('.').pop().toLowerCase();
     const artifactType = getArtifactType(fileExtension);
     
-    // Create message for signing
-    const timestamp = Date.now().toString();
-    const message = timestamp + uuid + title;
-    
-    // Create upload URL (adjust based on actual Sanora endpoints)
-    const url = `${sanoraUrl.replace(/\/$/, '')}/user/${uuid}/product/${encodeURIComponent(title)}/artifact`;
-    
-    // Call Tauri function
+    // The backend builds the upload URL and signs the request itself
     const result = await invoke('upload_artifact', {
       fileData: Array.from(fileData),
       fileName: artifactFile.name,
-      url: url,
-      message: message,
-      timestamp: timestamp,
+      sanoraUrl: sanoraUrl,
+      uuid: uuid,
+      title: title,
       artifactType: artifactType
     });
     
@@ -4,6 +4,7 @@
  */
 
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 
 // Environment configuration for photary
 function getEnvironmentConfig() {
@@ -529,7 +530,13 @@ async function refreshPhotoFeed() {
         if (result.success && result.data) {
             appState.photoFeed = result.data.image_posts || [];
             displayPhotoFeed(appState.photoFeed);
-            showMessage('Photo feed refreshed!', 'success');
+            if (result.data.sync?.status === 'failed' && appState.photoFeed.length === 0) {
+                showMessage('Could not reach any base', 'error');
+            } else if (result.data.stale) {
+                showMessage('Could not reach your base - showing saved photos', 'info');
+            } else {
+                showMessage('Photo feed refreshed!', 'success');
+            }
         } else {
             showMessage('Failed to refresh feed: ' + (result.error || 'Unknown error'), 'error');
         }
@@ -700,6 +707,13 @@ async function initializeApp() {
         
         // Show initial screen
         showScreen('feed');
+
+        // A stale feed is refreshed in the background; show it once it changes
+        listen('feed-updated', () => {
+            if (appState.currentScreen === 'feed' && !appState.loading) {
+                loadPhotoFeed();
+            }
+        });
         
         console.log('Photary app initialized successfully');
     } catch (error) {
@@ -1,67 +1,34 @@
 use addie_rs::structs::{PaymentIntent, Payee};
-use addie_rs::Addie;
 use bdo_rs::structs::BDOUser;
-use bdo_rs::BDO;
 use dolores_rs::structs::Feed;
-use dolores_rs::{Dolores, DoloresUser};
-use fount_rs::{Fount, FountUser};
+use dolores_rs::DoloresUser;
+use fount_rs::FountUser;
 use sanora_rs::structs::{Order, SanoraUser, ProductMeta};
-use sanora_rs::{Orders, Sanora};
-use reqwest::Client;
+use sanora_rs::Orders;
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 use serde_json::Value;
-use sessionless::hex::FromHex;
-use sessionless::{PrivateKey, Sessionless};
+use std::collections::BTreeMap;
 use std::env;
+use tracing::{debug, error, info, warn};
+
+// Shared identity, endpoints and service clients
+use nullary_core::{
+    addie_client, addie_user_uuid, bases_by_id, bdo_client_at, bdo_user_uuid_at, current_env,
+    discover_bases_at, dolores_client_at, forget_if_not_found, get_service_url, http,
+    queue_if_transient, sanora_client_at, BaseData, NullaryError,
+};
 
 /// Debug logging command for development
 #[tauri::command]
 fn dbg(log: &str) {
-    dbg!(log);
+    info!(target: "frontend", "{}", log);
 }
 
-/// Get environment configuration from RHAPSOLD_ENV variable
+/// Get the active environment from the shared endpoint config
 #[tauri::command]
 fn get_env_config() -> String {
-    env::var("RHAPSOLD_ENV").unwrap_or_else(|_| "dev".to_string())
-}
-
-/// Get service URL based on environment and service name
-fn get_service_url(service: &str) -> String {
-    let env = env::var("RHAPSOLD_ENV").unwrap_or_else(|_| "dev".to_string());
-    
-    match (env.as_str(), service) {
-        // Test environment (127.0.0.1:5111-5122)
-        ("test", "julia") => "http://127.0.0.1:5111/".to_string(),
-        ("test", "continuebee") => "http://127.0.0.1:5112/".to_string(),
-        ("test", "pref") => "http://127.0.0.1:5113/".to_string(),
-        ("test", "bdo") => "http://127.0.0.1:5114/".to_string(),
-        ("test", "joan") => "http://127.0.0.1:5115/".to_string(),
-        ("test", "addie") => "http://127.0.0.1:5116/".to_string(),
-        ("test", "fount") => "http://127.0.0.1:5117/".to_string(),
-        ("test", "dolores") => "http://127.0.0.1:5118/".to_string(),
-        ("test", "minnie") => "http://127.0.0.1:5119/".to_string(),
-        ("test", "aretha") => "http://127.0.0.1:5120/".to_string(),
-        ("test", "sanora") => "http://127.0.0.1:5121/".to_string(),
-        ("test", "covenant") => "http://127.0.0.1:5122/".to_string(),
-        
-        // Local environment (127.0.0.1:2999-7277)
-        ("local", "julia") => "http://127.0.0.1:3000/".to_string(),
-        ("local", "continuebee") => "http://127.0.0.1:2999/".to_string(),
-        ("local", "pref") => "http://127.0.0.1:3002/".to_string(),
-        ("local", "bdo") => "http://127.0.0.1:3003/".to_string(),
-        ("local", "joan") => "http://127.0.0.1:3004/".to_string(),
-        ("local", "addie") => "http://127.0.0.1:3005/".to_string(),
-        ("local", "fount") => "http://127.0.0.1:3006/".to_string(),
-        ("local", "dolores") => "http://127.0.0.1:3007/".to_string(),
-        ("local", "aretha") => "http://127.0.0.1:7277/".to_string(),
-        ("local", "minnie") => "http://127.0.0.1:2525/".to_string(),
-        ("local", "sanora") => "http://127.0.0.1:7243/".to_string(),
-        ("local", "covenant") => "http://127.0.0.1:3011/".to_string(),
-        
-        // Dev environment (default)
-        (_, service) => format!("https://dev.{}.allyabase.com/", service),
-    }
+    current_env()
 }
 
 /// Get current environment info for debugging
@@ -69,15 +36,8 @@ fn get_service_url(service: &str) -> String {
 fn get_environment_info() -> String {
     let mut info = Vec::new();
     
-    // Check for RHAPSOLD_ENV
-    match env::var("RHAPSOLD_ENV") {
-        Ok(env_val) => {
-            info.push(format!("RHAPSOLD_ENV = {}", env_val));
-        }
-        Err(_) => {
-            info.push("RHAPSOLD_ENV = (not set)".to_string());
-        }
-    }
+    // Active endpoint environment (NULLARY_ENV or endpoints.json)
+    info.push(format!("active = {}", current_env()));
     
     // Check for other common env vars
     let env_vars = ["NULLARY_ENV", "ENV", "NODE_ENV", "RUST_ENV"];
@@ -89,94 +49,30 @@ fn get_environment_info() -> String {
     }
     
     let result = info.join(", ");
-    println!("🌍 Environment Info: {}", result);
+    info!("🌍 Environment Info: {}", result);
     result
 }
 
-/// Get or create sessionless instance using environment variable or default key
-async fn get_sessionless() -> Result<Sessionless, String> {
-    let private_key = env::var("PRIVATE_KEY").unwrap_or_else(|_| {
-        println!("🔑 Using default development private key");
-        String::from("b75011b167c5e3a6b0de97d8e1950cd9548f83bb67f47112bed6a082db795496")
-    });
-    
-    println!("🔐 Initializing sessionless with key: {}...", &private_key[..8]);
-    let sessionless =
-        Sessionless::from_private_key(PrivateKey::from_hex(private_key).expect("private key"));
-    println!("✅ Sessionless initialized successfully");
-    Ok(sessionless)
-}
-
 /// Create a new Fount user for MAGIC transactions
 #[tauri::command]
-async fn create_fount_user() -> Result<FountUser, String> {
-    let s = get_sessionless().await;
-    match s {
-        Ok(sessionless) => {
-            let fount = Fount::new(
-                Some("https://dev.fount.allyabase.com/".to_string()),
-                Some(sessionless),
-            );
-            let _user = fount.create_user().await;
-            dbg!(&_user);
-            return match _user {
-                Ok(user) => Ok(user),
-                Err(_) => Err("no user".to_string()),
-            };
-        }
-        Err(_) => Err("no user".to_string()),
-    }
+async fn create_fount_user() -> Result<FountUser, NullaryError> {
+    nullary_core::create_fount_user().await
 }
 
 /// Create a new BDO user for big dumb object storage
 #[tauri::command]
-async fn create_bdo_user() -> Result<BDOUser, String> {
-    let s = get_sessionless().await;
-    let rhapsold = "rhapsold";
-    
-    // Get BDO URL based on environment
-    let bdo_url = get_service_url("bdo");
-    let env = env::var("RHAPSOLD_ENV").unwrap_or_else(|_| "dev".to_string());
-    
-    println!("🔗 Creating BDO user on: {} (env: {})", bdo_url, env);
-    
-    match s {
-        Ok(sessionless) => {
-            let bdo = BDO::new(
-                Some(bdo_url),
-                Some(sessionless),
-            );
-            let _user = bdo.create_user(&rhapsold, &json!({})).await;
-            dbg!(&_user);
-            return match _user {
-                Ok(user) => Ok(user),
-                Err(_) => Err("no user".to_string()),
-            };
-        }
-        Err(_) => Err("no user".to_string()),
-    }
+async fn create_bdo_user() -> Result<BDOUser, NullaryError> {
+    nullary_core::create_bdo_user("rhapsold").await
 }
 
 /// Get available bases (servers) for connecting to
 #[tauri::command]
-async fn get_bases(uuid: &str, bdo_url: &str) -> Result<Value, String> {
-    let s = get_sessionless().await;
-    let rhapsold = "rhapsold";
-    match s {
-        Ok(sessionless) => {
-            let bdo = BDO::new(Some(bdo_url.to_string()), Some(sessionless));
-            let bases_result = bdo.get_bases(&uuid, &rhapsold).await;
-
-            match bases_result {
-                Ok(bases) => Ok(bases),
-                Err(e) => {
-                    dbg!(e);
-                    Err("no bases".to_string())
-                }
-            }
-        }
-        Err(_) => Err("no bdo".to_string()),
-    }
+async fn get_bases(uuid: &str, bdo_url: &str) -> Result<BTreeMap<String, BaseData>, NullaryError> {
+    // Discovery signs as this app's own BDO user, which is the one `uuid`
+    // was created as
+    debug!("🔍 Discovering bases at {} for {}", bdo_url, uuid);
+    let page = discover_bases_at(bdo_url, false).await?;
+    Ok(bases_by_id(page.data))
 }
 
 /// Create payment intent with splits for multiple payees
@@ -185,36 +81,33 @@ async fn get_payment_intent_with_splits(
     amount: u32,
     currency: &str,
     payees: Vec<Payee>
-) -> Result<PaymentIntent, String> {
-    let s = get_sessionless().await;
+) -> Result<PaymentIntent, NullaryError> {
     let stripe = "stripe";
 
-    match s {
-        Ok(sessionless) => {
-            let addie = Addie::new(
-                Some("https://livetest.addie.allyabase.com/".to_string()),
-                Some(sessionless),
-            );
-            let addie_user = match addie.create_user().await {
-                Ok(user) => user,
-                Err(_) => {
-                    dbg!("The problem is getting the user");
-                    return Ok(PaymentIntent::new());
+    match addie_client().await {
+        Ok(addie) => {
+            let addie_uuid = match addie_user_uuid().await {
+                Ok(uuid) => uuid,
+                Err(e) => {
+                    warn!("The problem is getting the user");
+                    return Err(e);
                 }
             };
 
             match addie
-                .get_payment_intent(&addie_user.uuid, &stripe, &amount, &currency, &payees)
+                .get_payment_intent(&addie_uuid, &stripe, &amount, &currency, &payees)
                 .await
             {
                 Ok(intent) => Ok(intent),
                 Err(err) => {
-                    dbg!("the intent failed for some reason {}", err);
-                    return Ok(PaymentIntent::new());
+                    let error = NullaryError::service("addie", format!("Failed to create payment intent: {}", err));
+                    forget_if_not_found(&get_service_url("addie"), "addie", None, &error);
+                    error!("the intent failed for some reason {}", error);
+                    Err(error)
                 }
             }
         }
-        Err(_) => Ok(PaymentIntent::new()),
+        Err(e) => Err(e),
     }
 }
 
@@ -223,321 +116,307 @@ async fn get_payment_intent_with_splits(
 async fn get_payment_intent_without_splits(
     amount: u32,
     currency: &str,
-) -> Result<PaymentIntent, String> {
-    let s = get_sessionless().await;
+) -> Result<PaymentIntent, NullaryError> {
     let stripe = "stripe";
 
-    match s {
-        Ok(sessionless) => {
-            let addie = Addie::new(
-                Some("https://livetest.addie.allyabase.com/".to_string()),
-                Some(sessionless),
-            );
-            let addie_user = match addie.create_user().await {
-                Ok(user) => user,
-                Err(_) => {
-                    dbg!("The problem is getting the user");
-                    return Ok(PaymentIntent::new());
+    match addie_client().await {
+        Ok(addie) => {
+            let addie_uuid = match addie_user_uuid().await {
+                Ok(uuid) => uuid,
+                Err(e) => {
+                    warn!("The problem is getting the user");
+                    return Err(e);
                 }
             };
 
             match addie
-                .get_payment_intent_without_splits(&addie_user.uuid, &stripe, &amount, &currency)
+                .get_payment_intent_without_splits(&addie_uuid, &stripe, &amount, &currency)
                 .await
             {
                 Ok(intent) => Ok(intent),
                 Err(err) => {
-                    dbg!("the intent failed for some reason {}", err);
-                    return Ok(PaymentIntent::new());
+                    let error = NullaryError::service("addie", format!("Failed to create payment intent: {}", err));
+                    forget_if_not_found(&get_service_url("addie"), "addie", None, &error);
+                    error!("the intent failed for some reason {}", error);
+                    Err(error)
                 }
             }
         }
-        Err(_) => Ok(PaymentIntent::new()),
+        Err(e) => Err(e),
     }
 }
 
 /// Create a new Dolores user for video/media storage
 #[tauri::command]
-async fn create_dolores_user(dolores_url: &str) -> Result<DoloresUser, String> {
-    let s = get_sessionless().await;
-    match s {
-        Ok(sessionless) => {
-            let dolores = Dolores::new(Some(dolores_url.to_string()), Some(sessionless));
-            let _user = dolores.create_user().await;
-            dbg!(&_user);
-            return match _user {
-                Ok(user) => Ok(user),
-                Err(_) => Err("no user".to_string()),
-            };
-        }
-        Err(_) => Err("no user".to_string()),
-    }
+async fn create_dolores_user(dolores_url: &str) -> Result<DoloresUser, NullaryError> {
+    nullary_core::create_dolores_user_at(dolores_url).await
 }
 
 /// Get media feed from Dolores with specific tags
 #[tauri::command]
-async fn get_feed(uuid: &str, dolores_url: &str, tags: &str) -> Result<Feed, String> {
-    let s = get_sessionless().await;
-    match s {
-        Ok(sessionless) => {
-            let dolores = Dolores::new(Some(dolores_url.to_string()), Some(sessionless));
+async fn get_feed(uuid: &str, dolores_url: &str, tags: &str) -> Result<Feed, NullaryError> {
+    match dolores_client_at(dolores_url).await {
+        Ok(dolores) => {
             let feed_result = dolores.get_feed(&uuid, &tags).await;
 
             match feed_result {
                 Ok(feed) => Ok(feed),
                 Err(e) => {
-                    dbg!(e);
-                    Err("no feed".to_string())
+                    error!("{:?}", e);
+                    Err(NullaryError::service("dolores", format!("Failed to get feed: {}", e)))
                 }
             }
         }
-        Err(_) => Err("no dolores".to_string()),
+        Err(e) => Err(e),
     }
 }
 
 /// Create a new Sanora user for blog product hosting
 #[tauri::command]
-async fn create_sanora_user(sanora_url: &str) -> Result<SanoraUser, String> {
-    println!("🏗️  Creating Sanora user at URL: {}", sanora_url);
-    
-    let s = get_sessionless().await;
-    match s {
-        Ok(sessionless) => {
-            println!("🔗 Connecting to Sanora at: {}", sanora_url);
-            let sanora = Sanora::new(Some(sanora_url.to_string()), Some(sessionless));
-            
-            println!("📡 Making create_user API call to Sanora...");
-            let _user = sanora.create_user().await;
-            
-            match &_user {
-                Ok(user) => {
-                    println!("✅ Sanora user created successfully!");
-                    println!("   UUID: {}", user.uuid);
-                    println!("   Public Key: {}", user.pub_key);
-                },
-                Err(e) => {
-                    println!("❌ Failed to create Sanora user: {:?}", e);
-                }
-            }
-            
-            return match _user {
-                Ok(user) => Ok(user),
-                Err(e) => {
-                    let error_msg = format!("Failed to create Sanora user: {:?}", e);
-                    println!("🚨 {}", error_msg);
-                    Err(error_msg)
-                },
-            };
-        }
-        Err(e) => {
-            let error_msg = format!("Failed to get sessionless: {}", e);
-            println!("🚨 {}", error_msg);
-            Err(error_msg)
-        },
-    }
+async fn create_sanora_user(sanora_url: &str) -> Result<SanoraUser, NullaryError> {
+    nullary_core::create_sanora_user_at(sanora_url).await
 }
 
 /// Get orders for a specific blog product
 #[tauri::command]
-async fn get_orders_for_product_id(uuid: &str, sanora_url: &str, product_id: &str) -> Result<Orders, String> {
-    let s = get_sessionless().await;
-    match s {
-        Ok(sessionless) => {
-            let sanora = Sanora::new(Some(sanora_url.to_string()), Some(sessionless));
+async fn get_orders_for_product_id(uuid: &str, sanora_url: &str, product_id: &str) -> Result<Orders, NullaryError> {
+    match sanora_client_at(sanora_url).await {
+        Ok(sanora) => {
             let orders_result = sanora.get_orders_for_product_id(&uuid, &product_id).await;
         
             match orders_result {
                 Ok(orders) => Ok(orders),
                 Err(e) => {
-                    dbg!(e);
-                    Err("failed to get orders".to_string())
+                    error!("{:?}", e);
+                    Err(NullaryError::service("sanora", format!("Failed to get orders: {}", e)))
                 }
             }
         }
-        Err(_) => Err("no sanora".to_string()),
+        Err(e) => Err(e),
     }
 }
 
-/// Add an order for a blog product
-#[tauri::command]
-async fn add_order(uuid: &str, sanora_url: &str, order: Order) -> Result<SanoraUser, String> {
-    let s = get_sessionless().await;
-    match s {
-        Ok(sessionless) => {
-            let sanora = Sanora::new(Some(sanora_url.to_string()), Some(sessionless));
-            let order_result = sanora.add_order(&uuid, &order).await;
+/// An order as the outbox stores and replays it
+#[derive(Serialize, Deserialize)]
+struct NewOrder {
+    uuid: String,
+    sanora_url: String,
+    order: Order,
+}
+
+/// Send an order to Sanora
+async fn send_order(new_order: &NewOrder) -> Result<SanoraUser, NullaryError> {
+    match sanora_client_at(&new_order.sanora_url).await {
+        Ok(sanora) => {
+            let order_result = sanora.add_order(&new_order.uuid, &new_order.order).await;
 
             match order_result {
                 Ok(user) => Ok(user),
                 Err(e) => {
-                    dbg!(e);
-                    Err("failed to add order".to_string())
+                    error!("{:?}", e);
+                    Err(NullaryError::service("sanora", format!("Failed to add order: {}", e)))
                 }
             }
         }
-        Err(_) => Err("no sanora".to_string()),
+        Err(e) => Err(e),
     }
 }
 
-/// Create a new blog product in Sanora
+/// Add an order for a blog product, queued in the outbox while Sanora is unreachable
 #[tauri::command]
-async fn add_product(uuid: &str, sanora_url: &str, title: &str, description: &str, price: u32) -> Result<ProductMeta, String> {
-    let s = get_sessionless().await;
-    match s {
-        Ok(sessionless) => {
-            let sanora = Sanora::new(Some(sanora_url.to_string()), Some(sessionless));
-            let product_result = sanora.add_product(&uuid, &title, &description, &price).await;
+async fn add_order(uuid: &str, sanora_url: &str, order: Order) -> Result<SanoraUser, NullaryError> {
+    let new_order = NewOrder {
+        uuid: uuid.to_string(),
+        sanora_url: sanora_url.to_string(),
+        order,
+    };
+    let result = send_order(&new_order).await;
+    queue_if_transient(result, "add_order", "sanora", sanora_url, &new_order).await
+}
+
+/// A product as the outbox stores and replays it
+#[derive(Serialize, Deserialize)]
+struct NewProduct {
+    uuid: String,
+    sanora_url: String,
+    title: String,
+    description: String,
+    price: u32,
+}
+
+/// Send a product to Sanora
+async fn send_product(product: &NewProduct) -> Result<ProductMeta, NullaryError> {
+    match sanora_client_at(&product.sanora_url).await {
+        Ok(sanora) => {
+            let product_result = sanora.add_product(&product.uuid, &product.title, &product.description, &product.price).await;
 
             match product_result {
                 Ok(meta) => Ok(meta),
                 Err(e) => {
-                    dbg!(e);
-                    Err("failed to add product".to_string())
+                    error!("{:?}", e);
+                    Err(NullaryError::service("sanora", format!("Failed to add product: {}", e)))
                 }
             }
         }
-        Err(_) => Err("no sanora".to_string()),
+        Err(e) => Err(e),
     }
 }
 
+/// Create a new blog product in Sanora, queued in the outbox while Sanora is unreachable
+#[tauri::command]
+async fn add_product(uuid: &str, sanora_url: &str, title: &str, description: &str, price: u32) -> Result<ProductMeta, NullaryError> {
+    let product = NewProduct {
+        uuid: uuid.to_string(),
+        sanora_url: sanora_url.to_string(),
+        title: title.to_string(),
+        description: description.to_string(),
+        price,
+    };
+    let result = send_product(&product).await;
+    queue_if_transient(result, "add_product", "sanora", sanora_url, &product).await
+}
+
 /// Get Sanora user information (which includes their products)
 #[tauri::command]
-async fn get_sanora_user(uuid: &str, sanora_url: &str) -> Result<SanoraUser, String> {
-    println!("📚 Getting Sanora user data:");
-    println!("   UUID: {}", uuid);
-    println!("   Sanora URL: {}", sanora_url);
+async fn get_sanora_user(uuid: &str, sanora_url: &str) -> Result<SanoraUser, NullaryError> {
+    info!("📚 Getting Sanora user data:");
+    info!("   UUID: {}", uuid);
+    info!("   Sanora URL: {}", sanora_url);
     
-    let s = get_sessionless().await;
-    match s {
-        Ok(sessionless) => {
-            println!("🔗 Connecting to Sanora service...");
-            let sanora = Sanora::new(Some(sanora_url.to_string()), Some(sessionless));
+    match sanora_client_at(sanora_url).await {
+        Ok(sanora) => {
+            info!("🔗 Connecting to Sanora service...");
             
-            println!("📡 Making get_user_by_uuid API call...");
+            info!("📡 Making get_user_by_uuid API call...");
             let user_result = sanora.get_user_by_uuid(&uuid).await;
 
             match user_result {
                 Ok(user) => {
-                    println!("✅ Successfully retrieved Sanora user!");
-                    println!("   UUID: {}", user.uuid);
-                    println!("   Public Key: {}", user.pub_key);
-                    println!("   Base Public Key: {}", user.base_pub_key);
+                    info!("✅ Successfully retrieved Sanora user!");
+                    info!("   UUID: {}", user.uuid);
+                    info!("   Public Key: {}", user.pub_key);
+                    info!("   Base Public Key: {}", user.base_pub_key);
                     Ok(user)
                 },
                 Err(e) => {
-                    println!("❌ Failed to get Sanora user: {:?}", e);
-                    let error_msg = format!("Failed to get Sanora user: {:?}", e);
-                    Err(error_msg)
+                    error!("❌ Failed to get Sanora user: {:?}", e);
+                    Err(NullaryError::service("sanora", format!("Failed to get Sanora user: {}", e)))
                 }
             }
         }
         Err(e) => {
-            let error_msg = format!("Failed to get sessionless: {}", e);
-            println!("🚨 {}", error_msg);
-            Err(error_msg)
+            info!("🚨 Failed to get sessionless: {}", e);
+            Err(e)
         },
     }
 }
 
 /// Get all products/blogs available on the entire base (new /products/base endpoint)
 #[tauri::command]
-async fn get_all_base_products(sanora_url: &str) -> Result<Value, String> {
-    println!("🔄 Getting ALL base products/blogs from: {}", sanora_url);
-    
-    let client = Client::new();
+async fn get_all_base_products(sanora_url: &str) -> Result<Value, NullaryError> {
+    info!("🔄 Getting ALL base products/blogs from: {}", sanora_url);
     
     // Use the new /products/base endpoint (no authentication required)
     let products_url = format!("{}/products/base", sanora_url.trim_end_matches('/'));
     
-    println!("🔍 Trying base products endpoint: {}", products_url);
+    debug!("🔍 Trying base products endpoint: {}", products_url);
     
-    match client.get(&products_url).send().await {
+    match http::get("sanora", &products_url).await {
         Ok(response) => {
             let status = response.status();
-            println!("📡 Response status: {}", status);
+            info!("📡 Response status: {}", status);
             
             if status.is_success() {
                 match response.text().await {
                     Ok(body) => {
-                        println!("📄 Response body: {}", body);
+                        debug!(bytes = body.len(), "📄 Response body");
                         
                         // Try to parse as JSON
                         match serde_json::from_str::<Value>(&body) {
                             Ok(json_value) => {
-                                println!("✅ Got products/blogs JSON from base endpoint");
+                                info!("✅ Got products/blogs JSON from base endpoint");
                                 Ok(json_value)
                             }
                             Err(_) => {
                                 // If it's not JSON, return it as a string in an array
-                                println!("⚠️ Response is not JSON, wrapping as string");
+                                warn!("⚠️ Response is not JSON, wrapping as string");
                                 Ok(json!([{"response": body}]))
                             }
                         }
                     }
                     Err(e) => {
-                        println!("❌ Failed to read response body: {}", e);
-                        Err(format!("Failed to read response: {}", e))
+                        error!("❌ Failed to read response body: {}", e);
+                        Err(NullaryError::from_reqwest("sanora", "Failed to read response", &e))
                     }
                 }
             } else {
                 let error_body = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-                println!("❌ HTTP error {}: {}", status.as_u16(), error_body);
-                
-                // Instead of failing, return empty array for now
-                println!("⚠️ Returning empty array due to HTTP error");
-                Ok(json!([]))
+                error!("❌ HTTP error {}: {}", status.as_u16(), error_body);
+                Err(NullaryError::from_status("sanora", status.as_u16(), error_body))
             }
         }
         Err(e) => {
-            println!("❌ Request failed: {}", e);
-            Err(format!("Request failed: {}", e))
+            error!("❌ Request failed: {}", e);
+            Err(e)
         }
     }
 }
 
 /// Teleport content from a URL via BDO
 #[tauri::command]
-async fn teleport_content(bdo_url: &str, teleport_url: &str) -> Result<Value, String> {
-    println!("🌐 Teleporting content from: {} via BDO: {}", teleport_url, bdo_url);
+async fn teleport_content(bdo_url: &str, teleport_url: &str) -> Result<Value, NullaryError> {
+    info!("🌐 Teleporting content from: {} via BDO: {}", teleport_url, bdo_url);
     
-    let s = get_sessionless().await;
-    match s {
-        Ok(sessionless) => {
-            let bdo = BDO::new(Some(bdo_url.to_string()), Some(sessionless));
+    match bdo_client_at(bdo_url).await {
+        Ok(bdo) => {
             
             // Create/get BDO user first
             let rhapsold = "rhapsold";
-            let bdo_user = match bdo.create_user(&rhapsold, &json!({})).await {
-                Ok(user) => {
-                    println!("✅ BDO user ready for teleportation: {}", user.uuid);
-                    user
+            let bdo_uuid = match bdo_user_uuid_at(bdo_url, rhapsold).await {
+                Ok(uuid) => {
+                    info!("✅ BDO user ready for teleportation: {}", uuid);
+                    uuid
                 }
                 Err(e) => {
-                    println!("❌ Failed to create BDO user: {:?}", e);
-                    return Err(format!("Failed to create BDO user: {}", e));
+                    error!("❌ Failed to create BDO user: {:?}", e);
+                    return Err(e);
                 }
             };
             
             // Now teleport the content
-            println!("🚀 Starting teleportation with uuid: {}", bdo_user.uuid);
-            match bdo.teleport(&bdo_user.uuid, &rhapsold, teleport_url).await {
+            info!("🚀 Starting teleportation with uuid: {}", bdo_uuid);
+            match bdo.teleport(&bdo_uuid, &rhapsold, teleport_url).await {
                 Ok(teleported_content) => {
-                    println!("✅ Successfully teleported content: {:?}", teleported_content);
+                    info!("✅ Successfully teleported content: {:?}", teleported_content);
                     Ok(teleported_content)
                 }
                 Err(e) => {
-                    println!("❌ Teleportation failed: {:?}", e);
-                    Err(format!("Teleportation failed: {}", e))
+                    error!("❌ Teleportation failed: {:?}", e);
+                    let error = NullaryError::service("bdo", format!("Teleportation failed: {}", e));
+                    forget_if_not_found(bdo_url, "bdo", Some(rhapsold), &error);
+                    Err(error)
                 }
             }
         }
         Err(e) => {
-            println!("❌ Failed to get sessionless instance: {}", e);
-            Err(format!("Failed to get sessionless: {}", e))
+            error!("❌ Failed to get sessionless instance: {}", e);
+            Err(e)
         }
     }
 }
 
+/// Replay orders and products queued while Sanora was unreachable
+fn register_outbox_handlers() {
+    nullary_core::register_outbox_handler("add_order", |payload| async move {
+        let new_order: NewOrder = nullary_core::outbox_payload(payload)?;
+        Ok(json!(send_order(&new_order).await?))
+    });
+    nullary_core::register_outbox_handler("add_product", |payload| async move {
+        let product: NewProduct = nullary_core::outbox_payload(payload)?;
+        Ok(json!(send_product(&product).await?))
+    });
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
@@ -560,39 +439,71 @@ pub fn run() {
             add_product,
             get_sanora_user,
             get_all_base_products,
-            teleport_content
+            teleport_content,
+            // User Persistence Functions
+            nullary_core::generate_sessionless_keys,
+            nullary_core::stronghold_init,
+            nullary_core::stronghold_get_record,
+            nullary_core::stronghold_set_record,
+            nullary_core::stronghold_clear_vault,
+            nullary_core::read_user_data_file,
+            nullary_core::write_user_data_file,
+            nullary_core::clear_user_data,
+            // Documents
+            nullary_core::read_document,
+            nullary_core::write_document,
+            nullary_core::delete_document,
+            nullary_core::list_documents,
+            // Personas
+            nullary_core::list_personas,
+            nullary_core::create_persona,
+            nullary_core::rename_persona,
+            nullary_core::delete_persona,
+            nullary_core::switch_persona,
+            nullary_core::get_persona_service_uuids,
+            nullary_core::set_persona_service_uuid,
+            // Identity backup
+            nullary_core::export_identity_backup,
+            nullary_core::import_identity_backup,
+            nullary_core::export_recovery_words,
+            nullary_core::import_recovery_words,
+            // Key rotation
+            nullary_core::rotate_identity,
+            nullary_core::retry_identity_rotation,
+            nullary_core::get_key_rotations,
+            // Signing
+            nullary_core::get_signing_journal,
+            // Logs
+            nullary_core::export_logs,
+            // Health
+            nullary_core::check_service_health,
+            // Outbox
+            nullary_core::list_outbox,
+            nullary_core::retry_outbox_item,
+            nullary_core::cancel_outbox_item,
+            // Endpoint configuration
+            nullary_core::get_environment_config,
+            nullary_core::switch_environment
         ])
-        .setup(|_app| {
-            println!("🎭 Rhapsold backend is starting up...");
-            
-            // Log environment variables at startup
-            match env::var("RHAPSOLD_ENV") {
-                Ok(env_val) => {
-                    println!("🌍 RHAPSOLD_ENV detected: {}", env_val);
-                }
-                Err(_) => {
-                    println!("🌍 RHAPSOLD_ENV not set, using default");
-                }
+        .setup(|app| {
+            info!("🎭 Rhapsold backend is starting up...");
+
+            // Register the app and load (or create on first launch) its identity
+            if let Err(e) = nullary_core::init(app.handle(), "rhapsold") {
+                error!("❌ Failed to initialize nullary core: {}", e);
             }
+            register_outbox_handlers();
             
-            // Show other relevant env vars
-            let env_vars = ["NULLARY_ENV", "PRIVATE_KEY"];
-            for var in &env_vars {
-                match env::var(var) {
-                    Ok(val) => {
-                        if var == &"PRIVATE_KEY" {
-                            println!("🔑 {} = {}... (truncated)", var, &val[..8]);
-                        } else {
-                            println!("🌍 {} = {}", var, val);
-                        }
-                    }
-                    Err(_) => {
-                        println!("🌍 {} = (not set)", var);
-                    }
-                }
+            // Show relevant env vars; only whether the key override is set
+            match env::var("NULLARY_ENV") {
+                Ok(val) => info!("🌍 NULLARY_ENV = {}", val),
+                Err(_) => info!("🌍 NULLARY_ENV = (not set)"),
+            }
+            if env::var("PRIVATE_KEY").is_ok() {
+                info!("🔑 PRIVATE_KEY override is set");
             }
             
-            println!("✅ Rhapsold backend startup complete!");
+            info!("✅ Rhapsold backend startup complete!");
             Ok(())
         })
         .run(tauri::generate_context!())
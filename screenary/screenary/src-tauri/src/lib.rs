@@ -1,96 +1,49 @@
 use addie_rs::structs::{PaymentIntent, Payee};
-use addie_rs::Addie;
 use bdo_rs::structs::BDOUser;
-use bdo_rs::{Bases, Spellbook, BDO};
+use bdo_rs::{Bases, Spellbook};
 use dolores_rs::structs::Feed;
-use dolores_rs::{Dolores, DoloresUser};
+use dolores_rs::DoloresUser;
 use fount_rs::structs::Gateway;
-use fount_rs::{Fount, FountUser};
+use fount_rs::FountUser;
 use sanora_rs::structs::{Order, SanoraUser};
-use sanora_rs::{Orders, Sanora};
+use sanora_rs::Orders;
 use reqwest::Client;
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 use serde_json::Value;
-use sessionless::hex::FromHex;
-use sessionless::hex::IntoHex;
-use sessionless::{PrivateKey, Sessionless};
-use std::env;
+use std::collections::BTreeMap;
 use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{debug, error, info, warn};
+
+// Shared identity, endpoints and service clients
+use nullary_core::{
+    addie_client, addie_user_uuid, bases_by_id, discover_bases_at, dolores_client_at,
+    forget_if_not_found, get_service_url, queue_if_transient, sanora_client_at, BaseData,
+    NullaryError,
+};
 
 #[tauri::command]
 fn dbg(log: &str) {
-    dbg!(log);
-}
-
-async fn get_sessionless() -> Result<Sessionless, String> {
-    let private_key = env::var("PRIVATE_KEY").unwrap_or_else(|_| {
-        String::from("b75011b167c5e3a6b0de97d8e1950cd9548f83bb67f47112bed6a082db795496")
-    });
-    let sessionless =
-        Sessionless::from_private_key(PrivateKey::from_hex(private_key).expect("private key"));
-    Ok(sessionless)
+    info!(target: "frontend", "{}", log);
 }
 
 #[tauri::command]
-async fn create_fount_user() -> Result<FountUser, String> {
-    let s = get_sessionless().await;
-    match s {
-        Ok(sessionless) => {
-            let fount = Fount::new(
-                Some("https://dev.fount.allyabase.com/".to_string()),
-                Some(sessionless),
-            );
-            let _user = fount.create_user().await;
-            dbg!(&_user);
-            return match _user {
-                Ok(user) => Ok(user),
-                Err(_) => Err("no user".to_string()),
-            };
-        }
-        Err(_) => Err("no user".to_string()),
-    }
+async fn create_fount_user() -> Result<FountUser, NullaryError> {
+    nullary_core::create_fount_user().await
 }
 
 #[tauri::command]
-async fn create_bdo_user() -> Result<BDOUser, String> {
-    let s = get_sessionless().await;
-    let screenary = "screenary";
-    match s {
-        Ok(sessionless) => {
-            let bdo = BDO::new(
-                Some("https://dev.bdo.allyabase.com/".to_string()),
-                Some(sessionless),
-            );
-            let _user = bdo.create_user(&screenary, &json!({})).await;
-            dbg!(&_user);
-            return match _user {
-                Ok(user) => Ok(user),
-                Err(_) => Err("no user".to_string()),
-            };
-        }
-        Err(_) => Err("no user".to_string()),
-    }
+async fn create_bdo_user() -> Result<BDOUser, NullaryError> {
+    nullary_core::create_bdo_user("screenary").await
 }
 
 #[tauri::command]
-async fn get_bases(uuid: &str, bdo_url: &str) -> Result<Value, String> {
-    let s = get_sessionless().await;
-    let screenary = "screenary";
-    match s {
-        Ok(sessionless) => {
-            let bdo = BDO::new(Some(bdo_url.to_string()), Some(sessionless));
-            let bases_result = bdo.get_bases(&uuid, &screenary).await;
-
-            match bases_result {
-                Ok(bases) => Ok(bases),
-                Err(e) => {
-                    dbg!(e);
-                    Err("no bases".to_string())
-                }
-            }
-        }
-        Err(_) => Err("no bdo".to_string()),
-    }
+async fn get_bases(uuid: &str, bdo_url: &str) -> Result<BTreeMap<String, BaseData>, NullaryError> {
+    // Discovery signs as this app's own BDO user, which is the one `uuid`
+    // was created as
+    debug!("🔍 Discovering bases at {} for {}", bdo_url, uuid);
+    let page = discover_bases_at(bdo_url, false).await?;
+    Ok(bases_by_id(page.data))
 }
 
 #[tauri::command(rename_all = "snake_case")]
@@ -98,36 +51,33 @@ async fn get_payment_intent_with_splits(
     amount: u32,
     currency: &str,
     payees: Vec<Payee>
-) -> Result<PaymentIntent, String> {
-    let s = get_sessionless().await;
+) -> Result<PaymentIntent, NullaryError> {
     let stripe = "stripe";
 
-    match s {
-        Ok(sessionless) => {
-            let addie = Addie::new(
-                Some("https://livetest.addie.allyabase.com/".to_string()),
-                Some(sessionless),
-            );
-            let addie_user = match addie.create_user().await {
-                Ok(user) => user,
-                Err(_) => {
-                    dbg!("The problem is getting the user");
-                    return Ok(PaymentIntent::new());
+    match addie_client().await {
+        Ok(addie) => {
+            let addie_uuid = match addie_user_uuid().await {
+                Ok(uuid) => uuid,
+                Err(e) => {
+                    warn!("The problem is getting the user");
+                    return Err(e);
                 }
             };
 
             match addie
-                .get_payment_intent(&addie_user.uuid, &stripe, &amount, &currency, &payees)
+                .get_payment_intent(&addie_uuid, &stripe, &amount, &currency, &payees)
                 .await
             {
                 Ok(intent) => Ok(intent),
                 Err(err) => {
-                    dbg!("the intent failed for some reason {}", err);
-                    return Ok(PaymentIntent::new());
+                    let error = NullaryError::service("addie", format!("Failed to create payment intent: {}", err));
+                    forget_if_not_found(&get_service_url("addie"), "addie", None, &error);
+                    error!("the intent failed for some reason {}", error);
+                    Err(error)
                 }
             }
         }
-        Err(_) => Ok(PaymentIntent::new()),
+        Err(e) => Err(e),
     }
 }
 
@@ -137,139 +87,140 @@ async fn get_payment_intent_with_splits(
 async fn get_payment_intent_without_splits(
     amount: u32,
     currency: &str,
-) -> Result<PaymentIntent, String> {
-    let s = get_sessionless().await;
+) -> Result<PaymentIntent, NullaryError> {
     let stripe = "stripe";
 
-    match s {
-        Ok(sessionless) => {
-            let addie = Addie::new(
-                Some("https://livetest.addie.allyabase.com/".to_string()),
-                Some(sessionless),
-            );
-            let addie_user = match addie.create_user().await {
-                Ok(user) => user,
-                Err(_) => {
-                    dbg!("The problem is getting the user");
-                    return Ok(PaymentIntent::new());
+    match addie_client().await {
+        Ok(addie) => {
+            let addie_uuid = match addie_user_uuid().await {
+                Ok(uuid) => uuid,
+                Err(e) => {
+                    warn!("The problem is getting the user");
+                    return Err(e);
                 }
             };
 
             match addie
-                .get_payment_intent_without_splits(&addie_user.uuid, &stripe, &amount, &currency)
+                .get_payment_intent_without_splits(&addie_uuid, &stripe, &amount, &currency)
                 .await
             {
                 Ok(intent) => Ok(intent),
                 Err(err) => {
-                    dbg!("the intent failed for some reason {}", err);
-                    return Ok(PaymentIntent::new());
+                    let error = NullaryError::service("addie", format!("Failed to create payment intent: {}", err));
+                    forget_if_not_found(&get_service_url("addie"), "addie", None, &error);
+                    error!("the intent failed for some reason {}", error);
+                    Err(error)
                 }
             }
         }
-        Err(_) => Ok(PaymentIntent::new()),
+        Err(e) => Err(e),
     }
 }
 
 #[tauri::command]
-async fn create_dolores_user(dolores_url: &str) -> Result<DoloresUser, String> {
-    let s = get_sessionless().await;
-    match s {
-        Ok(sessionless) => {
-            let dolores = Dolores::new(Some(dolores_url.to_string()), Some(sessionless));
-            let _user = dolores.create_user().await;
-            dbg!(&_user);
-            return match _user {
-                Ok(user) => Ok(user),
-                Err(_) => Err("no user".to_string()),
-            };
-        }
-        Err(_) => Err("no user".to_string()),
-    }
+async fn create_dolores_user(dolores_url: &str) -> Result<DoloresUser, NullaryError> {
+    nullary_core::create_dolores_user_at(dolores_url).await
 }
 
 #[tauri::command]
-async fn get_feed(uuid: &str, dolores_url: &str, tags: &str) -> Result<Feed, String> {
-    let s = get_sessionless().await;
-    match s {
-        Ok(sessionless) => {
-            let dolores = Dolores::new(Some(dolores_url.to_string()), Some(sessionless));
+async fn get_feed(uuid: &str, dolores_url: &str, tags: &str) -> Result<Feed, NullaryError> {
+    match dolores_client_at(dolores_url).await {
+        Ok(dolores) => {
             let feed_result = dolores.get_feed(&uuid, &tags).await;
 
             match feed_result {
                 Ok(feed) => Ok(feed),
                 Err(e) => {
-                    dbg!(e);
-                    Err("no feed".to_string())
+                    error!("{:?}", e);
+                    Err(NullaryError::service("dolores", format!("Failed to get feed: {}", e)))
                 }
             }
         }
-        Err(_) => Err("no dolores".to_string()),
+        Err(e) => Err(e),
     }
 }
 
 #[tauri::command]
-async fn create_sanora_user(sanora_url: &str) -> Result<SanoraUser, String> {
-    let s = get_sessionless().await;
-    match s {
-        Ok(sessionless) => {
-            let sanora = Sanora::new(Some(sanora_url.to_string()), Some(sessionless));
-            let _user = sanora.create_user().await;
-            dbg!(&_user);
-            return match _user {
-                Ok(user) => Ok(user),
-                Err(_) => Err("no user".to_string()),
-            };
-        }
-        Err(_) => Err("no user".to_string()),
-    }
+async fn create_sanora_user(sanora_url: &str) -> Result<SanoraUser, NullaryError> {
+    nullary_core::create_sanora_user_at(sanora_url).await
 }
 
 #[tauri::command]
-async fn get_orders_for_product_id(uuid: &str, sanora_url: &str, product_id: &str) -> Result<Orders, String> {
-    let s = get_sessionless().await;
-    match s {
-        Ok(sessionless) => {
-            let sanora = Sanora::new(Some(sanora_url.to_string()), Some(sessionless));
+async fn get_orders_for_product_id(uuid: &str, sanora_url: &str, product_id: &str) -> Result<Orders, NullaryError> {
+    match sanora_client_at(sanora_url).await {
+        Ok(sanora) => {
             let orders_result = sanora.get_orders_for_product_id(&uuid, &product_id).await;
         
             match orders_result {
                 Ok(orders) => Ok(orders),
                 Err(e) => {
-                    dbg!(e);
-                    Err("failed to get orders".to_string())
+                    error!("{:?}", e);
+                    Err(NullaryError::service("sanora", format!("Failed to get orders: {}", e)))
                 }
             }
         }
-        Err(_) => Err("no sanora".to_string()),
+        Err(e) => Err(e),
     }
 }
 
-#[tauri::command]
-async fn add_order(uuid: &str, sanora_url: &str, order: Order) -> Result<SanoraUser, String> {
-    let s = get_sessionless().await;
-    match s {
-        Ok(sessionless) => {
-            let sanora = Sanora::new(Some(sanora_url.to_string()), Some(sessionless));
-            let order_result = sanora.add_order(&uuid, &order).await;
+/// An order as the outbox stores and replays it
+#[derive(Serialize, Deserialize)]
+struct NewOrder {
+    uuid: String,
+    sanora_url: String,
+    order: Order,
+}
+
+/// Send an order to Sanora
+async fn send_order(new_order: &NewOrder) -> Result<SanoraUser, NullaryError> {
+    match sanora_client_at(&new_order.sanora_url).await {
+        Ok(sanora) => {
+            let order_result = sanora.add_order(&new_order.uuid, &new_order.order).await;
 
             match order_result {
                 Ok(user) => Ok(user),
                 Err(e) => {
-                    dbg!(e);
-                    Err("failed to add order".to_string())
+                    error!("{:?}", e);
+                    Err(NullaryError::service("sanora", format!("Failed to add order: {}", e)))
                 }
             }
         }
-        Err(_) => Err("no sanora".to_string()),
+        Err(e) => Err(e),
     }
 }
 
+/// Add an order, queued in the outbox while Sanora is unreachable
+#[tauri::command]
+async fn add_order(uuid: &str, sanora_url: &str, order: Order) -> Result<SanoraUser, NullaryError> {
+    let new_order = NewOrder {
+        uuid: uuid.to_string(),
+        sanora_url: sanora_url.to_string(),
+        order,
+    };
+    let result = send_order(&new_order).await;
+    queue_if_transient(result, "add_order", "sanora", sanora_url, &new_order).await
+}
+
+/// Replay orders queued while Sanora was unreachable
+fn register_outbox_handlers() {
+    nullary_core::register_outbox_handler("add_order", |payload| async move {
+        let new_order: NewOrder = nullary_core::outbox_payload(payload)?;
+        Ok(json!(send_order(&new_order).await?))
+    });
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     #[allow(unused_mut)]
     tauri::Builder::default()
         .plugin(tauri_plugin_fs::init())
         .setup(move |app| {
+            // Register the app and load (or create on first launch) its identity
+            if let Err(e) = nullary_core::init(app.handle(), "screenary") {
+                error!("❌ Failed to initialize nullary core: {}", e);
+            }
+            register_outbox_handlers();
+
 //            #[cfg(mobile)]
 //            {
 //                app.handle().plugin(tauri_plugin_nfc::init())?;
@@ -286,7 +237,51 @@ pub fn run() {
             get_payment_intent_with_splits,
             get_payment_intent_without_splits,
             add_order,
-            get_orders_for_product_id
+            get_orders_for_product_id,
+            // User Persistence Functions
+            nullary_core::generate_sessionless_keys,
+            nullary_core::stronghold_init,
+            nullary_core::stronghold_get_record,
+            nullary_core::stronghold_set_record,
+            nullary_core::stronghold_clear_vault,
+            nullary_core::read_user_data_file,
+            nullary_core::write_user_data_file,
+            nullary_core::clear_user_data,
+            // Documents
+            nullary_core::read_document,
+            nullary_core::write_document,
+            nullary_core::delete_document,
+            nullary_core::list_documents,
+            // Personas
+            nullary_core::list_personas,
+            nullary_core::create_persona,
+            nullary_core::rename_persona,
+            nullary_core::delete_persona,
+            nullary_core::switch_persona,
+            nullary_core::get_persona_service_uuids,
+            nullary_core::set_persona_service_uuid,
+            // Identity backup
+            nullary_core::export_identity_backup,
+            nullary_core::import_identity_backup,
+            nullary_core::export_recovery_words,
+            nullary_core::import_recovery_words,
+            // Key rotation
+            nullary_core::rotate_identity,
+            nullary_core::retry_identity_rotation,
+            nullary_core::get_key_rotations,
+            // Signing
+            nullary_core::get_signing_journal,
+            // Logs
+            nullary_core::export_logs,
+            // Health
+            nullary_core::check_service_health,
+            // Outbox
+            nullary_core::list_outbox,
+            nullary_core::retry_outbox_item,
+            nullary_core::cancel_outbox_item,
+            // Endpoint configuration
+            nullary_core::get_environment_config,
+            nullary_core::switch_environment
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
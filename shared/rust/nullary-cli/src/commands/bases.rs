@@ -0,0 +1,89 @@
+// Bases BDO knows about, and the ones this machine has joined
+
+use clap::Subcommand;
+use nullary_core::{
+    discover_bases_at, discover_bases_near_at, join_base, leave_base, NearQuery, NullaryError,
+};
+use serde_json::Value;
+
+use super::{to_json, url_or_default};
+
+#[derive(Subcommand)]
+pub enum Bases {
+    /// Bases discovered through BDO, the home base first, marked joined or not
+    List {
+        /// BDO URL; defaults to the active environment's
+        #[arg(long)]
+        bdo: Option<String>,
+        /// Ask BDO even when the cached list is fresh
+        #[arg(long)]
+        refresh: bool,
+    },
+    /// Bases nearest first from a location, which is only used on this machine
+    Near {
+        /// Latitude in degrees
+        #[arg(long, allow_negative_numbers = true, requires = "lon")]
+        lat: Option<f64>,
+        /// Longitude in degrees
+        #[arg(long, allow_negative_numbers = true, requires = "lat")]
+        lon: Option<f64>,
+        /// Measure from the bases in this postal code instead
+        #[arg(long, conflicts_with_all = ["lat", "lon"])]
+        postal_code: Option<String>,
+        /// Only bases within this many kilometres
+        #[arg(long)]
+        radius: Option<f64>,
+        /// List bases without a location too, last
+        #[arg(long)]
+        all: bool,
+        /// BDO URL; defaults to the active environment's
+        #[arg(long)]
+        bdo: Option<String>,
+        /// Ask BDO even when the cached list is fresh
+        #[arg(long)]
+        refresh: bool,
+    },
+    /// Register with a base's services and join it for every Nullary app
+    Join {
+        /// Base id or name
+        base: String,
+    },
+    /// Leave a joined base
+    Leave {
+        /// Base id or name
+        base: String,
+        /// Also delete the app's BDO user on the base (shared users are kept)
+        #[arg(long)]
+        delete_users: bool,
+    },
+}
+
+pub async fn run(command: Bases) -> Result<Value, NullaryError> {
+    match command {
+        Bases::List { bdo, refresh } => {
+            let url = url_or_default(bdo, "bdo");
+            to_json(discover_bases_at(&url, refresh).await?)
+        }
+        Bases::Near {
+            lat,
+            lon,
+            postal_code,
+            radius,
+            all,
+            bdo,
+            refresh,
+        } => {
+            let url = url_or_default(bdo, "bdo");
+            let query = NearQuery {
+                latitude: lat,
+                longitude: lon,
+                postal_code,
+                radius_km: radius,
+                include_unlocated: all,
+            };
+            to_json(discover_bases_near_at(&url, &query, refresh).await?)
+        }
+        Bases::Join { base } => to_json(join_base(&base).await?),
+        Bases::Leave { base, delete_users } => to_json(leave_base(&base, delete_users).await?),
+    }
+}
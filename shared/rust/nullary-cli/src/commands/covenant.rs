@@ -0,0 +1,106 @@
+// Covenant contracts, signed with the same payload the covenant app sends
+
+use clap::Subcommand;
+use nullary_core::{
+    get_service_url, http, http_client, public_key, sign_operation, NullaryError, SigningPurpose,
+};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+#[derive(Subcommand)]
+pub enum Covenant {
+    /// Every contract on the base
+    List,
+    /// One contract with its steps
+    Show { contract: String },
+    /// Sign a step of a contract
+    Sign {
+        contract: String,
+        step: String,
+        /// Participant UUID to sign as; a new one is generated otherwise, as
+        /// the app does each session
+        #[arg(long = "as")]
+        user_uuid: Option<String>,
+    },
+}
+
+/// `{ timestamp, userUUID, pubKey, signature }` over the user UUID and, for
+/// contract calls, the contract UUID
+async fn auth_payload(
+    user_uuid: &str,
+    contract: &str,
+) -> Result<HashMap<String, String>, NullaryError> {
+    let signed = sign_operation(
+        "covenant",
+        SigningPurpose::AuthHeader,
+        &[user_uuid, contract],
+    )
+    .await?;
+
+    Ok(HashMap::from([
+        ("timestamp".to_string(), signed.timestamp),
+        ("userUUID".to_string(), user_uuid.to_string()),
+        ("pubKey".to_string(), public_key("covenant")?),
+        ("signature".to_string(), signed.signature),
+    ]))
+}
+
+async fn read_json(response: reqwest::Response) -> Result<Value, NullaryError> {
+    let status = response.status();
+    let text = response
+        .text()
+        .await
+        .map_err(|e| NullaryError::from_reqwest("covenant", "Failed to read response", &e))?;
+    if !status.is_success() {
+        return Err(NullaryError::from_status("covenant", status.as_u16(), text));
+    }
+    serde_json::from_str(&text)
+        .map_err(|e| NullaryError::service("covenant", format!("Failed to parse response: {}", e)))
+}
+
+pub async fn run(command: Covenant) -> Result<Value, NullaryError> {
+    let covenant_url = get_service_url("covenant");
+
+    match command {
+        Covenant::List => {
+            read_json(http::get("covenant", &format!("{}contracts", covenant_url)).await?).await
+        }
+        Covenant::Show { contract } => {
+            read_json(
+                http::get(
+                    "covenant",
+                    &format!("{}contract/{}", covenant_url, contract),
+                )
+                .await?,
+            )
+            .await
+        }
+        Covenant::Sign {
+            contract,
+            step,
+            user_uuid,
+        } => {
+            let user_uuid =
+                user_uuid.unwrap_or_else(|| sessionless::Sessionless::generate_uuid().to_string());
+            let mut payload = auth_payload(&user_uuid, &contract).await?;
+            payload.insert("step_id".to_string(), step.clone());
+
+            // Step signature over timestamp + userUUID + contract + step
+            let step_signed = sign_operation(
+                "covenant",
+                SigningPurpose::CovenantStep,
+                &[&user_uuid, &contract, &step],
+            )
+            .await?;
+            payload.insert("step_signature".to_string(), step_signed.signature);
+
+            let request = http_client()
+                .put(format!("{}contract/{}/sign", covenant_url, contract))
+                .json(&payload);
+            let response = read_json(http::send("covenant", request).await?).await?;
+            Ok(
+                json!({ "contract": contract, "step": step, "userUUID": user_uuid, "response": response }),
+            )
+        }
+    }
+}
@@ -0,0 +1,22 @@
+// Environments from endpoints.json
+
+use clap::Subcommand;
+use nullary_core::{get_environment_config, switch_environment, NullaryError};
+use serde_json::Value;
+
+use super::to_json;
+
+#[derive(Subcommand)]
+pub enum Env {
+    /// Active environment, the ones available and the service URLs
+    Show,
+    /// Switch environment and save it as the default for the app
+    Switch { environment: String },
+}
+
+pub fn run(command: Env) -> Result<Value, NullaryError> {
+    match command {
+        Env::Show => to_json(get_environment_config()),
+        Env::Switch { environment } => to_json(switch_environment(environment)?),
+    }
+}
@@ -0,0 +1,40 @@
+// Dolores feed from every joined base, with how each base answered
+
+use nullary_core::{
+    aggregate_feed, dolores_feed_at, feed_sources, without_blocked_posts, FeedFetch, NullaryError,
+    Validators,
+};
+use serde_json::{json, Value};
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// Tags to filter by, as the apps pass them (e.g. a JSON array)
+    #[arg(long, default_value = "")]
+    tags: String,
+    /// Dolores URL of a single base; defaults to the home base and every
+    /// joined base
+    #[arg(long)]
+    url: Option<String>,
+}
+
+async fn fetch_posts(
+    url: String,
+    tags: String,
+    validators: Validators,
+) -> Result<FeedFetch<Vec<Value>>, NullaryError> {
+    dolores_feed_at(&url, &tags, &validators).await
+}
+
+pub async fn get(args: Args) -> Result<Value, NullaryError> {
+    let sources = feed_sources("dolores", args.url.as_deref());
+    let tags = args.tags.clone();
+    let feed = aggregate_feed(sources, "dolores", &args.tags, true, move |url, validators| {
+        fetch_posts(url, tags.clone(), validators)
+    })
+    .await?;
+    Ok(json!({
+        "posts": without_blocked_posts(feed.data),
+        "stale": feed.stale,
+        "sync": feed.sync,
+    }))
+}
@@ -0,0 +1,70 @@
+// Identity and personas
+
+use clap::Subcommand;
+use nullary_core::{
+    active_persona, app_data_dir, app_name, create_persona, current_env, delete_persona,
+    list_personas, public_key_at, rename_persona, switch_persona, NullaryError, EXTRA_SERVICES,
+    SERVICES,
+};
+use serde_json::{json, Map, Value};
+
+use super::{to_json, url_or_default};
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// Show the keys for the base at this URL instead of the environment's
+    #[arg(long)]
+    url: Option<String>,
+}
+
+#[derive(Subcommand)]
+pub enum Personas {
+    /// Every persona and which one is active
+    List,
+    /// Add a persona with fresh keys
+    Create {
+        name: String,
+    },
+    /// Make a persona the active one
+    Switch {
+        name: String,
+    },
+    Rename {
+        name: String,
+        new_name: String,
+    },
+    /// Delete a persona and its data
+    Delete {
+        name: String,
+    },
+}
+
+/// Active persona, environment and the public key used for each service
+pub async fn show(args: Args) -> Result<Value, NullaryError> {
+    let mut keys = Map::new();
+    for service in SERVICES.iter().chain(EXTRA_SERVICES.iter()) {
+        let url = url_or_default(args.url.clone(), service);
+        keys.insert(
+            service.to_string(),
+            Value::String(public_key_at(&url, service)?),
+        );
+    }
+
+    Ok(json!({
+        "app": app_name(),
+        "persona": active_persona(),
+        "environment": current_env(),
+        "dataDir": app_data_dir()?,
+        "publicKeys": keys,
+    }))
+}
+
+pub async fn personas(command: Personas) -> Result<Value, NullaryError> {
+    match command {
+        Personas::List => to_json(list_personas().await?),
+        Personas::Create { name } => to_json(create_persona(name).await?),
+        Personas::Switch { name } => to_json(switch_persona(name).await?),
+        Personas::Rename { name, new_name } => to_json(rename_persona(name, new_name).await?),
+        Personas::Delete { name } => to_json(delete_persona(name).await?),
+    }
+}
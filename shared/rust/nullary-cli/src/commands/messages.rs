@@ -0,0 +1,64 @@
+// Stackchat messages, sent through Julia like the stackchat app sends them
+
+use clap::Subcommand;
+use julia_rs::JuliaUser;
+use nullary_core::{
+    app_name, forget_if_not_found, julia_client_at, service_user_uuid, NullaryError,
+};
+use serde_json::{json, Value};
+use sessionless::hex::IntoHex;
+
+use super::url_or_default;
+
+#[derive(Subcommand)]
+pub enum Messages {
+    /// Send a message to a connected Julia user
+    Send {
+        /// Julia UUID of the receiver
+        receiver: String,
+        text: String,
+        /// Julia URL; defaults to the active environment's
+        #[arg(long)]
+        url: Option<String>,
+    },
+}
+
+/// This identity's Julia user, created with the app name as its handle
+pub async fn julia_user_uuid_at(url: &str) -> Result<String, NullaryError> {
+    service_user_uuid(url, "julia", None, || async {
+        let julia = julia_client_at(url).await?;
+        let pub_key = julia.sessionless.public_key().to_hex();
+        julia
+            .create_user(JuliaUser::new(pub_key, app_name().to_string()))
+            .await
+            .map(|user| user.uuid)
+            .map_err(|e| {
+                NullaryError::service("julia", format!("Failed to create Julia user: {}", e))
+            })
+    })
+    .await
+}
+
+pub async fn run(command: Messages) -> Result<Value, NullaryError> {
+    match command {
+        Messages::Send {
+            receiver,
+            text,
+            url,
+        } => {
+            let url = url_or_default(url, "julia");
+            let sender = julia_user_uuid_at(&url).await?;
+
+            let result = julia_client_at(&url).await?
+                .post_message(&sender, &receiver, text.clone())
+                .await
+                .map_err(|e| {
+                    let error =
+                        NullaryError::service("julia", format!("Failed to send message: {}", e));
+                    forget_if_not_found(&url, "julia", None, &error);
+                    error
+                })?;
+            Ok(json!({ "sender": sender, "receiver": receiver, "text": text, "result": result }))
+        }
+    }
+}
@@ -0,0 +1,27 @@
+// One module per domain. Each command returns its result as JSON for main
+// to print, or the `NullaryError` to report.
+
+pub mod bases;
+pub mod covenant;
+pub mod env;
+pub mod feed;
+pub mod identity;
+pub mod messages;
+pub mod moderation;
+pub mod products;
+pub mod teleport;
+pub mod users;
+
+use nullary_core::{get_service_url, NullaryError};
+use serde::Serialize;
+use serde_json::Value;
+
+pub fn to_json<T: Serialize>(value: T) -> Result<Value, NullaryError> {
+    serde_json::to_value(value)
+        .map_err(|e| NullaryError::internal(format!("Failed to serialize output: {}", e)))
+}
+
+/// `--url` if given, otherwise the service's URL in the active environment
+pub fn url_or_default(url: Option<String>, service: &str) -> String {
+    url.unwrap_or_else(|| get_service_url(service))
+}
@@ -0,0 +1,58 @@
+// Muted and ignored bases and blocked users, shared with every app
+
+use clap::Subcommand;
+use nullary_core::{
+    get_moderation, set_base_ignored, set_base_muted, set_user_blocked, NullaryError,
+};
+use serde_json::Value;
+
+use super::to_json;
+
+#[derive(Subcommand)]
+pub enum Moderation {
+    /// The active environment's moderation lists
+    Show,
+    /// Keep a joined base's content out of feeds
+    Mute {
+        /// Base id or name
+        base: String,
+    },
+    /// Show a muted base's content again
+    Unmute {
+        /// Base id or name
+        base: String,
+    },
+    /// Keep a base out of discovery
+    Ignore {
+        /// Base id or name
+        base: String,
+    },
+    /// Discover an ignored base again
+    Unignore {
+        /// Base id
+        base: String,
+    },
+    /// Hide a user's posts and messages in every app
+    Block {
+        /// Author UUID or public key
+        user: String,
+    },
+    /// Show a blocked user's posts and messages again
+    Unblock {
+        /// Author UUID or public key
+        user: String,
+    },
+}
+
+pub async fn run(command: Moderation) -> Result<Value, NullaryError> {
+    let moderation = match command {
+        Moderation::Show => get_moderation(),
+        Moderation::Mute { base } => set_base_muted(base, true)?,
+        Moderation::Unmute { base } => set_base_muted(base, false)?,
+        Moderation::Ignore { base } => set_base_ignored(base, true).await?,
+        Moderation::Unignore { base } => set_base_ignored(base, false).await?,
+        Moderation::Block { user } => set_user_blocked(user, true)?,
+        Moderation::Unblock { user } => set_user_blocked(user, false)?,
+    };
+    to_json(moderation)
+}
@@ -0,0 +1,65 @@
+// Sanora products
+
+use clap::Subcommand;
+use nullary_core::{forget_if_not_found, sanora_client_at, sanora_user_uuid_at, NullaryError};
+use serde_json::Value;
+
+use super::{to_json, url_or_default};
+
+#[derive(Subcommand)]
+pub enum Products {
+    /// Add a product as this identity's Sanora user
+    Add {
+        #[arg(long)]
+        title: String,
+        #[arg(long, default_value = "")]
+        description: String,
+        /// Price in cents
+        #[arg(long)]
+        price: u32,
+        /// Sanora URL; defaults to the active environment's
+        #[arg(long)]
+        url: Option<String>,
+    },
+    /// Every product on the base
+    List {
+        /// Sanora URL; defaults to the active environment's
+        #[arg(long)]
+        url: Option<String>,
+    },
+}
+
+pub async fn run(command: Products) -> Result<Value, NullaryError> {
+    match command {
+        Products::Add {
+            title,
+            description,
+            price,
+            url,
+        } => {
+            let url = url_or_default(url, "sanora");
+            let uuid = sanora_user_uuid_at(&url).await?;
+
+            let meta = sanora_client_at(&url).await?
+                .add_product(&uuid, &title, &description, &price)
+                .await
+                .map_err(|e| {
+                    let error =
+                        NullaryError::service("sanora", format!("Failed to add product: {}", e));
+                    forget_if_not_found(&url, "sanora", None, &error);
+                    error
+                })?;
+            to_json(meta)
+        }
+        Products::List { url } => {
+            let url = url_or_default(url, "sanora");
+            let products = sanora_client_at(&url).await?
+                .get_all_base_products()
+                .await
+                .map_err(|e| {
+                    NullaryError::service("sanora", format!("Failed to get products: {}", e))
+                })?;
+            to_json(products)
+        }
+    }
+}
@@ -0,0 +1,33 @@
+// BDO teleportation
+
+use nullary_core::{app_name, bdo_client_at, bdo_user_uuid_at, forget_if_not_found, NullaryError};
+use serde_json::Value;
+
+use super::url_or_default;
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// URL of the content to teleport
+    teleport_url: String,
+    /// BDO URL; defaults to the active environment's
+    #[arg(long)]
+    bdo: Option<String>,
+    /// BDO hash; defaults to the app name
+    #[arg(long)]
+    hash: Option<String>,
+}
+
+pub async fn run(args: Args) -> Result<Value, NullaryError> {
+    let url = url_or_default(args.bdo, "bdo");
+    let hash = args.hash.unwrap_or_else(|| app_name().to_string());
+    let uuid = bdo_user_uuid_at(&url, &hash).await?;
+
+    bdo_client_at(&url).await?
+        .teleport(&uuid, &hash, &args.teleport_url)
+        .await
+        .map_err(|e| {
+            let error = NullaryError::service("bdo", format!("Teleportation failed: {}", e));
+            forget_if_not_found(&url, "bdo", Some(&hash), &error);
+            error
+        })
+}
@@ -0,0 +1,50 @@
+// Service users, created on first use and kept in the registry like the
+// apps keep them
+
+use clap::Subcommand;
+use nullary_core::{
+    addie_user_uuid_at, app_name, bdo_user_uuid_at, dolores_user_uuid_at, fount_user_uuid_at,
+    sanora_user_uuid_at, NullaryError,
+};
+use serde_json::{json, Value};
+
+use super::{messages::julia_user_uuid_at, url_or_default};
+
+#[derive(Subcommand)]
+pub enum Users {
+    /// This identity's user on a service, created if it has none
+    Create {
+        /// addie, bdo, dolores, fount, julia or sanora
+        service: String,
+        /// Service URL; defaults to the active environment's
+        #[arg(long)]
+        url: Option<String>,
+        /// BDO hash; defaults to the app name
+        #[arg(long)]
+        hash: Option<String>,
+    },
+}
+
+pub async fn run(command: Users) -> Result<Value, NullaryError> {
+    match command {
+        Users::Create { service, url, hash } => {
+            let url = url_or_default(url, &service);
+            let hash = hash.unwrap_or_else(|| app_name().to_string());
+            let uuid = match service.as_str() {
+                "addie" => addie_user_uuid_at(&url).await?,
+                "bdo" => bdo_user_uuid_at(&url, &hash).await?,
+                "dolores" => dolores_user_uuid_at(&url).await?,
+                "fount" => fount_user_uuid_at(&url).await?,
+                "julia" => julia_user_uuid_at(&url).await?,
+                "sanora" => sanora_user_uuid_at(&url).await?,
+                other => {
+                    return Err(NullaryError::validation(format!(
+                        "Can't create users on {}",
+                        other
+                    )))
+                }
+            };
+            Ok(json!({ "service": service, "url": url, "uuid": uuid }))
+        }
+    }
+}
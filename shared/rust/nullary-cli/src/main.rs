@@ -0,0 +1,225 @@
+// nullary: the apps' operations from the command line
+//
+//   nullary --app ninefy identity
+//   nullary --app ninefy --env test products add --title Zine --price 500
+//   nullary --app stackchat messages send <julia uuid> "hello"
+//
+// Runs nullary-core headless with an app's data dir, so the keys, personas,
+// endpoints.json, policies and service user registry are the ones that app
+// uses. Without `--app` the CLI has an identity of its own. Results are
+// printed to stdout as JSON; a failure prints the `NullaryError` as JSON to
+// stderr and exits with 1.
+
+mod commands;
+
+use clap::{Parser, Subcommand};
+use nullary_core::{
+    init_headless, set_confirmation_handler, AppDirs, ConfirmationRequest, NullaryError,
+};
+use serde_json::Value;
+use std::io::{BufRead, IsTerminal, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use commands::{
+    bases, covenant, env, feed, identity, messages, moderation, products, teleport, users,
+};
+
+/// Bundle identifiers of the apps, as their tauri.conf.json declares them
+const APPS: [(&str, &str); 16] = [
+    ("blogary", "app.planetnine.blogary"),
+    ("covenant", "app.planetnine.covenant-gui"),
+    ("eventary", "app.planetnine.eventary"),
+    ("grocary", "com.planetnine.grocary"),
+    ("idothis", "com.planetnine.idothis"),
+    ("lexary", "com.planetnine.lexary"),
+    ("mybase", "com.planetnine.mybase"),
+    ("nexus", "com.planetnine.nexus"),
+    ("ninefy", "com.planetnine.ninefy"),
+    ("photary", "com.planetnine.photary"),
+    ("postary", "postary.planetnine.app"),
+    ("rhapsold", "com.planetnine.rhapsold"),
+    ("screenary", "app.planetnine.screenary"),
+    ("stackchat", "com.planetnine.stackchat"),
+    ("viewaris", "app.planetnine"),
+    ("viewary", "com.planetnine.viewary"),
+];
+
+/// The CLI's own identity when no app is given
+const CLI_IDENTIFIER: &str = "com.planetnine.nullary";
+
+#[derive(Parser)]
+#[command(
+    name = "nullary",
+    version,
+    about = "Run the Nullary apps' operations without a webview"
+)]
+struct Cli {
+    /// Act as this app (name or bundle identifier) with its keys and config
+    #[arg(long, global = true)]
+    app: Option<String>,
+
+    /// Keep everything under this dir instead, e.g. for a throwaway identity
+    #[arg(long, global = true, conflicts_with = "app")]
+    data_dir: Option<PathBuf>,
+
+    /// Environment to run in; same as setting NULLARY_ENV
+    #[arg(long, global = true)]
+    env: Option<String>,
+
+    /// Approve signatures that the signing policy wants confirmed
+    #[arg(long, short, global = true)]
+    yes: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Active persona, environment and public keys
+    Identity(identity::Args),
+    /// List, create, switch, rename and delete personas
+    #[command(subcommand)]
+    Personas(identity::Personas),
+    /// Show or switch the environment
+    #[command(subcommand)]
+    Env(env::Env),
+    /// Probe every configured service
+    Health,
+    /// Create (or look up) this identity's users on the services
+    #[command(subcommand)]
+    Users(users::Users),
+    /// Bases known to BDO
+    #[command(subcommand)]
+    Bases(bases::Bases),
+    /// Mute or ignore bases and block users
+    #[command(subcommand)]
+    Moderation(moderation::Moderation),
+    /// Posts from Dolores
+    Feed(feed::Args),
+    /// Add and list Sanora products
+    #[command(subcommand)]
+    Products(products::Products),
+    /// Teleport content from a URL through BDO
+    Teleport(teleport::Args),
+    /// List, show and sign Covenant contracts
+    #[command(subcommand)]
+    Covenant(covenant::Covenant),
+    /// Send Stackchat messages through Julia
+    #[command(subcommand)]
+    Messages(messages::Messages),
+    /// Latest lines of the log
+    Logs {
+        /// How many lines
+        #[arg(long, default_value_t = 200)]
+        lines: usize,
+    },
+}
+
+/// App name and dirs for the identity the flags pick
+fn resolve_host(cli: &Cli) -> Result<(String, AppDirs), NullaryError> {
+    if let Some(root) = &cli.data_dir {
+        return Ok(("nullary".to_string(), AppDirs::under(root)));
+    }
+
+    let Some(app) = cli.app.as_deref() else {
+        return Ok(("nullary".to_string(), AppDirs::for_app(CLI_IDENTIFIER)?));
+    };
+    let (name, identifier) = APPS
+        .iter()
+        .find(|(name, identifier)| *name == app || *identifier == app)
+        .ok_or_else(|| {
+            let known: Vec<&str> = APPS.iter().map(|(name, _)| *name).collect();
+            NullaryError::validation(format!("Unknown app {} (known: {})", app, known.join(", ")))
+        })?;
+    Ok((name.to_string(), AppDirs::for_app(identifier)?))
+}
+
+/// Ask on the terminal, or go by `--yes` when there is no terminal to ask on
+fn confirm_on_terminal(request: &ConfirmationRequest, yes: bool) -> bool {
+    if yes {
+        return true;
+    }
+    if !std::io::stdin().is_terminal() {
+        eprintln!(
+            "⚠️ Declined {:?} signature for {} - pass --yes to approve without a prompt",
+            request.purpose, request.service
+        );
+        return false;
+    }
+
+    eprint!(
+        "✍️ Sign {:?} for {} on {}?\n   {}\n   [y/N] ",
+        request.purpose, request.service, request.base, request.message
+    );
+    let _ = std::io::stderr().flush();
+    let mut answer = String::new();
+    if std::io::stdin().lock().read_line(&mut answer).is_err() {
+        return false;
+    }
+    matches!(answer.trim(), "y" | "Y" | "yes")
+}
+
+async fn run(command: Command) -> Result<Value, NullaryError> {
+    match command {
+        Command::Identity(args) => identity::show(args).await,
+        Command::Personas(personas) => identity::personas(personas).await,
+        Command::Env(env) => env::run(env),
+        Command::Health => commands::to_json(nullary_core::check_service_health(Some(true)).await?),
+        Command::Users(users) => users::run(users).await,
+        Command::Bases(bases) => bases::run(bases).await,
+        Command::Moderation(moderation) => moderation::run(moderation).await,
+        Command::Feed(args) => feed::get(args).await,
+        Command::Products(products) => products::run(products).await,
+        Command::Teleport(args) => teleport::run(args).await,
+        Command::Covenant(covenant) => covenant::run(covenant).await,
+        Command::Messages(messages) => messages::run(messages).await,
+        Command::Logs { lines } => Ok(Value::String(nullary_core::export_logs(Some(lines)).await?)),
+    }
+}
+
+fn fail(error: &NullaryError) -> ExitCode {
+    let json = serde_json::to_string_pretty(error).unwrap_or_else(|_| error.to_string());
+    let _ = writeln!(std::io::stderr(), "{}", json);
+    ExitCode::FAILURE
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    // Read by `load_endpoint_config` during init
+    if let Some(env) = &cli.env {
+        std::env::set_var("NULLARY_ENV", env);
+    }
+
+    let (app_name, dirs) = match resolve_host(&cli) {
+        Ok(host) => host,
+        Err(e) => return fail(&e),
+    };
+    if let Err(e) = init_headless(&app_name, dirs) {
+        return fail(&e);
+    }
+
+    let yes = cli.yes;
+    set_confirmation_handler(move |request| confirm_on_terminal(request, yes));
+
+    match run(cli.command).await {
+        Ok(value) => {
+            let json = match serde_json::to_string_pretty(&value) {
+                Ok(json) => json,
+                Err(e) => {
+                    return fail(&NullaryError::internal(format!(
+                        "Failed to serialize output: {}",
+                        e
+                    )))
+                }
+            };
+            // A reader that stops early (`| head`) is not a failure
+            let _ = writeln!(std::io::stdout(), "{}", json);
+            ExitCode::SUCCESS
+        }
+        Err(e) => fail(&e),
+    }
+}
@@ -0,0 +1,383 @@
+// Identity backup and restore
+//
+// A persona's master seed can leave the machine two ways:
+// - an encrypted backup: the seed sealed with ChaCha20-Poly1305 under a key
+//   derived from a passphrase with Argon2id, stored as versioned JSON
+// - recovery words: the seed as a 24-word BIP-39 mnemonic for writing down
+//
+// Both restore into a new persona, so an import never overwrites an identity.
+// Every export is confirmed by the user outside the webview and journaled as
+// `identityExport` (see `signing`), so a script can't walk off with a seed.
+
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use argon2::{Algorithm, Argon2, Params, Version};
+use bip39::Mnemonic;
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::info;
+use zeroize::Zeroizing;
+
+use crate::error::NullaryError;
+use crate::signing::authorize_export;
+use crate::user_persistence::{add_persona, persona_or_active, persona_seed, MasterSeed, PersonaInfo};
+
+/// Written into every backup so other JSON is rejected up front
+pub const BACKUP_FORMAT: &str = "nullary-identity-backup";
+/// Bump when the layout or algorithms change; imports refuse other versions
+pub const BACKUP_VERSION: u32 = 1;
+
+const MIN_PASSPHRASE_LENGTH: usize = 8;
+
+// Argon2id cost for new backups (64 MiB, 3 passes)
+const KDF_MEMORY_KIB: u32 = 64 * 1024;
+const KDF_ITERATIONS: u32 = 3;
+const KDF_PARALLELISM: u32 = 1;
+
+// Upper bound on costs read from a backup, so a crafted file can't exhaust memory
+const KDF_MAX_MEMORY_KIB: u32 = 1024 * 1024;
+const KDF_MAX_ITERATIONS: u32 = 16;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupKdf {
+    pub algorithm: String,
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+    pub salt: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IdentityBackup {
+    pub format: String,
+    pub version: u32,
+    /// Persona the seed was exported from, used as the default name on import
+    pub persona: String,
+    pub created_at: i64,
+    pub kdf: BackupKdf,
+    pub cipher: String,
+    pub nonce: String,
+    pub ciphertext: String,
+    /// SHA-256 over the fields above, so damage is told apart from a wrong passphrase
+    pub checksum: String,
+}
+
+impl IdentityBackup {
+    fn compute_checksum(&self) -> String {
+        let fields = [
+            self.format.clone(),
+            self.version.to_string(),
+            self.persona.clone(),
+            self.created_at.to_string(),
+            self.kdf.algorithm.clone(),
+            self.kdf.memory_kib.to_string(),
+            self.kdf.iterations.to_string(),
+            self.kdf.parallelism.to_string(),
+            self.kdf.salt.clone(),
+            self.cipher.clone(),
+            self.nonce.clone(),
+            self.ciphertext.clone(),
+        ];
+
+        // Length-prefix each field so values can't shift between fields
+        let mut hasher = Sha256::new();
+        for field in &fields {
+            hasher.update((field.len() as u64).to_be_bytes());
+            hasher.update(field.as_bytes());
+        }
+        hex::encode(hasher.finalize())
+    }
+}
+
+fn derive_backup_key(passphrase: &str, kdf: &BackupKdf, salt: &[u8]) -> Result<Zeroizing<[u8; 32]>, NullaryError> {
+    let params = Params::new(kdf.memory_kib, kdf.iterations, kdf.parallelism, Some(32))
+        .map_err(|e| NullaryError::validation(format!("Invalid backup KDF parameters: {}", e)))?;
+
+    let mut key = Zeroizing::new([0u8; 32]);
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), salt, key.as_mut())
+        .map_err(|e| NullaryError::internal(format!("Failed to derive backup key: {}", e)))?;
+
+    Ok(key)
+}
+
+fn check_passphrase(passphrase: &str) -> Result<(), NullaryError> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_LENGTH {
+        return Err(NullaryError::validation(format!(
+            "Backup passphrase must be at least {} characters",
+            MIN_PASSPHRASE_LENGTH
+        )));
+    }
+    Ok(())
+}
+
+/// Seal a seed under a passphrase
+pub fn encrypt_seed(seed: &MasterSeed, passphrase: &str, persona: &str) -> Result<IdentityBackup, NullaryError> {
+    check_passphrase(passphrase)?;
+
+    let mut salt = [0u8; 16];
+    OsRng.fill_bytes(&mut salt);
+    let mut nonce = [0u8; 12];
+    OsRng.fill_bytes(&mut nonce);
+
+    let kdf = BackupKdf {
+        algorithm: "argon2id".to_string(),
+        memory_kib: KDF_MEMORY_KIB,
+        iterations: KDF_ITERATIONS,
+        parallelism: KDF_PARALLELISM,
+        salt: hex::encode(salt),
+    };
+
+    let key = derive_backup_key(passphrase, &kdf, &salt)?;
+    let ciphertext = ChaCha20Poly1305::new(Key::from_slice(key.as_slice()))
+        .encrypt(Nonce::from_slice(&nonce), seed.as_slice())
+        .map_err(|e| NullaryError::internal(format!("Failed to encrypt backup: {}", e)))?;
+
+    let mut backup = IdentityBackup {
+        format: BACKUP_FORMAT.to_string(),
+        version: BACKUP_VERSION,
+        persona: persona.to_string(),
+        created_at: chrono::Utc::now().timestamp_millis(),
+        kdf,
+        cipher: "chacha20poly1305".to_string(),
+        nonce: hex::encode(nonce),
+        ciphertext: hex::encode(ciphertext),
+        checksum: String::new(),
+    };
+    backup.checksum = backup.compute_checksum();
+
+    Ok(backup)
+}
+
+/// Parse and check a backup without decrypting it
+pub fn parse_backup(backup_json: &str) -> Result<IdentityBackup, NullaryError> {
+    // Read the header loosely first so a newer version gets a version error
+    // rather than a parse error
+    let header: serde_json::Value = serde_json::from_str(backup_json)
+        .map_err(|e| NullaryError::validation(format!("Backup is corrupted: {}", e)))?;
+
+    if header.get("format").and_then(|f| f.as_str()) != Some(BACKUP_FORMAT) {
+        return Err(NullaryError::validation("Not a Nullary identity backup"));
+    }
+
+    match header.get("version").and_then(|v| v.as_u64()) {
+        Some(version) if version == BACKUP_VERSION as u64 => {}
+        Some(version) => {
+            return Err(NullaryError::validation(format!(
+                "Unsupported backup version {} (this app reads version {})",
+                version, BACKUP_VERSION
+            )))
+        }
+        None => return Err(NullaryError::validation("Backup is corrupted: missing version")),
+    }
+
+    let backup: IdentityBackup = serde_json::from_value(header)
+        .map_err(|e| NullaryError::validation(format!("Backup is corrupted: {}", e)))?;
+
+    if backup.checksum != backup.compute_checksum() {
+        return Err(NullaryError::validation("Backup is corrupted: checksum mismatch"));
+    }
+
+    if backup.kdf.algorithm != "argon2id" || backup.cipher != "chacha20poly1305" {
+        return Err(NullaryError::validation(format!(
+            "Unsupported backup algorithms: {} / {}",
+            backup.kdf.algorithm, backup.cipher
+        )));
+    }
+
+    if backup.kdf.memory_kib > KDF_MAX_MEMORY_KIB || backup.kdf.iterations > KDF_MAX_ITERATIONS {
+        return Err(NullaryError::validation("Backup KDF parameters are out of range"));
+    }
+
+    Ok(backup)
+}
+
+/// Open a backup with its passphrase
+pub fn decrypt_seed(backup: &IdentityBackup, passphrase: &str) -> Result<MasterSeed, NullaryError> {
+    let salt = hex::decode(&backup.kdf.salt).map_err(|e| NullaryError::validation(format!("Backup is corrupted: {}", e)))?;
+    let nonce = hex::decode(&backup.nonce).map_err(|e| NullaryError::validation(format!("Backup is corrupted: {}", e)))?;
+    let ciphertext = hex::decode(&backup.ciphertext).map_err(|e| NullaryError::validation(format!("Backup is corrupted: {}", e)))?;
+
+    if nonce.len() != 12 {
+        return Err(NullaryError::validation("Backup is corrupted: bad nonce length"));
+    }
+
+    let key = derive_backup_key(passphrase, &backup.kdf, &salt)?;
+
+    // The checksum already passed, so a failed tag means the passphrase is wrong
+    let plaintext = Zeroizing::new(
+        ChaCha20Poly1305::new(Key::from_slice(key.as_slice()))
+            .decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+            .map_err(|_| NullaryError::validation("Wrong passphrase for this backup"))?,
+    );
+
+    let seed: [u8; 32] = plaintext
+        .as_slice()
+        .try_into()
+        .map_err(|_| NullaryError::validation("Backup is corrupted: unexpected seed length"))?;
+
+    Ok(Zeroizing::new(seed))
+}
+
+/// Seed as 24 BIP-39 words
+pub fn seed_to_words(seed: &MasterSeed) -> Result<Vec<String>, NullaryError> {
+    let mnemonic = Mnemonic::from_entropy(seed.as_slice())
+        .map_err(|e| NullaryError::internal(format!("Failed to encode recovery words: {}", e)))?;
+    Ok(mnemonic.words().map(|word| word.to_string()).collect())
+}
+
+/// Seed from BIP-39 words; the mnemonic checksum catches most typos
+pub fn words_to_seed(words: &str) -> Result<MasterSeed, NullaryError> {
+    let mnemonic = Mnemonic::parse_normalized(&words.to_lowercase())
+        .map_err(|e| NullaryError::validation(format!("Invalid recovery words: {}", e)))?;
+
+    let entropy = Zeroizing::new(mnemonic.to_entropy());
+    let seed: [u8; 32] = entropy
+        .as_slice()
+        .try_into()
+        .map_err(|_| NullaryError::validation(format!("Recovery words must be 24 words, got {}", mnemonic.word_count())))?;
+
+    Ok(Zeroizing::new(seed))
+}
+
+// ===== TAURI COMMANDS =====
+
+/// Export a persona (the active one by default) as an encrypted backup JSON
+/// string, once the user confirms it
+#[cfg_attr(feature = "tauri", tauri::command)]
+pub async fn export_identity_backup(
+    passphrase: String,
+    persona: Option<String>,
+) -> Result<String, NullaryError> {
+    let persona = persona_or_active(persona);
+    info!("📦 Exporting identity backup for persona: {}", persona);
+
+    // A passphrase that would be refused anyway shouldn't cost a prompt
+    check_passphrase(&passphrase)?;
+    authorize_export(&persona, "identity backup").await?;
+
+    let seed = persona_seed(&persona)?;
+    let backup = encrypt_seed(&seed, &passphrase, &persona)?;
+
+    info!("✅ Identity backup exported");
+    serde_json::to_string_pretty(&backup).map_err(|e| NullaryError::internal(format!("Failed to serialize backup: {}", e)))
+}
+
+/// Restore an encrypted backup as a new persona, named after the exported
+/// one unless `persona` is given
+#[cfg_attr(feature = "tauri", tauri::command)]
+pub async fn import_identity_backup(
+    backup: String,
+    passphrase: String,
+    persona: Option<String>,
+) -> Result<PersonaInfo, NullaryError> {
+    info!("📦 Importing identity backup");
+
+    let parsed = parse_backup(&backup)?;
+    let seed = decrypt_seed(&parsed, &passphrase)?;
+    let name = persona.unwrap_or(parsed.persona);
+
+    let info = add_persona(&name, Some(&seed))?;
+
+    info!("✅ Identity backup restored as persona: {}", name);
+    Ok(info)
+}
+
+/// Recovery words for a persona (the active one by default), once the user
+/// confirms it
+#[cfg_attr(feature = "tauri", tauri::command)]
+pub async fn export_recovery_words(persona: Option<String>) -> Result<Vec<String>, NullaryError> {
+    let persona = persona_or_active(persona);
+    info!("📝 Exporting recovery words for persona: {}", persona);
+
+    authorize_export(&persona, "recovery words").await?;
+    seed_to_words(&persona_seed(&persona)?)
+}
+
+/// Restore recovery words as a new persona
+#[cfg_attr(feature = "tauri", tauri::command)]
+pub async fn import_recovery_words(words: String, persona: String) -> Result<PersonaInfo, NullaryError> {
+    info!("📝 Importing recovery words as persona: {}", persona);
+
+    let seed = words_to_seed(&words)?;
+    let info = add_persona(&persona, Some(&seed))?;
+
+    info!("✅ Recovery words restored as persona: {}", persona);
+    Ok(info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PASSPHRASE: &str = "correct horse battery";
+
+    fn seed() -> MasterSeed {
+        Zeroizing::new([7u8; 32])
+    }
+
+    fn exported() -> String {
+        let backup = encrypt_seed(&seed(), PASSPHRASE, "default").unwrap();
+        serde_json::to_string(&backup).unwrap()
+    }
+
+    #[test]
+    fn backup_round_trips() {
+        let backup = parse_backup(&exported()).unwrap();
+        assert_eq!(backup.persona, "default");
+        assert_eq!(*decrypt_seed(&backup, PASSPHRASE).unwrap(), *seed());
+    }
+
+    #[test]
+    fn wrong_passphrase_is_refused() {
+        let backup = parse_backup(&exported()).unwrap();
+        let error = decrypt_seed(&backup, "not the passphrase").unwrap_err();
+        assert_eq!(error, NullaryError::validation("Wrong passphrase for this backup"));
+    }
+
+    #[test]
+    fn other_versions_are_refused() {
+        let mut backup: serde_json::Value = serde_json::from_str(&exported()).unwrap();
+        backup["version"] = serde_json::json!(BACKUP_VERSION + 1);
+
+        let error = parse_backup(&backup.to_string()).unwrap_err();
+        assert!(error.to_string().contains("Unsupported backup version"), "{}", error);
+    }
+
+    #[test]
+    fn tampered_backups_are_refused() {
+        let mut backup: serde_json::Value = serde_json::from_str(&exported()).unwrap();
+        backup["persona"] = serde_json::json!("someone else");
+
+        let error = parse_backup(&backup.to_string()).unwrap_err();
+        assert!(error.to_string().contains("checksum mismatch"), "{}", error);
+    }
+
+    #[test]
+    fn short_passphrases_are_refused() {
+        assert!(encrypt_seed(&seed(), "short", "default").is_err());
+    }
+
+    #[tokio::test]
+    async fn exports_need_confirmation() {
+        crate::signing::set_confirmation_handler(|_| false);
+
+        let error = export_recovery_words(Some("default".to_string())).await.unwrap_err();
+        assert!(error.to_string().contains("declined"), "{}", error);
+
+        let error = export_identity_backup(PASSPHRASE.to_string(), Some("default".to_string()))
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("declined"), "{}", error);
+    }
+
+    #[test]
+    fn recovery_words_round_trip() {
+        let words = seed_to_words(&seed()).unwrap();
+        assert_eq!(words.len(), 24);
+        assert_eq!(*words_to_seed(&words.join(" ").to_uppercase()).unwrap(), *seed());
+    }
+}
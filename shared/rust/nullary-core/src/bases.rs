@@ -0,0 +1,544 @@
+// Bases
+//
+// Every app discovers bases the same way: `discover_bases` asks the active
+// environment's BDO which bases the user can see, parses each entry into a
+// `BaseData` and marks the ones the user has joined. BDO's answer is kept in
+// the feed cache like a feed page, so the base screen opens with the last
+// list at once and while BDO is down, and is refreshed in the background.
+//
+// The environment's own base, the home base, is always listed and always
+// joined. Other joined bases are kept in `bases.json` in the dir shared by
+// every Nullary app on the machine, per environment, with the base as it was
+// when joined. A joined base BDO no longer lists is still returned from
+// there, so its content isn't lost when discovery fails.
+//
+// Joining a base registers the user with its services, from the URLs in its
+// `DnsData`, and adds it to `bases.json`; leaving removes it, optionally
+// deleting this app's BDO user there first. Feeds from `aggregate_feed` over
+// `feed_sources` cover the home base and every joined base that isn't
+// muted, so a join or leave shows in every app's feeds on its next load.
+// Muting and ignoring live in `moderation.rs`.
+
+use futures::future::{join_all, BoxFuture};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{LazyLock, Mutex};
+use tracing::{debug, info, warn};
+
+use crate::app_name;
+use crate::clients::{
+    addie_user_uuid_at, bdo_user_uuid_at, dolores_user_uuid_at, fount_user_uuid_at,
+    sanora_user_uuid_at,
+};
+use crate::documents::write_atomic;
+use crate::error::NullaryError;
+use crate::feed_cache::{
+    cached_feed, conditional_get, refresh_cached_feed, FeedFetch, FeedKey, FeedPage, Validators,
+};
+use crate::host::{emit, shared_data_dir};
+use crate::http::{self, http_client};
+use crate::moderation::moderate_bases;
+use crate::registry::{forget_if_not_found, forget_uuid, registered_uuid};
+use crate::services::{base_for_url, current_env, get_service_url, EXTRA_SERVICES, SERVICES};
+use crate::signing::{sign_operation_at, SigningPurpose};
+
+/// Event carrying the joined bases after a join or leave
+pub const BASES_EVENT: &str = "bases-changed";
+
+/// Services a join registers with, when the base runs them
+pub const JOIN_SERVICES: [&str; 5] = ["bdo", "dolores", "sanora", "addie", "fount"];
+
+const MEMBERSHIP_FILE: &str = "bases.json";
+// Feed cache tag the BDO base list is stored under
+const BASES_TAG: &str = "bases";
+
+// ===== TYPES =====
+
+/// A base as BDO describes it, plus whether the user has joined it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BaseData {
+    /// Key BDO lists the base under
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub location: Option<LocationData>,
+    #[serde(default)]
+    pub soma: SomaData,
+    #[serde(default)]
+    pub dns: DnsData,
+    #[serde(default)]
+    pub joined: bool,
+    /// The active environment's own base, which can't be left
+    #[serde(default)]
+    pub home: bool,
+    /// Joined, but kept out of feeds
+    #[serde(default)]
+    pub muted: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocationData {
+    pub latitude: f64,
+    pub longitude: f64,
+    #[serde(default, alias = "postalCode")]
+    pub postal_code: Option<String>,
+}
+
+/// Tags the base carries content under, per app (`lexary`, `photary`, ...)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SomaData(pub BTreeMap<String, Vec<String>>);
+
+impl SomaData {
+    /// Tags for `app`; empty when the base has none for it
+    pub fn tags(&self, app: &str) -> &[String] {
+        self.0.get(app).map(|tags| tags.as_slice()).unwrap_or(&[])
+    }
+}
+
+/// URL of each service the base runs
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct DnsData(pub BTreeMap<String, String>);
+
+impl DnsData {
+    pub fn url(&self, service: &str) -> Option<&str> {
+        self.0.get(service).map(|url| url.as_str())
+    }
+
+    pub fn services(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0
+            .iter()
+            .map(|(service, url)| (service.as_str(), url.as_str()))
+    }
+}
+
+impl BaseData {
+    /// `host:port` of the base's BDO, which identifies the base on the wire
+    pub fn bdo_base(&self) -> Option<String> {
+        self.dns.url("bdo").and_then(|url| base_for_url(url).ok())
+    }
+}
+
+// ===== MEMBERSHIP =====
+
+/// A joined base, remembered as it was when joined
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Membership {
+    pub base: BaseData,
+    pub joined_at: i64,
+}
+
+/// Environment -> base id -> membership
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct Memberships {
+    #[serde(default)]
+    pub environments: BTreeMap<String, BTreeMap<String, Membership>>,
+}
+
+fn membership_path() -> Result<PathBuf, NullaryError> {
+    Ok(shared_data_dir()?.join(MEMBERSHIP_FILE))
+}
+
+/// Read fresh on every call: another app may have joined or left since
+pub(crate) fn read_memberships() -> Result<Memberships, NullaryError> {
+    let path = membership_path()?;
+    let json = match fs::read_to_string(&path) {
+        Ok(json) => json,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Memberships::default()),
+        Err(e) => {
+            return Err(NullaryError::storage(format!(
+                "Failed to read joined bases: {}",
+                e
+            )))
+        }
+    };
+    serde_json::from_str(&json)
+        .map_err(|e| NullaryError::storage(format!("Failed to parse joined bases: {}", e)))
+}
+
+// Serializes read-modify-write within this app. Another app writing at the
+// same moment can still win the race; the file is replaced atomically, so
+// the worst case is that one of the two changes is lost.
+static MEMBERSHIP_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+
+/// Change the active environment's joined bases and save them
+fn update_memberships(
+    change: impl FnOnce(&mut BTreeMap<String, Membership>),
+) -> Result<(), NullaryError> {
+    let _guard = MEMBERSHIP_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    let mut memberships = read_memberships()?;
+    change(memberships.environments.entry(current_env()).or_default());
+
+    let json = serde_json::to_vec_pretty(&memberships)
+        .map_err(|e| NullaryError::internal(format!("Failed to serialize joined bases: {}", e)))?;
+    write_atomic(&membership_path()?, &json)
+}
+
+/// Bases the user has joined in the active environment, besides the home
+/// base
+pub fn joined_bases() -> Result<Vec<BaseData>, NullaryError> {
+    let mut memberships = read_memberships()?;
+    Ok(memberships
+        .environments
+        .remove(&current_env())
+        .unwrap_or_default()
+        .into_values()
+        .map(|membership| BaseData {
+            joined: true,
+            ..membership.base
+        })
+        .collect())
+}
+
+// ===== DISCOVERY =====
+
+/// The active environment's own base, from its endpoint config
+pub fn home_base() -> BaseData {
+    let env = current_env();
+    let dns = SERVICES
+        .iter()
+        .chain(EXTRA_SERVICES.iter())
+        .map(|service| (service.to_string(), get_service_url(service)))
+        .collect();
+    let bdo_url = get_service_url("bdo");
+
+    BaseData {
+        id: base_for_url(&bdo_url).unwrap_or(bdo_url),
+        name: env.to_uppercase(),
+        description: format!("Home base of the {} environment", env),
+        location: None,
+        soma: SomaData::default(),
+        dns: DnsData(dns),
+        joined: true,
+        home: true,
+        muted: false,
+    }
+}
+
+/// Parse BDO's bases, an object keyed by base id. Entries that don't parse
+/// are skipped so one bad base doesn't hide the rest.
+fn parse_bases(bases: Value) -> Vec<BaseData> {
+    let Value::Object(entries) = bases else {
+        warn!("⚠️ BDO returned bases that are not an object");
+        return Vec::new();
+    };
+
+    entries
+        .into_iter()
+        .filter_map(
+            |(id, entry)| match serde_json::from_value::<BaseData>(entry) {
+                Ok(base) => Some(BaseData {
+                    id,
+                    joined: false,
+                    home: false,
+                    ..base
+                }),
+                Err(e) => {
+                    warn!("⚠️ Skipping base {}: {}", id, e);
+                    None
+                }
+            },
+        )
+        .collect()
+}
+
+/// The bases BDO lists, fetched conditionally. Signs `timestamp + uuid +
+/// hash`, as BDO's `GET /user/:uuid/bases` expects.
+async fn fetch_bases(
+    bdo_url: &str,
+    validators: Validators,
+) -> Result<FeedFetch<Vec<BaseData>>, NullaryError> {
+    let hash = app_name();
+    let uuid = bdo_user_uuid_at(bdo_url, hash).await?;
+    let signed = sign_operation_at(bdo_url, "bdo", SigningPurpose::AuthHeader, &[&uuid, hash]).await?;
+    let request = http_client()
+        .get(format!("{}/user/{}/bases", bdo_url.trim_end_matches('/'), uuid))
+        .query(&[
+            ("timestamp", signed.timestamp.as_str()),
+            ("hash", hash),
+            ("signature", signed.signature.as_str()),
+        ]);
+
+    let bases = conditional_get::<Value>("bdo", request, &validators)
+        .await
+        .inspect_err(|e| {
+            forget_if_not_found(bdo_url, "bdo", Some(hash), e);
+        })?;
+
+    Ok(bases.map(|bases| {
+        let bases = parse_bases(bases);
+        info!("🏗️ BDO lists {} bases", bases.len());
+        bases
+    }))
+}
+
+/// Home base first, then the listed bases marked joined or not, then joined
+/// bases the listing left out; ignored bases dropped, muted ones marked
+fn merge_membership(listed: Vec<BaseData>) -> Vec<BaseData> {
+    let mut home = home_base();
+    let home_bdo = home.bdo_base();
+
+    let mut joined: BTreeMap<String, BaseData> = match joined_bases() {
+        Ok(bases) => bases
+            .into_iter()
+            .map(|base| (base.id.clone(), base))
+            .collect(),
+        Err(e) => {
+            warn!("⚠️ {} - no joined bases besides home", e);
+            BTreeMap::new()
+        }
+    };
+
+    let mut others = Vec::new();
+    for mut base in listed {
+        // BDO may list the home base itself; its entry has the richer data
+        if home_bdo.is_some() && base.bdo_base() == home_bdo {
+            base.joined = true;
+            base.home = true;
+            home = base;
+            continue;
+        }
+        base.joined = joined.remove(&base.id).is_some();
+        others.push(base);
+    }
+
+    let mut bases = vec![home];
+    bases.extend(others);
+    bases.extend(joined.into_values());
+    moderate_bases(bases)
+}
+
+/// Bases the BDO at `bdo_url` lists for the user, with the home base and
+/// joined state merged in. Served from the cache like a feed page unless
+/// `refresh` is set; with neither BDO nor a cached list, only the home base
+/// and joined bases are returned, flagged stale.
+pub async fn discover_bases_at(
+    bdo_url: &str,
+    refresh: bool,
+) -> Result<FeedPage<Vec<BaseData>>, NullaryError> {
+    let key = FeedKey::new(bdo_url, "bdo", BASES_TAG)?;
+    let url = bdo_url.to_string();
+    let fetch = move |validators| async move { fetch_bases(&url, validators).await };
+
+    let page = if refresh {
+        refresh_cached_feed(key, fetch).await
+    } else {
+        cached_feed(key, fetch).await
+    };
+    let page = match page {
+        Ok(page) => page,
+        Err(e) => {
+            warn!("🔌 Listing known bases only: {}", e);
+            FeedPage {
+                data: Vec::new(),
+                stale: true,
+                fetched_at: 0,
+            }
+        }
+    };
+
+    let bases = merge_membership(page.data);
+    debug!(
+        "🏗️ {} bases, {} joined",
+        bases.len(),
+        bases.iter().filter(|base| base.joined).count()
+    );
+    Ok(FeedPage {
+        data: bases,
+        stale: page.stale,
+        fetched_at: page.fetched_at,
+    })
+}
+
+/// Bases keyed by id, the shape BDO and `base-discovery.js` use
+pub fn bases_by_id(bases: Vec<BaseData>) -> BTreeMap<String, BaseData> {
+    bases
+        .into_iter()
+        .map(|base| (base.id.clone(), base))
+        .collect()
+}
+
+/// `base` by id or name
+fn find_base(bases: Vec<BaseData>, base: &str) -> Option<BaseData> {
+    let mut bases = bases.into_iter();
+    bases.find(|candidate| candidate.id == base || candidate.name == base)
+}
+
+/// `base` by id or name among the bases discovery lists
+pub async fn known_base(base: &str) -> Result<BaseData, NullaryError> {
+    let bases = discover_bases(None).await?.data;
+    find_base(bases, base).ok_or_else(|| NullaryError::validation(format!("Unknown base {}", base)))
+}
+
+// ===== JOIN AND LEAVE =====
+
+fn announce_bases() {
+    match joined_bases() {
+        Ok(bases) => {
+            if let Err(e) = emit(BASES_EVENT, &bases) {
+                warn!("⚠️ Failed to emit joined bases: {}", e);
+            }
+        }
+        Err(e) => warn!("⚠️ {}", e),
+    }
+}
+
+/// Register with each of `JOIN_SERVICES` the base runs, all at once
+async fn register_with_base(base: &BaseData) -> Result<(), NullaryError> {
+    let registrations: Vec<(&str, BoxFuture<'_, Result<String, NullaryError>>)> = JOIN_SERVICES
+        .iter()
+        .filter_map(|service| {
+            let url = base.dns.url(service)?;
+            let registration: BoxFuture<'_, _> = match *service {
+                "bdo" => Box::pin(bdo_user_uuid_at(url, app_name())),
+                "dolores" => Box::pin(dolores_user_uuid_at(url)),
+                "sanora" => Box::pin(sanora_user_uuid_at(url)),
+                "addie" => Box::pin(addie_user_uuid_at(url)),
+                "fount" => Box::pin(fount_user_uuid_at(url)),
+                _ => return None,
+            };
+            Some((*service, registration))
+        })
+        .collect();
+
+    if registrations.is_empty() {
+        return Err(NullaryError::validation(format!(
+            "Base {} lists none of the services to join",
+            base.name
+        )));
+    }
+
+    let (services, futures): (Vec<&str>, Vec<_>) = registrations.into_iter().unzip();
+    let mut first_error = None;
+    for (service, result) in services.into_iter().zip(join_all(futures).await) {
+        match result {
+            Ok(uuid) => debug!(service, uuid = %uuid, "👤 Registered on {}", base.name),
+            Err(e) => {
+                warn!(
+                    "⚠️ Failed to register with {} on {}: {}",
+                    service, base.name, e
+                );
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+    // Users that were created stay in the registry and are reused next time
+    first_error.map_or(Ok(()), Err)
+}
+
+/// Delete this app's BDO user at `url`, if it has one. Signs
+/// `timestamp + uuid + hash`, the way BDO's own `DELETE /user/:uuid`
+/// expects. The other services' users are shared by every app on the
+/// identity, so no single app deletes them.
+async fn delete_app_user(url: &str) -> Result<bool, NullaryError> {
+    let hash = app_name();
+    let Some(uuid) = registered_uuid(url, "bdo", Some(hash))? else {
+        return Ok(false);
+    };
+
+    let signed = sign_operation_at(url, "bdo", SigningPurpose::AuthHeader, &[&uuid, hash]).await?;
+    let body = json!({
+        "timestamp": signed.timestamp,
+        "uuid": uuid,
+        "hash": hash,
+        "signature": signed.signature,
+    });
+
+    let endpoint = format!("{}/user/{}", url.trim_end_matches('/'), uuid);
+    let response = http::send("bdo", http_client().delete(&endpoint).json(&body)).await?;
+    let status = response.status().as_u16();
+    // Already gone is as good as deleted
+    if !response.status().is_success() && status != 404 {
+        let message = response.text().await.unwrap_or_default();
+        return Err(NullaryError::from_status("bdo", status, message));
+    }
+
+    forget_uuid(url, "bdo", Some(hash))?;
+    Ok(true)
+}
+
+/// Join `base` (id or name) from the bases discovery lists: register with
+/// its services and remember it for every Nullary app. Joining a base that
+/// is already joined does nothing.
+pub async fn join_base(base: &str) -> Result<BaseData, NullaryError> {
+    let mut base = known_base(base).await?;
+    if base.joined {
+        debug!("🏗️ Already joined {}", base.name);
+        return Ok(base);
+    }
+
+    register_with_base(&base).await?;
+
+    base.joined = true;
+    let membership = Membership {
+        base: base.clone(),
+        joined_at: chrono::Utc::now().timestamp_millis(),
+    };
+    update_memberships(|joined| {
+        joined.insert(base.id.clone(), membership);
+    })?;
+    info!("📥 Joined base {}", base.name);
+
+    announce_bases();
+    Ok(base)
+}
+
+/// Leave `base` (id or name). With `delete_users`, this app's BDO user on
+/// the base is deleted first; if it can't be, that is logged and the base is
+/// left anyway. The Dolores, Sanora, Addie and Fount users are shared with
+/// the other apps and are kept, as are other apps' BDO users.
+pub async fn leave_base(base: &str, delete_users: bool) -> Result<BaseData, NullaryError> {
+    let Some(mut base) = find_base(joined_bases()?, base) else {
+        if find_base(vec![home_base()], base).is_some() {
+            return Err(NullaryError::validation("The home base can't be left"));
+        }
+        return Err(NullaryError::validation(format!(
+            "Not a member of base {}",
+            base
+        )));
+    };
+
+    if let Some(url) = base.dns.url("bdo").filter(|_| delete_users) {
+        match delete_app_user(url).await {
+            Ok(true) => info!("🗑️ Deleted {} user on {}", app_name(), base.name),
+            Ok(false) => {}
+            Err(e) => warn!(
+                "⚠️ Failed to delete {} user on {}: {}",
+                app_name(),
+                base.name,
+                e
+            ),
+        }
+    }
+
+    update_memberships(|joined| {
+        joined.remove(&base.id);
+    })?;
+    info!("📤 Left base {}", base.name);
+
+    announce_bases();
+    base.joined = false;
+    Ok(base)
+}
+
+// ===== COMMANDS =====
+
+/// Bases the user can see through the active environment's BDO, the home
+/// base first, each marked joined or not
+#[cfg_attr(feature = "tauri", tauri::command)]
+pub async fn discover_bases(
+    refresh: Option<bool>,
+) -> Result<FeedPage<Vec<BaseData>>, NullaryError> {
+    discover_bases_at(&get_service_url("bdo"), refresh.unwrap_or(false)).await
+}
@@ -0,0 +1,215 @@
+// Authenticated service clients
+//
+// Every constructor points at the service URL for the current environment and
+// signs with the key derived for that base and service, so no two bases see
+// the same public key. The `_at` variants take an explicit URL for commands
+// that talk to a specific base.
+//
+// The client crates sign their own auth messages, so a constructor only gets
+// the key through the signing policy, which journals the hand-over; that is
+// why they are async.
+
+use addie_rs::Addie;
+use bdo_rs::structs::BDOUser;
+use bdo_rs::BDO;
+use dolores_rs::{Dolores, DoloresUser};
+use fount_rs::{Fount, FountUser};
+use julia_rs::Julia;
+use prof_rs::ProfClient;
+use sanora_rs::structs::SanoraUser;
+use sanora_rs::Sanora;
+use serde_json::json;
+use sessionless::hex::IntoHex;
+use tracing::info;
+
+use sessionless::Sessionless;
+
+use crate::error::NullaryError;
+use crate::registry::service_user_uuid;
+use crate::services::{base_for_url, get_service_url};
+use crate::signing::client_sessionless;
+use crate::user_persistence::get_sessionless;
+
+/// Identity for `service` on the base serving `url`
+fn sessionless_at(url: &str, service: &str) -> Result<Sessionless, NullaryError> {
+    get_sessionless(&base_for_url(url)?, service)
+}
+
+// ===== CLIENT CONSTRUCTORS =====
+
+pub async fn addie_client() -> Result<Addie, NullaryError> {
+    addie_client_at(&get_service_url("addie")).await
+}
+
+pub async fn addie_client_at(url: &str) -> Result<Addie, NullaryError> {
+    Ok(Addie::new(Some(url.to_string()), Some(client_sessionless(url, "addie").await?)))
+}
+
+pub async fn bdo_client() -> Result<BDO, NullaryError> {
+    bdo_client_at(&get_service_url("bdo")).await
+}
+
+pub async fn bdo_client_at(url: &str) -> Result<BDO, NullaryError> {
+    Ok(BDO::new(Some(url.to_string()), Some(client_sessionless(url, "bdo").await?)))
+}
+
+pub async fn dolores_client() -> Result<Dolores, NullaryError> {
+    dolores_client_at(&get_service_url("dolores")).await
+}
+
+pub async fn dolores_client_at(url: &str) -> Result<Dolores, NullaryError> {
+    Ok(Dolores::new(Some(url.to_string()), Some(client_sessionless(url, "dolores").await?)))
+}
+
+pub async fn fount_client() -> Result<Fount, NullaryError> {
+    fount_client_at(&get_service_url("fount")).await
+}
+
+pub async fn fount_client_at(url: &str) -> Result<Fount, NullaryError> {
+    Ok(Fount::new(Some(url.to_string()), Some(client_sessionless(url, "fount").await?)))
+}
+
+pub async fn julia_client() -> Result<Julia, NullaryError> {
+    julia_client_at(&get_service_url("julia")).await
+}
+
+pub async fn julia_client_at(url: &str) -> Result<Julia, NullaryError> {
+    let mut julia = Julia::new(Some(url.to_string()));
+    julia.sessionless = client_sessionless(url, "julia").await?;
+    Ok(julia)
+}
+
+pub async fn prof_client() -> Result<ProfClient, NullaryError> {
+    prof_client_at(&get_service_url("prof")).await
+}
+
+pub async fn prof_client_at(url: &str) -> Result<ProfClient, NullaryError> {
+    Ok(ProfClient::new(url.to_string()).with_sessionless(client_sessionless(url, "prof").await?))
+}
+
+pub async fn sanora_client() -> Result<Sanora, NullaryError> {
+    sanora_client_at(&get_service_url("sanora")).await
+}
+
+pub async fn sanora_client_at(url: &str) -> Result<Sanora, NullaryError> {
+    Ok(Sanora::new(Some(url.to_string()), Some(client_sessionless(url, "sanora").await?)))
+}
+
+// ===== PUBLIC KEYS =====
+
+/// Public key this install uses for `service` on the base at `url`
+pub fn public_key_at(url: &str, service: &str) -> Result<String, NullaryError> {
+    Ok(sessionless_at(url, service)?.public_key().to_hex())
+}
+
+/// Public key this install uses for `service` in the current environment
+pub fn public_key(service: &str) -> Result<String, NullaryError> {
+    public_key_at(&get_service_url(service), service)
+}
+
+// ===== SERVICE USERS =====
+
+/// Create (or fetch) the BDO user for the given hash, usually the app name
+pub async fn create_bdo_user_at(url: &str, hash: &str) -> Result<BDOUser, NullaryError> {
+    info!("🔗 Creating BDO user on: {}", url);
+
+    bdo_client_at(url).await?
+        .create_user(hash, &json!({}))
+        .await
+        .map_err(|e| NullaryError::service("bdo", format!("Failed to create BDO user: {}", e)))
+}
+
+pub async fn create_bdo_user(hash: &str) -> Result<BDOUser, NullaryError> {
+    create_bdo_user_at(&get_service_url("bdo"), hash).await
+}
+
+pub async fn create_dolores_user_at(url: &str) -> Result<DoloresUser, NullaryError> {
+    info!("🔗 Creating Dolores user on: {}", url);
+
+    dolores_client_at(url).await?
+        .create_user()
+        .await
+        .map_err(|e| NullaryError::service("dolores", format!("Failed to create Dolores user: {}", e)))
+}
+
+pub async fn create_dolores_user() -> Result<DoloresUser, NullaryError> {
+    create_dolores_user_at(&get_service_url("dolores")).await
+}
+
+pub async fn create_fount_user_at(url: &str) -> Result<FountUser, NullaryError> {
+    info!("🔗 Creating Fount user on: {}", url);
+
+    fount_client_at(url).await?
+        .create_user()
+        .await
+        .map_err(|e| NullaryError::service("fount", format!("Failed to create Fount user: {}", e)))
+}
+
+pub async fn create_fount_user() -> Result<FountUser, NullaryError> {
+    create_fount_user_at(&get_service_url("fount")).await
+}
+
+pub async fn create_sanora_user_at(url: &str) -> Result<SanoraUser, NullaryError> {
+    info!("🔗 Creating Sanora user on: {}", url);
+
+    sanora_client_at(url).await?
+        .create_user()
+        .await
+        .map_err(|e| NullaryError::service("sanora", format!("Failed to create Sanora user: {}", e)))
+}
+
+pub async fn create_sanora_user() -> Result<SanoraUser, NullaryError> {
+    create_sanora_user_at(&get_service_url("sanora")).await
+}
+
+// ===== REGISTERED SERVICE USERS =====
+//
+// UUID of this identity's user on a service, created on first use and then
+// served from the registry (see registry.rs). Pass errors from calls that use
+// the UUID to `forget_if_not_found` so a deleted user is registered again.
+
+pub async fn addie_user_uuid_at(url: &str) -> Result<String, NullaryError> {
+    service_user_uuid(url, "addie", None, || async {
+        info!("🔗 Creating Addie user on: {}", url);
+
+        addie_client_at(url).await?
+            .create_user()
+            .await
+            .map(|user| user.uuid)
+            .map_err(|e| NullaryError::service("addie", format!("Failed to create Addie user: {}", e)))
+    })
+    .await
+}
+
+pub async fn addie_user_uuid() -> Result<String, NullaryError> {
+    addie_user_uuid_at(&get_service_url("addie")).await
+}
+
+/// BDO keeps a user per hash, so the hash is part of the registry key
+pub async fn bdo_user_uuid_at(url: &str, hash: &str) -> Result<String, NullaryError> {
+    service_user_uuid(url, "bdo", Some(hash), || async {
+        create_bdo_user_at(url, hash).await.map(|user| user.uuid)
+    })
+    .await
+}
+
+pub async fn dolores_user_uuid_at(url: &str) -> Result<String, NullaryError> {
+    service_user_uuid(url, "dolores", None, || async {
+        create_dolores_user_at(url).await.map(|user| user.uuid)
+    })
+    .await
+}
+
+pub async fn fount_user_uuid_at(url: &str) -> Result<String, NullaryError> {
+    service_user_uuid(url, "fount", None, || async {
+        create_fount_user_at(url).await.map(|user| user.uuid)
+    })
+    .await
+}
+
+pub async fn sanora_user_uuid_at(url: &str) -> Result<String, NullaryError> {
+    service_user_uuid(url, "sanora", None, || async {
+        create_sanora_user_at(url).await.map(|user| user.uuid)
+    })
+    .await
+}
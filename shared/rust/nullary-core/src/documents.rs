@@ -0,0 +1,502 @@
+// Keyed document store for per-persona user data
+//
+// Apps keep their non-sensitive data (preferences, service users, ...) as
+// JSON documents in the active persona's data dir. A key is a relative path
+// of safe segments such as `preferences.json` or `drafts/post-1.json`; it can
+// never leave the data dir. Each document is saved with its schema version:
+//
+// ```json
+// { "format": "nullary-document", "key": "preferences.json",
+//   "schemaVersion": 2, "updatedAt": 1700000000000, "data": { ... } }
+// ```
+//
+// Writes go to a temp file that is renamed over the document, so a crash
+// leaves the old or the new version, never half of one. Files written before
+// the store existed are read as version 0. When a document is older than the
+// migrations registered for its key, they run in order on load and the
+// result is saved back.
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use tracing::info;
+
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+
+use crate::error::NullaryError;
+use crate::rotation::ROTATIONS_FILE;
+use crate::user_persistence::{active_persona, get_user_data_dir, SERVICE_UUIDS_FILE};
+
+pub const DOCUMENT_FORMAT: &str = "nullary-document";
+
+const MAX_KEY_LENGTH: usize = 200;
+
+// Files nullary-core keeps in the persona dir itself
+const RESERVED_KEYS: [&str; 2] = [SERVICE_UUIDS_FILE, ROTATIONS_FILE];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StoredDocument {
+    format: String,
+    key: String,
+    schema_version: u32,
+    updated_at: i64,
+    data: Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentInfo {
+    pub key: String,
+    pub schema_version: u32,
+    pub updated_at: i64,
+}
+
+// ===== MIGRATIONS =====
+
+/// Upgrades a document's data from one schema version to the next
+pub type Migration = Box<dyn Fn(Value) -> Result<Value, String> + Send + Sync>;
+
+// Key -> version migrated from -> migration
+static MIGRATIONS: LazyLock<Mutex<BTreeMap<String, BTreeMap<u32, Migration>>>> =
+    LazyLock::new(|| Mutex::new(BTreeMap::new()));
+
+fn migrations() -> std::sync::MutexGuard<'static, BTreeMap<String, BTreeMap<u32, Migration>>> {
+    MIGRATIONS.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Register the migration from `from_version` to `from_version + 1` for a
+/// key. A key's current schema version is one past its highest migration, or
+/// 0 while it has none. Register at startup, before the document is loaded.
+pub fn register_migration<F>(key: &str, from_version: u32, migrate: F) -> Result<(), NullaryError>
+where
+    F: Fn(Value) -> Result<Value, String> + Send + Sync + 'static,
+{
+    validate_document_key(key)?;
+
+    let mut migrations = migrations();
+    let steps = migrations.entry(key.to_string()).or_default();
+    if steps.contains_key(&from_version) {
+        return Err(NullaryError::internal(format!(
+            "A migration for '{}' from version {} is already registered",
+            key, from_version
+        )));
+    }
+    steps.insert(from_version, Box::new(migrate));
+
+    Ok(())
+}
+
+/// Schema version new writes of `key` are saved with
+pub fn schema_version(key: &str) -> u32 {
+    migrations()
+        .get(key)
+        .and_then(|steps| steps.keys().next_back())
+        .map(|from| from + 1)
+        .unwrap_or(0)
+}
+
+/// Run the registered migrations from `version` up to the current version
+fn migrate(key: &str, mut version: u32, mut data: Value) -> Result<(u32, Value), NullaryError> {
+    let migrations = migrations();
+    let Some(steps) = migrations.get(key) else {
+        return Ok((version, data));
+    };
+    let current = steps.keys().next_back().map(|from| from + 1).unwrap_or(0);
+
+    while version < current {
+        let step = steps
+            .get(&version)
+            .ok_or_else(|| NullaryError::internal(format!("No migration for '{}' from version {}", key, version)))?;
+        data = step(data).map_err(|e| {
+            NullaryError::internal(format!("Failed to migrate '{}' from version {}: {}", key, version, e))
+        })?;
+        version += 1;
+    }
+
+    Ok((version, data))
+}
+
+// ===== PATHS =====
+
+fn is_safe_segment(segment: &str) -> bool {
+    !segment.is_empty()
+        && !segment.starts_with('.')
+        && segment
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+}
+
+/// Keys are `/`-separated segments of letters, digits, `-`, `_` and `.`;
+/// no segment may be empty or start with a dot, so `..` and hidden temp
+/// files are out of reach
+pub fn validate_document_key(key: &str) -> Result<(), NullaryError> {
+    if key.len() > MAX_KEY_LENGTH {
+        return Err(NullaryError::validation(format!(
+            "Document key is longer than {} characters",
+            MAX_KEY_LENGTH
+        )));
+    }
+
+    if !key.split('/').all(is_safe_segment) {
+        return Err(NullaryError::validation(format!("Invalid document key '{}'", key)));
+    }
+
+    if RESERVED_KEYS.contains(&key) {
+        return Err(NullaryError::validation(format!("Document key '{}' is reserved", key)));
+    }
+
+    Ok(())
+}
+
+/// Path of a document inside `dir`, checked to stay inside it even through
+/// symlinks that already exist on disk
+fn document_path(dir: &Path, key: &str) -> Result<PathBuf, NullaryError> {
+    validate_document_key(key)?;
+    let path = dir.join(key);
+
+    if !dir.exists() {
+        return Ok(path);
+    }
+
+    // The document itself or its nearest existing directory
+    let existing = path.ancestors().find(|ancestor| ancestor.exists()).unwrap_or(dir);
+    let root = dir
+        .canonicalize()
+        .map_err(|e| NullaryError::storage(format!("Failed to resolve user data directory: {}", e)))?;
+    let resolved = existing
+        .canonicalize()
+        .map_err(|e| NullaryError::storage(format!("Failed to resolve document path: {}", e)))?;
+    if !resolved.starts_with(&root) {
+        return Err(NullaryError::validation(format!(
+            "Document key '{}' resolves outside the user data directory",
+            key
+        )));
+    }
+
+    Ok(path)
+}
+
+/// Replace `path` with `bytes` via a temp file in the same directory
+pub(crate) fn write_atomic(path: &Path, bytes: &[u8]) -> Result<(), NullaryError> {
+    let dir = path
+        .parent()
+        .ok_or_else(|| NullaryError::storage(format!("No parent directory for {}", path.display())))?;
+    fs::create_dir_all(dir)
+        .map_err(|e| NullaryError::storage(format!("Failed to create directory {}: {}", dir.display(), e)))?;
+
+    let file_name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| NullaryError::storage(format!("Invalid file name: {}", path.display())))?;
+    let mut suffix = [0u8; 4];
+    OsRng.fill_bytes(&mut suffix);
+    let temp_path = dir.join(format!(".{}.{}.tmp", file_name, hex::encode(suffix)));
+
+    let result = (|| {
+        let mut file = fs::File::create(&temp_path)
+            .map_err(|e| NullaryError::storage(format!("Failed to create temp file: {}", e)))?;
+        file.write_all(bytes)
+            .map_err(|e| NullaryError::storage(format!("Failed to write temp file: {}", e)))?;
+        file.sync_all()
+            .map_err(|e| NullaryError::storage(format!("Failed to sync temp file: {}", e)))?;
+        fs::rename(&temp_path, path)
+            .map_err(|e| NullaryError::storage(format!("Failed to replace {}: {}", path.display(), e)))
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result
+}
+
+// ===== STORE =====
+
+/// Read a document from disk. Files without the envelope are legacy raw
+/// files: JSON content becomes the data as-is, anything else a string.
+fn read_stored(path: &Path, key: &str) -> Result<Option<StoredDocument>, NullaryError> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => {
+            return Err(NullaryError::storage(format!(
+                "Failed to read document '{}': {}",
+                key, e
+            )))
+        }
+    };
+
+    let parsed: Option<Value> = serde_json::from_str(&content).ok();
+    let is_envelope = parsed
+        .as_ref()
+        .and_then(|value| value.get("format"))
+        .and_then(|format| format.as_str())
+        == Some(DOCUMENT_FORMAT);
+
+    if is_envelope {
+        let stored = serde_json::from_value(parsed.unwrap_or_default())
+            .map_err(|e| NullaryError::storage(format!("Document '{}' is corrupted: {}", key, e)))?;
+        return Ok(Some(stored));
+    }
+
+    let modified = fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .map(|time| chrono::DateTime::<chrono::Utc>::from(time).timestamp_millis())
+        .unwrap_or(0);
+
+    Ok(Some(StoredDocument {
+        format: DOCUMENT_FORMAT.to_string(),
+        key: key.to_string(),
+        schema_version: 0,
+        updated_at: modified,
+        data: parsed.unwrap_or(Value::String(content)),
+    }))
+}
+
+fn write_stored(path: &Path, key: &str, schema_version: u32, data: Value) -> Result<DocumentInfo, NullaryError> {
+    let stored = StoredDocument {
+        format: DOCUMENT_FORMAT.to_string(),
+        key: key.to_string(),
+        schema_version,
+        updated_at: chrono::Utc::now().timestamp_millis(),
+        data,
+    };
+
+    let json = serde_json::to_string_pretty(&stored)
+        .map_err(|e| NullaryError::internal(format!("Failed to serialize document '{}': {}", key, e)))?;
+    write_atomic(path, json.as_bytes())?;
+
+    Ok(DocumentInfo {
+        key: stored.key,
+        schema_version: stored.schema_version,
+        updated_at: stored.updated_at,
+    })
+}
+
+/// Load a document's data from `dir`, migrating and saving it back first if
+/// it is older than the current schema version
+fn load_in(dir: &Path, key: &str) -> Result<Option<Value>, NullaryError> {
+    let path = document_path(dir, key)?;
+    let Some(stored) = read_stored(&path, key)? else {
+        return Ok(None);
+    };
+
+    let current = schema_version(key);
+    if stored.schema_version > current {
+        return Err(NullaryError::storage(format!(
+            "Document '{}' has schema version {} but this app knows up to {}",
+            key, stored.schema_version, current
+        )));
+    }
+
+    if stored.schema_version == current {
+        return Ok(Some(stored.data));
+    }
+
+    let (version, data) = migrate(key, stored.schema_version, stored.data)?;
+    write_stored(&path, key, version, data.clone())?;
+
+    info!(
+        "📄 Migrated document {} from version {} to {}",
+        key, stored.schema_version, version
+    );
+    Ok(Some(data))
+}
+
+/// Load a document from the active persona's data dir
+pub fn load_document<T: DeserializeOwned>(key: &str) -> Result<Option<T>, NullaryError> {
+    let dir = get_user_data_dir()?;
+
+    load_in(&dir, key)?
+        .map(|data| {
+            serde_json::from_value(data)
+                .map_err(|e| NullaryError::storage(format!("Document '{}' has unexpected contents: {}", key, e)))
+        })
+        .transpose()
+}
+
+/// Save a document to the active persona's data dir at the key's current
+/// schema version
+pub fn save_document<T: Serialize>(
+    key: &str,
+    data: &T,
+) -> Result<DocumentInfo, NullaryError> {
+    let dir = get_user_data_dir()?;
+    let path = document_path(&dir, key)?;
+    let data = serde_json::to_value(data)
+        .map_err(|e| NullaryError::internal(format!("Failed to serialize document '{}': {}", key, e)))?;
+
+    write_stored(&path, key, schema_version(key), data)
+}
+
+/// Remove a document; returns whether it existed
+pub fn remove_document(key: &str) -> Result<bool, NullaryError> {
+    let dir = get_user_data_dir()?;
+    let path = document_path(&dir, key)?;
+
+    match fs::remove_file(&path) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(NullaryError::storage(format!(
+            "Failed to remove document '{}': {}",
+            key, e
+        ))),
+    }
+}
+
+fn collect_keys(dir: &Path, prefix: &str, keys: &mut Vec<String>) -> Result<(), NullaryError> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(NullaryError::storage(format!("Failed to list documents: {}", e))),
+    };
+
+    for entry in entries {
+        let entry = entry.map_err(|e| NullaryError::storage(format!("Failed to list documents: {}", e)))?;
+        let Some(name) = entry.file_name().to_str().map(|name| name.to_string()) else {
+            continue;
+        };
+        if !is_safe_segment(&name) {
+            continue;
+        }
+
+        let key = if prefix.is_empty() {
+            name
+        } else {
+            format!("{}/{}", prefix, name)
+        };
+        let file_type = entry
+            .file_type()
+            .map_err(|e| NullaryError::storage(format!("Failed to list documents: {}", e)))?;
+        if file_type.is_dir() {
+            collect_keys(&entry.path(), &key, keys)?;
+        } else if file_type.is_file() && !RESERVED_KEYS.contains(&key.as_str()) {
+            keys.push(key);
+        }
+    }
+
+    Ok(())
+}
+
+/// Keys of every document in the active persona's data dir
+pub fn document_keys() -> Result<Vec<String>, NullaryError> {
+    let mut keys = Vec::new();
+    collect_keys(&get_user_data_dir()?, "", &mut keys)?;
+    keys.sort();
+    Ok(keys)
+}
+
+// ===== TAURI COMMANDS =====
+
+#[cfg_attr(feature = "tauri", tauri::command)]
+pub async fn read_document(key: String) -> Result<Option<Value>, NullaryError> {
+    info!("📄 Reading document {} for persona: {}", key, active_persona());
+    load_document(&key)
+}
+
+#[cfg_attr(feature = "tauri", tauri::command)]
+pub async fn write_document(
+    key: String,
+    data: Value,
+) -> Result<DocumentInfo, NullaryError> {
+    info!("💾 Writing document {} for persona: {}", key, active_persona());
+    save_document(&key, &data)
+}
+
+#[cfg_attr(feature = "tauri", tauri::command)]
+pub async fn delete_document(key: String) -> Result<bool, NullaryError> {
+    info!("🗑️ Deleting document {} for persona: {}", key, active_persona());
+    remove_document(&key)
+}
+
+#[cfg_attr(feature = "tauri", tauri::command)]
+pub async fn list_documents() -> Result<Vec<String>, NullaryError> {
+    document_keys()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn keys_stay_inside_the_data_dir() {
+        for key in ["preferences.json", "drafts/post-1.json", "a_b/c-d.e"] {
+            assert!(validate_document_key(key).is_ok(), "{}", key);
+        }
+        for key in [
+            "",
+            "../outside.json",
+            "drafts/../../outside.json",
+            "/etc/passwd",
+            "drafts//post.json",
+            ".hidden",
+            "drafts/.post.json.1234.tmp",
+            "back\\slash.json",
+            SERVICE_UUIDS_FILE,
+            ROTATIONS_FILE,
+        ] {
+            assert!(validate_document_key(key).is_err(), "{}", key);
+        }
+        assert!(validate_document_key(&"a".repeat(MAX_KEY_LENGTH + 1)).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinks_out_of_the_data_dir_are_refused() {
+        let dir = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        std::os::unix::fs::symlink(outside.path(), dir.path().join("linked")).unwrap();
+
+        assert!(document_path(dir.path(), "linked/stolen.json").is_err());
+        assert!(document_path(dir.path(), "drafts/post.json").is_ok());
+    }
+
+    #[test]
+    fn legacy_files_are_migrated_and_saved_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let key = "migrations-test/settings.json";
+        register_migration(key, 0, |data| Ok(json!({ "theme": data["theme"], "fontSize": 14 }))).unwrap();
+        register_migration(key, 1, |mut data| {
+            data["fontSize"] = json!(data["fontSize"].as_u64().unwrap_or(0) + 2);
+            Ok(data)
+        })
+        .unwrap();
+        assert_eq!(schema_version(key), 2);
+
+        let path = dir.path().join(key);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, r#"{ "theme": "dark" }"#).unwrap();
+
+        let expected = json!({ "theme": "dark", "fontSize": 16 });
+        assert_eq!(load_in(dir.path(), key).unwrap(), Some(expected.clone()));
+
+        let stored: StoredDocument = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(stored.schema_version, 2);
+        assert_eq!(stored.data, expected);
+
+        // Loading again doesn't migrate twice
+        assert_eq!(load_in(dir.path(), key).unwrap(), Some(expected));
+    }
+
+    #[test]
+    fn newer_documents_are_refused() {
+        let dir = tempfile::tempdir().unwrap();
+        let key = "migrations-test/newer.json";
+        write_stored(&dir.path().join(key), key, 3, json!({})).unwrap();
+
+        assert!(load_in(dir.path(), key).is_err());
+    }
+
+    #[test]
+    fn duplicate_migrations_are_refused() {
+        let key = "migrations-test/duplicate.json";
+        register_migration(key, 0, Ok).unwrap();
+        assert!(register_migration(key, 0, Ok).is_err());
+    }
+}
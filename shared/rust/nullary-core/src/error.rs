@@ -0,0 +1,287 @@
+// Error type returned by every Nullary command
+//
+// Failures reach the frontend as `{ kind, message, service?, status?, cause }`
+// so it can tell a base that is down (`network`, `timeout`) from one that
+// doesn't know this user (`notFound`) or rejected our keys (`auth`), and
+// local problems (`storage`, `validation`) from remote ones. A write that
+// couldn't reach its base but was kept in the outbox comes back as `queued`
+// with the item's `outboxId`. `cause` is the text of the underlying error;
+// `message` is the whole thing for display.
+
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NullaryError {
+    /// The service could not be reached (DNS, refused connection, TLS, ...)
+    Network { service: String, cause: String },
+    /// The service did not answer in time
+    Timeout { service: String, cause: String },
+    /// The service rejected our signature or doesn't accept this key
+    Auth { service: String, cause: String },
+    /// The user or resource does not exist on the service
+    NotFound { service: String, cause: String },
+    /// The request was invalid, whether checked here or by the service
+    Validation { cause: String },
+    /// The service answered with an error
+    Server {
+        service: String,
+        status: Option<u16>,
+        cause: String,
+    },
+    /// The vault, the filesystem or another local store failed
+    Storage { cause: String },
+    /// Anything else
+    Internal { cause: String },
+    /// The service could not be reached, so the write was queued in the
+    /// outbox as `id`
+    Queued {
+        service: String,
+        id: String,
+        cause: String,
+    },
+}
+
+impl NullaryError {
+    pub fn validation(cause: impl fmt::Display) -> Self {
+        NullaryError::Validation {
+            cause: cause.to_string(),
+        }
+    }
+
+    pub fn storage(cause: impl fmt::Display) -> Self {
+        NullaryError::Storage {
+            cause: cause.to_string(),
+        }
+    }
+
+    pub fn internal(cause: impl fmt::Display) -> Self {
+        NullaryError::Internal {
+            cause: cause.to_string(),
+        }
+    }
+
+    pub fn network(service: &str, cause: impl fmt::Display) -> Self {
+        NullaryError::Network {
+            service: service.to_string(),
+            cause: cause.to_string(),
+        }
+    }
+
+    pub fn not_found(service: &str, cause: impl fmt::Display) -> Self {
+        NullaryError::NotFound {
+            service: service.to_string(),
+            cause: cause.to_string(),
+        }
+    }
+
+    /// Classify an error from a `service` client by its HTTP status, a
+    /// `reqwest::StatusCode` or its `u16`
+    pub fn from_status(service: &str, status: impl Into<u16>, cause: impl fmt::Display) -> Self {
+        let service = service.to_string();
+        let cause = cause.to_string();
+        let status = status.into();
+
+        match status {
+            401 | 403 => NullaryError::Auth { service, cause },
+            404 => NullaryError::NotFound { service, cause },
+            408 | 504 => NullaryError::Timeout { service, cause },
+            400 | 422 => NullaryError::Validation {
+                cause: format!("{} rejected the request: {}", service, cause),
+            },
+            _ => NullaryError::Server {
+                service,
+                status: Some(status),
+                cause,
+            },
+        }
+    }
+
+    /// Classify a failed request made with reqwest directly, from the error
+    /// itself rather than its text
+    pub fn from_reqwest(service: &str, context: &str, error: &reqwest::Error) -> Self {
+        let cause = format!("{}: {}", context, error);
+        if error.is_timeout() {
+            NullaryError::Timeout {
+                service: service.to_string(),
+                cause,
+            }
+        } else if let Some(status) = error.status() {
+            NullaryError::from_status(service, status, cause)
+        } else if error.is_connect() {
+            NullaryError::network(service, cause)
+        } else {
+            NullaryError::service(service, cause)
+        }
+    }
+
+    /// Classify an error from a `service` client. The client crates only
+    /// expose their errors as text, so this goes by the usual reqwest and
+    /// hyper wording. It never reads a status out of the text; call sites
+    /// holding a response use `from_status`.
+    pub fn service(service: &str, error: impl fmt::Display) -> Self {
+        let cause = error.to_string();
+        let lower = cause.to_lowercase();
+
+        if lower.contains("timed out") || lower.contains("timeout") {
+            return NullaryError::Timeout {
+                service: service.to_string(),
+                cause,
+            };
+        }
+
+        if lower.contains("unauthorized") || lower.contains("forbidden") || lower.contains("signature") {
+            return NullaryError::Auth {
+                service: service.to_string(),
+                cause,
+            };
+        }
+
+        if lower.contains("not found") || lower.contains("notfound") {
+            return NullaryError::not_found(service, cause);
+        }
+
+        let unreachable = [
+            "error sending request",
+            "connection refused",
+            "connection reset",
+            "connect error",
+            "tcp connect",
+            "dns error",
+            "failed to lookup address",
+        ];
+        if unreachable.iter().any(|pattern| lower.contains(pattern)) {
+            return NullaryError::network(service, cause);
+        }
+
+        NullaryError::Server {
+            service: service.to_string(),
+            status: None,
+            cause,
+        }
+    }
+
+    /// Wire name of the kind, as sent to the frontend
+    pub fn kind(&self) -> &'static str {
+        match self {
+            NullaryError::Network { .. } => "network",
+            NullaryError::Timeout { .. } => "timeout",
+            NullaryError::Auth { .. } => "auth",
+            NullaryError::NotFound { .. } => "notFound",
+            NullaryError::Validation { .. } => "validation",
+            NullaryError::Server { .. } => "server",
+            NullaryError::Storage { .. } => "storage",
+            NullaryError::Internal { .. } => "internal",
+            NullaryError::Queued { .. } => "queued",
+        }
+    }
+
+    /// Service the error came from, if it came from one
+    pub fn service_name(&self) -> Option<&str> {
+        match self {
+            NullaryError::Network { service, .. }
+            | NullaryError::Timeout { service, .. }
+            | NullaryError::Auth { service, .. }
+            | NullaryError::NotFound { service, .. }
+            | NullaryError::Server { service, .. }
+            | NullaryError::Queued { service, .. } => Some(service),
+            _ => None,
+        }
+    }
+
+    /// Text of the underlying error
+    pub fn cause(&self) -> &str {
+        match self {
+            NullaryError::Network { cause, .. }
+            | NullaryError::Timeout { cause, .. }
+            | NullaryError::Auth { cause, .. }
+            | NullaryError::NotFound { cause, .. }
+            | NullaryError::Validation { cause }
+            | NullaryError::Server { cause, .. }
+            | NullaryError::Storage { cause }
+            | NullaryError::Internal { cause }
+            | NullaryError::Queued { cause, .. } => cause,
+        }
+    }
+
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, NullaryError::NotFound { .. })
+    }
+
+    /// Whether the failure says nothing about the request itself, so trying
+    /// again later may work
+    pub fn is_transient(&self) -> bool {
+        match self {
+            NullaryError::Network { .. } | NullaryError::Timeout { .. } => true,
+            NullaryError::Server { status, .. } => matches!(status, Some(502) | Some(503)),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for NullaryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NullaryError::Network { service, cause } => write!(f, "{} is unreachable: {}", service, cause),
+            NullaryError::Timeout { service, cause } => write!(f, "{} timed out: {}", service, cause),
+            NullaryError::Auth { service, cause } => write!(f, "{} rejected our credentials: {}", service, cause),
+            NullaryError::NotFound { service, cause } => write!(f, "Not found on {}: {}", service, cause),
+            NullaryError::Validation { cause } => write!(f, "{}", cause),
+            NullaryError::Server {
+                service,
+                status: Some(status),
+                cause,
+            } => write!(f, "{} returned {}: {}", service, status, cause),
+            NullaryError::Server { service, cause, .. } => write!(f, "{} failed: {}", service, cause),
+            NullaryError::Storage { cause } => write!(f, "Local storage error: {}", cause),
+            NullaryError::Internal { cause } => write!(f, "{}", cause),
+            NullaryError::Queued { service, cause, .. } => {
+                write!(f, "{} is unreachable, queued to send later: {}", service, cause)
+            }
+        }
+    }
+}
+
+impl std::error::Error for NullaryError {}
+
+impl Serialize for NullaryError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("NullaryError", 6)?;
+        state.serialize_field("kind", self.kind())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.serialize_field("service", &self.service_name())?;
+        let status = match self {
+            NullaryError::Server { status, .. } => *status,
+            _ => None,
+        };
+        state.serialize_field("status", &status)?;
+        state.serialize_field("cause", self.cause())?;
+        let outbox_id = match self {
+            NullaryError::Queued { id, .. } => Some(id.as_str()),
+            _ => None,
+        };
+        state.serialize_field("outboxId", &outbox_id)?;
+        state.end()
+    }
+}
+
+// Code that still reports errors as text converts both ways with `?`
+
+impl From<String> for NullaryError {
+    fn from(cause: String) -> Self {
+        NullaryError::Internal { cause }
+    }
+}
+
+impl From<&str> for NullaryError {
+    fn from(cause: &str) -> Self {
+        NullaryError::internal(cause)
+    }
+}
+
+impl From<NullaryError> for String {
+    fn from(error: NullaryError) -> Self {
+        error.to_string()
+    }
+}
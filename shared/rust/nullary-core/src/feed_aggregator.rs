@@ -0,0 +1,379 @@
+// Feed aggregation
+//
+// A feed covers the home base and every joined base that isn't muted.
+// `aggregate_feed` asks all of them at once, each through the feed cache and
+// each with its own timeout, so one slow base costs the feed its posts but
+// not its speed. The posts are merged, copies of a post carried by several
+// bases dropped, and sorted newest first.
+//
+// Alongside the posts comes a `FeedSync`: one `BaseSync` per base saying
+// whether it answered, how it failed and how long it took, plus the totals
+// `sync-status.js` shows as "Partial sync (2/3 bases)". A base that fails
+// still contributes its cached page, flagged stale, so a failed sync only
+// leaves the feed empty when nothing was ever cached.
+
+use futures::future::join_all;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::future::Future;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+use crate::bases::{home_base, joined_bases, BaseData};
+use crate::error::NullaryError;
+use crate::feed_cache::{cached_feed_as, cached_page, fetch_feed, FeedFetch, FeedKey, Validators};
+use crate::moderation::moderation;
+use crate::services::base_for_url;
+use crate::user_persistence::active_persona;
+
+// How long one base gets to answer before the feed goes on without it
+const BASE_TIMEOUT: Duration = Duration::from_secs(10);
+
+// ===== TYPES =====
+
+/// A base a feed is fetched from, and its URL for the feed's service
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedSource {
+    pub base_id: String,
+    pub base_name: String,
+    pub url: String,
+}
+
+impl FeedSource {
+    fn of(base: &BaseData, url: &str) -> Self {
+        Self {
+            base_id: base.id.clone(),
+            base_name: base.name.clone(),
+            url: url.to_string(),
+        }
+    }
+}
+
+/// How a sync went, in the words `sync-status.js` uses
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncStatus {
+    Success,
+    Partial,
+    Failed,
+}
+
+/// How one base answered
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BaseSync {
+    pub base_id: String,
+    pub base_name: String,
+    pub url: String,
+    /// `success` or `failed`
+    pub status: SyncStatus,
+    /// `NullaryError::kind()` of the failure: `timeout`, `network`, ...
+    pub error_kind: Option<String>,
+    pub error: Option<String>,
+    pub duration_ms: u64,
+    pub post_count: usize,
+    /// The posts are an old cached page
+    pub stale: bool,
+    /// When this base's posts were fetched; `None` if there are none
+    pub fetched_at: Option<i64>,
+}
+
+/// How every base answered, with the totals the sync banner shows
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedSync {
+    pub status: SyncStatus,
+    pub total_bases: usize,
+    pub successful_bases: usize,
+    pub failed_bases: usize,
+    pub bases: Vec<BaseSync>,
+}
+
+/// Merged posts from every base, with how each base answered
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AggregatedFeed<T> {
+    pub data: Vec<T>,
+    /// Some base's posts are an old cached page
+    pub stale: bool,
+    /// When the oldest page was fetched
+    pub fetched_at: i64,
+    pub sync: FeedSync,
+}
+
+impl<T> AggregatedFeed<T> {
+    /// Every base failed and none had posts cached
+    pub fn unavailable(&self) -> bool {
+        self.data.is_empty() && self.sync.status == SyncStatus::Failed
+    }
+}
+
+/// What merging needs to know about a post
+pub trait FeedItem {
+    /// Id the post keeps on every base that carries it
+    fn feed_id(&self) -> Option<String>;
+    /// When the post was made, in milliseconds since the epoch
+    fn feed_time(&self) -> Option<i64>;
+}
+
+impl FeedItem for Value {
+    fn feed_id(&self) -> Option<String> {
+        ["uuid", "id"]
+            .iter()
+            .find_map(|field| self.get(field).and_then(Value::as_str))
+            .filter(|id| !id.is_empty() && *id != "unknown")
+            .map(str::to_string)
+    }
+
+    fn feed_time(&self) -> Option<i64> {
+        ["timestamp", "created_at", "createdAt"]
+            .iter()
+            .find_map(|field| self.get(field).and_then(timestamp_ms))
+    }
+}
+
+/// A post time in milliseconds, from epoch seconds or milliseconds (as a
+/// number or a string) or an RFC 3339 date
+pub fn timestamp_ms(value: &Value) -> Option<i64> {
+    let number = match value {
+        Value::Number(number) => number
+            .as_i64()
+            .or_else(|| number.as_f64().map(|n| n as i64)),
+        Value::String(text) => match text.parse::<i64>() {
+            Ok(number) => Some(number),
+            Err(_) => {
+                return chrono::DateTime::parse_from_rfc3339(text)
+                    .ok()
+                    .map(|time| time.timestamp_millis())
+            }
+        },
+        _ => None,
+    }?;
+    // Seconds until the year 5138
+    Some(if number < 100_000_000_000 {
+        number * 1000
+    } else {
+        number
+    })
+}
+
+// ===== SOURCES =====
+
+/// Where a feed of `service` comes from. With a URL, just that base, named
+/// after the joined base it belongs to when there is one. Without, the home
+/// base first and then every joined base that runs the service and isn't
+/// muted, one source per base.
+pub fn feed_sources(service: &str, url: Option<&str>) -> Vec<FeedSource> {
+    let joined = joined_bases().unwrap_or_else(|e| {
+        warn!("⚠️ {} - home base only", e);
+        Vec::new()
+    });
+    let bases = std::iter::once(home_base()).chain(joined);
+
+    if let Some(url) = url {
+        let key = base_for_url(url).ok();
+        let source = bases
+            .filter_map(|base| {
+                let base_url = base.dns.url(service)?;
+                (base_for_url(base_url).ok() == key).then(|| FeedSource::of(&base, url))
+            })
+            .next();
+        return vec![source.unwrap_or_else(|| {
+            let id = key.unwrap_or_else(|| url.to_string());
+            FeedSource {
+                base_id: id.clone(),
+                base_name: id,
+                url: url.to_string(),
+            }
+        })];
+    }
+
+    let muted = moderation().muted_bases;
+    let mut seen = BTreeSet::new();
+    let mut sources = Vec::new();
+    for base in bases {
+        if muted.contains(&base.id) {
+            continue;
+        }
+        let Some(url) = base.dns.url(service) else {
+            continue;
+        };
+        let Ok(key) = base_for_url(url) else {
+            warn!("⚠️ Skipping {} URL of {}: {}", service, base.name, url);
+            continue;
+        };
+        if seen.insert(key) {
+            sources.push(FeedSource::of(&base, url));
+        }
+    }
+    sources
+}
+
+// ===== AGGREGATION =====
+
+/// One base's page through the feed cache, within `BASE_TIMEOUT`. On failure
+/// the cached page, if any, comes back with the failure.
+async fn sync_source<T, F, Fut>(
+    source: FeedSource,
+    service: String,
+    tags: String,
+    refresh: bool,
+    fetch: F,
+) -> (BaseSync, Option<(Vec<T>, bool, i64)>)
+where
+    T: Serialize + DeserializeOwned + Send + 'static,
+    F: FnOnce(String, Validators) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<FeedFetch<Vec<T>>, NullaryError>> + Send + 'static,
+{
+    let started = Instant::now();
+    let mut sync = BaseSync {
+        base_id: source.base_id,
+        base_name: source.base_name,
+        url: source.url.clone(),
+        status: SyncStatus::Success,
+        error_kind: None,
+        error: None,
+        duration_ms: 0,
+        post_count: 0,
+        stale: false,
+        fetched_at: None,
+    };
+
+    let key = match FeedKey::new(&source.url, &service, &tags) {
+        Ok(key) => key,
+        Err(e) => {
+            sync.status = SyncStatus::Failed;
+            sync.error_kind = Some(e.kind().to_string());
+            sync.error = Some(e.cause().to_string());
+            return (sync, None);
+        }
+    };
+    let url = source.url;
+    let fetch_page = move |validators| fetch(url, validators);
+    // The page and its fallback belong to the persona the sync started as
+    let persona = active_persona();
+
+    // Refreshing asks the base even when the cache is fresh
+    let page = if refresh {
+        tokio::time::timeout(BASE_TIMEOUT, fetch_feed(&key, &persona, fetch_page)).await
+    } else {
+        tokio::time::timeout(BASE_TIMEOUT, cached_feed_as(key.clone(), persona.clone(), fetch_page)).await
+    };
+    let page = page.unwrap_or_else(|_| {
+        Err(NullaryError::Timeout {
+            service: service.clone(),
+            cause: format!("No answer within {}s", BASE_TIMEOUT.as_secs()),
+        })
+    });
+    sync.duration_ms = started.elapsed().as_millis() as u64;
+
+    let page = match page {
+        Ok(page) => Some(page),
+        Err(e) => {
+            warn!(
+                "⚠️ {} {} feed failed after {}ms: {}",
+                sync.base_name, service, sync.duration_ms, e
+            );
+            sync.status = SyncStatus::Failed;
+            sync.error_kind = Some(e.kind().to_string());
+            sync.error = Some(e.cause().to_string());
+            cached_page::<Vec<T>>(&key, &persona)
+        }
+    };
+
+    let posts = page.map(|page| {
+        sync.post_count = page.data.len();
+        sync.stale = page.stale;
+        sync.fetched_at = Some(page.fetched_at);
+        (page.data, page.stale, page.fetched_at)
+    });
+    (sync, posts)
+}
+
+/// `service`'s feed from every source at once, merged, without duplicates
+/// and newest first, with how each base answered. `fetch` gets each source's
+/// URL and the validators of its cached page, for `conditional_get`. Only
+/// fails when there is no source; a feed where every base failed comes back
+/// with a `failed` sync and whatever was cached.
+pub async fn aggregate_feed<T, F, Fut>(
+    sources: Vec<FeedSource>,
+    service: &str,
+    tags: &str,
+    refresh: bool,
+    fetch: F,
+) -> Result<AggregatedFeed<T>, NullaryError>
+where
+    T: FeedItem + Serialize + DeserializeOwned + Send + 'static,
+    F: Fn(String, Validators) -> Fut + Clone + Send + 'static,
+    Fut: Future<Output = Result<FeedFetch<Vec<T>>, NullaryError>> + Send + 'static,
+{
+    if sources.is_empty() {
+        return Err(NullaryError::validation("No bases to fetch the feed from"));
+    }
+
+    let started = Instant::now();
+    let results = join_all(sources.into_iter().map(|source| {
+        sync_source(
+            source,
+            service.to_string(),
+            tags.to_string(),
+            refresh,
+            fetch.clone(),
+        )
+    }))
+    .await;
+
+    let mut data = Vec::new();
+    let mut stale = false;
+    let mut fetched_at = None::<i64>;
+    let mut bases = Vec::new();
+    for (sync, posts) in results {
+        if let Some((posts, page_stale, page_fetched_at)) = posts {
+            data.extend(posts);
+            stale |= page_stale;
+            fetched_at = Some(fetched_at.map_or(page_fetched_at, |at| at.min(page_fetched_at)));
+        }
+        bases.push(sync);
+    }
+
+    // Sources are in order, home first, so the home base's copy is kept
+    let mut seen = BTreeSet::new();
+    data.retain(|post| post.feed_id().is_none_or(|id| seen.insert(id)));
+    data.sort_by_key(|post| std::cmp::Reverse(post.feed_time()));
+
+    let total_bases = bases.len();
+    let successful_bases = bases
+        .iter()
+        .filter(|base| base.status == SyncStatus::Success)
+        .count();
+    let failed_bases = total_bases - successful_bases;
+    let status = match (successful_bases, failed_bases) {
+        (_, 0) => SyncStatus::Success,
+        (0, _) => SyncStatus::Failed,
+        _ => SyncStatus::Partial,
+    };
+    info!(
+        "🔄 {} feed: {}/{} bases, {} posts in {}ms",
+        service,
+        successful_bases,
+        total_bases,
+        data.len(),
+        started.elapsed().as_millis()
+    );
+
+    Ok(AggregatedFeed {
+        data,
+        stale: stale || failed_bases > 0,
+        fetched_at: fetched_at.unwrap_or(0),
+        sync: FeedSync {
+            status,
+            total_bases,
+            successful_bases,
+            failed_bases,
+            bases,
+        },
+    })
+}
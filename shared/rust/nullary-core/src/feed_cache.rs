@@ -0,0 +1,489 @@
+// Feed cache
+//
+// Feed pages are cached on disk in the app cache dir, one file per persona,
+// base, service and tag set, so a feed screen has something to show the
+// moment it opens and while its base is down. `cached_feed` answers from a
+// fresh page without touching the network, answers from a stale page at once
+// while refreshing it in the background, and only waits on the service when
+// nothing is cached. `refresh_cached_feed` always asks the service and falls
+// back to the cached page, flagged stale, when the service can't be reached.
+//
+// A fetch is handed the validators of the cached page and passes them to
+// `conditional_get`, which sends `If-None-Match`/`If-Modified-Since` and
+// turns a 304 into `NotModified`; the cached page is then kept and its age
+// reset. The service client crates don't expose response headers, so feeds
+// are fetched with signed requests of our own: `dolores_feed_at`,
+// `sanora_base_products_at` and BDO's bases in `bases.rs`. The frontend only
+// gets a `feed-updated` event when a page's content changed.
+//
+// The persona is read once, when a fetch starts, and the page is written
+// under that persona however long the fetch takes, so a persona switch
+// mid-fetch can't file one persona's page under another.
+
+use reqwest::header::{HeaderName, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::{RequestBuilder, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeSet;
+use std::fs;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::{LazyLock, Mutex, OnceLock};
+use tracing::{debug, info, warn};
+
+use crate::clients::dolores_user_uuid_at;
+use crate::documents::write_atomic;
+use crate::error::NullaryError;
+use crate::host::{app_cache_dir, emit, spawn};
+use crate::http::{self, http_client};
+use crate::registry::forget_if_not_found;
+use crate::services::base_for_url;
+use crate::signing::{sign_operation_at, SigningPurpose};
+use crate::user_persistence::active_persona;
+
+/// Event naming a cached page whose content changed
+pub const FEED_EVENT: &str = "feed-updated";
+
+const CACHE_DIR: &str = "feeds";
+// A page older than this is served flagged stale and refreshed
+const FEED_TTL_MS: i64 = 5 * 60 * 1000;
+
+// ===== TYPES =====
+
+/// Which feed page: the base and service it comes from and the tags asked for
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedKey {
+    pub base: String,
+    pub service: String,
+    pub tags: String,
+}
+
+impl FeedKey {
+    /// Key for the `service` at `url`, with `tags` as the service is sent them
+    pub fn new(url: &str, service: &str, tags: &str) -> Result<Self, NullaryError> {
+        Ok(Self {
+            base: base_for_url(url)?,
+            service: service.to_string(),
+            tags: tags.to_string(),
+        })
+    }
+
+    // Feeds are fetched as a persona's service user, so each persona gets its
+    // own pages
+    fn file_name(&self, persona: &str) -> String {
+        let mut hasher = Sha256::new();
+        for part in [persona, &self.base, &self.service, &self.tags] {
+            hasher.update(part.as_bytes());
+            hasher.update([0]);
+        }
+        format!("{}.json", hex::encode(hasher.finalize()))
+    }
+}
+
+/// What the service said about the cached page last time
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Validators {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+/// Result of fetching a feed page
+#[derive(Debug)]
+pub enum FeedFetch<T> {
+    Fresh {
+        data: T,
+        validators: Validators,
+    },
+    /// The cached page is still current (HTTP 304)
+    NotModified,
+}
+
+impl<T> FeedFetch<T> {
+    /// A page fetched without validators, e.g. through a client crate
+    pub fn fresh(data: T) -> Self {
+        FeedFetch::Fresh {
+            data,
+            validators: Validators::default(),
+        }
+    }
+
+    /// Reshape a fresh page, keeping its validators
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> FeedFetch<U> {
+        match self {
+            FeedFetch::Fresh { data, validators } => FeedFetch::Fresh {
+                data: f(data),
+                validators,
+            },
+            FeedFetch::NotModified => FeedFetch::NotModified,
+        }
+    }
+}
+
+/// A feed page and how old it is
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedPage<T> {
+    pub data: T,
+    /// Older than the cache TTL, or served because the service is unreachable
+    pub stale: bool,
+    pub fetched_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CacheEntry {
+    key_base: String,
+    key_service: String,
+    key_tags: String,
+    fetched_at: i64,
+    validators: Validators,
+    /// Hash of `data`, to tell a changed page from a re-download
+    hash: String,
+    data: Value,
+}
+
+impl CacheEntry {
+    fn page<T: DeserializeOwned>(&self) -> Option<FeedPage<T>> {
+        let data = serde_json::from_value(self.data.clone()).ok()?;
+        Some(FeedPage {
+            data,
+            stale: chrono::Utc::now().timestamp_millis() - self.fetched_at >= FEED_TTL_MS,
+            fetched_at: self.fetched_at,
+        })
+    }
+}
+
+// ===== STORAGE =====
+
+static CACHE_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+/// Open the feed cache in the app cache dir
+pub fn load_feed_cache() -> Result<(), NullaryError> {
+    let dir = app_cache_dir()?.join(CACHE_DIR);
+    fs::create_dir_all(&dir)
+        .map_err(|e| NullaryError::storage(format!("Failed to create feed cache dir: {}", e)))?;
+
+    let pages = fs::read_dir(&dir)
+        .map(|entries| entries.count())
+        .unwrap_or(0);
+    info!("🗂️ Feed cache has {} pages", pages);
+    let _ = CACHE_PATH.set(dir);
+    Ok(())
+}
+
+fn entry_path(key: &FeedKey, persona: &str) -> Option<PathBuf> {
+    CACHE_PATH.get().map(|dir| dir.join(key.file_name(persona)))
+}
+
+fn read_entry(key: &FeedKey, persona: &str) -> Option<CacheEntry> {
+    let path = entry_path(key, persona)?;
+    let content = fs::read_to_string(&path).ok()?;
+    match serde_json::from_str(&content) {
+        Ok(entry) => Some(entry),
+        Err(e) => {
+            warn!(
+                "⚠️ Ignoring unreadable feed cache {}: {}",
+                path.display(),
+                e
+            );
+            None
+        }
+    }
+}
+
+fn write_entry(key: &FeedKey, persona: &str, entry: &CacheEntry) {
+    let Some(path) = entry_path(key, persona) else {
+        return;
+    };
+    let result = serde_json::to_vec(entry)
+        .map_err(|e| NullaryError::internal(format!("Failed to serialize feed page: {}", e)))
+        .and_then(|json| write_atomic(&path, &json));
+    if let Err(e) = result {
+        warn!("⚠️ Failed to cache {} feed: {}", key.service, e);
+    }
+}
+
+// ===== FETCHING =====
+
+/// Fetch `key` for `persona`, the persona active when the fetch started, and
+/// cache the page under it
+async fn fetch_and_store<T, F, Fut>(
+    key: &FeedKey,
+    persona: &str,
+    cached: Option<CacheEntry>,
+    fetch: F,
+) -> Result<FeedPage<T>, NullaryError>
+where
+    T: Serialize + DeserializeOwned,
+    F: FnOnce(Validators) -> Fut,
+    Fut: Future<Output = Result<FeedFetch<T>, NullaryError>>,
+{
+    let validators = cached
+        .as_ref()
+        .map(|entry| entry.validators.clone())
+        .unwrap_or_default();
+    let now = chrono::Utc::now().timestamp_millis();
+
+    match fetch(validators).await? {
+        FeedFetch::NotModified => {
+            let Some(mut entry) = cached else {
+                return Err(NullaryError::internal(format!(
+                    "{} said the feed was not modified, but none is cached",
+                    key.service
+                )));
+            };
+            debug!(service = %key.service, tags = %key.tags, "🗂️ Feed not modified");
+            entry.fetched_at = now;
+            write_entry(key, persona, &entry);
+            entry.page().ok_or_else(|| {
+                NullaryError::internal("Cached feed page no longer matches its type")
+            })
+        }
+        FeedFetch::Fresh { data, validators } => {
+            let value = serde_json::to_value(&data).map_err(|e| {
+                NullaryError::internal(format!("Failed to serialize feed page: {}", e))
+            })?;
+            let hash = hex::encode(Sha256::digest(value.to_string().as_bytes()));
+            let changed = cached.as_ref().is_none_or(|entry| entry.hash != hash);
+
+            write_entry(
+                key,
+                persona,
+                &CacheEntry {
+                    key_base: key.base.clone(),
+                    key_service: key.service.clone(),
+                    key_tags: key.tags.clone(),
+                    fetched_at: now,
+                    validators,
+                    hash,
+                    data: value,
+                },
+            );
+
+            // The screens showing now belong to another persona after a switch
+            if changed && persona == active_persona() {
+                if let Err(e) = emit(FEED_EVENT, key) {
+                    warn!("⚠️ Failed to emit feed update: {}", e);
+                }
+            }
+
+            Ok(FeedPage {
+                data,
+                stale: false,
+                fetched_at: now,
+            })
+        }
+    }
+}
+
+// Pages with a background refresh running
+static REFRESHING: LazyLock<Mutex<BTreeSet<String>>> =
+    LazyLock::new(|| Mutex::new(BTreeSet::new()));
+
+fn refresh_in_background<T, F, Fut>(key: FeedKey, persona: String, cached: CacheEntry, fetch: F)
+where
+    T: Serialize + DeserializeOwned + Send + 'static,
+    F: FnOnce(Validators) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<FeedFetch<T>, NullaryError>> + Send + 'static,
+{
+    let id = key.file_name(&persona);
+    let started = REFRESHING
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .insert(id.clone());
+    if !started {
+        return;
+    }
+
+    spawn(async move {
+        if let Err(e) = fetch_and_store(&key, &persona, Some(cached), fetch).await {
+            warn!(
+                "⚠️ Background refresh of {} feed failed: {}",
+                key.service, e
+            );
+        }
+        REFRESHING
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .remove(&id);
+    });
+}
+
+/// A feed page, from the cache when there is one. A stale page is returned
+/// at once, flagged, and refreshed in the background; `feed-updated` says
+/// when the refresh brought something new.
+pub async fn cached_feed<T, F, Fut>(key: FeedKey, fetch: F) -> Result<FeedPage<T>, NullaryError>
+where
+    T: Serialize + DeserializeOwned + Send + 'static,
+    F: FnOnce(Validators) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<FeedFetch<T>, NullaryError>> + Send + 'static,
+{
+    cached_feed_as(key, active_persona(), fetch).await
+}
+
+/// `cached_feed` for a persona the caller already read
+pub(crate) async fn cached_feed_as<T, F, Fut>(
+    key: FeedKey,
+    persona: String,
+    fetch: F,
+) -> Result<FeedPage<T>, NullaryError>
+where
+    T: Serialize + DeserializeOwned + Send + 'static,
+    F: FnOnce(Validators) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<FeedFetch<T>, NullaryError>> + Send + 'static,
+{
+    let Some(entry) = read_entry(&key, &persona) else {
+        return fetch_and_store(&key, &persona, None, fetch).await;
+    };
+    // A page cached in an older shape is fetched again
+    let Some(page) = entry.page() else {
+        return fetch_and_store(&key, &persona, None, fetch).await;
+    };
+
+    if page.stale {
+        refresh_in_background(key, persona, entry, fetch);
+    }
+    Ok(page)
+}
+
+/// `persona`'s feed page straight from the service, conditionally when the
+/// cached page has validators, with no fallback to the cache
+pub(crate) async fn fetch_feed<T, F, Fut>(
+    key: &FeedKey,
+    persona: &str,
+    fetch: F,
+) -> Result<FeedPage<T>, NullaryError>
+where
+    T: Serialize + DeserializeOwned,
+    F: FnOnce(Validators) -> Fut,
+    Fut: Future<Output = Result<FeedFetch<T>, NullaryError>>,
+{
+    fetch_and_store(key, persona, read_entry(key, persona), fetch).await
+}
+
+/// `persona`'s cached page for `key`, flagged stale, for when its service
+/// failed
+pub(crate) fn cached_page<T: DeserializeOwned>(key: &FeedKey, persona: &str) -> Option<FeedPage<T>> {
+    let mut page = read_entry(key, persona)?.page()?;
+    page.stale = true;
+    Some(page)
+}
+
+/// A feed page straight from the service, conditionally when the cached page
+/// has validators. If the service can't be reached the cached page is
+/// returned flagged stale.
+pub async fn refresh_cached_feed<T, F, Fut>(
+    key: FeedKey,
+    fetch: F,
+) -> Result<FeedPage<T>, NullaryError>
+where
+    T: Serialize + DeserializeOwned,
+    F: FnOnce(Validators) -> Fut,
+    Fut: Future<Output = Result<FeedFetch<T>, NullaryError>>,
+{
+    let persona = active_persona();
+    let entry = read_entry(&key, &persona);
+    match fetch_and_store(&key, &persona, entry.clone(), fetch).await {
+        Ok(page) => Ok(page),
+        Err(e) if e.is_transient() => {
+            let Some(mut page) = entry.as_ref().and_then(|entry| entry.page::<T>()) else {
+                return Err(e);
+            };
+            warn!("🔌 Serving cached {} feed: {}", key.service, e);
+            page.stale = true;
+            Ok(page)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Send a feed `request` (a GET) with the cached page's validators. A 304
+/// becomes `NotModified`; otherwise the body is parsed and the new
+/// validators kept.
+pub async fn conditional_get<T: DeserializeOwned>(
+    service: &str,
+    mut request: RequestBuilder,
+    validators: &Validators,
+) -> Result<FeedFetch<T>, NullaryError> {
+    if let Some(etag) = &validators.etag {
+        request = request.header(IF_NONE_MATCH, etag);
+    }
+    if let Some(last_modified) = &validators.last_modified {
+        request = request.header(IF_MODIFIED_SINCE, last_modified);
+    }
+
+    let response = http::send(service, request).await?;
+    let status = response.status();
+    if status == StatusCode::NOT_MODIFIED {
+        return Ok(FeedFetch::NotModified);
+    }
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(NullaryError::from_status(service, status.as_u16(), body));
+    }
+
+    let header = |name: HeaderName| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.to_string())
+    };
+    let validators = Validators {
+        etag: header(ETAG),
+        last_modified: header(LAST_MODIFIED),
+    };
+    let data = response
+        .json()
+        .await
+        .map_err(|e| NullaryError::from_reqwest(service, "Failed to parse feed", &e))?;
+
+    Ok(FeedFetch::Fresh { data, validators })
+}
+
+// ===== SERVICE FEEDS =====
+
+#[derive(Deserialize)]
+struct DoloresFeed {
+    #[serde(rename = "allPosts", default)]
+    all_posts: Vec<Value>,
+}
+
+/// Dolores posts with `tags` (comma separated) for this install's user on
+/// the Dolores at `url`, fetched conditionally. Signs
+/// `timestamp + uuid + tags`, as Dolores' `GET /user/:uuid/feed` expects.
+pub async fn dolores_feed_at(
+    url: &str,
+    tags: &str,
+    validators: &Validators,
+) -> Result<FeedFetch<Vec<Value>>, NullaryError> {
+    let uuid = dolores_user_uuid_at(url).await?;
+    let signed = sign_operation_at(url, "dolores", SigningPurpose::AuthHeader, &[&uuid, tags]).await?;
+    let request = http_client()
+        .get(format!("{}/user/{}/feed", url.trim_end_matches('/'), uuid))
+        .query(&[
+            ("timestamp", signed.timestamp.as_str()),
+            ("tags", tags),
+            ("signature", signed.signature.as_str()),
+        ]);
+
+    let feed = conditional_get::<DoloresFeed>("dolores", request, validators)
+        .await
+        .inspect_err(|e| {
+            forget_if_not_found(url, "dolores", None, e);
+        })?;
+    Ok(feed.map(|feed| feed.all_posts))
+}
+
+/// Every product on the Sanora at `url`, fetched conditionally
+pub async fn sanora_base_products_at<T: DeserializeOwned>(
+    url: &str,
+    validators: &Validators,
+) -> Result<FeedFetch<Vec<T>>, NullaryError> {
+    let request = http_client().get(format!("{}/products/base", url.trim_end_matches('/')));
+    conditional_get("sanora", request, validators).await
+}
@@ -0,0 +1,197 @@
+// Service health
+//
+// `check_service_health` probes every configured service in the active
+// environment at once and reports, per service, whether it answered, the
+// HTTP status, round-trip latency, the version it advertises (a version
+// header or a `version` field in a JSON body) and how far its clock is from
+// ours. Services reject signed requests whose timestamp is too far off, so
+// skew is worth watching; it comes from the `Date` header and is only good
+// to about a second.
+//
+// Probes are single attempts with a short timeout and bypass the retry and
+// breaker logic in `http.rs`. A report is cached for a few seconds and every
+// fresh one is pushed to the frontend as a `service-health` event. A fresh
+// report that finds services up also starts an outbox replay.
+
+use futures::future::join_all;
+use serde::Serialize;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+use crate::error::NullaryError;
+use crate::host::{emit, spawn};
+use crate::http::http_client;
+use crate::outbox::{has_queued_writes, replay_outbox};
+use crate::services::{current_env, get_service_url, EXTRA_SERVICES, SERVICES};
+
+/// Event carrying each fresh `HealthReport`
+pub const HEALTH_EVENT: &str = "service-health";
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+const CACHE_TTL: Duration = Duration::from_secs(15);
+const VERSION_HEADERS: [&str; 3] = ["x-service-version", "x-pn-version", "x-version"];
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceHealth {
+    pub service: String,
+    pub url: String,
+    pub reachable: bool,
+    pub status: Option<u16>,
+    pub latency_ms: Option<u64>,
+    pub version: Option<String>,
+    /// Service clock minus ours, in ms; positive means the service is ahead
+    pub clock_skew_ms: Option<i64>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthReport {
+    pub environment: String,
+    pub checked_at: i64,
+    pub services: Vec<ServiceHealth>,
+}
+
+impl HealthReport {
+    /// Services that did not answer
+    pub fn unreachable(&self) -> impl Iterator<Item = &ServiceHealth> {
+        self.services.iter().filter(|health| !health.reachable)
+    }
+}
+
+// Last report and when it was taken
+static CACHE: LazyLock<Mutex<Option<(Instant, HealthReport)>>> = LazyLock::new(|| Mutex::new(None));
+
+fn cache() -> std::sync::MutexGuard<'static, Option<(Instant, HealthReport)>> {
+    CACHE
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+// ===== PROBES =====
+
+async fn probe(service: &str) -> ServiceHealth {
+    let url = get_service_url(service);
+    let mut health = ServiceHealth {
+        service: service.to_string(),
+        url: url.clone(),
+        reachable: false,
+        status: None,
+        latency_ms: None,
+        version: None,
+        clock_skew_ms: None,
+        error: None,
+    };
+
+    let sent_at = chrono::Utc::now().timestamp_millis();
+    let started = Instant::now();
+    let response = match http_client().get(&url).timeout(PROBE_TIMEOUT).send().await {
+        Ok(response) => response,
+        Err(e) => {
+            health.error = Some(e.to_string());
+            return health;
+        }
+    };
+    let elapsed = started.elapsed();
+
+    health.reachable = true;
+    health.status = Some(response.status().as_u16());
+    health.latency_ms = Some(elapsed.as_millis() as u64);
+
+    // Compare against the midpoint of the round trip
+    health.clock_skew_ms = response
+        .headers()
+        .get(reqwest::header::DATE)
+        .and_then(|date| date.to_str().ok())
+        .and_then(|date| chrono::DateTime::parse_from_rfc2822(date).ok())
+        .map(|date| date.timestamp_millis() - (sent_at + elapsed.as_millis() as i64 / 2));
+
+    health.version = VERSION_HEADERS
+        .iter()
+        .find_map(|name| response.headers().get(*name))
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_string());
+
+    let is_json = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.contains("json"));
+    if health.version.is_none() && is_json {
+        health.version = response
+            .json::<serde_json::Value>()
+            .await
+            .ok()
+            .and_then(|body| body.get("version").cloned())
+            .map(|version| match version {
+                serde_json::Value::String(version) => version,
+                other => other.to_string(),
+            });
+    }
+
+    debug!(
+        service,
+        status = health.status,
+        latency_ms = health.latency_ms,
+        "🩺 Probed"
+    );
+    health
+}
+
+/// Probe every configured service now, cache the report and push it to the
+/// frontend
+pub async fn probe_services() -> HealthReport {
+    let probes = SERVICES
+        .iter()
+        .chain(EXTRA_SERVICES.iter())
+        .map(|service| probe(service));
+    let report = HealthReport {
+        environment: current_env(),
+        checked_at: chrono::Utc::now().timestamp_millis(),
+        services: join_all(probes).await,
+    };
+
+    let down: Vec<&str> = report
+        .unreachable()
+        .map(|health| health.service.as_str())
+        .collect();
+    if down.is_empty() {
+        info!("🩺 All {} services reachable", report.services.len());
+    } else {
+        warn!("🩺 {} unreachable: {}", down.len(), down.join(", "));
+    }
+
+    *cache() = Some((Instant::now(), report.clone()));
+
+    // A base that answers again may have queued writes waiting for it
+    if report.services.iter().any(|health| health.reachable) && has_queued_writes() {
+        spawn(replay_outbox());
+    }
+    if let Err(e) = emit(HEALTH_EVENT, &report) {
+        warn!("⚠️ Failed to emit service health: {}", e);
+    }
+    report
+}
+
+/// Cached report if it is fresh and for the active environment
+pub fn cached_health() -> Option<HealthReport> {
+    let cache = cache();
+    let (taken, report) = cache.as_ref()?;
+    (taken.elapsed() < CACHE_TTL && report.environment == current_env()).then(|| report.clone())
+}
+
+// ===== COMMANDS =====
+
+/// Health of every configured service, from the cache unless it is stale or
+/// `refresh` is set
+#[cfg_attr(feature = "tauri", tauri::command)]
+pub async fn check_service_health(refresh: Option<bool>) -> Result<HealthReport, NullaryError> {
+    if !refresh.unwrap_or(false) {
+        if let Some(report) = cached_health() {
+            return Ok(report);
+        }
+    }
+    Ok(probe_services().await)
+}
@@ -0,0 +1,250 @@
+// Host
+//
+// Core runs inside a Tauri app or headless, in the `nullary` CLI. The host
+// decides where files live and whether anyone is listening for events:
+//
+// - A Tauri app (`init`) resolves its dirs from the app handle and emits
+//   events to the webview.
+// - A headless host (`init_headless`) is given its dirs. `AppDirs::for_app`
+//   resolves the same dirs Tauri would for a bundle identifier, so the CLI
+//   can run with an app's identity, endpoints and registry. Events have no
+//   listener and are dropped.
+//
+// Besides its own dirs every app sees one dir shared by all Nullary apps on
+// the machine, for state that is not per app, such as joined bases.
+//
+// Questions that must not be answerable from the webview, such as signing
+// confirmations, are asked in native dialogs.
+//
+// Without the `tauri` feature only the headless host exists.
+
+use serde::Serialize;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
+use tracing::debug;
+
+use crate::error::NullaryError;
+
+/// Where core keeps its files
+#[derive(Debug, Clone)]
+pub struct AppDirs {
+    /// Identity, personas, endpoints, policies, registry and outbox
+    pub data: PathBuf,
+    /// Daily log files
+    pub log: PathBuf,
+    /// Feed pages and other data that can be fetched again
+    pub cache: PathBuf,
+    /// Shared by every Nullary app on the machine
+    pub shared: PathBuf,
+}
+
+/// Name of the shared dir in the platform data dir
+const SHARED_DIR: &str = "nullary";
+
+impl AppDirs {
+    /// The dirs Tauri gives the app with bundle `identifier`, e.g.
+    /// `com.planetnine.ninefy`
+    pub fn for_app(identifier: &str) -> Result<Self, NullaryError> {
+        let unknown = |dir: &str| NullaryError::storage(format!("Failed to find the {} dir", dir));
+
+        let data = dirs::data_dir()
+            .ok_or_else(|| unknown("data"))?
+            .join(identifier);
+        #[cfg(target_os = "macos")]
+        let log = dirs::home_dir()
+            .ok_or_else(|| unknown("home"))?
+            .join("Library/Logs")
+            .join(identifier);
+        #[cfg(not(target_os = "macos"))]
+        let log = dirs::data_local_dir()
+            .ok_or_else(|| unknown("local data"))?
+            .join(identifier)
+            .join("logs");
+        let cache = dirs::cache_dir()
+            .ok_or_else(|| unknown("cache"))?
+            .join(identifier);
+        let shared = dirs::data_dir()
+            .ok_or_else(|| unknown("data"))?
+            .join(SHARED_DIR);
+
+        Ok(Self {
+            data,
+            log,
+            cache,
+            shared,
+        })
+    }
+
+    /// Everything under one root, for throwaway identities and test runs
+    pub fn under(root: &Path) -> Self {
+        Self {
+            data: root.to_path_buf(),
+            log: root.join("logs"),
+            cache: root.join("cache"),
+            shared: root.join("shared"),
+        }
+    }
+
+    #[cfg(feature = "tauri")]
+    fn for_app_handle(app_handle: &tauri::AppHandle) -> Result<Self, NullaryError> {
+        use tauri::Manager;
+
+        let path = app_handle.path();
+        Ok(Self {
+            data: path
+                .app_data_dir()
+                .map_err(|e| NullaryError::storage(format!("Failed to get app data dir: {}", e)))?,
+            log: path
+                .app_log_dir()
+                .map_err(|e| NullaryError::storage(format!("Failed to get app log dir: {}", e)))?,
+            cache: path.app_cache_dir().map_err(|e| {
+                NullaryError::storage(format!("Failed to get app cache dir: {}", e))
+            })?,
+            shared: path
+                .data_dir()
+                .map_err(|e| NullaryError::storage(format!("Failed to get data dir: {}", e)))?
+                .join(SHARED_DIR),
+        })
+    }
+}
+
+struct Host {
+    dirs: AppDirs,
+    /// Handle for emitting events to the frontend; `None` when headless
+    #[cfg(feature = "tauri")]
+    app_handle: Option<tauri::AppHandle>,
+}
+
+// Set once by `init` or `init_headless`
+static HOST: OnceLock<Host> = OnceLock::new();
+
+fn host() -> Result<&'static Host, NullaryError> {
+    HOST.get()
+        .ok_or_else(|| NullaryError::internal("Nullary core is not initialized"))
+}
+
+#[cfg(feature = "tauri")]
+pub(crate) fn set_tauri_host(app_handle: &tauri::AppHandle) -> Result<(), NullaryError> {
+    use tauri::Manager;
+
+    let dirs = AppDirs::for_app_handle(app_handle)?;
+
+    // Signing confirmations are asked in native dialogs. No app grants the
+    // plugin's permissions, so the webview can't open or answer one.
+    if app_handle
+        .try_state::<tauri_plugin_dialog::Dialog<tauri::Wry>>()
+        .is_none()
+    {
+        app_handle
+            .plugin(tauri_plugin_dialog::init())
+            .map_err(|e| NullaryError::internal(format!("Failed to add the dialog plugin: {}", e)))?;
+    }
+
+    let _ = HOST.set(Host {
+        dirs,
+        app_handle: Some(app_handle.clone()),
+    });
+    Ok(())
+}
+
+pub(crate) fn set_headless_host(dirs: AppDirs) {
+    let _ = HOST.set(Host {
+        dirs,
+        #[cfg(feature = "tauri")]
+        app_handle: None,
+    });
+}
+
+// ===== DIRS =====
+
+pub fn app_dirs() -> Result<&'static AppDirs, NullaryError> {
+    host().map(|host| &host.dirs)
+}
+
+pub fn app_data_dir() -> Result<PathBuf, NullaryError> {
+    Ok(app_dirs()?.data.clone())
+}
+
+pub fn app_log_dir() -> Result<PathBuf, NullaryError> {
+    Ok(app_dirs()?.log.clone())
+}
+
+pub fn app_cache_dir() -> Result<PathBuf, NullaryError> {
+    Ok(app_dirs()?.cache.clone())
+}
+
+pub fn shared_data_dir() -> Result<PathBuf, NullaryError> {
+    Ok(app_dirs()?.shared.clone())
+}
+
+// ===== FRONTEND =====
+
+/// Whether a frontend receives events; false when headless or before init
+pub fn has_frontend() -> bool {
+    #[cfg(feature = "tauri")]
+    {
+        HOST.get().is_some_and(|host| host.app_handle.is_some())
+    }
+    #[cfg(not(feature = "tauri"))]
+    {
+        false
+    }
+}
+
+/// Send `event` to the frontend. Without one the event is dropped.
+pub(crate) fn emit<S: Serialize + Clone>(event: &str, payload: S) -> Result<(), NullaryError> {
+    #[cfg(feature = "tauri")]
+    if let Some(app_handle) = HOST.get().and_then(|host| host.app_handle.as_ref()) {
+        use tauri::Emitter;
+
+        return app_handle
+            .emit(event, payload)
+            .map_err(|e| NullaryError::internal(format!("Failed to emit {}: {}", event, e)));
+    }
+
+    let _ = payload;
+    debug!("📭 No frontend for {}", event);
+    Ok(())
+}
+
+/// Ask the user a yes/no question in a native dialog, outside the webview,
+/// with `approve` as the label of the yes button. No app window or no answer
+/// within `timeout` count as no; an answer after the timeout is ignored.
+pub(crate) async fn ask(title: &str, message: &str, approve: &str, timeout: Duration) -> bool {
+    #[cfg(feature = "tauri")]
+    if let Some(app_handle) = HOST.get().and_then(|host| host.app_handle.as_ref()) {
+        use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+        let (sender, receiver) = tokio::sync::oneshot::channel();
+        app_handle
+            .dialog()
+            .message(message)
+            .title(title)
+            .kind(MessageDialogKind::Warning)
+            .buttons(MessageDialogButtons::OkCancelCustom(
+                approve.to_string(),
+                "Decline".to_string(),
+            ))
+            .show(move |answer| {
+                let _ = sender.send(answer);
+            });
+        return matches!(tokio::time::timeout(timeout, receiver).await, Ok(Ok(true)));
+    }
+
+    let _ = (message, approve, timeout);
+    debug!("📭 No app window to ask {}", title);
+    false
+}
+
+/// Run `future` in the background on the host's async runtime
+pub(crate) fn spawn<F>(future: F)
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    #[cfg(feature = "tauri")]
+    tauri::async_runtime::spawn(future);
+    #[cfg(not(feature = "tauri"))]
+    tokio::spawn(future);
+}
@@ -0,0 +1,412 @@
+// Shared HTTP client
+//
+// Every command that talks HTTP itself (uploads, covenant, product listings)
+// goes through one long-lived `reqwest::Client`, so connections to a base are
+// pooled and every request has a deadline. `send` adds two things per
+// service:
+//
+// - Idempotent requests (GET, HEAD, OPTIONS) that fail with a network error,
+//   a timeout or a 502/503/504 are retried with jittered exponential backoff.
+// - A circuit breaker per service and base. After `breakerThreshold`
+//   consecutive failures, calls fail fast with a `network` error until
+//   `breakerCooldownMs` has passed; then one trial request is let through.
+//
+// Policies live in `http.json` in the app data dir: client-wide timeouts and
+// pool settings, a default policy and per-service overrides. The file is
+// seeded with the defaults on first launch.
+
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use reqwest::{Client, Method, Request, RequestBuilder, Response};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::sync::{LazyLock, Mutex, RwLock};
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+use crate::documents::write_atomic;
+use crate::error::NullaryError;
+use crate::host::app_data_dir;
+use crate::services::base_for_url;
+
+const CONFIG_FILE: &str = "http.json";
+
+// ===== CONFIG =====
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpConfig {
+    /// How long to wait for a connection to a base
+    #[serde(default = "default_connect_timeout_ms")]
+    pub connect_timeout_ms: u64,
+    /// How long a response may go without sending data
+    #[serde(default = "default_read_timeout_ms")]
+    pub read_timeout_ms: u64,
+    /// How long an idle pooled connection is kept
+    #[serde(default = "default_pool_idle_timeout_ms")]
+    pub pool_idle_timeout_ms: u64,
+    #[serde(default = "default_pool_max_idle_per_host")]
+    pub pool_max_idle_per_host: usize,
+    /// Policy for services without an override
+    #[serde(default)]
+    pub default: ServicePolicy,
+    /// Service name -> fields that differ from `default`
+    #[serde(default)]
+    pub services: BTreeMap<String, PolicyOverride>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ServicePolicy {
+    /// Deadline for a whole request, including the body
+    pub timeout_ms: u64,
+    /// Extra attempts for idempotent requests
+    pub retries: u32,
+    pub backoff_base_ms: u64,
+    pub backoff_max_ms: u64,
+    /// Consecutive failures that open the breaker; 0 disables it
+    pub breaker_threshold: u32,
+    pub breaker_cooldown_ms: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyOverride {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retries: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backoff_base_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backoff_max_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub breaker_threshold: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub breaker_cooldown_ms: Option<u64>,
+}
+
+fn default_connect_timeout_ms() -> u64 {
+    10_000
+}
+
+fn default_read_timeout_ms() -> u64 {
+    30_000
+}
+
+fn default_pool_idle_timeout_ms() -> u64 {
+    90_000
+}
+
+fn default_pool_max_idle_per_host() -> usize {
+    8
+}
+
+impl Default for ServicePolicy {
+    fn default() -> Self {
+        ServicePolicy {
+            timeout_ms: 30_000,
+            retries: 3,
+            backoff_base_ms: 250,
+            backoff_max_ms: 5_000,
+            breaker_threshold: 5,
+            breaker_cooldown_ms: 30_000,
+        }
+    }
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        // Uploads carry whole files, so Sanora gets a longer deadline
+        let mut services = BTreeMap::new();
+        services.insert(
+            "sanora".to_string(),
+            PolicyOverride {
+                timeout_ms: Some(300_000),
+                ..PolicyOverride::default()
+            },
+        );
+
+        HttpConfig {
+            connect_timeout_ms: default_connect_timeout_ms(),
+            read_timeout_ms: default_read_timeout_ms(),
+            pool_idle_timeout_ms: default_pool_idle_timeout_ms(),
+            pool_max_idle_per_host: default_pool_max_idle_per_host(),
+            default: ServicePolicy::default(),
+            services,
+        }
+    }
+}
+
+impl HttpConfig {
+    /// Policy for `service`: the default with its override applied
+    pub fn policy(&self, service: &str) -> ServicePolicy {
+        let mut policy = self.default.clone();
+        if let Some(o) = self.services.get(service) {
+            policy.timeout_ms = o.timeout_ms.unwrap_or(policy.timeout_ms);
+            policy.retries = o.retries.unwrap_or(policy.retries);
+            policy.backoff_base_ms = o.backoff_base_ms.unwrap_or(policy.backoff_base_ms);
+            policy.backoff_max_ms = o.backoff_max_ms.unwrap_or(policy.backoff_max_ms);
+            policy.breaker_threshold = o.breaker_threshold.unwrap_or(policy.breaker_threshold);
+            policy.breaker_cooldown_ms = o.breaker_cooldown_ms.unwrap_or(policy.breaker_cooldown_ms);
+        }
+        policy
+    }
+
+    fn build_client(&self) -> Result<Client, NullaryError> {
+        Client::builder()
+            .connect_timeout(Duration::from_millis(self.connect_timeout_ms))
+            .read_timeout(Duration::from_millis(self.read_timeout_ms))
+            .pool_idle_timeout(Duration::from_millis(self.pool_idle_timeout_ms))
+            .pool_max_idle_per_host(self.pool_max_idle_per_host)
+            .build()
+            .map_err(|e| NullaryError::internal(format!("Failed to build HTTP client: {}", e)))
+    }
+}
+
+struct Http {
+    config: HttpConfig,
+    client: Client,
+}
+
+// Built-in defaults until `load_http_config` runs at startup
+static HTTP: LazyLock<RwLock<Http>> = LazyLock::new(|| {
+    let config = HttpConfig::default();
+    let client = config.build_client().unwrap_or_else(|e| {
+        warn!("⚠️ {} - using reqwest defaults", e);
+        Client::new()
+    });
+    RwLock::new(Http { config, client })
+});
+
+/// Load `http.json` from the app data dir, seeding it with the defaults on
+/// first launch, and rebuild the shared client from it
+pub fn load_http_config() -> Result<(), NullaryError> {
+    let app_data_dir = app_data_dir()?;
+    let path = app_data_dir.join(CONFIG_FILE);
+
+    let config = if path.exists() {
+        let contents = fs::read_to_string(&path)
+            .map_err(|e| NullaryError::storage(format!("Failed to read HTTP config: {}", e)))?;
+        let config: HttpConfig = serde_json::from_str(&contents)
+            .map_err(|e| NullaryError::validation(format!("Failed to parse HTTP config {}: {}", path.display(), e)))?;
+        info!("📡 Loaded HTTP config: {}", path.display());
+        config
+    } else {
+        let config = HttpConfig::default();
+        let json = serde_json::to_vec_pretty(&config)
+            .map_err(|e| NullaryError::internal(format!("Failed to serialize HTTP config: {}", e)))?;
+        write_atomic(&path, &json)?;
+        info!("📡 Writing default HTTP config: {}", path.display());
+        config
+    };
+
+    let client = config.build_client()?;
+    let mut http = HTTP.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+    *http = Http { config, client };
+    Ok(())
+}
+
+/// The shared client. Clones share one connection pool.
+pub fn http_client() -> Client {
+    HTTP.read().unwrap_or_else(|poisoned| poisoned.into_inner()).client.clone()
+}
+
+/// Policy in effect for `service`
+pub fn http_policy(service: &str) -> ServicePolicy {
+    HTTP.read().unwrap_or_else(|poisoned| poisoned.into_inner()).config.policy(service)
+}
+
+// ===== CIRCUIT BREAKERS =====
+
+#[derive(Default)]
+struct Breaker {
+    failures: u32,
+    open_until: Option<Instant>,
+    trial_in_flight: bool,
+}
+
+// "service@host:port" -> breaker
+static BREAKERS: LazyLock<Mutex<HashMap<String, Breaker>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn breakers() -> std::sync::MutexGuard<'static, HashMap<String, Breaker>> {
+    BREAKERS.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn breaker_key(service: &str, url: &str) -> String {
+    let base = base_for_url(url).unwrap_or_else(|_| url.to_string());
+    format!("{}@{}", service, base)
+}
+
+/// The half-open trial let through by `check_breaker`. A trial that never
+/// reaches `record_outcome`, because its future was dropped by a caller's
+/// timeout or a cancelled command, hands the slot back on drop so the next
+/// caller becomes the trial instead of the breaker staying open for good.
+struct Trial {
+    key: Option<String>,
+}
+
+impl Trial {
+    fn none() -> Self {
+        Trial { key: None }
+    }
+
+    /// The outcome was recorded, which already ended the trial
+    fn finish(mut self) {
+        self.key = None;
+    }
+}
+
+impl Drop for Trial {
+    fn drop(&mut self) {
+        let Some(key) = self.key.take() else {
+            return;
+        };
+
+        if let Some(breaker) = breakers().get_mut(&key) {
+            breaker.trial_in_flight = false;
+        }
+        debug!(breaker = %key, "🔌 Trial request dropped, next caller retries");
+    }
+}
+
+/// Fail fast while the breaker is open. Once the cooldown has passed, the
+/// first caller gets through as a trial and the rest keep failing fast.
+fn check_breaker(key: &str, service: &str, policy: &ServicePolicy) -> Result<Trial, NullaryError> {
+    if policy.breaker_threshold == 0 {
+        return Ok(Trial::none());
+    }
+
+    let mut breakers = breakers();
+    let breaker = breakers.entry(key.to_string()).or_default();
+    let Some(open_until) = breaker.open_until else {
+        return Ok(Trial::none());
+    };
+
+    let now = Instant::now();
+    if now >= open_until && !breaker.trial_in_flight {
+        breaker.trial_in_flight = true;
+        return Ok(Trial {
+            key: Some(key.to_string()),
+        });
+    }
+
+    let wait = open_until.saturating_duration_since(now).as_secs();
+    Err(NullaryError::network(
+        service,
+        format!(
+            "{} is failing ({} errors in a row), not retrying for another {}s",
+            key, breaker.failures, wait
+        ),
+    ))
+}
+
+fn record_outcome(key: &str, policy: &ServicePolicy, failed: bool) {
+    if policy.breaker_threshold == 0 {
+        return;
+    }
+
+    let mut breakers = breakers();
+    let breaker = breakers.entry(key.to_string()).or_default();
+    breaker.trial_in_flight = false;
+
+    if !failed {
+        if breaker.open_until.is_some() {
+            info!("📡 {} is answering again", key);
+        }
+        *breaker = Breaker::default();
+        return;
+    }
+
+    breaker.failures += 1;
+    if breaker.failures >= policy.breaker_threshold {
+        if breaker.open_until.is_none() {
+            warn!("🔌 {} failed {} times in a row, failing fast", key, breaker.failures);
+        }
+        breaker.open_until = Some(Instant::now() + Duration::from_millis(policy.breaker_cooldown_ms));
+    }
+}
+
+// ===== SENDING =====
+
+fn is_idempotent(method: &Method) -> bool {
+    matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS)
+}
+
+fn is_transient_status(status: u16) -> bool {
+    matches!(status, 502..=504)
+}
+
+/// Delay before retry `attempt` (1-based): exponential, capped, with full jitter
+fn backoff(policy: &ServicePolicy, attempt: u32) -> Duration {
+    let exp = policy
+        .backoff_base_ms
+        .saturating_mul(1u64 << attempt.saturating_sub(1).min(16));
+    let cap = exp.min(policy.backoff_max_ms).max(1);
+    Duration::from_millis(OsRng.next_u64() % cap + 1)
+}
+
+/// Send a request to `service` on the shared client. Idempotent requests are
+/// retried; every request passes through the service's breaker. Responses
+/// are returned whatever their status, so callers still check it.
+pub async fn send(service: &str, request: RequestBuilder) -> Result<Response, NullaryError> {
+    let (client, request) = request.build_split();
+    let mut request =
+        request.map_err(|e| NullaryError::validation(format!("Invalid request to {}: {}", service, e)))?;
+
+    let policy = http_policy(service);
+    let key = breaker_key(service, request.url().as_str());
+    if request.timeout().is_none() {
+        *request.timeout_mut() = Some(Duration::from_millis(policy.timeout_ms));
+    }
+
+    let attempts = if is_idempotent(request.method()) {
+        policy.retries + 1
+    } else {
+        1
+    };
+
+    let mut pending = Some(request);
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        let trial = check_breaker(&key, service, &policy)?;
+
+        // Keep a copy for the next attempt; streaming bodies can't be copied
+        let current = pending.take().expect("request for this attempt");
+        let retry: Option<Request> = if attempt < attempts { current.try_clone() } else { None };
+
+        debug!(service, method = %current.method(), url = %current.url(), attempt, "📡 Sending request");
+        let result = client.execute(current).await;
+        let failed = match &result {
+            Ok(response) => is_transient_status(response.status().as_u16()),
+            Err(_) => true,
+        };
+        record_outcome(&key, &policy, failed);
+        trial.finish();
+
+        if !failed || retry.is_none() {
+            return result.map_err(|e| NullaryError::from_reqwest(service, "Request failed", &e));
+        }
+
+        let delay = backoff(&policy, attempt);
+        let delay_ms = delay.as_millis() as u64;
+        match &result {
+            Ok(response) => warn!(
+                breaker = %key,
+                status = response.status().as_u16(),
+                delay_ms,
+                attempt,
+                "🔁 Transient status, retrying"
+            ),
+            Err(e) => warn!(breaker = %key, error = %e, delay_ms, attempt, "🔁 Request failed, retrying"),
+        }
+        tokio::time::sleep(delay).await;
+        pending = retry;
+    }
+}
+
+/// GET `url` from `service` with retries
+pub async fn get(service: &str, url: &str) -> Result<Response, NullaryError> {
+    send(service, http_client().get(url)).await
+}
@@ -0,0 +1,190 @@
+// Nullary Core
+//
+// Shared backend for every Nullary Tauri app: the install identity, personas,
+// backups, key rotation and Stronghold vault, the per-persona document
+// store, service endpoint resolution, authenticated client constructors,
+// request signing with its policy and journal, the shared HTTP client with
+// its retries and circuit breakers, the service user registry, the
+// `NullaryError` every command returns, the `ServiceResponse` envelope,
+// redacted, rotating logs, service health probes, the offline outbox, the
+// on-disk feed cache, multi-base feed aggregation, base discovery (also by
+// distance) and the moderation lists.
+//
+// The `tauri` feature (on by default) provides the commands and `init`.
+// Without it, or without a frontend, `init_headless` runs the same core for
+// the `nullary` CLI; see `host.rs`.
+//
+// Usage in an app's lib.rs:
+// ```rust
+// tauri::Builder::default()
+//     .invoke_handler(tauri::generate_handler![
+//         nullary_core::generate_sessionless_keys,
+//         nullary_core::stronghold_init,
+//         nullary_core::stronghold_get_record,
+//         nullary_core::stronghold_set_record,
+//         nullary_core::stronghold_clear_vault,
+//         nullary_core::read_user_data_file,
+//         nullary_core::write_user_data_file,
+//         nullary_core::clear_user_data,
+//         nullary_core::read_document,
+//         nullary_core::write_document,
+//         nullary_core::delete_document,
+//         nullary_core::list_documents,
+//         nullary_core::list_personas,
+//         nullary_core::create_persona,
+//         nullary_core::rename_persona,
+//         nullary_core::delete_persona,
+//         nullary_core::switch_persona,
+//         nullary_core::get_persona_service_uuids,
+//         nullary_core::set_persona_service_uuid,
+//         nullary_core::export_identity_backup,
+//         nullary_core::import_identity_backup,
+//         nullary_core::export_recovery_words,
+//         nullary_core::import_recovery_words,
+//         nullary_core::rotate_identity,
+//         nullary_core::retry_identity_rotation,
+//         nullary_core::get_key_rotations,
+//         nullary_core::get_signing_journal,
+//         nullary_core::export_logs,
+//         nullary_core::check_service_health,
+//         nullary_core::list_outbox,
+//         nullary_core::retry_outbox_item,
+//         nullary_core::cancel_outbox_item,
+//         nullary_core::discover_bases,
+//         nullary_core::discover_bases_near,
+//         nullary_core::bases_near_me,
+//         nullary_core::get_moderation,
+//         nullary_core::set_base_muted,
+//         nullary_core::set_base_ignored,
+//         nullary_core::set_user_blocked,
+//         nullary_core::get_environment_config,
+//         nullary_core::switch_environment,
+//         // ... your other functions
+//     ])
+//     .setup(|app| {
+//         if let Err(e) = nullary_core::init(app.handle(), "ninefy") {
+//             error!("❌ Failed to initialize nullary core: {}", e);
+//         }
+//         Ok(())
+//     })
+// ```
+
+use std::sync::OnceLock;
+use tracing::{error, info};
+
+pub mod backup;
+pub mod bases;
+pub mod clients;
+pub mod documents;
+pub mod error;
+pub mod feed_aggregator;
+pub mod feed_cache;
+pub mod health;
+pub mod host;
+pub mod http;
+pub mod logging;
+pub mod moderation;
+pub mod nearby;
+pub mod outbox;
+pub mod registry;
+pub mod response;
+pub mod rotation;
+pub mod services;
+pub mod signing;
+pub mod user_persistence;
+
+pub use backup::*;
+pub use bases::*;
+pub use clients::*;
+pub use documents::*;
+pub use error::NullaryError;
+pub use feed_aggregator::*;
+pub use feed_cache::*;
+pub use health::*;
+pub use host::{
+    app_cache_dir, app_data_dir, app_dirs, app_log_dir, has_frontend, shared_data_dir, AppDirs,
+};
+pub use http::{http_client, http_policy, load_http_config, HttpConfig, PolicyOverride, ServicePolicy};
+pub use logging::*;
+pub use moderation::*;
+pub use nearby::*;
+pub use outbox::*;
+pub use registry::*;
+pub use response::ServiceResponse;
+pub use rotation::*;
+pub use services::*;
+pub use signing::*;
+pub use user_persistence::*;
+
+// Name of the running app, set once by `init`
+static APP_NAME: OnceLock<String> = OnceLock::new();
+
+/// Register the running app, load its endpoint config and identity. Call
+/// from the Tauri `setup` hook before any command runs.
+#[cfg(feature = "tauri")]
+pub fn init(app_handle: &tauri::AppHandle, app_name: &str) -> Result<(), NullaryError> {
+    host::set_tauri_host(app_handle)?;
+    init_core(app_name, true)
+}
+
+/// Same as `init` for a host without a frontend, such as the `nullary` CLI.
+/// The outbox is left unloaded: replaying it needs the handlers the app that
+/// queued the writes registers, so headless writes fail instead of queueing.
+pub fn init_headless(app_name: &str, dirs: AppDirs) -> Result<(), NullaryError> {
+    host::set_headless_host(dirs);
+    init_core(app_name, false)
+}
+
+fn init_core(app_name: &str, with_outbox: bool) -> Result<(), NullaryError> {
+    let _ = APP_NAME.set(app_name.to_string());
+
+    // Logging first so everything below lands in the log file
+    if let Err(e) = init_logging(app_name) {
+        logging::init_console_logging();
+        error!("❌ {} - logging to stderr only", e);
+    }
+
+    // A bad endpoints.json shouldn't cost the app its identity; keep the
+    // built-in table and report the problem
+    if let Err(e) = load_endpoint_config() {
+        error!("❌ {} - using built-in endpoints", e);
+    }
+    info!("🌍 {} running in {} environment", app_name, current_env());
+
+    // Same for http.json: the built-in timeouts and policies still apply
+    if let Err(e) = load_http_config() {
+        error!("❌ {} - using default HTTP policies", e);
+    }
+
+    // A bad signing.json keeps the built-in policy; the journal still opens
+    if let Err(e) = load_signing_policy() {
+        error!("❌ {} - using the default signing policy", e);
+    }
+
+    // Identity first: it selects the persona whose data dir is used
+    init_identity()?;
+    ensure_user_data_dir_exists()?;
+
+    // A damaged registry only costs extra create_user calls
+    if let Err(e) = load_uuid_registry() {
+        error!("❌ {} - starting with an empty UUID registry", e);
+    }
+
+    // An unreadable outbox is left alone on disk; writes just aren't queued
+    if with_outbox {
+        if let Err(e) = load_outbox() {
+            error!("❌ {} - offline writes will not be queued", e);
+        }
+    }
+
+    // Without a cache dir feeds are just fetched every time
+    if let Err(e) = load_feed_cache() {
+        error!("❌ {} - feeds will not be cached", e);
+    }
+    Ok(())
+}
+
+/// Name of the running app, or `nullary` before `init` has run
+pub fn app_name() -> &'static str {
+    APP_NAME.get().map(|name| name.as_str()).unwrap_or("nullary")
+}
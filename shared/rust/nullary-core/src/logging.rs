@@ -0,0 +1,313 @@
+// Logging
+//
+// Backends log through `tracing`. `init_logging` installs one subscriber that
+// writes to stdout and to a daily log file in the app log dir, keeping the
+// last `maxFiles` files. Headless runs keep stdout for their output and only
+// print warnings and errors, to stderr, unless `RUST_LOG` is set. Both
+// outputs pass through a redacting writer that scrubs private keys, seeds,
+// signatures, passwords and auth headers before anything is written, so a
+// stray `{:?}` can't leak a secret to disk.
+//
+// Levels live in `logging.json` in the app data dir: a default level and
+// per-module overrides (`"nullary_core::http": "debug"`). `RUST_LOG` wins
+// when set. `export_logs` returns the most recent lines for bug reports.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, OnceLock};
+use tracing::{error, info};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::Layer;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::EnvFilter;
+
+use crate::documents::write_atomic;
+use crate::error::NullaryError;
+use crate::host::{app_data_dir, app_log_dir, has_frontend};
+use crate::services::current_env;
+
+const CONFIG_FILE: &str = "logging.json";
+const DEFAULT_EXPORT_LINES: usize = 2000;
+
+// ===== CONFIG =====
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct LoggingConfig {
+    /// Level for modules without an override: error, warn, info, debug, trace
+    pub level: String,
+    /// Module path -> level
+    pub modules: BTreeMap<String, String>,
+    /// Daily log files to keep
+    pub max_files: usize,
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            level: "info".to_string(),
+            modules: BTreeMap::from([
+                ("hyper".to_string(), "warn".to_string()),
+                ("reqwest".to_string(), "warn".to_string()),
+            ]),
+            max_files: 7,
+        }
+    }
+}
+
+impl LoggingConfig {
+    /// `EnvFilter` directives for this config
+    fn directives(&self) -> String {
+        let mut directives = vec![self.level.clone()];
+        for (module, level) in &self.modules {
+            directives.push(format!("{}={}", module, level));
+        }
+        directives.join(",")
+    }
+}
+
+fn read_config(path: &Path) -> Result<LoggingConfig, NullaryError> {
+    if !path.exists() {
+        let config = LoggingConfig::default();
+        let json = serde_json::to_vec_pretty(&config).map_err(|e| {
+            NullaryError::internal(format!("Failed to serialize logging config: {}", e))
+        })?;
+        write_atomic(path, &json)?;
+        return Ok(config);
+    }
+
+    let contents = fs::read_to_string(path)
+        .map_err(|e| NullaryError::storage(format!("Failed to read logging config: {}", e)))?;
+    serde_json::from_str(&contents).map_err(|e| {
+        NullaryError::validation(format!(
+            "Failed to parse logging config {}: {}",
+            path.display(),
+            e
+        ))
+    })
+}
+
+// ===== REDACTION =====
+
+// `key: value` / `key=value` pairs whose value is a secret
+static SECRET_FIELDS: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(?i)((?:private[_-]?key|seed|signature|password|authorization|x-pn-signature)["']?\s*[:=]\s*["']?)([^"'\s,;&}\]]+)"#,
+    )
+    .expect("valid secret field pattern")
+});
+
+// Bare hex long enough to be a private key or signature
+static LONG_HEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b[0-9a-fA-F]{64,}\b").expect("valid hex pattern"));
+
+/// Scrub private keys, seeds, signatures, passwords and auth headers from a
+/// log line. Compressed public keys (66 hex chars starting 02/03) are kept.
+pub fn redact(text: &str) -> Cow<'_, str> {
+    let fields = SECRET_FIELDS.replace_all(text, "${1}[REDACTED]");
+    let hex = LONG_HEX.replace_all(&fields, |caps: &regex::Captures| {
+        let value = &caps[0];
+        if value.len() == 66 && (value.starts_with("02") || value.starts_with("03")) {
+            value.to_string()
+        } else {
+            "[REDACTED]".to_string()
+        }
+    });
+
+    if let Cow::Owned(hex) = hex {
+        return Cow::Owned(hex);
+    }
+    fields
+}
+
+/// Wraps a writer factory so every formatted event is redacted first
+struct Redacting<M>(M);
+
+impl<'a, M: MakeWriter<'a>> MakeWriter<'a> for Redacting<M> {
+    type Writer = RedactingWriter<M::Writer>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RedactingWriter(self.0.make_writer())
+    }
+}
+
+struct RedactingWriter<W>(W);
+
+impl<W: Write> Write for RedactingWriter<W> {
+    // The fmt layer writes each event in one call, so a secret is never
+    // split across writes
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let text = String::from_utf8_lossy(buf);
+        self.0.write_all(redact(&text).as_bytes())?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
+// ===== INIT =====
+
+// Keeps the background file writer alive for the life of the app
+static GUARD: OnceLock<WorkerGuard> = OnceLock::new();
+static LOG_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Install the subscriber: stdout (stderr when headless) plus a rotating
+/// file in the app log dir, all redacted. Only the first call has any effect.
+pub fn init_logging(app_name: &str) -> Result<(), NullaryError> {
+    if LOG_DIR.get().is_some() {
+        return Ok(());
+    }
+
+    let config_path = app_data_dir()?.join(CONFIG_FILE);
+    let log_dir = app_log_dir()?;
+
+    // A bad logging.json still gets a working logger, then gets reported
+    let (config, config_error) = match read_config(&config_path) {
+        Ok(config) => (config, None),
+        Err(e) => (LoggingConfig::default(), Some(e)),
+    };
+
+    let filter = EnvFilter::try_from_default_env()
+        .or_else(|_| EnvFilter::try_new(config.directives()))
+        .unwrap_or_else(|_| EnvFilter::new("info"));
+
+    // The appender prunes old files before it creates the dir
+    fs::create_dir_all(&log_dir).map_err(|e| {
+        NullaryError::storage(format!(
+            "Failed to create log dir {}: {}",
+            log_dir.display(),
+            e
+        ))
+    })?;
+    let appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(app_name)
+        .filename_suffix("log")
+        .max_log_files(config.max_files.max(1))
+        .build(&log_dir)
+        .map_err(|e| {
+            NullaryError::storage(format!(
+                "Failed to open log dir {}: {}",
+                log_dir.display(),
+                e
+            ))
+        })?;
+    let (file_writer, guard) = tracing_appender::non_blocking(appender);
+
+    let (stdout, stderr) = if has_frontend() {
+        let stdout = tracing_subscriber::fmt::layer().with_writer(Redacting(io::stdout));
+        (Some(stdout), None)
+    } else {
+        let level = if std::env::var_os("RUST_LOG").is_some() {
+            LevelFilter::TRACE
+        } else {
+            LevelFilter::WARN
+        };
+        let stderr = tracing_subscriber::fmt::layer()
+            .with_writer(Redacting(io::stderr))
+            .with_filter(level);
+        (None, Some(stderr))
+    };
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_ansi(false)
+                .with_writer(Redacting(file_writer)),
+        )
+        .with(stdout)
+        .with(stderr)
+        .try_init()
+        .map_err(|e| NullaryError::internal(format!("Failed to install logger: {}", e)))?;
+
+    let _ = GUARD.set(guard);
+    let _ = LOG_DIR.set(log_dir.clone());
+
+    info!(dir = %log_dir.display(), "📝 Logging to file");
+    if let Some(e) = config_error {
+        error!("❌ {} - using default log levels", e);
+    }
+    Ok(())
+}
+
+/// Fallback for when `init_logging` fails: a redacted stderr subscriber with
+/// no log file, so the app still reports what goes wrong. Does nothing if a
+/// subscriber is already installed.
+pub(crate) fn init_console_logging() {
+    let filter = EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| EnvFilter::new(if has_frontend() { "info" } else { "warn" }));
+
+    let _ = tracing_subscriber::registry()
+        .with(filter)
+        .with(tracing_subscriber::fmt::layer().with_writer(Redacting(io::stderr)))
+        .try_init();
+}
+
+// ===== EXPORT =====
+
+/// This app's log files, oldest first. Daily files sort by name.
+fn log_files(log_dir: &Path, app_name: &str) -> Result<Vec<PathBuf>, NullaryError> {
+    let entries = fs::read_dir(log_dir)
+        .map_err(|e| NullaryError::storage(format!("Failed to read log dir: {}", e)))?;
+
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(app_name) && name.ends_with(".log"))
+        })
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// The most recent log lines (default 2000), redacted, with a header naming
+/// the app and environment. Meant to be attached to bug reports.
+#[cfg_attr(feature = "tauri", tauri::command)]
+pub async fn export_logs(lines: Option<usize>) -> Result<String, NullaryError> {
+    let wanted = lines.unwrap_or(DEFAULT_EXPORT_LINES);
+    let Some(log_dir) = LOG_DIR.get() else {
+        return Err(NullaryError::internal("Logging is not initialized"));
+    };
+
+    // Walk back from the newest file until enough lines are collected
+    let mut collected: Vec<String> = Vec::new();
+    for path in log_files(log_dir, crate::app_name())?.iter().rev() {
+        if collected.len() >= wanted {
+            break;
+        }
+        let contents = fs::read_to_string(path).map_err(|e| {
+            NullaryError::storage(format!("Failed to read {}: {}", path.display(), e))
+        })?;
+        let mut file_lines: Vec<String> = contents
+            .lines()
+            .map(|line| redact(line).into_owned())
+            .collect();
+        file_lines.append(&mut collected);
+        collected = file_lines;
+    }
+
+    let start = collected.len().saturating_sub(wanted);
+    let header = format!(
+        "# {} logs ({} environment), exported {}\n",
+        crate::app_name(),
+        current_env(),
+        chrono::Utc::now().to_rfc3339()
+    );
+
+    info!(lines = collected.len() - start, "📤 Exported logs");
+    Ok(header + &collected[start..].join("\n"))
+}
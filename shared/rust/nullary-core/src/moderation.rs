@@ -0,0 +1,292 @@
+// Moderation
+//
+// What the user has chosen not to see, shared by every Nullary app on the
+// machine like the joined bases: `moderation.json` in the shared data dir,
+// per environment.
+//
+// - Muted bases stay joined but their content is left out of feeds:
+//   `feed_sources` skips them.
+// - Ignored bases are left out of discovery altogether. A joined base has to
+//   be left before it can be ignored, and the home base can be neither
+//   muted nor ignored.
+// - Blocked users are author UUIDs and public keys. Feeds drop their posts
+//   when the page is read, not when it's fetched, so a block also hides
+//   posts already in the feed cache; StackChat drops their messages and
+//   marks their connections blocked.
+//
+// The file is read fresh on every call, so a block made in one app applies
+// in every other app's next feed load.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{LazyLock, Mutex};
+use tracing::{info, warn};
+
+use crate::bases::{home_base, joined_bases, known_base, BaseData};
+use crate::documents::write_atomic;
+use crate::error::NullaryError;
+use crate::host::{emit, shared_data_dir};
+use crate::services::current_env;
+
+/// Event carrying the moderation lists after any change
+pub const MODERATION_EVENT: &str = "moderation-changed";
+
+const MODERATION_FILE: &str = "moderation.json";
+
+// Post fields that name the author, as Dolores and the apps' feeds use them;
+// `author` may also be an object with a `uuid` or `pubKey`
+const AUTHOR_FIELDS: [&str; 6] = [
+    "author",
+    "author_uuid",
+    "authorUUID",
+    "pubKey",
+    "author_pub_key",
+    "authorPubKey",
+];
+
+// ===== TYPES =====
+
+/// The active environment's moderation lists
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Moderation {
+    /// Ids of joined bases whose content is kept out of feeds
+    pub muted_bases: BTreeSet<String>,
+    /// Ids of bases kept out of discovery
+    pub ignored_bases: BTreeSet<String>,
+    /// Author UUIDs, on any service
+    pub blocked_users: BTreeSet<String>,
+    /// Author public keys
+    pub blocked_keys: BTreeSet<String>,
+}
+
+impl Moderation {
+    /// Whether `author`, a UUID or a public key, is blocked
+    pub fn blocks(&self, author: &str) -> bool {
+        self.blocked_users.contains(author) || self.blocked_keys.contains(author)
+    }
+
+    /// Whether any author field of `post` names a blocked user
+    pub fn blocks_post(&self, post: &Value) -> bool {
+        if self.blocked_users.is_empty() && self.blocked_keys.is_empty() {
+            return false;
+        }
+        AUTHOR_FIELDS.iter().any(|field| match post.get(field) {
+            Some(Value::String(author)) => self.blocks(author),
+            Some(author @ Value::Object(_)) => ["uuid", "pubKey"].iter().any(|field| {
+                author
+                    .get(field)
+                    .and_then(Value::as_str)
+                    .is_some_and(|author| self.blocks(author))
+            }),
+            _ => false,
+        })
+    }
+}
+
+/// Environment -> moderation lists
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ModerationFile {
+    #[serde(default)]
+    environments: BTreeMap<String, Moderation>,
+}
+
+fn moderation_path() -> Result<PathBuf, NullaryError> {
+    Ok(shared_data_dir()?.join(MODERATION_FILE))
+}
+
+fn read_moderation_file() -> Result<ModerationFile, NullaryError> {
+    let path = moderation_path()?;
+    let json = match fs::read_to_string(&path) {
+        Ok(json) => json,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(ModerationFile::default()),
+        Err(e) => {
+            return Err(NullaryError::storage(format!(
+                "Failed to read moderation lists: {}",
+                e
+            )))
+        }
+    };
+    serde_json::from_str(&json)
+        .map_err(|e| NullaryError::storage(format!("Failed to parse moderation lists: {}", e)))
+}
+
+/// The active environment's moderation lists, read fresh. A file that can't
+/// be read moderates nothing rather than failing the feed.
+pub fn moderation() -> Moderation {
+    match read_moderation_file() {
+        Ok(mut file) => file.environments.remove(&current_env()).unwrap_or_default(),
+        Err(e) => {
+            warn!("⚠️ {} - nothing filtered", e);
+            Moderation::default()
+        }
+    }
+}
+
+// Same race as the joined bases: serialized within this app, last writer
+// wins between apps
+static MODERATION_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+
+/// Change the active environment's lists, save them and tell every window
+fn update_moderation(change: impl FnOnce(&mut Moderation)) -> Result<Moderation, NullaryError> {
+    let _guard = MODERATION_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    let mut file = read_moderation_file()?;
+    let moderation = file.environments.entry(current_env()).or_default();
+    change(moderation);
+    let moderation = moderation.clone();
+
+    let json = serde_json::to_vec_pretty(&file).map_err(|e| {
+        NullaryError::internal(format!("Failed to serialize moderation lists: {}", e))
+    })?;
+    write_atomic(&moderation_path()?, &json)?;
+
+    if let Err(e) = emit(MODERATION_EVENT, &moderation) {
+        warn!("⚠️ Failed to emit moderation lists: {}", e);
+    }
+    Ok(moderation)
+}
+
+// ===== FILTERS =====
+
+/// `posts` without those by blocked users
+pub fn without_blocked_posts(posts: Vec<Value>) -> Vec<Value> {
+    let moderation = moderation();
+    posts
+        .into_iter()
+        .filter(|post| !moderation.blocks_post(post))
+        .collect()
+}
+
+/// `bases` without ignored ones, muted ones marked
+pub(crate) fn moderate_bases(bases: Vec<BaseData>) -> Vec<BaseData> {
+    let moderation = moderation();
+    bases
+        .into_iter()
+        .filter(|base| base.home || !moderation.ignored_bases.contains(&base.id))
+        .map(|base| BaseData {
+            muted: !base.home && moderation.muted_bases.contains(&base.id),
+            ..base
+        })
+        .collect()
+}
+
+// Compressed secp256k1 public key, as sessionless uses
+fn is_public_key(author: &str) -> bool {
+    author.len() == 66
+        && (author.starts_with("02") || author.starts_with("03"))
+        && author.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+// ===== COMMANDS =====
+
+/// The active environment's moderation lists
+#[cfg_attr(feature = "tauri", tauri::command)]
+pub fn get_moderation() -> Moderation {
+    moderation()
+}
+
+/// Mute or unmute a joined base, by id or name
+#[cfg_attr(feature = "tauri", tauri::command)]
+pub fn set_base_muted(base: String, muted: bool) -> Result<Moderation, NullaryError> {
+    let home = home_base();
+    if home.id == base || home.name == base {
+        return Err(NullaryError::validation("The home base can't be muted"));
+    }
+    let id = match joined_bases()?
+        .into_iter()
+        .find(|joined| joined.id == base || joined.name == base)
+    {
+        Some(joined) => joined.id,
+        None if muted => {
+            return Err(NullaryError::validation(format!(
+                "Not a member of base {}",
+                base
+            )))
+        }
+        // Left since it was muted
+        None => base,
+    };
+
+    let moderation = update_moderation(|moderation| {
+        if muted {
+            moderation.muted_bases.insert(id.clone());
+        } else {
+            moderation.muted_bases.remove(&id);
+        }
+    })?;
+    info!("🔇 Base {} {}", id, if muted { "muted" } else { "unmuted" });
+    Ok(moderation)
+}
+
+/// Ignore a base, by id or name, or stop ignoring one, by id
+#[cfg_attr(feature = "tauri", tauri::command)]
+pub async fn set_base_ignored(base: String, ignored: bool) -> Result<Moderation, NullaryError> {
+    // Ignored bases aren't discovered, so only the id can bring one back
+    let id = if ignored {
+        let known = known_base(&base).await?;
+        if known.home {
+            return Err(NullaryError::validation("The home base can't be ignored"));
+        }
+        if known.joined {
+            return Err(NullaryError::validation(format!(
+                "Leave base {} before ignoring it",
+                known.name
+            )));
+        }
+        known.id
+    } else {
+        base
+    };
+
+    let moderation = update_moderation(|moderation| {
+        if ignored {
+            moderation.ignored_bases.insert(id.clone());
+        } else {
+            moderation.ignored_bases.remove(&id);
+        }
+    })?;
+    info!(
+        "🙈 Base {} {}",
+        id,
+        if ignored {
+            "ignored"
+        } else {
+            "no longer ignored"
+        }
+    );
+    Ok(moderation)
+}
+
+/// Block or unblock a user by UUID or public key, in every app
+#[cfg_attr(feature = "tauri", tauri::command)]
+pub fn set_user_blocked(user: String, blocked: bool) -> Result<Moderation, NullaryError> {
+    let user = user.trim().to_string();
+    if user.is_empty() {
+        return Err(NullaryError::validation("No user to block"));
+    }
+
+    let moderation = update_moderation(|moderation| {
+        let list = if is_public_key(&user) {
+            &mut moderation.blocked_keys
+        } else {
+            &mut moderation.blocked_users
+        };
+        if blocked {
+            list.insert(user.clone());
+        } else {
+            list.remove(&user);
+        }
+    })?;
+    info!(
+        "🚫 User {} {}",
+        user,
+        if blocked { "blocked" } else { "unblocked" }
+    );
+    Ok(moderation)
+}
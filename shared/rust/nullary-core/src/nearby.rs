@@ -0,0 +1,325 @@
+// Nearby bases
+//
+// Ranks discovered bases by great-circle distance from a location the user
+// gives with each query, for community-local content. The location is only
+// used here: discovery asks BDO for the same list as always and the
+// distances are worked out on this machine, so no base ever sees where the
+// user is. It isn't stored or logged either.
+//
+// A postal code stands in for coordinates through its centroid: the mean
+// location of the known bases that list the same code. Nothing outside the
+// base list is consulted, so a code no base uses can't be located.
+
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::bases::{discover_bases_at, BaseData, LocationData};
+use crate::error::NullaryError;
+use crate::feed_cache::FeedPage;
+use crate::services::get_service_url;
+
+// Mean Earth radius
+const EARTH_RADIUS_KM: f64 = 6371.0088;
+/// Radius "bases near me" uses when the query doesn't set one
+pub const NEAR_ME_RADIUS_KM: f64 = 50.0;
+
+// ===== TYPES =====
+
+/// Where to measure from, and how far to look. Either coordinates or a
+/// postal code.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NearQuery {
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub postal_code: Option<String>,
+    /// Only bases at most this many kilometres away
+    pub radius_km: Option<f64>,
+    /// List bases without a location too, after the ranked ones
+    pub include_unlocated: bool,
+}
+
+/// A base and how far it is from the query's location
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NearbyBase {
+    #[serde(flatten)]
+    pub base: BaseData,
+    /// `None` for a base without a location
+    pub distance_km: Option<f64>,
+}
+
+// ===== DISTANCE =====
+
+/// Great-circle distance in kilometres, by the haversine formula
+pub fn distance_km(from: (f64, f64), to: (f64, f64)) -> f64 {
+    let (lat1, lon1) = (from.0.to_radians(), from.1.to_radians());
+    let (lat2, lon2) = (to.0.to_radians(), to.1.to_radians());
+    let a = ((lat2 - lat1) / 2.0).sin().powi(2)
+        + lat1.cos() * lat2.cos() * ((lon2 - lon1) / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * a.sqrt().min(1.0).asin()
+}
+
+// ZIP+4 and spacing differences ("94103-1234", "sw1a 1aa") don't matter
+fn normalize_postal_code(code: &str) -> String {
+    let code = code.split('-').next().unwrap_or(code);
+    code.chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_uppercase()
+}
+
+/// Mean location of the bases listing `postal_code`
+fn postal_code_centroid(bases: &[BaseData], postal_code: &str) -> Option<(f64, f64)> {
+    let code = normalize_postal_code(postal_code);
+    let located: Vec<&LocationData> = bases
+        .iter()
+        .filter_map(|base| base.location.as_ref())
+        .filter(|location| {
+            location
+                .postal_code
+                .as_deref()
+                .is_some_and(|candidate| normalize_postal_code(candidate) == code)
+        })
+        .collect();
+    if located.is_empty() {
+        return None;
+    }
+
+    // Averaged as vectors so codes near the antimeridian don't land halfway
+    // round the world
+    let (x, y, z) = located.iter().fold((0.0, 0.0, 0.0), |(x, y, z), location| {
+        let (lat, lon) = (
+            location.latitude.to_radians(),
+            location.longitude.to_radians(),
+        );
+        (
+            x + lat.cos() * lon.cos(),
+            y + lat.cos() * lon.sin(),
+            z + lat.sin(),
+        )
+    });
+    let latitude = z.atan2((x * x + y * y).sqrt()).to_degrees();
+    let longitude = y.atan2(x).to_degrees();
+    Some((latitude, longitude))
+}
+
+/// The point `query` measures from
+fn query_origin(query: &NearQuery, bases: &[BaseData]) -> Result<(f64, f64), NullaryError> {
+    match (
+        query.latitude,
+        query.longitude,
+        query.postal_code.as_deref(),
+    ) {
+        (Some(latitude), Some(longitude), _) => {
+            if !(-90.0..=90.0).contains(&latitude) || !(-180.0..=180.0).contains(&longitude) {
+                return Err(NullaryError::validation(
+                    "Latitude must be within ±90 and longitude within ±180",
+                ));
+            }
+            Ok((latitude, longitude))
+        }
+        (Some(_), None, _) | (None, Some(_), _) => {
+            Err(NullaryError::validation("Give both latitude and longitude"))
+        }
+        (None, None, Some(code)) if !code.trim().is_empty() => postal_code_centroid(bases, code)
+            .ok_or_else(|| {
+                NullaryError::validation(format!(
+                    "No known base is in postal code {}; give latitude and longitude instead",
+                    code.trim()
+                ))
+            }),
+        _ => Err(NullaryError::validation(
+            "Give a latitude and longitude or a postal code",
+        )),
+    }
+}
+
+/// `bases` nearest first, within the query's radius; bases without a
+/// location last, by name, when the query asks for them
+pub fn rank_bases(
+    bases: Vec<BaseData>,
+    query: &NearQuery,
+) -> Result<Vec<NearbyBase>, NullaryError> {
+    if query
+        .radius_km
+        .is_some_and(|radius| radius.is_nan() || radius < 0.0)
+    {
+        return Err(NullaryError::validation("Radius can't be negative"));
+    }
+    let origin = query_origin(query, &bases)?;
+
+    let (mut located, mut unlocated): (Vec<NearbyBase>, Vec<NearbyBase>) = bases
+        .into_iter()
+        .map(|base| NearbyBase {
+            distance_km: base
+                .location
+                .as_ref()
+                .map(|location| distance_km(origin, (location.latitude, location.longitude))),
+            base,
+        })
+        .partition(|nearby| nearby.distance_km.is_some());
+
+    if let Some(radius) = query.radius_km {
+        located.retain(|nearby| {
+            nearby
+                .distance_km
+                .is_some_and(|distance| distance <= radius)
+        });
+    }
+    located.sort_by(|a, b| {
+        a.distance_km
+            .partial_cmp(&b.distance_km)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.base.name.cmp(&b.base.name))
+    });
+
+    if query.include_unlocated {
+        unlocated.sort_by(|a, b| a.base.name.cmp(&b.base.name));
+        located.extend(unlocated);
+    }
+    Ok(located)
+}
+
+/// The bases the BDO at `bdo_url` lists, ranked by distance from `query`.
+/// The list is discovered as usual; the location never leaves this machine.
+pub async fn discover_bases_near_at(
+    bdo_url: &str,
+    query: &NearQuery,
+    refresh: bool,
+) -> Result<FeedPage<Vec<NearbyBase>>, NullaryError> {
+    let page = discover_bases_at(bdo_url, refresh).await?;
+    let total = page.data.len();
+    let ranked = rank_bases(page.data, query)?;
+    debug!("📍 {} of {} bases ranked by distance", ranked.len(), total);
+
+    Ok(FeedPage {
+        data: ranked,
+        stale: page.stale,
+        fetched_at: page.fetched_at,
+    })
+}
+
+// ===== COMMANDS =====
+
+/// Bases ranked by distance from a location given with the query, filtered
+/// by its radius if it has one
+#[cfg_attr(feature = "tauri", tauri::command)]
+pub async fn discover_bases_near(
+    query: NearQuery,
+    refresh: Option<bool>,
+) -> Result<FeedPage<Vec<NearbyBase>>, NullaryError> {
+    discover_bases_near_at(&get_service_url("bdo"), &query, refresh.unwrap_or(false)).await
+}
+
+/// Located bases within `NEAR_ME_RADIUS_KM` of the query's location, unless
+/// it sets its own radius, nearest first
+#[cfg_attr(feature = "tauri", tauri::command)]
+pub async fn bases_near_me(
+    query: NearQuery,
+    refresh: Option<bool>,
+) -> Result<FeedPage<Vec<NearbyBase>>, NullaryError> {
+    let query = NearQuery {
+        radius_km: Some(query.radius_km.unwrap_or(NEAR_ME_RADIUS_KM)),
+        include_unlocated: false,
+        ..query
+    };
+    discover_bases_near(query, refresh).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn base(name: &str, location: Option<(f64, f64, &str)>) -> BaseData {
+        let location = location.map(|(latitude, longitude, postal_code)| {
+            json!({ "latitude": latitude, "longitude": longitude, "postalCode": postal_code })
+        });
+        serde_json::from_value(json!({ "id": name, "name": name, "location": location })).unwrap()
+    }
+
+    fn bases() -> Vec<BaseData> {
+        vec![
+            base("london", Some((51.5074, -0.1278, "EC1A 1BB"))),
+            base("oakland", Some((37.8044, -122.2712, "94612"))),
+            base("unlocated", None),
+            base("san-francisco", Some((37.7749, -122.4194, "94103"))),
+        ]
+    }
+
+    fn names(ranked: &[NearbyBase]) -> Vec<&str> {
+        ranked.iter().map(|nearby| nearby.base.name.as_str()).collect()
+    }
+
+    #[test]
+    fn distances_match_known_values() {
+        assert_eq!(distance_km((10.0, 20.0), (10.0, 20.0)), 0.0);
+        // San Francisco to London is about 8,616 km
+        let distance = distance_km((37.7749, -122.4194), (51.5074, -0.1278));
+        assert!((distance - 8616.0).abs() < 10.0, "{}", distance);
+        // A degree of longitude on the equator
+        let degree = distance_km((0.0, 0.0), (0.0, 1.0));
+        assert!((degree - 111.195).abs() < 0.01, "{}", degree);
+        // Antipodes are half the circumference apart
+        let half = distance_km((0.0, 0.0), (0.0, 180.0));
+        assert!((half - std::f64::consts::PI * EARTH_RADIUS_KM).abs() < 1e-6, "{}", half);
+    }
+
+    #[test]
+    fn bases_are_ranked_nearest_first() {
+        let query = NearQuery {
+            latitude: Some(37.7793),
+            longitude: Some(-122.4193),
+            ..NearQuery::default()
+        };
+        let ranked = rank_bases(bases(), &query).unwrap();
+
+        assert_eq!(names(&ranked), vec!["san-francisco", "oakland", "london"]);
+        assert!(ranked.windows(2).all(|pair| pair[0].distance_km <= pair[1].distance_km));
+    }
+
+    #[test]
+    fn radius_and_unlocated_bases() {
+        let query = NearQuery {
+            latitude: Some(37.7793),
+            longitude: Some(-122.4193),
+            radius_km: Some(50.0),
+            include_unlocated: true,
+            ..NearQuery::default()
+        };
+        let ranked = rank_bases(bases(), &query).unwrap();
+
+        assert_eq!(names(&ranked), vec!["san-francisco", "oakland", "unlocated"]);
+        assert_eq!(ranked[2].distance_km, None);
+    }
+
+    #[test]
+    fn postal_codes_measure_from_their_bases() {
+        let query = NearQuery {
+            postal_code: Some("94103-1234".to_string()),
+            ..NearQuery::default()
+        };
+        let ranked = rank_bases(bases(), &query).unwrap();
+        assert_eq!(names(&ranked), vec!["san-francisco", "oakland", "london"]);
+        assert!(ranked[0].distance_km.is_some_and(|distance| distance < 1e-6));
+
+        let unknown = NearQuery {
+            postal_code: Some("10001".to_string()),
+            ..NearQuery::default()
+        };
+        assert!(rank_bases(bases(), &unknown).is_err());
+    }
+
+    #[test]
+    fn invalid_queries_are_refused() {
+        for query in [
+            NearQuery::default(),
+            NearQuery { latitude: Some(1.0), ..NearQuery::default() },
+            NearQuery { latitude: Some(91.0), longitude: Some(0.0), ..NearQuery::default() },
+            NearQuery { latitude: Some(0.0), longitude: Some(0.0), radius_km: Some(-1.0), ..NearQuery::default() },
+        ] {
+            assert!(rank_bases(bases(), &query).is_err(), "{:?}", query);
+        }
+    }
+}
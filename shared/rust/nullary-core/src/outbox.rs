@@ -0,0 +1,614 @@
+// Offline outbox
+//
+// A write that fails because its base can't be reached is kept instead of
+// lost. The command hands the failed result to `queue_if_transient`, which
+// stores the write in `outbox.json` (app data dir) together with an
+// idempotency key and an intent signed by the persona that made it, and
+// answers with a `queued` error so the frontend can say so. Everything else
+// passes through untouched.
+//
+// Queued writes are replayed through handlers the app registers at startup,
+// oldest first within each service and base: a write that still can't get
+// through holds back the ones queued after it, while one the service rejects
+// is marked failed with the reason and stays listed until it is retried or
+// cancelled. Replay runs on a timer, after a health probe finds a service
+// reachable, and when an item is retried.
+//
+// The idempotency key hashes the operation, base, service, item id and
+// payload, so every queued write has its own: two identical writes are two
+// items and both are sent. A replayed item's key is remembered for a day so
+// the same item is never sent twice. The intent signature is checked against
+// the persona's key before each replay, and the key recomputed, so an item
+// edited on disk is refused. Services don't see the key: a write that was in
+// flight when the app quit is sent again on the next launch.
+
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock, Mutex, RwLock};
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+use crate::documents::write_atomic;
+use crate::error::NullaryError;
+use crate::host::{app_data_dir, emit, spawn};
+use crate::services::base_for_url;
+use crate::signing::{sign_operation_at, verify_operation_at, OperationSignature, SigningPurpose};
+use crate::user_persistence::active_persona;
+
+/// Event carrying every outbox item after each change
+pub const OUTBOX_EVENT: &str = "outbox-changed";
+/// Event carrying a replayed item and what the service answered
+pub const DELIVERED_EVENT: &str = "outbox-delivered";
+
+const OUTBOX_FILE: &str = "outbox.json";
+const REPLAY_INTERVAL: Duration = Duration::from_secs(30);
+// How long a replayed item's key is remembered
+const DELIVERED_TTL_MS: i64 = 24 * 60 * 60 * 1000;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "camelCase")]
+pub enum OutboxStatus {
+    /// Waiting for its base to come back
+    Queued,
+    /// Being replayed right now
+    Sending,
+    /// The service rejected it; it is only sent again if retried
+    Failed { reason: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutboxItem {
+    pub id: String,
+    /// Name of the registered handler that replays it, e.g. `add_product`
+    pub operation: String,
+    pub service: String,
+    pub base: String,
+    pub url: String,
+    /// Persona whose keys made the write; it is only replayed as that persona
+    pub persona: String,
+    /// Whatever the handler needs to make the write again
+    pub payload: Value,
+    /// Hash of the operation, base, service, id and payload
+    pub idempotency_key: String,
+    /// Signature over `timestamp + operation + idempotencyKey`, made when
+    /// the write was queued
+    pub intent: OperationSignature,
+    pub status: OutboxStatus,
+    pub attempts: u32,
+    pub queued_at: i64,
+    pub last_attempt_at: Option<i64>,
+    pub last_error: Option<String>,
+}
+
+impl OutboxItem {
+    /// Writes in the same lane are replayed strictly in order
+    fn lane(&self) -> (String, String) {
+        (self.service.clone(), self.base.clone())
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct OutboxFile {
+    items: Vec<OutboxItem>,
+    /// Idempotency key -> when its item was replayed
+    delivered: BTreeMap<String, i64>,
+}
+
+#[derive(Default)]
+struct Outbox {
+    path: Option<PathBuf>,
+    file: OutboxFile,
+}
+
+impl Outbox {
+    fn save(&self) -> Result<(), NullaryError> {
+        let Some(path) = &self.path else {
+            return Err(NullaryError::internal("Outbox is not loaded"));
+        };
+        let json = serde_json::to_vec_pretty(&self.file)
+            .map_err(|e| NullaryError::internal(format!("Failed to serialize outbox: {}", e)))?;
+        write_atomic(path, &json)
+    }
+
+    fn item_mut(&mut self, id: &str) -> Result<&mut OutboxItem, NullaryError> {
+        self.file
+            .items
+            .iter_mut()
+            .find(|item| item.id == id)
+            .ok_or_else(|| NullaryError::validation(format!("No outbox item {}", id)))
+    }
+
+    /// Save, and tell the frontend either way so it shows what is in memory
+    fn commit(&self) {
+        if let Err(e) = self.save() {
+            error!("❌ {}", e);
+        }
+        self.notify();
+    }
+
+    fn notify(&self) {
+        if let Err(e) = emit(OUTBOX_EVENT, &self.file.items) {
+            warn!("⚠️ Failed to emit outbox change: {}", e);
+        }
+    }
+}
+
+// Nothing is queued until `load_outbox` runs at startup
+static OUTBOX: LazyLock<Mutex<Outbox>> = LazyLock::new(|| Mutex::new(Outbox::default()));
+
+fn outbox() -> std::sync::MutexGuard<'static, Outbox> {
+    OUTBOX
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Load `outbox.json` from the app data dir and start the replay timer.
+/// Writes that were being sent when the app quit are queued again.
+pub fn load_outbox() -> Result<(), NullaryError> {
+    let path = app_data_dir()?.join(OUTBOX_FILE);
+
+    let mut file: OutboxFile = match fs::read_to_string(&path) {
+        Ok(content) => serde_json::from_str(&content).map_err(|e| {
+            NullaryError::storage(format!("Failed to parse outbox {}: {}", path.display(), e))
+        })?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => OutboxFile::default(),
+        Err(e) => {
+            return Err(NullaryError::storage(format!(
+                "Failed to read outbox: {}",
+                e
+            )))
+        }
+    };
+
+    for item in &mut file.items {
+        if item.status == OutboxStatus::Sending {
+            item.status = OutboxStatus::Queued;
+        }
+    }
+
+    let mut outbox = outbox();
+    outbox.file = file;
+    outbox.path = Some(path);
+    info!("📮 Loaded {} outbox items", outbox.file.items.len());
+    drop(outbox);
+
+    start_replay_timer();
+    Ok(())
+}
+
+// ===== HANDLERS =====
+
+/// Replays one queued write from its payload and returns what the service
+/// answered
+pub type OutboxHandler =
+    Arc<dyn Fn(Value) -> BoxFuture<'static, Result<Value, NullaryError>> + Send + Sync>;
+
+static HANDLERS: LazyLock<RwLock<BTreeMap<String, OutboxHandler>>> =
+    LazyLock::new(|| RwLock::new(BTreeMap::new()));
+
+/// Register the handler that replays queued `operation` writes. Register at
+/// startup, next to `nullary_core::init`.
+///
+/// ```rust,ignore
+/// nullary_core::register_outbox_handler("add_product", |payload| async move {
+///     let product: NewProduct = nullary_core::outbox_payload(payload)?;
+///     Ok(serde_json::json!(send_product(&product).await?))
+/// });
+/// ```
+pub fn register_outbox_handler<F, Fut>(operation: &str, handler: F)
+where
+    F: Fn(Value) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<Value, NullaryError>> + Send + 'static,
+{
+    let handler: OutboxHandler = Arc::new(move |payload| Box::pin(handler(payload)));
+    HANDLERS
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .insert(operation.to_string(), handler);
+}
+
+/// Read a queued payload back into the type it was queued from
+pub fn outbox_payload<T: serde::de::DeserializeOwned>(payload: Value) -> Result<T, NullaryError> {
+    serde_json::from_value(payload)
+        .map_err(|e| NullaryError::validation(format!("Queued write no longer parses: {}", e)))
+}
+
+fn handler(operation: &str) -> Option<OutboxHandler> {
+    HANDLERS
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .get(operation)
+        .cloned()
+}
+
+// ===== QUEUEING =====
+
+fn idempotency_key(operation: &str, base: &str, service: &str, id: &str, payload: &Value) -> String {
+    let mut hasher = Sha256::new();
+    for part in [operation, base, service, id, &payload.to_string()] {
+        hasher.update(part.as_bytes());
+        hasher.update([0]);
+    }
+    hex::encode(hasher.finalize())
+}
+
+/// Pass `result` through, unless it failed because `service` at `url` could
+/// not be reached. Then the write is queued to be replayed by the
+/// `operation` handler with `payload`, and the caller gets a `queued` error
+/// naming the outbox item.
+pub async fn queue_if_transient<T, P: Serialize>(
+    result: Result<T, NullaryError>,
+    operation: &str,
+    service: &str,
+    url: &str,
+    payload: &P,
+) -> Result<T, NullaryError> {
+    let cause = match result {
+        Err(e) if e.is_transient() => e,
+        other => return other,
+    };
+
+    let payload = match serde_json::to_value(payload) {
+        Ok(payload) => payload,
+        Err(e) => {
+            error!("❌ Failed to serialize {} for the outbox: {}", operation, e);
+            return Err(cause);
+        }
+    };
+
+    match enqueue(operation, service, url, payload).await {
+        Ok(item) => {
+            info!("📮 Queued {} for {} as {}", operation, service, item.id);
+            Err(NullaryError::Queued {
+                service: service.to_string(),
+                id: item.id,
+                cause: cause.to_string(),
+            })
+        }
+        Err(e) => {
+            error!("❌ Failed to queue {}: {}", operation, e);
+            Err(cause)
+        }
+    }
+}
+
+async fn enqueue(
+    operation: &str,
+    service: &str,
+    url: &str,
+    payload: Value,
+) -> Result<OutboxItem, NullaryError> {
+    let base = base_for_url(url)?;
+    // The item id is the intent's nonce: identical writes get their own keys
+    let id = sessionless::Sessionless::generate_uuid().to_string();
+    let key = idempotency_key(operation, &base, service, &id, &payload);
+
+    if outbox().path.is_none() {
+        return Err(NullaryError::internal("Outbox is not loaded"));
+    }
+
+    let intent = sign_operation_at(
+        url,
+        service,
+        SigningPurpose::OutboxIntent,
+        &[operation, &key],
+    )
+    .await?;
+    let item = OutboxItem {
+        id,
+        operation: operation.to_string(),
+        service: service.to_string(),
+        base,
+        url: url.to_string(),
+        persona: active_persona(),
+        payload,
+        idempotency_key: key,
+        intent,
+        status: OutboxStatus::Queued,
+        attempts: 0,
+        queued_at: chrono::Utc::now().timestamp_millis(),
+        last_attempt_at: None,
+        last_error: None,
+    };
+
+    let mut outbox = outbox();
+    outbox.file.items.push(item.clone());
+    if let Err(e) = outbox.save() {
+        outbox.file.items.pop();
+        return Err(e);
+    }
+    outbox.notify();
+    Ok(item)
+}
+
+// ===== REPLAY =====
+
+// One replay pass at a time
+static REPLAYING: LazyLock<tokio::sync::Mutex<()>> = LazyLock::new(|| tokio::sync::Mutex::new(()));
+static TIMER_STARTED: AtomicBool = AtomicBool::new(false);
+
+fn start_replay_timer() {
+    if TIMER_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    spawn(async {
+        loop {
+            tokio::time::sleep(REPLAY_INTERVAL).await;
+            replay_outbox().await;
+        }
+    });
+}
+
+/// Whether anything is waiting to be replayed
+pub fn has_queued_writes() -> bool {
+    outbox()
+        .file
+        .items
+        .iter()
+        .any(|item| item.status == OutboxStatus::Queued)
+}
+
+/// Next queued write of the active persona whose lane isn't held back,
+/// marked as sending
+fn take_next(blocked: &BTreeSet<(String, String)>) -> Option<OutboxItem> {
+    let persona = active_persona();
+    let mut outbox = outbox();
+    let item = outbox.file.items.iter_mut().find(|item| {
+        item.status == OutboxStatus::Queued
+            && item.persona == persona
+            && !blocked.contains(&item.lane())
+    })?;
+
+    item.status = OutboxStatus::Sending;
+    item.attempts += 1;
+    item.last_attempt_at = Some(chrono::Utc::now().timestamp_millis());
+    let item = item.clone();
+    outbox.commit();
+    Some(item)
+}
+
+/// Drop `item` without sending it if it was already replayed, e.g. an
+/// outbox file restored from before the delivery
+fn already_delivered(item: &OutboxItem) -> bool {
+    let mut outbox = outbox();
+    if !outbox.file.delivered.contains_key(&item.idempotency_key) {
+        return false;
+    }
+    info!(
+        "📭 {} {} was already sent, dropping it",
+        item.operation, item.id
+    );
+    outbox.file.items.retain(|queued| queued.id != item.id);
+    outbox.commit();
+    true
+}
+
+async fn replay(item: &OutboxItem) -> Result<Value, NullaryError> {
+    let Some(handler) = handler(&item.operation) else {
+        return Err(NullaryError::validation(format!(
+            "No outbox handler for {}",
+            item.operation
+        )));
+    };
+    let key = idempotency_key(
+        &item.operation,
+        &item.base,
+        &item.service,
+        &item.id,
+        &item.payload,
+    );
+    if key != item.idempotency_key {
+        return Err(NullaryError::validation(
+            "Queued write was changed after it was signed",
+        ));
+    }
+    verify_operation_at(
+        &item.url,
+        &item.service,
+        &item.intent,
+        &[&item.operation, &item.idempotency_key],
+    )
+    .map_err(|e| NullaryError::validation(format!("Queued write's intent: {}", e)))?;
+    handler(item.payload.clone()).await
+}
+
+/// Replay queued writes, oldest first within each service and base. A write
+/// that still can't get through holds back the rest of its lane until the
+/// next pass.
+pub async fn replay_outbox() {
+    let Ok(_replaying) = REPLAYING.try_lock() else {
+        return;
+    };
+
+    let mut blocked = BTreeSet::new();
+    while let Some(item) = take_next(&blocked) {
+        if already_delivered(&item) {
+            continue;
+        }
+        let result = replay(&item).await;
+
+        let mut outbox = outbox();
+        let now = chrono::Utc::now().timestamp_millis();
+        match result {
+            Ok(response) => {
+                info!(
+                    "📬 Replayed {} {} to {}",
+                    item.operation, item.id, item.service
+                );
+                outbox.file.items.retain(|queued| queued.id != item.id);
+                outbox
+                    .file
+                    .delivered
+                    .retain(|_, at| now - *at < DELIVERED_TTL_MS);
+                outbox
+                    .file
+                    .delivered
+                    .insert(item.idempotency_key.clone(), now);
+                let delivered = serde_json::json!({ "item": item, "response": response });
+                if let Err(e) = emit(DELIVERED_EVENT, &delivered) {
+                    warn!("⚠️ Failed to emit outbox delivery: {}", e);
+                }
+            }
+            Err(e) => {
+                let transient = e.is_transient();
+                if transient {
+                    warn!(
+                        "🔌 {} {} still can't be sent: {}",
+                        item.operation, item.id, e
+                    );
+                    blocked.insert(item.lane());
+                } else {
+                    error!(
+                        "❌ {} {} failed permanently: {}",
+                        item.operation, item.id, e
+                    );
+                }
+                if let Ok(queued) = outbox.item_mut(&item.id) {
+                    queued.last_error = Some(e.to_string());
+                    queued.status = if transient {
+                        OutboxStatus::Queued
+                    } else {
+                        OutboxStatus::Failed {
+                            reason: e.to_string(),
+                        }
+                    };
+                }
+            }
+        }
+        outbox.commit();
+    }
+}
+
+// ===== COMMANDS =====
+
+/// Every queued, sending and failed write, oldest first
+#[cfg_attr(feature = "tauri", tauri::command)]
+pub async fn list_outbox() -> Result<Vec<OutboxItem>, NullaryError> {
+    Ok(outbox().file.items.clone())
+}
+
+/// Queue a failed write again, or replay a queued one now
+#[cfg_attr(feature = "tauri", tauri::command)]
+pub async fn retry_outbox_item(id: String) -> Result<OutboxItem, NullaryError> {
+    let item = {
+        let mut outbox = outbox();
+        let item = outbox.item_mut(&id)?;
+        if item.status == OutboxStatus::Sending {
+            return Err(NullaryError::validation(format!(
+                "Outbox item {} is being sent",
+                id
+            )));
+        }
+        item.status = OutboxStatus::Queued;
+        let item = item.clone();
+        outbox.commit();
+        item
+    };
+
+    info!("🔁 Retrying {} {}", item.operation, item.id);
+    spawn(replay_outbox());
+    Ok(item)
+}
+
+/// Drop a write that is queued or failed; it is never sent
+#[cfg_attr(feature = "tauri", tauri::command)]
+pub async fn cancel_outbox_item(id: String) -> Result<OutboxItem, NullaryError> {
+    let mut outbox = outbox();
+    let position = outbox
+        .file
+        .items
+        .iter()
+        .position(|item| item.id == id)
+        .ok_or_else(|| NullaryError::validation(format!("No outbox item {}", id)))?;
+    if outbox.file.items[position].status == OutboxStatus::Sending {
+        return Err(NullaryError::validation(format!(
+            "Outbox item {} is being sent",
+            id
+        )));
+    }
+
+    let item = outbox.file.items.remove(position);
+    outbox.commit();
+
+    info!("🗑️ Cancelled {} {}", item.operation, item.id);
+    Ok(item)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn item(id: &str, payload: Value) -> OutboxItem {
+        OutboxItem {
+            id: id.to_string(),
+            operation: "add_product".to_string(),
+            service: "sanora".to_string(),
+            base: "home".to_string(),
+            url: "http://127.0.0.1:5121/".to_string(),
+            persona: "default".to_string(),
+            idempotency_key: idempotency_key("add_product", "home", "sanora", id, &payload),
+            payload,
+            intent: OperationSignature {
+                timestamp: "0".to_string(),
+                signature: String::new(),
+            },
+            status: OutboxStatus::Queued,
+            attempts: 0,
+            queued_at: 0,
+            last_attempt_at: None,
+            last_error: None,
+        }
+    }
+
+    #[test]
+    fn identical_writes_get_their_own_keys() {
+        let payload = json!({ "title": "Poster", "price": 1000 });
+        let first = item("first", payload.clone());
+        let second = item("second", payload);
+
+        assert_ne!(first.idempotency_key, second.idempotency_key);
+    }
+
+    #[test]
+    fn keys_cover_the_whole_write() {
+        let payload = json!({ "title": "Poster", "price": 1000 });
+        let key = idempotency_key("add_product", "home", "sanora", "id", &payload);
+
+        assert_eq!(key, idempotency_key("add_product", "home", "sanora", "id", &payload));
+        assert_ne!(key, idempotency_key("add_product", "home", "sanora", "id", &json!({ "title": "Poster", "price": 1 })));
+        assert_ne!(key, idempotency_key("add_product", "other", "sanora", "id", &payload));
+        assert_ne!(key, idempotency_key("delete_product", "home", "sanora", "id", &payload));
+        // Parts are separated, so they can't shift into each other
+        assert_ne!(
+            idempotency_key("ab", "c", "sanora", "id", &payload),
+            idempotency_key("a", "bc", "sanora", "id", &payload)
+        );
+    }
+
+    #[test]
+    fn delivered_items_are_dropped_without_sending() {
+        let delivered = item("delivered", json!({ "title": "Poster" }));
+        let pending = item("pending", json!({ "title": "Poster" }));
+        {
+            let mut outbox = outbox();
+            outbox.file.items = vec![delivered.clone(), pending.clone()];
+            outbox.file.delivered = BTreeMap::from([(delivered.idempotency_key.clone(), 0)]);
+        }
+
+        assert!(already_delivered(&delivered));
+        assert!(!already_delivered(&pending));
+
+        let ids: Vec<String> = outbox().file.items.iter().map(|item| item.id.clone()).collect();
+        assert_eq!(ids, vec!["pending".to_string()]);
+    }
+}
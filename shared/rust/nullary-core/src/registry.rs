@@ -0,0 +1,170 @@
+// Service user registry
+//
+// Remembers the user UUID each identity has on each base and service, so
+// commands create their service user once instead of on every call. Entries
+// are keyed by (public key, base, service); the public key is the one derived
+// for that base and service, so switching persona or rotating keys naturally
+// misses the cache and registers again.
+//
+// The registry lives in `uuid-registry.json` in the app data dir. A call that
+// gets a not-found back for a registered UUID should hand the error to
+// `forget_if_not_found`, and the next call will register again.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::{LazyLock, Mutex};
+use tracing::{info, warn};
+
+use crate::clients::public_key_at;
+use crate::documents::write_atomic;
+use crate::error::NullaryError;
+use crate::host::app_data_dir;
+use crate::services::base_for_url;
+
+const REGISTRY_FILE: &str = "uuid-registry.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistryEntry {
+    pub identity: String,
+    pub base: String,
+    pub service: String,
+    pub uuid: String,
+    pub registered_at: i64,
+}
+
+#[derive(Default)]
+struct Registry {
+    entries: BTreeMap<String, RegistryEntry>,
+    path: Option<PathBuf>,
+}
+
+impl Registry {
+    fn save(&self) -> Result<(), NullaryError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        let json = serde_json::to_string_pretty(&self.entries)
+            .map_err(|e| NullaryError::internal(format!("Failed to serialize UUID registry: {}", e)))?;
+        write_atomic(path, json.as_bytes())
+    }
+}
+
+// In-memory only until `load_uuid_registry` runs at startup
+static REGISTRY: LazyLock<Mutex<Registry>> = LazyLock::new(|| Mutex::new(Registry::default()));
+
+fn registry() -> std::sync::MutexGuard<'static, Registry> {
+    REGISTRY.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Load `uuid-registry.json` from the app data dir
+pub fn load_uuid_registry() -> Result<(), NullaryError> {
+    let path = app_data_dir()?.join(REGISTRY_FILE);
+
+    let entries = match fs::read_to_string(&path) {
+        Ok(content) => serde_json::from_str(&content)
+            .map_err(|e| NullaryError::storage(format!("Failed to parse UUID registry {}: {}", path.display(), e)))?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+        Err(e) => return Err(NullaryError::storage(format!("Failed to read UUID registry: {}", e))),
+    };
+
+    let mut registry = registry();
+    registry.entries = entries;
+    registry.path = Some(path);
+
+    info!("📇 Loaded {} registered service users", registry.entries.len());
+    Ok(())
+}
+
+/// Identity, base and registry key for a service user. `hash` separates
+/// users a service keeps per app (BDO); the identity is always the key the
+/// service itself signs with.
+fn registry_key(url: &str, service: &str, hash: Option<&str>) -> Result<(String, String, String), NullaryError> {
+    let identity = public_key_at(url, service)?;
+    let base = base_for_url(url)?;
+    let service = match hash {
+        Some(hash) => format!("{}/{}", service, hash),
+        None => service.to_string(),
+    };
+    let key = format!("{}|{}|{}", identity, base, service);
+
+    Ok((key, identity, base))
+}
+
+/// Registered UUID for this identity's user on `service` at `url`, if any
+pub fn registered_uuid(url: &str, service: &str, hash: Option<&str>) -> Result<Option<String>, NullaryError> {
+    let (key, _, _) = registry_key(url, service, hash)?;
+    Ok(registry().entries.get(&key).map(|entry| entry.uuid.clone()))
+}
+
+/// Remember this identity's user UUID on `service` at `url`
+pub fn register_uuid(url: &str, service: &str, hash: Option<&str>, uuid: &str) -> Result<(), NullaryError> {
+    let (key, identity, base) = registry_key(url, service, hash)?;
+
+    let mut registry = registry();
+    registry.entries.insert(
+        key,
+        RegistryEntry {
+            identity,
+            base,
+            service: service.to_string(),
+            uuid: uuid.to_string(),
+            registered_at: chrono::Utc::now().timestamp_millis(),
+        },
+    );
+    registry.save()
+}
+
+/// Drop the registered UUID so the next call registers again
+pub fn forget_uuid(url: &str, service: &str, hash: Option<&str>) -> Result<(), NullaryError> {
+    let (key, _, _) = registry_key(url, service, hash)?;
+
+    let mut registry = registry();
+    if registry.entries.remove(&key).is_some() {
+        info!("📇 Forgot {} user on {}", service, url);
+        registry.save()?;
+    }
+    Ok(())
+}
+
+/// Forget the registered UUID when `error` is a not-found. Returns whether it did.
+pub fn forget_if_not_found(url: &str, service: &str, hash: Option<&str>, error: &NullaryError) -> bool {
+    if !error.is_not_found() {
+        return false;
+    }
+
+    match forget_uuid(url, service, hash) {
+        Ok(()) => true,
+        Err(e) => {
+            warn!("⚠️ Failed to forget {} user: {}", service, e);
+            false
+        }
+    }
+}
+
+/// Registered UUID for `service` at `url`, creating the user with `create`
+/// the first time
+pub async fn service_user_uuid<F, Fut>(
+    url: &str,
+    service: &str,
+    hash: Option<&str>,
+    create: F,
+) -> Result<String, NullaryError>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<String, NullaryError>>,
+{
+    if let Some(uuid) = registered_uuid(url, service, hash)? {
+        return Ok(uuid);
+    }
+
+    let uuid = create().await?;
+    register_uuid(url, service, hash, &uuid)?;
+
+    info!("📇 Registered {} user {} on {}", service, uuid, url);
+    Ok(uuid)
+}
@@ -0,0 +1,53 @@
+// Response envelope for commands that report success in-band
+
+use crate::error::NullaryError;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ServiceResponse<T> {
+    pub success: bool,
+    pub data: Option<T>,
+    pub error: Option<String>,
+    /// The typed error behind `error`, when there is one
+    #[serde(default, skip_deserializing, skip_serializing_if = "Option::is_none")]
+    pub details: Option<NullaryError>,
+}
+
+impl<T> ServiceResponse<T> {
+    pub fn ok(data: T) -> Self {
+        ServiceResponse {
+            success: true,
+            data: Some(data),
+            error: None,
+            details: None,
+        }
+    }
+
+    pub fn err(error: impl Into<NullaryError>) -> Self {
+        let error = error.into();
+        ServiceResponse {
+            success: false,
+            data: None,
+            error: Some(error.to_string()),
+            details: Some(error),
+        }
+    }
+}
+
+impl<T> From<Result<T, String>> for ServiceResponse<T> {
+    fn from(result: Result<T, String>) -> Self {
+        match result {
+            Ok(data) => ServiceResponse::ok(data),
+            Err(e) => ServiceResponse::err(e),
+        }
+    }
+}
+
+impl<T> From<Result<T, NullaryError>> for ServiceResponse<T> {
+    fn from(result: Result<T, NullaryError>) -> Self {
+        match result {
+            Ok(data) => ServiceResponse::ok(data),
+            Err(e) => ServiceResponse::err(e),
+        }
+    }
+}
@@ -0,0 +1,593 @@
+// Key rotation
+//
+// `rotate_identity` gives the active persona a new master seed and re-creates
+// its users with the new keys: every persona service on the home base, and
+// every service user registered on a joined base. For each service that moves, a statement naming the old and new key
+// and UUID is signed with the old key; the statements are published as a BDO
+// owned by the old BDO key so anyone holding the old identity can follow it.
+//
+// The old seed stays in the vault and every rotation is logged in the
+// persona's `key-rotations.json`, so services that failed can be retried
+// later with `retry_identity_rotation`.
+
+use addie_rs::Addie;
+use bdo_rs::BDO;
+use dolores_rs::Dolores;
+use fount_rs::Fount;
+use julia_rs::{Julia, JuliaUser};
+use prof_rs::{ProfClient, ProfError};
+use sanora_rs::Sanora;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sessionless::hex::IntoHex;
+use sessionless::Sessionless;
+use std::collections::HashMap;
+use tracing::{error, info, warn};
+
+use crate::bases::{home_base, joined_bases};
+use crate::documents::write_atomic;
+use crate::error::NullaryError;
+use crate::registry::{register_uuid, registered_uuid};
+use crate::services::{base_for_url, current_env, get_service_url};
+use crate::signing::{hand_over_key, sign_with, SignatureRequest, SigningPurpose};
+use crate::user_persistence::{
+    active_persona, cache_service_uuid, derive_child_key, get_persona_data_dir, new_master_seed,
+    persona_seed, persona_service_uuids, read_seed, replace_persona_seed, MasterSeed, ServiceUuids,
+    PERSONA_SERVICES,
+};
+
+pub(crate) const ROTATIONS_FILE: &str = "key-rotations.json";
+
+/// BDO hash the signed rotation statements are published under
+pub const ROTATION_BDO_HASH: &str = "nullary-key-rotation";
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RotationStatus {
+    /// User re-created under the new key
+    Migrated,
+    /// Nothing to move (e.g. no Prof profile)
+    Skipped,
+    /// Service call failed; retry with `retry_identity_rotation`
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceRotation {
+    pub service: String,
+    pub base: String,
+    /// Service URL on that base; empty in logs from before joined bases
+    /// were rotated, which only covered the home base
+    #[serde(default)]
+    pub url: String,
+    pub status: RotationStatus,
+    pub old_pub_key: String,
+    pub new_pub_key: String,
+    #[serde(rename = "oldUUID")]
+    pub old_uuid: Option<String>,
+    #[serde(rename = "newUUID")]
+    pub new_uuid: Option<String>,
+    pub error: Option<String>,
+}
+
+/// The statement signed by the old key for one service
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RotationStatement<'a> {
+    r#type: &'a str,
+    service: &'a str,
+    base: &'a str,
+    old_pub_key: &'a str,
+    new_pub_key: &'a str,
+    #[serde(rename = "oldUUID")]
+    old_uuid: Option<&'a str>,
+    #[serde(rename = "newUUID")]
+    new_uuid: Option<&'a str>,
+    timestamp: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RotationReport {
+    pub persona: String,
+    pub environment: String,
+    pub rotated_at: i64,
+    /// False when every service failed; the old identity is still in use
+    pub committed: bool,
+    /// Vault record of the old seed, kept so retries can sign with it
+    pub retired_seed_record: Option<String>,
+    /// Whether the signed statements reached BDO
+    pub published: bool,
+    pub publish_error: Option<String>,
+    /// Why the new UUIDs couldn't be cached; the services still moved
+    #[serde(default)]
+    pub cache_error: Option<String>,
+    pub services: Vec<ServiceRotation>,
+    /// `service@base` for each user that moved or had nothing to move
+    pub succeeded: Vec<String>,
+    /// `service@base` for each user still on the old key
+    pub needs_retry: Vec<String>,
+}
+
+impl RotationReport {
+    fn summarize(&mut self) {
+        self.succeeded = self
+            .services
+            .iter()
+            .filter(|s| s.status != RotationStatus::Failed)
+            .map(|s| format!("{}@{}", s.service, s.base))
+            .collect();
+        self.needs_retry = self
+            .services
+            .iter()
+            .filter(|s| s.status == RotationStatus::Failed)
+            .map(|s| format!("{}@{}", s.service, s.base))
+            .collect();
+    }
+}
+
+impl ServiceRotation {
+    fn url(&self) -> String {
+        if self.url.is_empty() {
+            get_service_url(&self.service)
+        } else {
+            self.url.clone()
+        }
+    }
+}
+
+/// A service user to move to the new key
+struct RotationTarget {
+    service: &'static str,
+    url: String,
+    old_uuid: Option<String>,
+}
+
+/// Every service user the persona has: each persona service on the home
+/// base, whether or not a user is known there, and each service on a joined
+/// base where the old key has a registered user
+fn rotation_targets(home_uuids: &ServiceUuids) -> Result<Vec<RotationTarget>, NullaryError> {
+    let mut targets = Vec::new();
+
+    let home = home_base();
+    for service in PERSONA_SERVICES {
+        let Some(url) = home.dns.url(service) else {
+            continue;
+        };
+        let old_uuid = match home_uuids.get(service) {
+            Some(uuid) => Some(uuid.clone()),
+            None => registered_uuid(url, service, registry_hash(service))?,
+        };
+        targets.push(RotationTarget {
+            service,
+            url: url.to_string(),
+            old_uuid,
+        });
+    }
+
+    // A damaged bases.json shouldn't stop the home base from rotating
+    let joined = joined_bases().unwrap_or_else(|e| {
+        warn!("⚠️ {} - rotating the home base only", e);
+        Vec::new()
+    });
+    for base in joined {
+        for service in PERSONA_SERVICES {
+            let Some(url) = base.dns.url(service) else {
+                continue;
+            };
+            if let Some(old_uuid) = registered_uuid(url, service, registry_hash(service))? {
+                targets.push(RotationTarget {
+                    service,
+                    url: url.to_string(),
+                    old_uuid: Some(old_uuid),
+                });
+            }
+        }
+    }
+
+    Ok(targets)
+}
+
+/// Registry hash of a service's users: BDO keeps one per app
+fn registry_hash(service: &str) -> Option<&'static str> {
+    (service == "bdo").then(crate::app_name)
+}
+
+/// Keypair a seed derives for a service on `base` (`host:port`)
+fn service_sessionless(seed: &MasterSeed, base: &str, service: &str) -> Sessionless {
+    Sessionless::from_private_key(derive_child_key(seed.as_slice(), base, service))
+}
+
+/// Create the user for `service` under `new`. Prof has no bare user, so the
+/// profile read with `old` is re-created instead. Both keys reach the client
+/// crates through the signing policy and journal as `keyRotation`.
+async fn create_service_user(service: &str, url: &str, old: Sessionless, new: Sessionless) -> Result<Option<String>, NullaryError> {
+    let new = hand_over_key(new, url, service, SigningPurpose::KeyRotation).await?;
+    let url = url.to_string();
+
+    match service {
+        "bdo" => BDO::new(Some(url), Some(new))
+            .create_user(crate::app_name(), &json!({}))
+            .await
+            .map(|user| Some(user.uuid))
+            .map_err(|e| NullaryError::service("bdo", format!("Failed to create BDO user: {}", e))),
+        "addie" => Addie::new(Some(url), Some(new))
+            .create_user()
+            .await
+            .map(|user| Some(user.uuid))
+            .map_err(|e| NullaryError::service("addie", format!("Failed to create Addie user: {}", e))),
+        "dolores" => Dolores::new(Some(url), Some(new))
+            .create_user()
+            .await
+            .map(|user| Some(user.uuid))
+            .map_err(|e| NullaryError::service("dolores", format!("Failed to create Dolores user: {}", e))),
+        "sanora" => Sanora::new(Some(url), Some(new))
+            .create_user()
+            .await
+            .map(|user| Some(user.uuid))
+            .map_err(|e| NullaryError::service("sanora", format!("Failed to create Sanora user: {}", e))),
+        "fount" => Fount::new(Some(url), Some(new))
+            .create_user()
+            .await
+            .map(|user| Some(user.uuid))
+            .map_err(|e| NullaryError::service("fount", format!("Failed to create Fount user: {}", e))),
+        "julia" => {
+            let pub_key = new.public_key().to_hex();
+            let mut julia = Julia::new(Some(url));
+            julia.sessionless = new;
+            julia
+                .create_user(JuliaUser::new(pub_key, crate::app_name().to_string()))
+                .await
+                .map(|user| Some(user.uuid))
+                .map_err(|e| NullaryError::service("julia", format!("Failed to create Julia user: {}", e)))
+        }
+        "prof" => {
+            let old = hand_over_key(old, &url, service, SigningPurpose::KeyRotation).await?;
+            let profile = match ProfClient::new(url.clone()).with_sessionless(old).get_profile(None).await {
+                Ok(profile) => profile,
+                Err(ProfError::NotFound(_)) => return Ok(None),
+                Err(e) => return Err(NullaryError::service("prof", format!("Failed to read Prof profile: {}", e))),
+            };
+
+            let mut profile_data: HashMap<String, Value> = profile.additional_fields.clone();
+            profile_data.insert("name".to_string(), Value::String(profile.name));
+            profile_data.insert("email".to_string(), Value::String(profile.email));
+
+            ProfClient::new(url)
+                .with_sessionless(new)
+                .create_profile(profile_data, None)
+                .await
+                .map(|profile| Some(profile.uuid))
+                .map_err(|e| NullaryError::service("prof", format!("Failed to create Prof profile: {}", e)))
+        }
+        other => Err(NullaryError::validation(format!("Unknown persona service: {}", other))),
+    }
+}
+
+/// Move one service user at `url` from the old seed's key to the new seed's key
+async fn migrate_service(
+    service: &str,
+    url: &str,
+    old_seed: &MasterSeed,
+    new_seed: &MasterSeed,
+    old_uuid: Option<String>,
+) -> ServiceRotation {
+    let mut rotation = ServiceRotation {
+        service: service.to_string(),
+        base: base_for_url(url).unwrap_or_else(|_| url.to_string()),
+        url: url.to_string(),
+        status: RotationStatus::Failed,
+        old_pub_key: String::new(),
+        new_pub_key: String::new(),
+        old_uuid,
+        new_uuid: None,
+        error: None,
+    };
+
+    let old = service_sessionless(old_seed, &rotation.base, service);
+    let new = service_sessionless(new_seed, &rotation.base, service);
+    rotation.old_pub_key = old.public_key().to_hex();
+    rotation.new_pub_key = new.public_key().to_hex();
+
+    info!("🔄 Rotating {} on {}", service, rotation.base);
+    match create_service_user(service, url, old, new).await {
+        Ok(Some(new_uuid)) => {
+            info!("✅ {} user re-created: {}", service, new_uuid);
+            rotation.status = RotationStatus::Migrated;
+            rotation.new_uuid = Some(new_uuid);
+        }
+        Ok(None) => {
+            info!("📭 Nothing to rotate on {}", service);
+            rotation.status = RotationStatus::Skipped;
+        }
+        Err(e) => {
+            error!("❌ {} rotation failed: {}", service, e);
+            rotation.error = Some(e.to_string());
+        }
+    }
+
+    rotation
+}
+
+/// Sign a statement for every migrated service with its old key and publish
+/// them as a BDO owned by the old BDO key
+async fn publish_statements(batch: &StatementBatch<'_>) -> Result<(), NullaryError> {
+    let mut statements = Vec::new();
+
+    for rotation in batch.services.iter().filter(|r| r.status == RotationStatus::Migrated) {
+        let old = service_sessionless(batch.old_seed, &rotation.base, &rotation.service);
+
+        let statement = RotationStatement {
+            r#type: "nullary-key-rotation",
+            service: &rotation.service,
+            base: &rotation.base,
+            old_pub_key: &rotation.old_pub_key,
+            new_pub_key: &rotation.new_pub_key,
+            old_uuid: rotation.old_uuid.as_deref(),
+            new_uuid: rotation.new_uuid.as_deref(),
+            timestamp: batch.rotated_at,
+        };
+        let message = serde_json::to_string(&statement)
+            .map_err(|e| NullaryError::internal(format!("Failed to serialize rotation statement: {}", e)))?;
+        let signature = sign_with(
+            &old,
+            SignatureRequest {
+                purpose: SigningPurpose::KeyRotation,
+                service: &rotation.service,
+                base: &rotation.base,
+                message: &message,
+            },
+        )
+        .await?;
+
+        statements.push(json!({ "statement": message, "signature": signature }));
+    }
+
+    if statements.is_empty() {
+        return Ok(());
+    }
+
+    let bdo_url = get_service_url("bdo");
+    let old_bdo = hand_over_key(
+        service_sessionless(batch.old_seed, &base_for_url(&bdo_url)?, "bdo"),
+        &bdo_url,
+        "bdo",
+        SigningPurpose::KeyRotation,
+    )
+    .await?;
+    BDO::new(Some(bdo_url), Some(old_bdo))
+        .create_user(ROTATION_BDO_HASH, &json!({ "rotations": statements }))
+        .await
+        .map_err(|e| NullaryError::service("bdo", format!("Failed to publish rotation statements: {}", e)))?;
+
+    info!("📣 Published {} rotation statement(s)", statements.len());
+    Ok(())
+}
+
+/// Services whose statements are signed by one old seed
+struct StatementBatch<'a> {
+    old_seed: &'a MasterSeed,
+    rotated_at: i64,
+    services: &'a [ServiceRotation],
+}
+
+// ===== ROTATION LOG =====
+
+fn read_rotations(persona: &str) -> Result<Vec<RotationReport>, NullaryError> {
+    let path = get_persona_data_dir(persona)?.join(ROTATIONS_FILE);
+
+    match std::fs::read_to_string(&path) {
+        Ok(content) => serde_json::from_str(&content)
+            .map_err(|e| NullaryError::storage(format!("Rotation log is corrupted: {}", e))),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(NullaryError::storage(format!("Failed to read rotation log: {}", e))),
+    }
+}
+
+fn write_rotations(persona: &str, rotations: &[RotationReport]) -> Result<(), NullaryError> {
+    let persona_dir = get_persona_data_dir(persona)?;
+    std::fs::create_dir_all(&persona_dir)
+        .map_err(|e| NullaryError::storage(format!("Failed to create user data directory: {}", e)))?;
+
+    let content = serde_json::to_string_pretty(rotations)
+        .map_err(|e| NullaryError::internal(format!("Failed to serialize rotation log: {}", e)))?;
+    write_atomic(&persona_dir.join(ROTATIONS_FILE), content.as_bytes())
+        .map_err(|e| NullaryError::storage(format!("Failed to write rotation log: {}", e)))
+}
+
+/// Replace the last entry of the rotation log with `report`
+fn update_last_rotation(persona: &str, rotations: &mut [RotationReport], report: &RotationReport) {
+    if let Some(last) = rotations.last_mut() {
+        *last = report.clone();
+    }
+    if let Err(e) = write_rotations(persona, rotations) {
+        warn!("⚠️ {} - the logged rotation lacks the cache and publish results", e);
+    }
+}
+
+/// Register each migrated user's new UUID, and remember the home base's
+/// for the persona
+fn cache_new_uuids(services: &[ServiceRotation]) -> Result<(), NullaryError> {
+    for rotation in services.iter().filter(|r| r.status == RotationStatus::Migrated) {
+        if let Some(new_uuid) = &rotation.new_uuid {
+            let url = rotation.url();
+            if url == get_service_url(&rotation.service) {
+                cache_service_uuid(&rotation.service, new_uuid)?;
+            }
+
+            // The new key is live now, so this registers under the new identity
+            register_uuid(&url, &rotation.service, registry_hash(&rotation.service), new_uuid)?;
+        }
+    }
+    Ok(())
+}
+
+// ===== TAURI COMMANDS =====
+
+/// Replace the active persona's keys and re-register it on every persona
+/// service. The report lists which services moved and which need a retry.
+#[cfg_attr(feature = "tauri", tauri::command)]
+pub async fn rotate_identity() -> Result<RotationReport, NullaryError> {
+    if std::env::var("PRIVATE_KEY").is_ok() {
+        return Err(NullaryError::validation("Cannot rotate keys while the PRIVATE_KEY override is set"));
+    }
+
+    let persona = active_persona();
+    info!("🔄 Rotating identity for persona: {}", persona);
+
+    let old_seed = persona_seed(&persona)?;
+    let new_seed = new_master_seed();
+    let old_uuids = persona_service_uuids(&persona)?;
+
+    let mut services = Vec::new();
+    for target in rotation_targets(&old_uuids)? {
+        services.push(migrate_service(target.service, &target.url, &old_seed, &new_seed, target.old_uuid).await);
+    }
+
+    let mut report = RotationReport {
+        persona: persona.clone(),
+        environment: current_env(),
+        rotated_at: chrono::Utc::now().timestamp_millis(),
+        committed: false,
+        retired_seed_record: None,
+        published: false,
+        publish_error: None,
+        cache_error: None,
+        services,
+        succeeded: Vec::new(),
+        needs_retry: Vec::new(),
+    };
+    report.summarize();
+
+    // Keep the old identity if nothing could move to the new one
+    if report.services.iter().all(|s| s.status == RotationStatus::Failed) {
+        error!("❌ Every service failed - identity unchanged");
+        return Ok(report);
+    }
+
+    // Read before the swap so a damaged log stops the rotation while the old
+    // seed is still the live one
+    let mut rotations = read_rotations(&persona)?;
+
+    let retired_record = replace_persona_seed(&persona, &new_seed)?;
+    report.retired_seed_record = Some(retired_record.clone());
+    report.committed = true;
+
+    // Log the retired seed straight away: retries can't sign without it
+    rotations.push(report.clone());
+    write_rotations(&persona, &rotations).map_err(|e| {
+        NullaryError::storage(format!(
+            "Identity rotated but not logged; the old seed is vault record {}: {}",
+            retired_record, e
+        ))
+    })?;
+
+    if let Err(e) = cache_new_uuids(&report.services) {
+        warn!("⚠️ {}", e);
+        report.cache_error = Some(e.to_string());
+    }
+
+    let batch = StatementBatch {
+        old_seed: &old_seed,
+        rotated_at: report.rotated_at,
+        services: &report.services,
+    };
+    match publish_statements(&batch).await {
+        Ok(()) => report.published = true,
+        Err(e) => {
+            warn!("⚠️ {}", e);
+            report.publish_error = Some(e.to_string());
+        }
+    }
+
+    update_last_rotation(&persona, &mut rotations, &report);
+
+    info!(
+        "✅ Identity rotated: {} succeeded, {} need a retry",
+        report.succeeded.len(),
+        report.needs_retry.len()
+    );
+    Ok(report)
+}
+
+/// Retry the services that failed in the active persona's last rotation,
+/// and its UUID caching or publishing if those failed
+#[cfg_attr(feature = "tauri", tauri::command)]
+pub async fn retry_identity_rotation() -> Result<RotationReport, NullaryError> {
+    let persona = active_persona();
+    let mut rotations = read_rotations(&persona)?;
+
+    let report = rotations
+        .last_mut()
+        .filter(|report| {
+            !report.needs_retry.is_empty() || report.cache_error.is_some() || report.publish_error.is_some()
+        })
+        .ok_or_else(|| NullaryError::validation("No rotation needs a retry"))?;
+
+    let retired_record = report
+        .retired_seed_record
+        .clone()
+        .ok_or_else(|| NullaryError::storage("Rotation log is missing the retired seed"))?;
+    let old_seed = read_seed(&retired_record)?;
+    let new_seed = persona_seed(&persona)?;
+
+    info!("🔄 Retrying rotation for: {}", report.needs_retry.join(", "));
+
+    let mut retried = Vec::new();
+    for rotation in report.services.iter_mut().filter(|r| r.status == RotationStatus::Failed) {
+        let service = rotation.service.clone();
+        let url = rotation.url();
+        *rotation = migrate_service(&service, &url, &old_seed, &new_seed, rotation.old_uuid.clone()).await;
+        retried.push(rotation.clone());
+    }
+    report.summarize();
+
+    // Same for the UUID cache
+    let moved = if report.cache_error.is_some() {
+        report.services.clone()
+    } else {
+        retried.clone()
+    };
+    report.cache_error = cache_new_uuids(&moved).err().map(|e| {
+        warn!("⚠️ {}", e);
+        e.to_string()
+    });
+
+    // Republish everything if the last publish failed, otherwise just the
+    // services that moved now
+    let services = if report.publish_error.is_some() {
+        report.services.clone()
+    } else {
+        retried
+    };
+    let batch = StatementBatch {
+        old_seed: &old_seed,
+        rotated_at: report.rotated_at,
+        services: &services,
+    };
+    match publish_statements(&batch).await {
+        Ok(()) => {
+            report.published = true;
+            report.publish_error = None;
+        }
+        Err(e) => {
+            warn!("⚠️ {}", e);
+            report.published = false;
+            report.publish_error = Some(e.to_string());
+        }
+    }
+
+    let report = report.clone();
+    write_rotations(&persona, &rotations)?;
+
+    info!("✅ Retry finished: {} still need a retry", report.needs_retry.len());
+    Ok(report)
+}
+
+/// Every rotation of the active persona, oldest first, with the old-to-new
+/// UUID mapping for each service
+#[cfg_attr(feature = "tauri", tauri::command)]
+pub async fn get_key_rotations() -> Result<Vec<RotationReport>, NullaryError> {
+    read_rotations(&active_persona())
+}
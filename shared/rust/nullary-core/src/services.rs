@@ -0,0 +1,384 @@
+// Service endpoint resolution shared by every Nullary app
+//
+// Endpoints live in `endpoints.json` in the app data dir as named
+// environments, each mapping every allyabase service to a URL. `NULLARY_ENV`
+// picks the environment at startup and `switch_environment` changes it while
+// the app runs. On first launch the file is seeded from the built-in table
+// below, so edit the file rather than this table to point an install
+// somewhere else.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{LazyLock, Mutex};
+use tracing::{info, warn};
+use url::Url;
+
+use crate::documents::write_atomic;
+use crate::error::NullaryError;
+use crate::host::app_data_dir;
+
+/// Every allyabase service a Nullary app may talk to. Each environment in the
+/// config must map all of them.
+pub const SERVICES: [&str; 12] = [
+    "julia",
+    "continuebee",
+    "pref",
+    "bdo",
+    "joan",
+    "addie",
+    "fount",
+    "dolores",
+    "minnie",
+    "aretha",
+    "sanora",
+    "covenant",
+];
+
+/// Services some apps use that are not part of the allyabase core
+pub const EXTRA_SERVICES: [&str; 1] = ["prof"];
+
+const CONFIG_FILE: &str = "endpoints.json";
+const DEFAULT_ENV: &str = "dev";
+const BUILTIN_ENVS: [&str; 3] = ["dev", "test", "local"];
+
+// ===== CONFIG =====
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EndpointConfig {
+    /// Environment used when `NULLARY_ENV` is not set
+    #[serde(default = "default_env")]
+    pub environment: String,
+    /// Environment name -> service name -> URL
+    pub environments: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+fn default_env() -> String {
+    DEFAULT_ENV.to_string()
+}
+
+impl EndpointConfig {
+    /// The dev, test and local environments every install starts with
+    pub fn builtin() -> Self {
+        let environments = BUILTIN_ENVS
+            .iter()
+            .map(|env| {
+                let services = SERVICES
+                    .iter()
+                    .chain(EXTRA_SERVICES.iter())
+                    .map(|service| (service.to_string(), builtin_service_url(env, service)))
+                    .collect();
+                (env.to_string(), services)
+            })
+            .collect();
+
+        EndpointConfig {
+            environment: default_env(),
+            environments,
+        }
+    }
+
+    /// Check that every environment maps every service to a valid URL and that
+    /// no two services in an environment share a host:port
+    pub fn validate(&self) -> Result<(), NullaryError> {
+        if self.environments.is_empty() {
+            return Err(NullaryError::validation("Endpoint config has no environments"));
+        }
+
+        if !self.environments.contains_key(&self.environment) {
+            return Err(NullaryError::validation(format!(
+                "Default environment '{}' is not defined",
+                self.environment
+            )));
+        }
+
+        for (env, services) in &self.environments {
+            for service in SERVICES {
+                if !services.contains_key(service) {
+                    return Err(NullaryError::validation(format!(
+                        "Environment '{}' is missing service '{}'",
+                        env, service
+                    )));
+                }
+            }
+
+            let mut seen: HashMap<String, &str> = HashMap::new();
+            for (service, url) in services {
+                let host_port = host_port(url)
+                    .map_err(|e| NullaryError::validation(format!("Environment '{}', service '{}': {}", env, service, e)))?;
+
+                if let Some(other) = seen.insert(host_port.clone(), service) {
+                    return Err(NullaryError::validation(format!(
+                        "Environment '{}': '{}' and '{}' both use {}",
+                        env, other, service, host_port
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// `host:port` of a service URL, with the scheme's default port filled in
+fn host_port(url: &str) -> Result<String, NullaryError> {
+    let parsed = Url::parse(url).map_err(|e| NullaryError::validation(format!("Invalid URL '{}': {}", url, e)))?;
+    let host = parsed
+        .host_str()
+        .ok_or_else(|| NullaryError::validation(format!("URL '{}' has no host", url)))?;
+    let port = parsed
+        .port_or_known_default()
+        .ok_or_else(|| NullaryError::validation(format!("URL '{}' has no port", url)))?;
+
+    Ok(format!("{}:{}", host.to_lowercase(), port))
+}
+
+/// Identifier of the base a service URL belongs to, used as the context for
+/// per-base identities. Two URLs on the same `host:port` are the same base.
+pub fn base_for_url(url: &str) -> Result<String, NullaryError> {
+    host_port(url)
+}
+
+/// Built-in URL for a service, used to seed the config and for services an
+/// edited config leaves out
+fn builtin_service_url(env: &str, service: &str) -> String {
+    match (env, service) {
+        // Test environment (127.0.0.1:5111-5123)
+        ("test", "julia") => "http://127.0.0.1:5111/".to_string(),
+        ("test", "continuebee") => "http://127.0.0.1:5112/".to_string(),
+        ("test", "pref") => "http://127.0.0.1:5113/".to_string(),
+        ("test", "bdo") => "http://127.0.0.1:5114/".to_string(),
+        ("test", "joan") => "http://127.0.0.1:5115/".to_string(),
+        ("test", "addie") => "http://127.0.0.1:5116/".to_string(),
+        ("test", "fount") => "http://127.0.0.1:5117/".to_string(),
+        ("test", "dolores") => "http://127.0.0.1:5118/".to_string(),
+        ("test", "minnie") => "http://127.0.0.1:5119/".to_string(),
+        ("test", "aretha") => "http://127.0.0.1:5120/".to_string(),
+        ("test", "sanora") => "http://127.0.0.1:5121/".to_string(),
+        ("test", "covenant") => "http://127.0.0.1:5122/".to_string(),
+        ("test", "prof") => "http://127.0.0.1:5123/".to_string(),
+
+        // Local environment (127.0.0.1:2525-7277)
+        ("local", "julia") => "http://127.0.0.1:3000/".to_string(),
+        ("local", "continuebee") => "http://127.0.0.1:2999/".to_string(),
+        ("local", "pref") => "http://127.0.0.1:3002/".to_string(),
+        ("local", "bdo") => "http://127.0.0.1:3003/".to_string(),
+        ("local", "joan") => "http://127.0.0.1:3004/".to_string(),
+        ("local", "addie") => "http://127.0.0.1:3005/".to_string(),
+        ("local", "fount") => "http://127.0.0.1:3006/".to_string(),
+        ("local", "dolores") => "http://127.0.0.1:3007/".to_string(),
+        ("local", "prof") => "http://127.0.0.1:3008/".to_string(),
+        ("local", "covenant") => "http://127.0.0.1:3011/".to_string(),
+        ("local", "aretha") => "http://127.0.0.1:7277/".to_string(),
+        ("local", "minnie") => "http://127.0.0.1:2525/".to_string(),
+        ("local", "sanora") => "http://127.0.0.1:7243/".to_string(),
+
+        // Dev environment (default)
+        (_, service) => format!("https://dev.{}.allyabase.com/", service),
+    }
+}
+
+// ===== ACTIVE ENVIRONMENT =====
+
+struct Endpoints {
+    config: EndpointConfig,
+    active: String,
+    path: Option<PathBuf>,
+}
+
+impl Endpoints {
+    fn url(&self, env: &str, service: &str) -> String {
+        if let Some(url) = self
+            .config
+            .environments
+            .get(env)
+            .and_then(|services| services.get(service))
+        {
+            return with_trailing_slash(url);
+        }
+
+        // The built-in table only knows its own environments; anything else
+        // lands on dev, which is never what a custom environment meant
+        let url = builtin_service_url(env, service);
+        if !BUILTIN_ENVS.contains(&env) && warn_fallback_once(env, service) {
+            warn!(
+                "⚠️ Environment '{}' has no URL for '{}', falling back to {} - add it to {}",
+                env, service, url, CONFIG_FILE
+            );
+        }
+        url
+    }
+
+    fn save(&self) -> Result<(), NullaryError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        let json = serde_json::to_vec_pretty(&self.config)
+            .map_err(|e| NullaryError::internal(format!("Failed to serialize endpoint config: {}", e)))?;
+        write_atomic(path, &json)
+    }
+}
+
+fn with_trailing_slash(url: &str) -> String {
+    if url.ends_with('/') {
+        url.to_string()
+    } else {
+        format!("{}/", url)
+    }
+}
+
+// Built-in table until `load_endpoint_config` runs at startup
+static ENDPOINTS: LazyLock<Mutex<Endpoints>> = LazyLock::new(|| {
+    Mutex::new(Endpoints {
+        config: EndpointConfig::builtin(),
+        active: env::var("NULLARY_ENV").unwrap_or_else(|_| default_env()),
+        path: None,
+    })
+});
+
+fn endpoints() -> std::sync::MutexGuard<'static, Endpoints> {
+    ENDPOINTS.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+// (environment, service) pairs already warned about, so a missing URL is
+// reported once rather than on every request
+static FALLBACK_WARNED: LazyLock<Mutex<HashSet<(String, String)>>> =
+    LazyLock::new(|| Mutex::new(HashSet::new()));
+
+fn warn_fallback_once(env: &str, service: &str) -> bool {
+    FALLBACK_WARNED
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .insert((env.to_string(), service.to_string()))
+}
+
+/// Load `endpoints.json` from the app data dir, seeding it from the built-in
+/// table on first launch, and select `NULLARY_ENV` (or the file's default)
+pub fn load_endpoint_config() -> Result<(), NullaryError> {
+    let app_data_dir = app_data_dir()?;
+    fs::create_dir_all(&app_data_dir)
+        .map_err(|e| NullaryError::storage(format!("Failed to create app data dir: {}", e)))?;
+    let path = app_data_dir.join(CONFIG_FILE);
+    let seed = !path.exists();
+
+    let config = if !seed {
+        let contents = fs::read_to_string(&path)
+            .map_err(|e| NullaryError::storage(format!("Failed to read endpoint config: {}", e)))?;
+        let config: EndpointConfig = serde_json::from_str(&contents).map_err(|e| {
+            NullaryError::validation(format!("Failed to parse endpoint config {}: {}", path.display(), e))
+        })?;
+        config
+            .validate()
+            .map_err(|e| NullaryError::validation(format!("Invalid endpoint config {}: {}", path.display(), e)))?;
+        info!("📍 Loaded endpoint config: {}", path.display());
+        config
+    } else {
+        info!("📍 Writing default endpoint config: {}", path.display());
+        EndpointConfig::builtin()
+    };
+
+    let active = match env::var("NULLARY_ENV") {
+        Ok(env) if config.environments.contains_key(&env) => env,
+        Ok(env) => {
+            warn!(
+                "⚠️ NULLARY_ENV '{}' is not defined in {}, using '{}'",
+                env,
+                path.display(),
+                config.environment
+            );
+            config.environment.clone()
+        }
+        Err(_) => config.environment.clone(),
+    };
+
+    let mut endpoints = endpoints();
+    *endpoints = Endpoints {
+        config,
+        active,
+        path: Some(path),
+    };
+
+    if seed {
+        endpoints.save()?;
+    }
+
+    Ok(())
+}
+
+/// Name of the active environment
+pub fn current_env() -> String {
+    endpoints().active.clone()
+}
+
+/// Resolve a service URL for the active environment. URLs always end in `/`.
+pub fn get_service_url(service: &str) -> String {
+    let endpoints = endpoints();
+    endpoints.url(&endpoints.active, service)
+}
+
+/// Resolve a service URL for a named environment. URLs always end in `/`.
+pub fn service_url(env: &str, service: &str) -> String {
+    endpoints().url(env, service)
+}
+
+/// Make `env` the active environment and remember it as the default for
+/// later launches
+pub fn set_environment(env: &str) -> Result<(), NullaryError> {
+    let mut endpoints = endpoints();
+
+    if !endpoints.config.environments.contains_key(env) {
+        let known: Vec<&str> = endpoints.config.environments.keys().map(|k| k.as_str()).collect();
+        return Err(NullaryError::validation(format!(
+            "Unknown environment '{}' (expected one of: {})",
+            env,
+            known.join(", ")
+        )));
+    }
+
+    endpoints.active = env.to_string();
+    endpoints.config.environment = env.to_string();
+    endpoints.save()?;
+
+    info!("🌍 Switched to {} environment", env);
+    Ok(())
+}
+
+// ===== TAURI COMMANDS =====
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EnvironmentInfo {
+    pub environment: String,
+    pub environments: Vec<String>,
+    pub services: BTreeMap<String, String>,
+}
+
+fn environment_info() -> EnvironmentInfo {
+    let endpoints = endpoints();
+    let services = SERVICES
+        .iter()
+        .chain(EXTRA_SERVICES.iter())
+        .map(|service| (service.to_string(), endpoints.url(&endpoints.active, service)))
+        .collect();
+
+    EnvironmentInfo {
+        environment: endpoints.active.clone(),
+        environments: endpoints.config.environments.keys().cloned().collect(),
+        services,
+    }
+}
+
+/// Active environment, the environments available and the active service URLs
+#[cfg_attr(feature = "tauri", tauri::command)]
+pub fn get_environment_config() -> EnvironmentInfo {
+    environment_info()
+}
+
+/// Switch environments at runtime; every later service call uses the new URLs
+#[cfg_attr(feature = "tauri", tauri::command)]
+pub fn switch_environment(environment: String) -> Result<EnvironmentInfo, NullaryError> {
+    set_environment(&environment)?;
+    Ok(environment_info())
+}
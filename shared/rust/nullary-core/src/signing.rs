@@ -0,0 +1,672 @@
+// Request signing
+//
+// Planet Nine services authenticate a request by checking a signature over
+// `timestamp + uuid + resource...`. The backend always builds that message
+// itself: callers name the operation's parts, the timestamp is taken here,
+// and the key is the one derived for the base and service being called.
+// Documents that carry their own timestamp (connection invites) are signed
+// with `sign_document_at` and a purpose; no command signs what the webview
+// hands it.
+//
+// Every signature made here goes through `sign_with`, which classifies it by
+// purpose and applies the policy in `signing.json` (app data dir): allow it,
+// ask the user in a native dialog (or through the confirmation handler a
+// headless host sets), or deny it. Each decision is appended to
+// `signing-journal.jsonl` with the message hash, and `get_signing_journal`
+// reads it back. The policy can only be changed by editing the file, and a
+// confirmation can only be answered by the user, never from the webview.
+//
+// The service client crates sign their auth messages with the key they are
+// constructed with, so `client_sessionless` puts that hand-over through the
+// same policy and journal as an `authHeader` entry, and `hand_over_key` does
+// the same for the keys a rotation derives itself. That is the limit of what
+// can be seen from here: the crates expose no hook around their own signing,
+// so the requests a client makes after the hand-over are neither checked nor
+// journaled one by one. Its journal entry stands for all of them. An
+// operation that needs its own decision or journal entry is signed with
+// `sign_operation_at` and sent with `http::send` instead of a client crate.
+
+use reqwest::RequestBuilder;
+use serde::{Deserialize, Serialize};
+use sessionless::hex::{FromHex, IntoHex};
+use sessionless::{Sessionless, Signature};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{LazyLock, Mutex, OnceLock, RwLock};
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+use crate::documents::write_atomic;
+use crate::error::NullaryError;
+use crate::host::{app_data_dir, ask, has_frontend};
+use crate::services::{base_for_url, get_service_url};
+use crate::user_persistence::{active_persona, get_sessionless};
+
+/// Header carrying the signed timestamp
+pub const TIMESTAMP_HEADER: &str = "x-pn-timestamp";
+/// Header carrying the signature over the canonical message
+pub const SIGNATURE_HEADER: &str = "x-pn-signature";
+
+const POLICY_FILE: &str = "signing.json";
+const JOURNAL_FILE: &str = "signing-journal.jsonl";
+
+// ===== POLICY =====
+
+/// What a signature is for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SigningPurpose {
+    /// Authenticating a request to a service
+    AuthHeader,
+    /// Signing a step of a covenant contract
+    CovenantStep,
+    /// Answering a Julia association prompt
+    JuliaPrompt,
+    /// A connection invite handed to someone else
+    ConnectionInvite,
+    /// A statement moving a service user to a rotated key
+    KeyRotation,
+    /// A write queued in the outbox to be sent when its base is back
+    OutboxIntent,
+    /// A persona's seed leaving the app as a backup or recovery words. Signs
+    /// nothing, but hands over every key, so it is always confirmed: the
+    /// policy can only deny it.
+    IdentityExport,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SigningAction {
+    Allow,
+    /// Sign only if the user approves within `confirmTimeoutMs`
+    Confirm,
+    Deny,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SigningPolicy {
+    /// Action for purposes without an entry
+    pub default: SigningAction,
+    pub purposes: BTreeMap<SigningPurpose, SigningAction>,
+    /// How long to wait for the user before treating it as declined
+    pub confirm_timeout_ms: u64,
+}
+
+impl Default for SigningPolicy {
+    fn default() -> Self {
+        Self {
+            default: SigningAction::Confirm,
+            purposes: BTreeMap::from([
+                (SigningPurpose::AuthHeader, SigningAction::Allow),
+                (SigningPurpose::CovenantStep, SigningAction::Confirm),
+                (SigningPurpose::JuliaPrompt, SigningAction::Allow),
+                (SigningPurpose::ConnectionInvite, SigningAction::Confirm),
+                (SigningPurpose::OutboxIntent, SigningAction::Allow),
+                (SigningPurpose::KeyRotation, SigningAction::Allow),
+                (SigningPurpose::IdentityExport, SigningAction::Confirm),
+            ]),
+            confirm_timeout_ms: 120_000,
+        }
+    }
+}
+
+impl SigningPolicy {
+    pub fn action(&self, purpose: SigningPurpose) -> SigningAction {
+        self.purposes.get(&purpose).copied().unwrap_or(self.default)
+    }
+}
+
+#[derive(Default)]
+struct Signer {
+    policy: SigningPolicy,
+    journal: Option<PathBuf>,
+}
+
+// Built-in policy and no journal file until `load_signing_policy` runs
+static SIGNER: LazyLock<RwLock<Signer>> = LazyLock::new(|| RwLock::new(Signer::default()));
+
+/// Load `signing.json` from the app data dir, seeding it with the defaults
+/// on first launch, and open the journal next to it. A policy file that
+/// can't be parsed leaves the built-in policy in place.
+pub fn load_signing_policy() -> Result<(), NullaryError> {
+    let app_data_dir = app_data_dir()?;
+    SIGNER
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .journal = Some(app_data_dir.join(JOURNAL_FILE));
+
+    let path = app_data_dir.join(POLICY_FILE);
+    let policy = if path.exists() {
+        let contents = fs::read_to_string(&path)
+            .map_err(|e| NullaryError::storage(format!("Failed to read signing policy: {}", e)))?;
+        let policy: SigningPolicy = serde_json::from_str(&contents).map_err(|e| {
+            NullaryError::validation(format!(
+                "Failed to parse signing policy {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        info!("🔏 Loaded signing policy: {}", path.display());
+        policy
+    } else {
+        let policy = SigningPolicy::default();
+        let json = serde_json::to_vec_pretty(&policy).map_err(|e| {
+            NullaryError::internal(format!("Failed to serialize signing policy: {}", e))
+        })?;
+        write_atomic(&path, &json)?;
+        info!("🔏 Writing default signing policy: {}", path.display());
+        policy
+    };
+
+    SIGNER
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .policy = policy;
+    Ok(())
+}
+
+/// Policy in effect
+pub fn signing_policy() -> SigningPolicy {
+    SIGNER
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .policy
+        .clone()
+}
+
+// ===== JOURNAL =====
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalEntry {
+    pub at: i64,
+    pub purpose: SigningPurpose,
+    pub service: String,
+    pub base: String,
+    pub persona: String,
+    /// Key that signed; empty for an identity export
+    pub public_key: String,
+    /// SHA-256 of the signed message, hex
+    pub message_hash: String,
+    /// Action the policy called for
+    pub action: SigningAction,
+    /// Whether a signature was produced, or for an export whether it went ahead
+    pub signed: bool,
+    /// The key was handed to a service client, which signs its own auth
+    /// messages; `messageHash` is over the client's URL. The entry covers
+    /// every request that client makes, which are not journaled themselves.
+    #[serde(default)]
+    pub client: bool,
+}
+
+// Serializes appends from concurrent commands
+static JOURNAL_LOCK: Mutex<()> = Mutex::new(());
+
+fn journal_path() -> Option<PathBuf> {
+    SIGNER
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .journal
+        .clone()
+}
+
+fn append_journal(entry: &JournalEntry) -> Result<(), NullaryError> {
+    let Some(path) = journal_path() else {
+        return Ok(());
+    };
+
+    let mut line = serde_json::to_string(entry)
+        .map_err(|e| NullaryError::internal(format!("Failed to serialize journal entry: {}", e)))?;
+    line.push('\n');
+
+    let _guard = JOURNAL_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| NullaryError::storage(format!("Failed to open signing journal: {}", e)))?;
+    file.write_all(line.as_bytes())
+        .and_then(|_| file.sync_data())
+        .map_err(|e| NullaryError::storage(format!("Failed to write signing journal: {}", e)))
+}
+
+fn read_journal() -> Result<Vec<JournalEntry>, NullaryError> {
+    let Some(path) = journal_path() else {
+        return Ok(Vec::new());
+    };
+
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => {
+            return Err(NullaryError::storage(format!(
+                "Failed to read signing journal: {}",
+                e
+            )))
+        }
+    };
+
+    let mut entries = Vec::new();
+    for (number, line) in contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+    {
+        match serde_json::from_str(line) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => warn!("⚠️ Skipping signing journal line {}: {}", number + 1, e),
+        }
+    }
+    Ok(entries)
+}
+
+// ===== CONFIRMATION =====
+
+/// A signature waiting for the user's approval
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfirmationRequest {
+    pub purpose: SigningPurpose,
+    pub service: String,
+    pub base: String,
+    pub message: String,
+}
+
+impl ConfirmationRequest {
+    /// The question put to the user
+    fn prompt(&self) -> String {
+        if self.purpose == SigningPurpose::IdentityExport {
+            return format!(
+                "{}\n\nWhoever holds it can act as this persona on every base.",
+                self.message
+            );
+        }
+
+        let action = match self.purpose {
+            SigningPurpose::AuthHeader => "authenticate a request",
+            SigningPurpose::CovenantStep => "sign a covenant contract step",
+            SigningPurpose::JuliaPrompt => "answer a connection prompt",
+            SigningPurpose::ConnectionInvite => "sign a connection invite",
+            SigningPurpose::KeyRotation => "sign a key rotation statement",
+            SigningPurpose::OutboxIntent => "queue a write to send when the base is back",
+            SigningPurpose::IdentityExport => "export this identity",
+        };
+        format!(
+            "Allow your {} key on {} to {}?\n\n{}",
+            self.service, self.base, action, self.message
+        )
+    }
+}
+
+type ConfirmationHandler = Box<dyn Fn(&ConfirmationRequest) -> bool + Send + Sync>;
+
+// Answers confirmations in place of the native dialog, set by a headless host
+static CONFIRMATION_HANDLER: OnceLock<ConfirmationHandler> = OnceLock::new();
+
+/// Answer signing confirmations with `handler` instead of a native dialog,
+/// e.g. a terminal prompt. Only the first call has any effect.
+pub fn set_confirmation_handler(
+    handler: impl Fn(&ConfirmationRequest) -> bool + Send + Sync + 'static,
+) {
+    let _ = CONFIRMATION_HANDLER.set(Box::new(handler));
+}
+
+/// Ask the user to approve a signature in a native dialog (or through the
+/// confirmation handler). The webview never sees the request, so a script
+/// can't answer it. No answer or no app window both count as declined.
+async fn confirm(request: &SignatureRequest<'_>, timeout_ms: u64) -> bool {
+    let payload = ConfirmationRequest {
+        purpose: request.purpose,
+        service: request.service.to_string(),
+        base: request.base.to_string(),
+        message: request.message.to_string(),
+    };
+
+    if let Some(handler) = CONFIRMATION_HANDLER.get() {
+        return handler(&payload);
+    }
+    if !has_frontend() {
+        error!(
+            "❌ No app window to confirm {:?} signature",
+            request.purpose
+        );
+        return false;
+    }
+
+    info!(
+        "⏳ Waiting for confirmation of {:?} signature for {}",
+        request.purpose, request.service
+    );
+    let (title, approve) = match request.purpose {
+        SigningPurpose::IdentityExport => ("Identity export", "Export"),
+        _ => ("Signing request", "Sign"),
+    };
+    let approved = ask(
+        title,
+        &payload.prompt(),
+        approve,
+        Duration::from_millis(timeout_ms),
+    )
+    .await;
+    info!(
+        "{} {:?} signature for {}",
+        if approved { "✅ Approved" } else { "🚫 Declined" },
+        request.purpose,
+        request.service
+    );
+    approved
+}
+
+// ===== SIGNING =====
+
+/// One signature, as seen by the policy and the journal
+pub(crate) struct SignatureRequest<'a> {
+    pub purpose: SigningPurpose,
+    pub service: &'a str,
+    pub base: &'a str,
+    pub message: &'a str,
+}
+
+/// The single place a sessionless key signs: apply the policy, journal the
+/// decision, then sign
+pub(crate) async fn sign_with(
+    sessionless: &Sessionless,
+    request: SignatureRequest<'_>,
+) -> Result<String, NullaryError> {
+    authorize(sessionless, &request, false).await?;
+    Ok(sessionless.sign(request.message).into_hex())
+}
+
+/// The key for `service` on the base at `url`, for a service client crate.
+/// The client crates sign their own auth messages, so the policy and the
+/// journal see the hand-over instead: one `authHeader` entry per client,
+/// hashed over its URL. The requests the client then signs are not
+/// journaled individually; see the module comment.
+pub(crate) async fn client_sessionless(url: &str, service: &str) -> Result<Sessionless, NullaryError> {
+    let sessionless = get_sessionless(&base_for_url(url)?, service)?;
+    hand_over_key(sessionless, url, service, SigningPurpose::AuthHeader).await
+}
+
+/// Same as `client_sessionless` for a key the caller derived itself, such as
+/// the old and new keys of a rotation, under the caller's purpose
+pub(crate) async fn hand_over_key(
+    sessionless: Sessionless,
+    url: &str,
+    service: &str,
+    purpose: SigningPurpose,
+) -> Result<Sessionless, NullaryError> {
+    let base = base_for_url(url)?;
+    let request = SignatureRequest {
+        purpose,
+        service,
+        base: &base,
+        message: url,
+    };
+    authorize(&sessionless, &request, true).await?;
+    Ok(sessionless)
+}
+
+/// Apply the policy to `request` and journal the decision. Errors if the
+/// policy denies it, the user declines or the journal can't take the entry.
+async fn authorize(
+    sessionless: &Sessionless,
+    request: &SignatureRequest<'_>,
+    client: bool,
+) -> Result<(), NullaryError> {
+    let policy = signing_policy();
+    let action = policy.action(request.purpose);
+    let signed = match action {
+        SigningAction::Allow => true,
+        SigningAction::Confirm => confirm(request, policy.confirm_timeout_ms).await,
+        SigningAction::Deny => false,
+    };
+
+    // Nothing is signed unless the journal took the entry
+    append_journal(&JournalEntry {
+        at: chrono::Utc::now().timestamp_millis(),
+        purpose: request.purpose,
+        service: request.service.to_string(),
+        base: request.base.to_string(),
+        persona: active_persona(),
+        public_key: sessionless.public_key().to_hex(),
+        message_hash: hex::encode(Sha256::digest(request.message.as_bytes())),
+        action,
+        signed,
+        client,
+    })?;
+
+    if !signed {
+        let reason = match action {
+            SigningAction::Deny => "denied by signing policy",
+            _ => "declined",
+        };
+        warn!(
+            "🚫 {:?} signature for {} {}",
+            request.purpose, request.service, reason
+        );
+        return Err(NullaryError::validation(format!(
+            "{:?} signature for {} {}",
+            request.purpose, request.service, reason
+        )));
+    }
+    Ok(())
+}
+
+/// Confirm that `persona`'s seed may leave the app as `what` (a backup,
+/// recovery words) and journal the decision. The user is always asked, even
+/// where the policy allows it; a `deny` refuses without asking.
+pub(crate) async fn authorize_export(persona: &str, what: &str) -> Result<(), NullaryError> {
+    let policy = signing_policy();
+    let action = policy.action(SigningPurpose::IdentityExport);
+    let message = format!("Export the {} of persona '{}'?", what, persona);
+    let request = SignatureRequest {
+        purpose: SigningPurpose::IdentityExport,
+        service: "identity",
+        base: "this device",
+        message: &message,
+    };
+    let approved = match action {
+        SigningAction::Deny => false,
+        SigningAction::Allow | SigningAction::Confirm => {
+            confirm(&request, policy.confirm_timeout_ms).await
+        }
+    };
+
+    // Nothing leaves unless the journal took the entry
+    append_journal(&JournalEntry {
+        at: chrono::Utc::now().timestamp_millis(),
+        purpose: request.purpose,
+        service: request.service.to_string(),
+        base: request.base.to_string(),
+        persona: persona.to_string(),
+        public_key: String::new(),
+        message_hash: hex::encode(Sha256::digest(message.as_bytes())),
+        action,
+        signed: approved,
+        client: false,
+    })?;
+
+    if !approved {
+        let reason = match action {
+            SigningAction::Deny => "denied by signing policy",
+            _ => "declined",
+        };
+        warn!("🚫 Export of {} for {} {}", what, persona, reason);
+        return Err(NullaryError::validation(format!(
+            "Export of {} for {} {}",
+            what, persona, reason
+        )));
+    }
+    Ok(())
+}
+
+/// Timestamp and signature for one signed operation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OperationSignature {
+    pub timestamp: String,
+    pub signature: String,
+}
+
+/// Canonical message for an operation: the timestamp followed by each part
+fn canonical_message(timestamp: &str, parts: &[&str]) -> String {
+    let mut message = String::from(timestamp);
+    for part in parts {
+        message.push_str(part);
+    }
+    message
+}
+
+/// Sign `timestamp + parts` as this install's identity for `service` on the
+/// base at `url`, with the timestamp taken now
+pub async fn sign_operation_at(
+    url: &str,
+    service: &str,
+    purpose: SigningPurpose,
+    parts: &[&str],
+) -> Result<OperationSignature, NullaryError> {
+    let timestamp = chrono::Utc::now().timestamp_millis().to_string();
+    sign_operation_with_timestamp_at(url, service, purpose, &timestamp, parts).await
+}
+
+/// Sign `timestamp + parts` with a timestamp the caller already holds, for a
+/// request that carries several signatures under one `timestamp` field
+pub async fn sign_operation_with_timestamp_at(
+    url: &str,
+    service: &str,
+    purpose: SigningPurpose,
+    timestamp: &str,
+    parts: &[&str],
+) -> Result<OperationSignature, NullaryError> {
+    let base = base_for_url(url)?;
+    let sessionless = get_sessionless(&base, service)?;
+    let message = canonical_message(timestamp, parts);
+
+    let signature = sign_with(
+        &sessionless,
+        SignatureRequest {
+            purpose,
+            service,
+            base: &base,
+            message: &message,
+        },
+    )
+    .await?;
+
+    Ok(OperationSignature {
+        timestamp: timestamp.to_string(),
+        signature,
+    })
+}
+
+/// Check that `signed` is this install's signature over `timestamp + parts`
+/// for `service` on the base at `url`, e.g. one made earlier and read back
+/// from disk
+pub(crate) fn verify_operation_at(
+    url: &str,
+    service: &str,
+    signed: &OperationSignature,
+    parts: &[&str],
+) -> Result<(), NullaryError> {
+    let sessionless = get_sessionless(&base_for_url(url)?, service)?;
+    let signature = Signature::from_hex(&signed.signature)
+        .map_err(|e| NullaryError::validation(format!("Invalid signature: {}", e)))?;
+    sessionless
+        .verify(
+            canonical_message(&signed.timestamp, parts),
+            sessionless.public_key(),
+            &signature,
+        )
+        .map_err(|_| {
+            NullaryError::validation(format!(
+                "Signature doesn't match this persona's {} key",
+                service
+            ))
+        })
+}
+
+/// Sign an operation for `service` in the current environment
+pub async fn sign_operation(
+    service: &str,
+    purpose: SigningPurpose,
+    parts: &[&str],
+) -> Result<OperationSignature, NullaryError> {
+    sign_operation_at(&get_service_url(service), service, purpose, parts).await
+}
+
+/// Sign a self-describing document (one that carries its own timestamp and
+/// is handed to someone other than the service) for `service` on the base at
+/// `url`
+pub async fn sign_document_at(
+    url: &str,
+    service: &str,
+    purpose: SigningPurpose,
+    document: &str,
+) -> Result<String, NullaryError> {
+    let base = base_for_url(url)?;
+    let sessionless = get_sessionless(&base, service)?;
+
+    sign_with(
+        &sessionless,
+        SignatureRequest {
+            purpose,
+            service,
+            base: &base,
+            message: document,
+        },
+    )
+    .await
+}
+
+/// Attach `x-pn-timestamp` and `x-pn-signature` for an operation on the base
+/// at `url`
+///
+/// ```rust,ignore
+/// let request = signed_request(http_client().put(&url), &url, "sanora", &[&uuid, &title]).await?;
+/// let response = http::send("sanora", request).await?;
+/// ```
+pub async fn signed_request(
+    request: RequestBuilder,
+    url: &str,
+    service: &str,
+    parts: &[&str],
+) -> Result<RequestBuilder, NullaryError> {
+    let signed = sign_operation_at(url, service, SigningPurpose::AuthHeader, parts).await?;
+    Ok(request
+        .header(TIMESTAMP_HEADER, signed.timestamp)
+        .header(SIGNATURE_HEADER, signed.signature))
+}
+
+// ===== COMMANDS =====
+
+/// Journal entries, newest first, optionally filtered by purpose, service
+/// and time (ms since the epoch)
+#[cfg_attr(feature = "tauri", tauri::command)]
+pub async fn get_signing_journal(
+    purpose: Option<SigningPurpose>,
+    service: Option<String>,
+    since: Option<i64>,
+    limit: Option<usize>,
+) -> Result<Vec<JournalEntry>, NullaryError> {
+    let mut entries: Vec<JournalEntry> = read_journal()?
+        .into_iter()
+        .filter(|entry| purpose.is_none_or(|purpose| entry.purpose == purpose))
+        .filter(|entry| {
+            service
+                .as_deref()
+                .is_none_or(|service| entry.service == service)
+        })
+        .filter(|entry| since.is_none_or(|since| entry.at >= since))
+        .collect();
+
+    entries.reverse();
+    if let Some(limit) = limit {
+        entries.truncate(limit);
+    }
+    Ok(entries)
+}
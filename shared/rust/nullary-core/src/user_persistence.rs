@@ -0,0 +1,1220 @@
+// User Persistence Backend for The Nullary
+// 
+// Provides Rust backend functions for secure key storage and user data persistence.
+// Shared by all Nullary Tauri applications through nullary-core.
+//
+// Features:
+// - Stronghold vault (password-protected encrypted snapshot) for private keys,
+//   the identity vault's password kept in the OS keychain
+// - Filesystem user data persistence (non-sensitive data, see documents.rs)
+// - Sessionless key generation and management
+// - Master seed with per-base, per-service child keys for backend signing
+//   (see clients.rs for service users)
+// - Named personas, each with its own seed, service UUIDs and data subtree
+//
+// Commands are re-exported from the crate root; see lib.rs for wiring them
+// into an app.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use tracing::{debug, error, info, warn};
+
+use crate::documents::{load_document, save_document, write_atomic};
+use crate::error::NullaryError;
+use crate::host::{app_data_dir, emit};
+use crate::services::current_env;
+
+// Stronghold vault and password-based key derivation
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use iota_stronghold::{Client, KeyProvider, SnapshotPath, Stronghold};
+use zeroize::Zeroizing;
+
+// Import sessionless for key generation
+use sessionless::hex::IntoHex;
+use sessionless::{PrivateKey, PublicKey, Sessionless};
+use sha3::{Digest, Keccak256};
+
+// Child key derivation from the master seed
+use hkdf::Hkdf;
+use sha2::Sha256;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionlessKeys {
+    pub private_key: String,
+    pub public_key: String,
+    pub address: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StrongholdRecord {
+    pub data: serde_json::Value,
+}
+
+// ===== SESSIONLESS KEY GENERATION =====
+
+/// Ethereum-style address for a public key: last 20 bytes of the Keccak-256
+/// hash of the uncompressed key (without its 0x04 prefix)
+fn address_for(public_key: &PublicKey) -> String {
+    let uncompressed = public_key.serialize_uncompressed();
+    let digest = Keccak256::digest(&uncompressed[1..]);
+    format!("0x{}", hex::encode(&digest[12..]))
+}
+
+/// Build the key set for a sessionless instance
+fn keys_for(sessionless: &Sessionless) -> SessionlessKeys {
+    SessionlessKeys {
+        private_key: sessionless.private_key().to_hex(),
+        public_key: sessionless.public_key().to_hex(),
+        address: address_for(sessionless.public_key()),
+    }
+}
+
+#[cfg_attr(feature = "tauri", tauri::command)]
+pub async fn generate_sessionless_keys() -> Result<SessionlessKeys, NullaryError> {
+    info!("🔑 Generating new sessionless keys...");
+    
+    // Sessionless::new() draws a fresh keypair from the OS RNG
+    let keys = keys_for(&Sessionless::new());
+    
+    info!("✅ Sessionless keys generated successfully");
+    Ok(keys)
+}
+
+// ===== TAURI STRONGHOLD INTEGRATION =====
+
+/// Client path used for all Nullary records inside a vault snapshot
+const VAULT_CLIENT_PATH: &[u8] = b"nullary-client";
+
+/// Directory (inside the app data dir) holding vault snapshots and their metadata
+const VAULT_DIRECTORY: &str = "stronghold";
+
+/// An unlocked vault: the Stronghold instance, its snapshot location and the
+/// key derived from the user's password. Records live in the client's store
+/// and are committed to the encrypted snapshot on every write.
+struct Vault {
+    stronghold: Stronghold,
+    client: Client,
+    key_provider: KeyProvider,
+    snapshot_path: SnapshotPath,
+}
+
+/// Per-vault metadata stored next to the snapshot. The salt feeds the key
+/// derivation, the verifier lets us report a wrong password before touching
+/// the snapshot (so a bad password is never mistaken for a corrupted vault).
+#[derive(Debug, Serialize, Deserialize)]
+struct VaultMetadata {
+    salt: String,
+    verifier: String,
+}
+
+// Unlocked vaults by name. The identity vault keeps its own entry however
+// many other vaults the frontend opens, so the two never displace each other.
+static VAULTS: LazyLock<Mutex<HashMap<String, Vault>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+// Vault the webview's record commands act on: the identity vault until
+// `stronghold_init` opens another one
+static FRONTEND_VAULT: LazyLock<Mutex<String>> = LazyLock::new(|| Mutex::new(DEFAULT_VAULT.to_string()));
+
+fn vaults() -> Result<std::sync::MutexGuard<'static, HashMap<String, Vault>>, NullaryError> {
+    VAULTS
+        .lock()
+        .map_err(|e| NullaryError::internal(format!("Failed to lock vault: {}", e)))
+}
+
+fn frontend_vault() -> String {
+    FRONTEND_VAULT
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone()
+}
+
+impl Vault {
+    /// Persist the client state into the encrypted snapshot file
+    fn commit(&self) -> Result<(), NullaryError> {
+        self.stronghold
+            .write_client(VAULT_CLIENT_PATH)
+            .map_err(|e| NullaryError::storage(format!("Failed to write vault client: {}", e)))?;
+        self.stronghold
+            .commit_with_keyprovider(&self.snapshot_path, &self.key_provider)
+            .map_err(|e| NullaryError::storage(format!("Failed to commit vault snapshot: {}", e)))
+    }
+}
+
+/// Where a vault's snapshot and metadata live, and where a re-key writes
+/// them before swapping them in
+struct VaultPaths {
+    snapshot: PathBuf,
+    metadata: PathBuf,
+    rekeyed_snapshot: PathBuf,
+    rekeyed_metadata: PathBuf,
+}
+
+impl VaultPaths {
+    fn new(vault_dir: &Path, vault: &str) -> Self {
+        Self {
+            snapshot: vault_dir.join(format!("{}.hold", vault)),
+            metadata: vault_dir.join(format!("{}.json", vault)),
+            rekeyed_snapshot: vault_dir.join(format!("{}.hold.rekey", vault)),
+            rekeyed_metadata: vault_dir.join(format!("{}.json.rekey", vault)),
+        }
+    }
+
+    /// Complete or undo a re-key that was cut short. The metadata is swapped
+    /// in first, so a re-keyed snapshot without its metadata next to it
+    /// belongs to the metadata already in place.
+    fn finish_rekey(&self) -> Result<(), NullaryError> {
+        if !self.rekeyed_snapshot.exists() {
+            return Ok(());
+        }
+        let result = if self.rekeyed_metadata.exists() {
+            warn!("⚠️ Discarding an unfinished vault re-key");
+            std::fs::remove_file(&self.rekeyed_snapshot).and_then(|_| std::fs::remove_file(&self.rekeyed_metadata))
+        } else {
+            warn!("⚠️ Finishing an interrupted vault re-key");
+            std::fs::rename(&self.rekeyed_snapshot, &self.snapshot)
+        };
+        result.map_err(|e| NullaryError::storage(format!("Failed to recover vault re-key: {}", e)))
+    }
+}
+
+/// Vault and persona names become file names, so keep them to a safe
+/// character set
+fn is_safe_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 64
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+fn validate_vault_name(vault: &str) -> Result<(), NullaryError> {
+    if is_safe_name(vault) {
+        Ok(())
+    } else {
+        Err(NullaryError::validation(format!("Invalid vault name: {}", vault)))
+    }
+}
+
+/// Load the vault metadata, creating a fresh salt and password verifier
+/// the first time a vault is opened
+fn load_or_create_vault_metadata(metadata_path: &Path, password: &str) -> Result<VaultMetadata, NullaryError> {
+    match std::fs::read_to_string(metadata_path) {
+        Ok(content) => serde_json::from_str(&content)
+            .map_err(|e| NullaryError::storage(format!("Vault metadata is corrupted: {}", e))),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            let metadata = new_vault_metadata(password)?;
+            write_vault_metadata(metadata_path, &metadata)?;
+            Ok(metadata)
+        }
+        Err(e) => Err(NullaryError::storage(format!("Failed to read vault metadata: {}", e))),
+    }
+}
+
+/// Derive the 32-byte snapshot key from the password and the vault salt
+fn derive_key_provider(password: &str, metadata: &VaultMetadata) -> Result<KeyProvider, NullaryError> {
+    let salt = hex::decode(&metadata.salt)
+        .map_err(|e| NullaryError::storage(format!("Vault metadata is corrupted: {}", e)))?;
+
+    let mut key = Zeroizing::new(vec![0u8; 32]);
+    Argon2::default()
+        .hash_password_into(password.as_bytes(), &salt, &mut key)
+        .map_err(|e| NullaryError::internal(format!("Failed to derive vault key: {}", e)))?;
+
+    KeyProvider::try_from(key).map_err(|e| NullaryError::internal(format!("Failed to create vault key: {:?}", e)))
+}
+
+/// Whether `password` matches the verifier stored with the vault
+fn password_matches(metadata: &VaultMetadata, password: &str) -> Result<bool, NullaryError> {
+    let verifier = PasswordHash::new(&metadata.verifier)
+        .map_err(|e| NullaryError::storage(format!("Vault metadata is corrupted: {}", e)))?;
+    Ok(Argon2::default().verify_password(password.as_bytes(), &verifier).is_ok())
+}
+
+/// Check `password` against the verifier stored with the vault
+fn verify_vault_password(metadata: &VaultMetadata, password: &str, vault: &str) -> Result<(), NullaryError> {
+    if !password_matches(metadata, password)? {
+        error!("❌ Wrong password for Stronghold vault: {}", vault);
+        return Err(NullaryError::validation("Invalid vault password"));
+    }
+    Ok(())
+}
+
+/// New salt and password verifier for `password`
+fn new_vault_metadata(password: &str) -> Result<VaultMetadata, NullaryError> {
+    let mut salt = [0u8; 16];
+    OsRng.fill_bytes(&mut salt);
+
+    let verifier = Argon2::default()
+        .hash_password(password.as_bytes(), &SaltString::generate(&mut OsRng))
+        .map_err(|e| NullaryError::internal(format!("Failed to hash vault password: {}", e)))?
+        .to_string();
+
+    Ok(VaultMetadata {
+        salt: hex::encode(salt),
+        verifier,
+    })
+}
+
+fn write_vault_metadata(metadata_path: &Path, metadata: &VaultMetadata) -> Result<(), NullaryError> {
+    let content = serde_json::to_vec_pretty(metadata)
+        .map_err(|e| NullaryError::internal(format!("Failed to serialize vault metadata: {}", e)))?;
+    write_atomic(metadata_path, &content)
+}
+
+/// Run a closure against the named vault, which must be unlocked
+fn with_vault<T>(vault: &str, f: impl FnOnce(&Vault) -> Result<T, NullaryError>) -> Result<T, NullaryError> {
+    let guard = vaults()?;
+    let opened = guard
+        .get(vault)
+        .ok_or_else(|| NullaryError::storage(format!("Vault {} is locked - call stronghold_init first", vault)))?;
+    f(opened)
+}
+
+/// Unlock (or create) the named vault with the given password
+pub fn open_vault(password: &str, vault: &str) -> Result<(), NullaryError> {
+    validate_vault_name(vault)?;
+
+    let mut guard = vaults()?;
+
+    let vault_dir = app_data_dir()?.join(VAULT_DIRECTORY);
+
+    std::fs::create_dir_all(&vault_dir)
+        .map_err(|e| NullaryError::storage(format!("Failed to create vault directory: {}", e)))?;
+
+    let paths = VaultPaths::new(&vault_dir, vault);
+    paths.finish_rekey()?;
+    let metadata_path = paths.metadata.clone();
+    let snapshot_path = SnapshotPath::from_path(&paths.snapshot);
+
+    let metadata = load_or_create_vault_metadata(&metadata_path, password)?;
+
+    // Check the password before deriving keys so a typo gets a clear error,
+    // and before reusing an unlocked vault so a wrong password never gets in
+    verify_vault_password(&metadata, password, vault)?;
+
+    if guard.contains_key(vault) {
+        info!("✅ Stronghold vault already unlocked: {}", vault);
+        return Ok(());
+    }
+
+    let key_provider = derive_key_provider(password, &metadata)?;
+    let stronghold = Stronghold::default();
+
+    let client = if snapshot_path.exists() {
+        stronghold
+            .load_client_from_snapshot(VAULT_CLIENT_PATH, &key_provider, &snapshot_path)
+            .map_err(|e| NullaryError::storage(format!("Failed to load vault snapshot: {}", e)))?
+    } else {
+        info!("📝 Creating new Stronghold snapshot for vault: {}", vault);
+        stronghold
+            .create_client(VAULT_CLIENT_PATH)
+            .map_err(|e| NullaryError::storage(format!("Failed to create vault client: {}", e)))?
+    };
+
+    let opened = Vault {
+        stronghold,
+        client,
+        key_provider,
+        snapshot_path,
+    };
+
+    // Make sure a brand-new vault exists on disk straight away
+    opened.commit()?;
+    guard.insert(vault.to_string(), opened);
+
+    info!("✅ Stronghold vault unlocked: {}", vault);
+    Ok(())
+}
+
+/// Re-encrypt the unlocked vault `vault` under `new_password`. The new
+/// snapshot and metadata are written next to the old ones and then renamed
+/// over them, metadata first, so a crash leaves a vault `finish_rekey` can
+/// open with one password or the other.
+fn rekey_vault(vault: &str, new_password: &str) -> Result<(), NullaryError> {
+    let mut guard = vaults()?;
+    let opened = guard
+        .get_mut(vault)
+        .ok_or_else(|| NullaryError::internal(format!("Vault {} must be unlocked to re-key it", vault)))?;
+
+    let paths = VaultPaths::new(&app_data_dir()?.join(VAULT_DIRECTORY), vault);
+    let metadata = new_vault_metadata(new_password)?;
+    let key_provider = derive_key_provider(new_password, &metadata)?;
+
+    opened
+        .stronghold
+        .write_client(VAULT_CLIENT_PATH)
+        .map_err(|e| NullaryError::storage(format!("Failed to write vault client: {}", e)))?;
+    opened
+        .stronghold
+        .commit_with_keyprovider(&SnapshotPath::from_path(&paths.rekeyed_snapshot), &key_provider)
+        .map_err(|e| NullaryError::storage(format!("Failed to write re-keyed vault snapshot: {}", e)))?;
+    write_vault_metadata(&paths.rekeyed_metadata, &metadata)?;
+
+    std::fs::rename(&paths.rekeyed_metadata, &paths.metadata)
+        .and_then(|_| std::fs::rename(&paths.rekeyed_snapshot, &paths.snapshot))
+        .map_err(|e| NullaryError::storage(format!("Failed to swap in re-keyed vault: {}", e)))?;
+
+    opened.key_provider = key_provider;
+    info!("🔐 Stronghold vault re-keyed: {}", vault);
+    Ok(())
+}
+
+/// Read a record from the unlocked vault `vault`
+pub fn get_record(vault: &str, record_id: &str) -> Result<Option<serde_json::Value>, NullaryError> {
+    with_vault(vault, |vault| {
+        let bytes = vault
+            .client
+            .store()
+            .get(record_id.as_bytes())
+            .map_err(|e| NullaryError::storage(format!("Failed to read vault record: {}", e)))?;
+
+        match bytes {
+            Some(bytes) => {
+                let record: StrongholdRecord = serde_json::from_slice(&bytes)
+                    .map_err(|e| NullaryError::storage(format!("Vault record {} is corrupted: {}", record_id, e)))?;
+                Ok(Some(record.data))
+            }
+            None => Ok(None),
+        }
+    })
+}
+
+/// Write a record to the unlocked vault `vault` and commit the snapshot
+pub fn set_record(vault: &str, record_id: &str, data: serde_json::Value) -> Result<(), NullaryError> {
+    with_vault(vault, |vault| {
+        let bytes = serde_json::to_vec(&StrongholdRecord { data })
+            .map_err(|e| NullaryError::internal(format!("Failed to serialize vault record: {}", e)))?;
+
+        vault
+            .client
+            .store()
+            .insert(record_id.as_bytes().to_vec(), bytes, None)
+            .map_err(|e| NullaryError::storage(format!("Failed to write vault record: {}", e)))?;
+
+        vault.commit()
+    })
+}
+
+/// Delete a record from the unlocked vault `vault` and commit the snapshot
+pub fn remove_record(vault: &str, record_id: &str) -> Result<(), NullaryError> {
+    with_vault(vault, |vault| {
+        vault
+            .client
+            .store()
+            .delete(record_id.as_bytes())
+            .map_err(|e| NullaryError::storage(format!("Failed to delete vault record: {}", e)))?;
+
+        vault.commit()
+    })
+}
+
+#[cfg_attr(feature = "tauri", tauri::command)]
+pub async fn stronghold_init(password: String, vault: String) -> Result<String, NullaryError> {
+    info!("🔐 Initializing Stronghold vault: {}", vault);
+
+    open_vault(&password, &vault)?;
+    *FRONTEND_VAULT.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = vault;
+    Ok("Stronghold initialized".to_string())
+}
+
+/// Records the backend keeps its identity in. The record commands below are
+/// open to the webview, so they can neither read, overwrite nor clear these.
+fn is_reserved_record(record_id: &str) -> bool {
+    record_id == SEED_RECORD
+        || record_id == PERSONAS_RECORD
+        || record_id == KEYS_RECORD
+        || record_id.strip_prefix(SEED_RECORD).is_some_and(|rest| rest.starts_with(':'))
+}
+
+fn check_record_access(record_id: &str) -> Result<(), NullaryError> {
+    if is_reserved_record(record_id) {
+        warn!("🚫 Refused access to reserved vault record: {}", record_id);
+        return Err(NullaryError::validation(format!("Vault record {} is reserved", record_id)));
+    }
+    Ok(())
+}
+
+#[cfg_attr(feature = "tauri", tauri::command)]
+pub async fn stronghold_get_record(record_id: String) -> Result<Option<serde_json::Value>, NullaryError> {
+    debug!("🔍 Getting Stronghold record: {}", record_id);
+    check_record_access(&record_id)?;
+
+    let record = get_record(&frontend_vault(), &record_id)?;
+    if record.is_none() {
+        info!("📝 Stronghold record not found: {}", record_id);
+    }
+
+    Ok(record)
+}
+
+#[cfg_attr(feature = "tauri", tauri::command)]
+pub async fn stronghold_set_record(record_id: String, data: serde_json::Value) -> Result<String, NullaryError> {
+    info!("💾 Setting Stronghold record: {}", record_id);
+    check_record_access(&record_id)?;
+
+    set_record(&frontend_vault(), &record_id, data)?;
+
+    info!("✅ Stronghold record stored: {}", record_id);
+    Ok("Record set".to_string())
+}
+
+#[cfg_attr(feature = "tauri", tauri::command)]
+pub async fn stronghold_clear_vault() -> Result<String, NullaryError> {
+    info!("🗑️ Clearing Stronghold vault");
+
+    // Everything but the backend's identity
+    with_vault(&frontend_vault(), |vault| {
+        let store = vault.client.store();
+        let record_ids = store
+            .keys()
+            .map_err(|e| NullaryError::storage(format!("Failed to list vault records: {}", e)))?;
+        for record_id in record_ids {
+            if is_reserved_record(&String::from_utf8_lossy(&record_id)) {
+                continue;
+            }
+            store
+                .delete(&record_id)
+                .map_err(|e| NullaryError::storage(format!("Failed to clear vault: {}", e)))?;
+        }
+
+        vault.commit()
+    })?;
+
+    info!("✅ Stronghold vault cleared");
+    Ok("Vault cleared".to_string())
+}
+
+// ===== PERSISTENT APP IDENTITY =====
+//
+// The vault holds one random master seed. Every key the backend signs with is
+// derived from it for a (base, service) pair, so two bases never see the same
+// public key and cannot link a user's activity, while backing up the seed is
+// enough to recover every identity.
+
+/// Vault the backend keeps the identity in; `user-persistence.js` uses the
+/// same one once the backend has unlocked it
+pub const DEFAULT_VAULT: &str = "nullary-vault";
+/// Password for the identity vault on hosts without an OS keychain (the CLI
+/// on a server, CI)
+pub const VAULT_PASSWORD_ENV: &str = "NULLARY_VAULT_PASSWORD";
+pub const KEYS_RECORD: &str = "sessionless-keys";
+pub const SEED_RECORD: &str = "master-seed";
+
+/// HKDF salt for child keys; changing it changes every derived identity
+const DERIVATION_SALT: &[u8] = b"nullary-identity-v1";
+
+pub(crate) type MasterSeed = Zeroizing<[u8; 32]>;
+
+// Master seed loaded from the vault at startup
+static MASTER_SEED: LazyLock<Mutex<Option<MasterSeed>>> = LazyLock::new(|| Mutex::new(None));
+
+/// Derive the child key for a base and service from a master seed.
+/// Deterministic: the same seed, base and service always give the same key.
+pub fn derive_child_key(seed: &[u8], base: &str, service: &str) -> PrivateKey {
+    let hkdf = Hkdf::<Sha256>::new(Some(DERIVATION_SALT), seed);
+    let mut okm = Zeroizing::new([0u8; 32]);
+
+    // Length-prefixed so no two (base, service) pairs share an info string.
+    // The counter skips the ~2^-128 chance of an out-of-range scalar.
+    let mut counter: u32 = 0;
+    loop {
+        let info = format!(
+            "{}:{}/{}:{}/{}",
+            base.len(),
+            base,
+            service.len(),
+            service,
+            counter
+        );
+        hkdf.expand(info.as_bytes(), okm.as_mut())
+            .expect("32 bytes is a valid HKDF-SHA256 output length");
+
+        if let Ok(private_key) = PrivateKey::from_slice(okm.as_slice()) {
+            return private_key;
+        }
+        counter += 1;
+    }
+}
+
+pub(crate) fn seed_from_hex(seed_hex: &str) -> Result<MasterSeed, NullaryError> {
+    let bytes = Zeroizing::new(hex::decode(seed_hex).map_err(|e| NullaryError::validation(format!("Invalid seed hex: {}", e)))?);
+    let seed: [u8; 32] = bytes
+        .as_slice()
+        .try_into()
+        .map_err(|_| NullaryError::validation(format!("Seed must be 32 bytes, got {}", bytes.len())))?;
+    Ok(Zeroizing::new(seed))
+}
+
+/// Read a master seed record, creating it if missing. The default persona's
+/// record falls back to the pre-seed `sessionless-keys` identity.
+fn load_or_create_seed(record_id: &str) -> Result<MasterSeed, NullaryError> {
+    let stored_seed = get_record(DEFAULT_VAULT, record_id)?
+        .and_then(|record| record.get("seed").and_then(|s| s.as_str()).map(|s| s.to_string()));
+
+    if let Some(seed_hex) = stored_seed {
+        info!("🔑 Loaded master seed from vault");
+        return seed_from_hex(&seed_hex).map_err(|e| NullaryError::storage(format!("Stored master seed is invalid: {}", e)));
+    }
+
+    // Installs from before the seed existed keep their key material: the old
+    // private key becomes the seed, so a backup of it still recovers everything
+    let legacy_key = if record_id == SEED_RECORD {
+        get_record(DEFAULT_VAULT, KEYS_RECORD)?
+            .and_then(|record| record.get("privateKey").and_then(|k| k.as_str()).map(|k| k.to_string()))
+    } else {
+        None
+    };
+
+    let seed = match legacy_key {
+        Some(private_key_hex) => {
+            info!("🔑 Migrating stored identity to a master seed");
+            seed_from_hex(&private_key_hex)
+                .map_err(|e| NullaryError::storage(format!("Stored private key is invalid: {}", e)))?
+        }
+        None => {
+            info!("🔑 No identity in vault, generating a master seed");
+            new_master_seed()
+        }
+    };
+
+    store_seed(record_id, &seed)?;
+    Ok(seed)
+}
+
+/// Fresh random master seed
+pub(crate) fn new_master_seed() -> MasterSeed {
+    let mut seed = Zeroizing::new([0u8; 32]);
+    OsRng.fill_bytes(seed.as_mut());
+    seed
+}
+
+/// Read an existing seed record without creating it
+pub(crate) fn read_seed(record_id: &str) -> Result<MasterSeed, NullaryError> {
+    let seed_hex = get_record(DEFAULT_VAULT, record_id)?
+        .and_then(|record| record.get("seed").and_then(|s| s.as_str()).map(|s| s.to_string()))
+        .ok_or_else(|| NullaryError::storage(format!("Seed record not found: {}", record_id)))?;
+
+    seed_from_hex(&seed_hex).map_err(|e| NullaryError::storage(format!("Stored master seed is invalid: {}", e)))
+}
+
+fn store_seed(record_id: &str, seed: &MasterSeed) -> Result<(), NullaryError> {
+    set_record(DEFAULT_VAULT, record_id, serde_json::json!({
+        "seed": hex::encode(seed.as_slice()),
+        "createdAt": chrono::Utc::now().timestamp_millis()
+    }))
+}
+
+/// Keychain service the identity vault password is stored under
+const KEYCHAIN_SERVICE: &str = "nullary";
+
+/// Password every identity vault was created with before the keychain held
+/// one. Only used to re-key those vaults.
+const LEGACY_VAULT_PASSWORD: &str = "nullary-default-password";
+
+/// The identity vault's password: `NULLARY_VAULT_PASSWORD` when set,
+/// otherwise a random one kept in the OS keychain, made on first use. Each
+/// data dir gets its own keychain entry.
+fn vault_password() -> Result<Zeroizing<String>, NullaryError> {
+    if let Ok(password) = std::env::var(VAULT_PASSWORD_ENV) {
+        if !password.is_empty() {
+            return Ok(Zeroizing::new(password));
+        }
+    }
+
+    let account = app_data_dir()?.join(VAULT_DIRECTORY).join(DEFAULT_VAULT);
+    let entry = keyring::Entry::new(KEYCHAIN_SERVICE, &account.to_string_lossy())
+        .map_err(|e| NullaryError::storage(format!("Failed to open keychain entry: {}", e)))?;
+
+    match entry.get_password() {
+        Ok(password) => Ok(Zeroizing::new(password)),
+        Err(keyring::Error::NoEntry) => {
+            let mut bytes = Zeroizing::new([0u8; 32]);
+            OsRng.fill_bytes(bytes.as_mut());
+            let password = Zeroizing::new(hex::encode(bytes.as_slice()));
+            entry
+                .set_password(&password)
+                .map_err(|e| NullaryError::storage(format!("Failed to store vault password in keychain: {}", e)))?;
+            info!("🔐 New vault password stored in the OS keychain");
+            Ok(password)
+        }
+        Err(e) => Err(NullaryError::storage(format!(
+            "No OS keychain for the vault password ({}); set {}",
+            e, VAULT_PASSWORD_ENV
+        ))),
+    }
+}
+
+fn vault_is_open(vault: &str) -> Result<bool, NullaryError> {
+    Ok(vaults()?.contains_key(vault))
+}
+
+/// Unlock the identity vault unless it already is. A vault still under the
+/// old built-in password is re-keyed to the keychain password.
+fn open_identity_vault() -> Result<(), NullaryError> {
+    if vault_is_open(DEFAULT_VAULT)? {
+        return Ok(());
+    }
+
+    let password = vault_password()?;
+    match open_vault(&password, DEFAULT_VAULT) {
+        Err(NullaryError::Validation { .. }) if is_legacy_vault()? => {
+            info!("🔐 Re-keying identity vault off the built-in password");
+            open_vault(LEGACY_VAULT_PASSWORD, DEFAULT_VAULT)?;
+            rekey_vault(DEFAULT_VAULT, &password)
+        }
+        result => result,
+    }
+}
+
+fn is_legacy_vault() -> Result<bool, NullaryError> {
+    let metadata_path = app_data_dir()?
+        .join(VAULT_DIRECTORY)
+        .join(format!("{}.json", DEFAULT_VAULT));
+    let content = std::fs::read_to_string(&metadata_path)
+        .map_err(|e| NullaryError::storage(format!("Failed to read vault metadata: {}", e)))?;
+    let metadata: VaultMetadata = serde_json::from_str(&content)
+        .map_err(|e| NullaryError::storage(format!("Vault metadata is corrupted: {}", e)))?;
+    password_matches(&metadata, LEGACY_VAULT_PASSWORD)
+}
+
+/// Load the active persona's master seed from the vault, creating the default
+/// persona on first launch. Call this from the Tauri `setup` hook.
+pub fn init_identity() -> Result<(), NullaryError> {
+    if std::env::var("PRIVATE_KEY").is_ok() {
+        info!("🔑 PRIVATE_KEY override set - skipping vault identity");
+        return Ok(());
+    }
+
+    open_identity_vault()?;
+
+    let index = load_persona_index()?;
+    let mut active = ACTIVE_PERSONA.lock().map_err(|e| NullaryError::internal(format!("Failed to lock active persona: {}", e)))?;
+    activate_persona(&index, &index.active, &mut active)?;
+
+    info!("✅ Identity ready for persona: {}", active);
+    Ok(())
+}
+
+/// Master seed for this install. `PRIVATE_KEY` stays available as an explicit
+/// developer override and is used as the seed; otherwise the vault seed is.
+fn master_seed() -> Result<MasterSeed, NullaryError> {
+    if let Ok(env_key) = std::env::var("PRIVATE_KEY") {
+        return seed_from_hex(&env_key).map_err(|e| NullaryError::validation(format!("Invalid PRIVATE_KEY: {}", e)));
+    }
+
+    let master_seed = MASTER_SEED.lock().map_err(|e| NullaryError::internal(format!("Failed to lock master seed: {}", e)))?;
+    master_seed
+        .clone()
+        .ok_or_else(|| NullaryError::internal("Identity not initialized - init_identity must run at startup"))
+}
+
+/// Sessionless instance for one service on one base. Bases are identified by
+/// `host:port` (see `base_for_url`); each pair gets its own unlinkable key.
+/// Crate-only: outside code signs through `signing`, never with the raw key.
+pub(crate) fn get_sessionless(base: &str, service: &str) -> Result<Sessionless, NullaryError> {
+    let seed = master_seed()?;
+    Ok(Sessionless::from_private_key(derive_child_key(seed.as_slice(), base, service)))
+}
+
+// ===== PERSONAS =====
+//
+// An install can hold several named personas ("work", "personal", ...). Each
+// has its own master seed in the vault, its own cached service UUIDs and its
+// own `nullary-users/<persona>` data subtree. Backend signing and the user
+// data commands always use the active persona.
+
+pub const DEFAULT_PERSONA: &str = "default";
+pub const PERSONAS_RECORD: &str = "personas";
+
+/// Emitted to every window after `switch_persona`; frontends should reload
+/// their user state when they receive it
+pub const PERSONA_SWITCHED_EVENT: &str = "persona-switched";
+
+/// Services whose user UUIDs are cached per persona, and that key rotation
+/// moves to the new key
+pub const PERSONA_SERVICES: [&str; 7] = ["bdo", "dolores", "sanora", "addie", "fount", "julia", "prof"];
+
+pub(crate) const SERVICE_UUIDS_FILE: &str = "service-uuids.json";
+
+/// Service name -> user UUID
+pub type ServiceUuids = BTreeMap<String, String>;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PersonaEntry {
+    name: String,
+    seed_record: String,
+    created_at: i64,
+}
+
+/// Vault record listing every persona and the one selected at startup
+#[derive(Debug, Serialize, Deserialize)]
+struct PersonaIndex {
+    active: String,
+    personas: Vec<PersonaEntry>,
+}
+
+impl PersonaIndex {
+    fn find(&self, name: &str) -> Result<&PersonaEntry, NullaryError> {
+        self.personas
+            .iter()
+            .find(|persona| persona.name == name)
+            .ok_or_else(|| NullaryError::validation(format!("Unknown persona: {}", name)))
+    }
+
+    fn contains(&self, name: &str) -> bool {
+        self.personas.iter().any(|persona| persona.name == name)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PersonaInfo {
+    pub name: String,
+    pub active: bool,
+    pub created_at: i64,
+    /// Cached service UUIDs for the current environment
+    pub service_uuids: ServiceUuids,
+}
+
+// Active persona; persona commands hold this lock for their whole update
+static ACTIVE_PERSONA: LazyLock<Mutex<String>> = LazyLock::new(|| Mutex::new(DEFAULT_PERSONA.to_string()));
+
+/// Name of the active persona
+pub fn active_persona() -> String {
+    ACTIVE_PERSONA
+        .lock()
+        .map(|persona| persona.clone())
+        .unwrap_or_else(|poisoned| poisoned.into_inner().clone())
+}
+
+fn lock_active_persona() -> Result<std::sync::MutexGuard<'static, String>, NullaryError> {
+    ACTIVE_PERSONA.lock().map_err(|e| NullaryError::internal(format!("Failed to lock active persona: {}", e)))
+}
+
+fn validate_persona_name(name: &str) -> Result<(), NullaryError> {
+    if is_safe_name(name) {
+        Ok(())
+    } else {
+        Err(NullaryError::validation(format!("Invalid persona name: {} (use letters, digits, - and _)", name)))
+    }
+}
+
+fn save_persona_index(index: &PersonaIndex) -> Result<(), NullaryError> {
+    let data = serde_json::to_value(index)
+        .map_err(|e| NullaryError::internal(format!("Failed to serialize persona index: {}", e)))?;
+    set_record(DEFAULT_VAULT, PERSONAS_RECORD, data)
+}
+
+/// Load the persona index from the vault. The first time, the existing seed
+/// and user data become the default persona.
+fn load_persona_index() -> Result<PersonaIndex, NullaryError> {
+    if let Some(data) = get_record(DEFAULT_VAULT, PERSONAS_RECORD)? {
+        return serde_json::from_value(data).map_err(|e| NullaryError::storage(format!("Persona index is corrupted: {}", e)));
+    }
+
+    info!("👤 Creating default persona");
+    migrate_legacy_user_data()?;
+
+    let index = PersonaIndex {
+        active: DEFAULT_PERSONA.to_string(),
+        personas: vec![PersonaEntry {
+            name: DEFAULT_PERSONA.to_string(),
+            seed_record: SEED_RECORD.to_string(),
+            created_at: chrono::Utc::now().timestamp_millis(),
+        }],
+    };
+    save_persona_index(&index)?;
+
+    Ok(index)
+}
+
+/// Move files written before personas existed into the default persona's subtree
+fn migrate_legacy_user_data() -> Result<(), NullaryError> {
+    let root = users_root_dir()?;
+    if !root.exists() {
+        return Ok(());
+    }
+
+    let target = root.join(DEFAULT_PERSONA);
+    std::fs::create_dir_all(&target)
+        .map_err(|e| NullaryError::storage(format!("Failed to create user data directory: {}", e)))?;
+
+    let entries = std::fs::read_dir(&root)
+        .map_err(|e| NullaryError::storage(format!("Failed to read user data directory: {}", e)))?;
+    for entry in entries {
+        let entry = entry.map_err(|e| NullaryError::storage(format!("Failed to read user data directory: {}", e)))?;
+        let destination = target.join(entry.file_name());
+
+        if entry.path().is_file() && !destination.exists() {
+            std::fs::rename(entry.path(), &destination)
+                .map_err(|e| NullaryError::storage(format!("Failed to move user data file: {}", e)))?;
+            info!("📁 Moved {} into the default persona", entry.file_name().to_string_lossy());
+        }
+    }
+
+    Ok(())
+}
+
+/// Load a persona's seed and make it the active one. `active` is the held
+/// active persona lock.
+fn activate_persona(index: &PersonaIndex, name: &str, active: &mut String) -> Result<(), NullaryError> {
+    let seed = load_or_create_seed(&index.find(name)?.seed_record)?;
+
+    let mut master_seed = MASTER_SEED.lock().map_err(|e| NullaryError::internal(format!("Failed to lock master seed: {}", e)))?;
+    *master_seed = Some(seed);
+    *active = name.to_string();
+
+    Ok(())
+}
+
+/// Persona index from the default vault, unlocking it if needed
+fn persona_index() -> Result<PersonaIndex, NullaryError> {
+    open_identity_vault()?;
+    load_persona_index()
+}
+
+/// Environment -> cached service UUIDs for one persona
+fn read_service_uuids(persona: &str) -> Result<BTreeMap<String, ServiceUuids>, NullaryError> {
+    let path = get_persona_data_dir(persona)?.join(SERVICE_UUIDS_FILE);
+
+    match std::fs::read_to_string(&path) {
+        Ok(content) => serde_json::from_str(&content)
+            .map_err(|e| NullaryError::storage(format!("Service UUID cache is corrupted: {}", e))),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+        Err(e) => Err(NullaryError::storage(format!("Failed to read service UUID cache: {}", e))),
+    }
+}
+
+/// Cached service UUIDs of a persona for the current environment
+pub fn persona_service_uuids(persona: &str) -> Result<ServiceUuids, NullaryError> {
+    Ok(read_service_uuids(persona)?
+        .remove(&current_env())
+        .unwrap_or_default())
+}
+
+/// Remember the active persona's user UUID for a service in the current environment
+pub fn cache_service_uuid(service: &str, uuid: &str) -> Result<(), NullaryError> {
+    if !PERSONA_SERVICES.contains(&service) {
+        return Err(NullaryError::validation(format!(
+            "Unknown persona service: {} (expected one of: {})",
+            service,
+            PERSONA_SERVICES.join(", ")
+        )));
+    }
+
+    let persona = active_persona();
+    let mut uuids = read_service_uuids(&persona)?;
+    uuids
+        .entry(current_env())
+        .or_default()
+        .insert(service.to_string(), uuid.to_string());
+
+    let persona_dir = get_persona_data_dir(&persona)?;
+    std::fs::create_dir_all(&persona_dir)
+        .map_err(|e| NullaryError::storage(format!("Failed to create user data directory: {}", e)))?;
+
+    let content = serde_json::to_string_pretty(&uuids)
+        .map_err(|e| NullaryError::internal(format!("Failed to serialize service UUID cache: {}", e)))?;
+    write_atomic(&persona_dir.join(SERVICE_UUIDS_FILE), content.as_bytes())
+        .map_err(|e| NullaryError::storage(format!("Failed to write service UUID cache: {}", e)))
+}
+
+fn persona_info(entry: &PersonaEntry, active: &str) -> Result<PersonaInfo, NullaryError> {
+    Ok(PersonaInfo {
+        name: entry.name.clone(),
+        active: entry.name == active,
+        created_at: entry.created_at,
+        service_uuids: persona_service_uuids(&entry.name)?,
+    })
+}
+
+#[cfg_attr(feature = "tauri", tauri::command)]
+pub async fn list_personas() -> Result<Vec<PersonaInfo>, NullaryError> {
+    let active = lock_active_persona()?;
+    let index = persona_index()?;
+
+    index
+        .personas
+        .iter()
+        .map(|entry| persona_info(entry, &active))
+        .collect()
+}
+
+/// Record ids are not tied to persona names so a rename never touches the seed
+fn new_seed_record_id() -> String {
+    let mut record_suffix = [0u8; 8];
+    OsRng.fill_bytes(&mut record_suffix);
+    format!("{}:{}", SEED_RECORD, hex::encode(record_suffix))
+}
+
+/// Give a persona a new master seed. The old seed record is kept (not
+/// deleted) and its id returned so key rotation can still sign with it.
+pub(crate) fn replace_persona_seed(persona: &str, seed: &MasterSeed) -> Result<String, NullaryError> {
+    let active = lock_active_persona()?;
+    let mut index = persona_index()?;
+    index.find(persona)?;
+
+    let new_record = new_seed_record_id();
+    store_seed(&new_record, seed)?;
+
+    let mut retired_record = String::new();
+    for entry in index.personas.iter_mut().filter(|entry| entry.name == persona) {
+        retired_record = std::mem::replace(&mut entry.seed_record, new_record.clone());
+    }
+    save_persona_index(&index)?;
+
+    if *active == persona {
+        let mut master_seed = MASTER_SEED.lock().map_err(|e| NullaryError::internal(format!("Failed to lock master seed: {}", e)))?;
+        *master_seed = Some(seed.clone());
+    }
+
+    Ok(retired_record)
+}
+
+/// Master seed of a persona, for backups
+pub(crate) fn persona_seed(persona: &str) -> Result<MasterSeed, NullaryError> {
+    let _active = lock_active_persona()?;
+    let index = persona_index()?;
+    load_or_create_seed(&index.find(persona)?.seed_record)
+}
+
+/// Add a persona with a new random seed, or with `seed` when restoring one
+pub(crate) fn add_persona(name: &str, seed: Option<&MasterSeed>) -> Result<PersonaInfo, NullaryError> {
+    validate_persona_name(name)?;
+
+    let active = lock_active_persona()?;
+    let mut index = persona_index()?;
+    if index.contains(name) {
+        return Err(NullaryError::validation(format!("Persona already exists: {}", name)));
+    }
+
+    let entry = PersonaEntry {
+        name: name.to_string(),
+        seed_record: new_seed_record_id(),
+        created_at: chrono::Utc::now().timestamp_millis(),
+    };
+    match seed {
+        Some(seed) => store_seed(&entry.seed_record, seed)?,
+        None => {
+            load_or_create_seed(&entry.seed_record)?;
+        }
+    }
+
+    std::fs::create_dir_all(get_persona_data_dir(name)?)
+        .map_err(|e| NullaryError::storage(format!("Failed to create persona data directory: {}", e)))?;
+
+    index.personas.push(entry.clone());
+    save_persona_index(&index)?;
+
+    persona_info(&entry, &active)
+}
+
+/// Name of the persona a command targets: the given one, or the active one
+pub(crate) fn persona_or_active(persona: Option<String>) -> String {
+    persona.unwrap_or_else(active_persona)
+}
+
+#[cfg_attr(feature = "tauri", tauri::command)]
+pub async fn create_persona(name: String) -> Result<PersonaInfo, NullaryError> {
+    info!("👤 Creating persona: {}", name);
+
+    let info = add_persona(&name, None)?;
+
+    info!("✅ Persona created: {}", name);
+    Ok(info)
+}
+
+#[cfg_attr(feature = "tauri", tauri::command)]
+pub async fn rename_persona(name: String, new_name: String) -> Result<PersonaInfo, NullaryError> {
+    info!("👤 Renaming persona: {} -> {}", name, new_name);
+    validate_persona_name(&new_name)?;
+
+    let mut active = lock_active_persona()?;
+    let mut index = persona_index()?;
+    index.find(&name)?;
+    if index.contains(&new_name) {
+        return Err(NullaryError::validation(format!("Persona already exists: {}", new_name)));
+    }
+
+    let old_dir = get_persona_data_dir(&name)?;
+    if old_dir.exists() {
+        std::fs::rename(&old_dir, get_persona_data_dir(&new_name)?)
+            .map_err(|e| NullaryError::storage(format!("Failed to move persona data directory: {}", e)))?;
+    }
+
+    for entry in index.personas.iter_mut().filter(|entry| entry.name == name) {
+        entry.name = new_name.clone();
+    }
+    if index.active == name {
+        index.active = new_name.clone();
+    }
+    save_persona_index(&index)?;
+
+    if *active == name {
+        *active = new_name.clone();
+    }
+
+    info!("✅ Persona renamed: {}", new_name);
+    persona_info(index.find(&new_name)?, &active)
+}
+
+#[cfg_attr(feature = "tauri", tauri::command)]
+pub async fn delete_persona(name: String) -> Result<String, NullaryError> {
+    info!("🗑️ Deleting persona: {}", name);
+
+    let active = lock_active_persona()?;
+    let mut index = persona_index()?;
+    if *active == name || index.active == name {
+        return Err(NullaryError::validation("Cannot delete the active persona - switch to another persona first"));
+    }
+
+    let seed_record = index.find(&name)?.seed_record.clone();
+    index.personas.retain(|entry| entry.name != name);
+    save_persona_index(&index)?;
+    remove_record(DEFAULT_VAULT, &seed_record)?;
+
+    let persona_dir = get_persona_data_dir(&name)?;
+    if persona_dir.exists() {
+        std::fs::remove_dir_all(&persona_dir)
+            .map_err(|e| NullaryError::storage(format!("Failed to remove persona data directory: {}", e)))?;
+    }
+
+    info!("✅ Persona deleted: {}", name);
+    Ok("Persona deleted".to_string())
+}
+
+/// Make another persona active. Emits `persona-switched` with the new
+/// persona's info so every window can reload.
+#[cfg_attr(feature = "tauri", tauri::command)]
+pub async fn switch_persona(name: String) -> Result<PersonaInfo, NullaryError> {
+    info!("👤 Switching to persona: {}", name);
+
+    let info = {
+        let mut active = lock_active_persona()?;
+        let mut index = persona_index()?;
+
+        activate_persona(&index, &name, &mut active)?;
+        index.active = name.clone();
+        save_persona_index(&index)?;
+
+        persona_info(index.find(&name)?, &active)?
+    };
+
+    if std::env::var("PRIVATE_KEY").is_ok() {
+        warn!("⚠️ PRIVATE_KEY override set - signing keys are unchanged");
+    }
+
+    emit(PERSONA_SWITCHED_EVENT, &info)?;
+
+    info!("✅ Active persona: {}", name);
+    Ok(info)
+}
+
+#[cfg_attr(feature = "tauri", tauri::command)]
+pub async fn get_persona_service_uuids() -> Result<ServiceUuids, NullaryError> {
+    persona_service_uuids(&active_persona())
+}
+
+#[cfg_attr(feature = "tauri", tauri::command)]
+pub async fn set_persona_service_uuid(service: String, uuid: String) -> Result<String, NullaryError> {
+    cache_service_uuid(&service, &uuid)?;
+
+    info!("✅ Cached {} UUID for persona {}", service, active_persona());
+    Ok("Service UUID cached".to_string())
+}
+
+// ===== FILESYSTEM USER DATA PERSISTENCE =====
+//
+// `read_user_data_file` and `write_user_data_file` are kept for apps written
+// before the document store (documents.rs); the filename is the document key.
+
+/// Read a document as a string: string data as-is, anything else as JSON
+#[cfg_attr(feature = "tauri", tauri::command)]
+pub async fn read_user_data_file(filename: String) -> Result<Option<String>, NullaryError> {
+    info!("📁 Reading user data file: {}", filename);
+
+    match load_document::<serde_json::Value>(&filename)? {
+        Some(serde_json::Value::String(content)) => Ok(Some(content)),
+        Some(data) => serde_json::to_string_pretty(&data)
+            .map(Some)
+            .map_err(|e| NullaryError::internal(format!("Failed to serialize user data file: {}", e))),
+        None => {
+            info!("📝 User data file not found (will be created): {}", filename);
+            Ok(None)
+        }
+    }
+}
+
+/// Write a string as a document, stored as JSON when it parses as JSON
+#[cfg_attr(feature = "tauri", tauri::command)]
+pub async fn write_user_data_file(filename: String, data: String) -> Result<String, NullaryError> {
+    info!("💾 Writing user data file: {}", filename);
+
+    let value = serde_json::from_str(&data).unwrap_or(serde_json::Value::String(data));
+    save_document(&filename, &value)?;
+
+    info!("✅ User data file written successfully: {}", filename);
+    Ok("File written successfully".to_string())
+}
+
+/// Clear the active persona's user data; other personas are untouched
+#[cfg_attr(feature = "tauri", tauri::command)]
+pub async fn clear_user_data() -> Result<String, NullaryError> {
+    info!("🗑️ Clearing user data for persona: {}", active_persona());
+    
+    let user_data_dir = get_user_data_dir()?;
+    
+    // Remove the persona's user data directory
+    if user_data_dir.exists() {
+        std::fs::remove_dir_all(&user_data_dir)
+            .map_err(|e| NullaryError::storage(format!("Failed to remove user data directory: {}", e)))?;
+        
+        info!("✅ User data directory cleared");
+    } else {
+        info!("📝 User data directory doesn't exist - nothing to clear");
+    }
+    
+    Ok("User data cleared successfully".to_string())
+}
+
+// ===== UTILITY FUNCTIONS =====
+
+/**
+ * Get the root user data directory holding every persona's subtree
+ */
+fn users_root_dir() -> Result<PathBuf, NullaryError> {
+    let app_data_dir = app_data_dir()?;
+    
+    Ok(app_data_dir.join("nullary-users"))
+}
+
+/**
+ * Get a persona's user data directory path
+ */
+pub fn get_persona_data_dir(persona: &str) -> Result<PathBuf, NullaryError> {
+    validate_persona_name(persona)?;
+    Ok(users_root_dir()?.join(persona))
+}
+
+/**
+ * Get the active persona's user data directory path
+ */
+pub fn get_user_data_dir() -> Result<PathBuf, NullaryError> {
+    get_persona_data_dir(&active_persona())
+}
+
+/**
+ * Ensure user data directory exists
+ */
+pub fn ensure_user_data_dir_exists() -> Result<PathBuf, NullaryError> {
+    let user_data_dir = get_user_data_dir()?;
+    
+    std::fs::create_dir_all(&user_data_dir)
+        .map_err(|e| NullaryError::storage(format!("Failed to create user data directory: {}", e)))?;
+    
+    Ok(user_data_dir)
+}
@@ -0,0 +1,221 @@
+// nullary-core against the allyabase stand-in, started in-process on free
+// ports. The core runs headless with a throwaway data dir and a fixed key.
+
+use nullary_core::http;
+use nullary_core::{
+    dolores_feed_at, init_headless, public_key_at, refresh_cached_feed, register_uuid,
+    registered_uuid, sanora_base_products_at, set_confirmation_handler, AppDirs, FeedFetch,
+    FeedKey, NullaryError, Validators,
+};
+use nullary_standin::{start, FaultRule, Standin, StandinConfig};
+use serde_json::{json, Value};
+use sessionless::hex::IntoHex;
+use sessionless::Sessionless;
+use std::sync::Once;
+use std::time::Duration;
+
+static INIT: Once = Once::new();
+
+fn init() {
+    INIT.call_once(|| {
+        let root = std::env::temp_dir().join(format!("nullary-core-tests-{}", std::process::id()));
+        // Fount's breaker opens on the first failure and cools down quickly,
+        // so the breaker tests don't wait out the default 30s
+        std::fs::create_dir_all(&root).unwrap();
+        let http = json!({
+            "services": {
+                "fount": { "retries": 0, "breakerThreshold": 1, "breakerCooldownMs": 100 }
+            }
+        });
+        std::fs::write(root.join("http.json"), http.to_string()).unwrap();
+        std::env::set_var("PRIVATE_KEY", "11".repeat(32));
+        set_confirmation_handler(|_| true);
+        init_headless("nullary-tests", AppDirs::under(&root)).unwrap();
+    });
+}
+
+async fn standin() -> Standin {
+    init();
+    start(StandinConfig::ephemeral()).await.unwrap()
+}
+
+fn fault(service: &str, status: u16, times: Option<u32>) -> FaultRule {
+    FaultRule {
+        service: Some(service.to_string()),
+        status: Some(status),
+        times,
+        ..FaultRule::default()
+    }
+}
+
+fn fresh<T>(fetch: FeedFetch<T>) -> (T, Validators) {
+    match fetch {
+        FeedFetch::Fresh { data, validators } => (data, validators),
+        FeedFetch::NotModified => panic!("expected a fresh page"),
+    }
+}
+
+/// Register this install's key with the stand-in's Dolores, as
+/// `create_dolores_user_at` would, and remember the UUID
+fn dolores_user(standin: &Standin) -> String {
+    let url = standin.url("dolores").unwrap();
+    let pub_key = public_key_at(&url, "dolores").unwrap();
+    let user = standin
+        .state()
+        .store()
+        .user_for_key("dolores", &pub_key, None, json!({}));
+    register_uuid(&url, "dolores", None, &user.uuid).unwrap();
+    user.uuid
+}
+
+#[tokio::test]
+async fn dolores_feeds_are_signed_and_revalidated() {
+    let standin = standin().await;
+    let url = standin.url("dolores").unwrap();
+    dolores_user(&standin);
+
+    let (posts, validators) = fresh(dolores_feed_at(&url, "photos", &Validators::default()).await.unwrap());
+    assert_eq!(posts.len(), 1);
+    assert_eq!(posts[0]["title"], "Stand-in photos");
+    assert!(validators.etag.is_some());
+
+    let again = dolores_feed_at(&url, "photos", &validators).await.unwrap();
+    assert!(matches!(again, FeedFetch::NotModified));
+
+    // Other tags are another feed
+    let (posts, _) = fresh(dolores_feed_at(&url, "videos", &validators).await.unwrap());
+    assert_eq!(posts[0]["title"], "Stand-in video");
+
+    standin.shutdown().await;
+}
+
+#[tokio::test]
+async fn unknown_dolores_users_are_forgotten() {
+    let standin = standin().await;
+    let url = standin.url("dolores").unwrap();
+    register_uuid(&url, "dolores", None, "not-a-dolores-user").unwrap();
+
+    // The stand-in answers 404 for the user, so the next call creates one
+    let error = dolores_feed_at(&url, "photos", &Validators::default()).await.unwrap_err();
+    assert!(error.is_not_found(), "{}", error);
+    assert_eq!(registered_uuid(&url, "dolores", None).unwrap(), None);
+
+    standin.shutdown().await;
+}
+
+#[tokio::test]
+async fn transient_failures_are_retried() {
+    let standin = standin().await;
+    let url = standin.url("sanora").unwrap();
+
+    standin.state().faults.push(fault("sanora", 503, Some(2)));
+    let (products, _) = fresh(sanora_base_products_at::<Value>(&url, &Validators::default()).await.unwrap());
+    assert_eq!(products.len(), 2);
+    assert!(standin.state().faults.list().is_empty());
+
+    standin.shutdown().await;
+}
+
+#[tokio::test]
+async fn failures_are_classified_by_status() {
+    let standin = standin().await;
+    let url = standin.url("sanora").unwrap();
+
+    standin.state().faults.push(fault("sanora", 503, None));
+    let error = sanora_base_products_at::<Value>(&url, &Validators::default()).await.unwrap_err();
+    assert!(error.is_transient(), "{}", error);
+
+    standin.state().faults.replace(vec![fault("sanora", 400, None)]);
+    let error = sanora_base_products_at::<Value>(&url, &Validators::default()).await.unwrap_err();
+    assert_eq!(error.kind(), "validation");
+    assert!(!error.is_transient());
+
+    standin.state().faults.replace(vec![FaultRule {
+        service: Some("sanora".to_string()),
+        malformed: true,
+        ..FaultRule::default()
+    }]);
+    let error = sanora_base_products_at::<Value>(&url, &Validators::default()).await.unwrap_err();
+    assert!(!error.is_transient(), "{}", error);
+
+    standin.shutdown().await;
+}
+
+#[tokio::test]
+async fn cancelled_trials_reopen_the_breaker() {
+    let standin = standin().await;
+    let url = format!("{}user/nobody", standin.url("fount").unwrap());
+
+    // One failure opens the breaker and later calls fail fast
+    standin.state().faults.push(fault("fount", 503, Some(1)));
+    assert_eq!(http::get("fount", &url).await.unwrap().status(), 503);
+    let error = http::get("fount", &url).await.unwrap_err();
+    assert!(error.to_string().contains("is failing"), "{}", error);
+
+    // After the cooldown the trial hangs and its caller gives up on it
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    standin.state().faults.push(FaultRule {
+        service: Some("fount".to_string()),
+        latency_ms: Some(5_000),
+        times: Some(1),
+        ..FaultRule::default()
+    });
+    let trial = tokio::time::timeout(Duration::from_millis(100), http::get("fount", &url)).await;
+    assert!(trial.is_err(), "the trial should still be pending");
+
+    // The dropped trial must not hold the breaker open
+    standin.state().faults.replace(Vec::new());
+    let response = http::get("fount", &url).await.unwrap();
+    assert_ne!(response.status(), 503);
+
+    standin.shutdown().await;
+}
+
+#[tokio::test]
+async fn cached_pages_outlive_outages() {
+    let standin = standin().await;
+    let url = standin.url("sanora").unwrap();
+    let key = || FeedKey::new(&url, "sanora", "products").unwrap();
+    let fetch = |validators: Validators| {
+        let url = url.clone();
+        async move { sanora_base_products_at::<Value>(&url, &validators).await }
+    };
+
+    let page = refresh_cached_feed(key(), fetch).await.unwrap();
+    assert_eq!(page.data.len(), 2);
+    assert!(!page.stale);
+
+    // Nothing changed: the cached page is kept
+    let page = refresh_cached_feed(key(), fetch).await.unwrap();
+    assert_eq!(page.data.len(), 2);
+    assert!(!page.stale);
+
+    // Sanora is down: the cached page is served, flagged
+    standin.state().faults.push(fault("sanora", 503, None));
+    let page = refresh_cached_feed(key(), fetch).await.unwrap();
+    assert_eq!(page.data.len(), 2);
+    assert!(page.stale);
+
+    // Back, with a new product
+    standin.state().faults.replace(Vec::new());
+    standin.state().store().products.push(json!({ "title": "New", "tags": [] }));
+    let page = refresh_cached_feed(key(), fetch).await.unwrap();
+    assert_eq!(page.data.len(), 3);
+
+    standin.shutdown().await;
+}
+
+#[tokio::test]
+async fn unsigned_keys_are_refused() {
+    let standin = standin().await;
+    let url = standin.url("dolores").unwrap();
+    // A user registered under someone else's key
+    let other_key = Sessionless::new().public_key().to_hex();
+    let other = standin.state().store().user_for_key("dolores", &other_key, None, json!({}));
+    register_uuid(&url, "dolores", None, &other.uuid).unwrap();
+
+    let error = dolores_feed_at(&url, "photos", &Validators::default()).await.unwrap_err();
+    assert!(matches!(error, NullaryError::Auth { .. }), "{:?}", error);
+
+    standin.shutdown().await;
+}
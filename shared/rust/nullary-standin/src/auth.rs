@@ -0,0 +1,56 @@
+// Sessionless signature checks
+//
+// Every signed request is checked against the message layout the real
+// service uses: the timestamp followed by the operation's parts, usually
+// starting with the user's UUID. A user's public key is the one they
+// registered with `/user/create`.
+
+use sessionless::hex::FromHex;
+use sessionless::{PublicKey, Sessionless, Signature};
+use std::sync::LazyLock;
+use tracing::debug;
+
+use crate::error::{ApiError, ApiResult};
+use crate::state::{Store, User};
+
+// Verification only needs the secp256k1 context; its own keys are unused
+static VERIFIER: LazyLock<Sessionless> = LazyLock::new(Sessionless::new);
+
+/// `timestamp` followed by each part
+pub fn message(timestamp: &str, parts: &[&str]) -> String {
+    let mut message = String::from(timestamp);
+    for part in parts {
+        message.push_str(part);
+    }
+    message
+}
+
+/// Check `signature` over `message` with the hex `pub_key`
+pub fn verify(pub_key: &str, message: &str, signature: &str) -> ApiResult<()> {
+    let pub_key = PublicKey::from_hex(pub_key)
+        .map_err(|e| ApiError::bad_request(format!("Invalid public key: {}", e)))?;
+    let signature = Signature::from_hex(signature)
+        .map_err(|e| ApiError::auth(format!("Invalid signature: {}", e)))?;
+
+    VERIFIER.verify(message, &pub_key, &signature).map_err(|_| {
+        debug!(message, "🚫 Signature did not verify");
+        ApiError::auth("Auth error")
+    })
+}
+
+/// The user `uuid` on `service`, once their signature over
+/// `timestamp + uuid + parts` checks out
+pub fn verify_user(
+    store: &Store,
+    service: &str,
+    uuid: &str,
+    timestamp: &str,
+    signature: &str,
+    parts: &[&str],
+) -> ApiResult<User> {
+    let user = store.user(service, uuid)?.clone();
+    let mut signed = vec![uuid];
+    signed.extend_from_slice(parts);
+    verify(&user.pub_key, &message(timestamp, &signed), signature)?;
+    Ok(user)
+}
@@ -0,0 +1,50 @@
+// Conditional GETs
+//
+// Every successful GET is answered with an `ETag` over its body, and a GET
+// whose `If-None-Match` names the current one gets `304 Not Modified`
+// without a body, the way the real services' Express stack answers. That
+// lets the apps' feed cache keep its page when nothing changed.
+
+use axum::body::{self, Body};
+use axum::extract::Request;
+use axum::http::{header, HeaderValue, Method, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use tracing::{debug, error};
+
+/// Middleware tagging GET responses and answering matching revalidations
+pub async fn etag(request: Request, next: Next) -> Response {
+    if request.method() != Method::GET {
+        return next.run(request).await;
+    }
+    let if_none_match = request.headers().get(header::IF_NONE_MATCH).cloned();
+
+    let response = next.run(request).await;
+    if response.status() != StatusCode::OK {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match body::to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            error!("❌ Failed to read response body: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    let tag = format!("W/\"{:016x}\"", hasher.finish());
+    let tag = HeaderValue::from_str(&tag).expect("hex digits are a valid header value");
+
+    if if_none_match.as_ref() == Some(&tag) {
+        debug!("📭 Not modified");
+        return (StatusCode::NOT_MODIFIED, [(header::ETAG, tag)]).into_response();
+    }
+
+    parts.headers.insert(header::ETAG, tag);
+    Response::from_parts(parts, Body::from(bytes))
+}
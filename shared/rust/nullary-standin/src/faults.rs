@@ -0,0 +1,160 @@
+// Fault injection
+//
+// Rules are matched against every request in order; the first match wins.
+// A rule can hold the request for `latencyMs`, answer it with `status`
+// instead of running the handler, or run the handler and replace the body
+// with JSON that doesn't parse. `probability` makes a rule fire only some
+// of the time and `times` retires it after that many hits, so a test can
+// script "the next two calls to /user/create fail with 503".
+//
+// Rules come from `--faults <file>` at startup and from the control API
+// (`GET`/`PUT`/`POST`/`DELETE /faults`) while running.
+
+use axum::body::Body;
+use axum::extract::{Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::{RwLock, RwLockWriteGuard};
+use std::time::Duration;
+use tracing::info;
+
+use crate::state::SharedState;
+
+const MALFORMED_BODY: &str = "{\"stand-in\": [\"malformed";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FaultRule {
+    /// Service the rule applies to; all of them when absent
+    #[serde(default)]
+    pub service: Option<String>,
+    /// HTTP method, e.g. `PUT`; any when absent
+    #[serde(default)]
+    pub method: Option<String>,
+    /// Path prefix, e.g. `/user/create`; any path when absent
+    #[serde(default)]
+    pub path: Option<String>,
+    /// Delay before the request is handled
+    #[serde(default)]
+    pub latency_ms: Option<u64>,
+    /// Answer with this status instead of running the handler
+    #[serde(default)]
+    pub status: Option<u16>,
+    /// Replace the handler's body with JSON that doesn't parse
+    #[serde(default)]
+    pub malformed: bool,
+    /// Chance (0 to 1) that a matching request is faulted; always when absent
+    #[serde(default)]
+    pub probability: Option<f64>,
+    /// Number of hits before the rule is retired; never when absent
+    #[serde(default)]
+    pub times: Option<u32>,
+}
+
+impl FaultRule {
+    fn matches(&self, service: &str, method: &str, path: &str) -> bool {
+        self.service.as_deref().is_none_or(|s| s == service)
+            && self
+                .method
+                .as_deref()
+                .is_none_or(|m| m.eq_ignore_ascii_case(method))
+            && self.path.as_deref().is_none_or(|p| path.starts_with(p))
+    }
+}
+
+#[derive(Default)]
+pub struct Faults {
+    rules: RwLock<Vec<FaultRule>>,
+}
+
+impl Faults {
+    pub fn new(rules: Vec<FaultRule>) -> Self {
+        Self {
+            rules: RwLock::new(rules),
+        }
+    }
+
+    fn rules(&self) -> RwLockWriteGuard<'_, Vec<FaultRule>> {
+        self.rules
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub fn list(&self) -> Vec<FaultRule> {
+        self.rules().clone()
+    }
+
+    pub fn replace(&self, rules: Vec<FaultRule>) {
+        *self.rules() = rules;
+    }
+
+    pub fn push(&self, rule: FaultRule) {
+        self.rules().push(rule);
+    }
+
+    /// The rule that fires for this request, if any, counting the hit
+    fn fire(&self, service: &str, method: &str, path: &str) -> Option<FaultRule> {
+        let mut rules = self.rules();
+        let index = rules
+            .iter()
+            .position(|rule| rule.matches(service, method, path))?;
+
+        let probability = rules[index].probability.unwrap_or(1.0);
+        if probability < 1.0 && !rand::thread_rng().gen_bool(probability.max(0.0)) {
+            return None;
+        }
+
+        let rule = rules[index].clone();
+        match rules[index].times {
+            Some(times) if times <= 1 => {
+                rules.remove(index);
+            }
+            Some(times) => rules[index].times = Some(times - 1),
+            None => {}
+        }
+        Some(rule)
+    }
+}
+
+/// Service a router serves, with the shared state, for the middleware
+#[derive(Clone)]
+pub struct FaultContext {
+    pub state: SharedState,
+    pub service: &'static str,
+}
+
+/// Middleware applying the first matching fault rule
+pub async fn inject(State(context): State<FaultContext>, request: Request, next: Next) -> Response {
+    let method = request.method().to_string();
+    let path = request.uri().path().to_string();
+    let Some(rule) = context.state.faults.fire(context.service, &method, &path) else {
+        return next.run(request).await;
+    };
+    info!(service = context.service, %method, %path, ?rule, "💥 Injecting fault");
+
+    if let Some(latency_ms) = rule.latency_ms {
+        tokio::time::sleep(Duration::from_millis(latency_ms)).await;
+    }
+
+    if let Some(status) = rule.status {
+        let status = StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        return (status, Json(json!({ "error": "Injected fault" }))).into_response();
+    }
+
+    let response = next.run(request).await;
+    if !rule.malformed {
+        return response;
+    }
+    let (mut parts, _) = response.into_parts();
+    parts.headers.remove(header::CONTENT_LENGTH);
+    parts.headers.insert(
+        header::CONTENT_TYPE,
+        header::HeaderValue::from_static("application/json"),
+    );
+    Response::from_parts(parts, Body::from(MALFORMED_BODY))
+}
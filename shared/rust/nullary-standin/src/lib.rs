@@ -0,0 +1,259 @@
+// Nullary Stand-in
+//
+// An in-memory stand-in for the allyabase services the Nullary apps talk to
+// (BDO, Dolores, Sanora, Addie, Fount, Julia, Prof and Covenant), listening
+// on the ports of the `test` environment so an app started with
+// `NULLARY_ENV=test` runs against it unchanged. Signatures are verified the
+// way the real services verify them, and faults (latency, error statuses,
+// malformed JSON) can be scripted per service and path. Successful GETs
+// carry an ETag and are answered with 304 when it is sent back.
+//
+// The control API on its own port lists and replaces fault rules, resets the
+// store to its seed and dumps the current state:
+//
+//   GET/PUT/POST/DELETE /faults   list, replace, add to or clear the rules
+//   POST /reset                   forget everything but the seed
+//   GET /state                    every user, post, product, contract...
+
+use axum::extract::State;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::io;
+use std::net::SocketAddr;
+use tokio::net::TcpListener;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tracing::{error, info};
+
+mod auth;
+mod conditional;
+mod error;
+mod faults;
+mod services;
+mod state;
+
+pub use faults::FaultRule;
+pub use services::SERVICES;
+pub use state::{SharedState, StandinState};
+
+/// Port of the control API, just below the `test` service ports
+pub const CONTROL_PORT: u16 = 5110;
+
+// ===== CONFIGURATION =====
+
+#[derive(Debug, Clone)]
+pub struct StandinConfig {
+    /// Address every service binds to
+    pub host: String,
+    /// Services to stand in for, with their ports; port 0 picks a free one
+    pub services: Vec<(String, u16)>,
+    /// Port of the control API; `None` leaves it off
+    pub control_port: Option<u16>,
+    /// Fault rules active from the start
+    pub faults: Vec<FaultRule>,
+    /// Start with the seeded base, posts and products
+    pub seed: bool,
+}
+
+impl Default for StandinConfig {
+    fn default() -> Self {
+        Self {
+            host: "127.0.0.1".to_string(),
+            services: SERVICES
+                .iter()
+                .map(|(service, port)| (service.to_string(), *port))
+                .collect(),
+            control_port: Some(CONTROL_PORT),
+            faults: Vec::new(),
+            seed: true,
+        }
+    }
+}
+
+impl StandinConfig {
+    /// Every service on a free port and no control API, for tests that run
+    /// several stand-ins side by side
+    pub fn ephemeral() -> Self {
+        Self {
+            services: SERVICES
+                .iter()
+                .map(|(service, _)| (service.to_string(), 0))
+                .collect(),
+            control_port: None,
+            ..Self::default()
+        }
+    }
+}
+
+// ===== RUNNING STAND-IN =====
+
+pub struct Standin {
+    state: SharedState,
+    addrs: BTreeMap<String, SocketAddr>,
+    control_addr: Option<SocketAddr>,
+    shutdown: watch::Sender<bool>,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl Standin {
+    /// Base URL of `service`, with the trailing slash the clients expect
+    pub fn url(&self, service: &str) -> Option<String> {
+        self.addrs
+            .get(service)
+            .map(|addr| format!("http://{}/", addr))
+    }
+
+    pub fn addrs(&self) -> &BTreeMap<String, SocketAddr> {
+        &self.addrs
+    }
+
+    pub fn control_addr(&self) -> Option<SocketAddr> {
+        self.control_addr
+    }
+
+    pub fn state(&self) -> &SharedState {
+        &self.state
+    }
+
+    /// Stop every service and wait for in-flight requests to finish
+    pub async fn shutdown(self) {
+        let _ = self.shutdown.send(true);
+        for task in self.tasks {
+            let _ = task.await;
+        }
+        info!("🛑 Stand-in stopped");
+    }
+}
+
+/// Bind every configured service and the control API, then serve them in
+/// the background until `Standin::shutdown`
+pub async fn start(config: StandinConfig) -> io::Result<Standin> {
+    // Bind first, so the seeded base can point at the real ports
+    let mut listeners = Vec::new();
+    let mut addrs = BTreeMap::new();
+    for (service, port) in &config.services {
+        if !SERVICES.iter().any(|(name, _)| name == service) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("The stand-in doesn't implement {}", service),
+            ));
+        }
+        let listener = TcpListener::bind((config.host.as_str(), *port)).await?;
+        addrs.insert(service.clone(), listener.local_addr()?);
+        listeners.push((service.clone(), listener));
+    }
+
+    let dns = addrs
+        .iter()
+        .map(|(service, addr)| (service.clone(), format!("http://{}/", addr)))
+        .collect();
+    let state = StandinState::new(dns, faults::Faults::new(config.faults), config.seed);
+    let (shutdown, signal) = watch::channel(false);
+    let mut tasks = Vec::new();
+
+    for (service, listener) in listeners {
+        let router = services::router(&service, state.clone())
+            .expect("services are checked against SERVICES above");
+        info!(%service, addr = %addrs[&service], "🚀 Standing in");
+        tasks.push(serve(service, listener, router, signal.clone()));
+    }
+
+    let control_addr = match config.control_port {
+        Some(port) => {
+            let listener = TcpListener::bind((config.host.as_str(), port)).await?;
+            let addr = listener.local_addr()?;
+            info!(%addr, "🎛️ Control API listening");
+            tasks.push(serve(
+                "control".to_string(),
+                listener,
+                control_router(state.clone()),
+                signal.clone(),
+            ));
+            Some(addr)
+        }
+        None => None,
+    };
+
+    Ok(Standin {
+        state,
+        addrs,
+        control_addr,
+        shutdown,
+        tasks,
+    })
+}
+
+fn serve(
+    name: String,
+    listener: TcpListener,
+    router: Router,
+    mut signal: watch::Receiver<bool>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let stopped = async move {
+            let _ = signal.wait_for(|stop| *stop).await;
+        };
+        if let Err(e) = axum::serve(listener, router)
+            .with_graceful_shutdown(stopped)
+            .await
+        {
+            error!("❌ {} stopped serving: {}", name, e);
+        }
+    })
+}
+
+// ===== CONTROL API =====
+
+fn control_router(state: SharedState) -> Router {
+    Router::new()
+        .route(
+            "/faults",
+            get(list_faults)
+                .put(replace_faults)
+                .post(add_fault)
+                .delete(clear_faults),
+        )
+        .route("/reset", post(reset))
+        .route("/state", get(dump_state))
+        .with_state(state)
+}
+
+async fn list_faults(State(state): State<SharedState>) -> Json<Vec<FaultRule>> {
+    Json(state.faults.list())
+}
+
+async fn replace_faults(
+    State(state): State<SharedState>,
+    Json(rules): Json<Vec<FaultRule>>,
+) -> Json<Vec<FaultRule>> {
+    info!(count = rules.len(), "💥 Fault rules replaced");
+    state.faults.replace(rules);
+    Json(state.faults.list())
+}
+
+async fn add_fault(
+    State(state): State<SharedState>,
+    Json(rule): Json<FaultRule>,
+) -> Json<Vec<FaultRule>> {
+    info!(?rule, "💥 Fault rule added");
+    state.faults.push(rule);
+    Json(state.faults.list())
+}
+
+async fn clear_faults(State(state): State<SharedState>) -> Json<Vec<FaultRule>> {
+    info!("🧹 Fault rules cleared");
+    state.faults.replace(Vec::new());
+    Json(Vec::new())
+}
+
+async fn reset(State(state): State<SharedState>) -> Json<Value> {
+    state.reset();
+    info!("🧹 Store reset to its seed");
+    Json(json!({ "success": true }))
+}
+
+async fn dump_state(State(state): State<SharedState>) -> Json<Value> {
+    Json(json!(*state.store()))
+}
@@ -0,0 +1,102 @@
+// nullary-standin: run the allyabase stand-in until Ctrl-C
+//
+//   cargo run -p nullary-standin
+//   cargo run -p nullary-standin -- --only bdo,dolores --faults faults.json
+//
+// then start an app with `NULLARY_ENV=test`.
+
+use clap::Parser;
+use nullary_standin::{start, FaultRule, StandinConfig, CONTROL_PORT, SERVICES};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use tracing::{error, info};
+use tracing_subscriber::EnvFilter;
+
+#[derive(Parser)]
+#[command(
+    name = "nullary-standin",
+    version,
+    about = "In-memory stand-in for the allyabase services on the test ports"
+)]
+struct Args {
+    /// Address to bind every service to
+    #[arg(long, default_value = "127.0.0.1")]
+    host: String,
+
+    /// Only stand in for these services (comma separated)
+    #[arg(long, value_delimiter = ',')]
+    only: Vec<String>,
+
+    /// JSON file with an array of fault rules to start with
+    #[arg(long)]
+    faults: Option<PathBuf>,
+
+    /// Port of the control API
+    #[arg(long, default_value_t = CONTROL_PORT)]
+    control_port: u16,
+
+    /// Start empty instead of with the seeded base, posts and products
+    #[arg(long)]
+    no_seed: bool,
+}
+
+fn load_faults(path: &Path) -> Result<Vec<FaultRule>, String> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&contents)
+        .map_err(|e| format!("Failed to parse fault rules in {}: {}", path.display(), e))
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+        )
+        .init();
+
+    let args = Args::parse();
+    let faults = match args.faults.as_deref().map(load_faults).transpose() {
+        Ok(faults) => faults.unwrap_or_default(),
+        Err(e) => {
+            error!("❌ {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let services = SERVICES
+        .iter()
+        .filter(|(service, _)| args.only.is_empty() || args.only.iter().any(|s| s == service))
+        .map(|(service, port)| (service.to_string(), *port))
+        .collect::<Vec<_>>();
+    if let Some(unknown) = args
+        .only
+        .iter()
+        .find(|s| !SERVICES.iter().any(|(service, _)| service == s))
+    {
+        error!("❌ The stand-in doesn't implement {}", unknown);
+        return ExitCode::FAILURE;
+    }
+
+    let config = StandinConfig {
+        host: args.host,
+        services,
+        control_port: Some(args.control_port),
+        faults,
+        seed: !args.no_seed,
+    };
+    let standin = match start(config).await {
+        Ok(standin) => standin,
+        Err(e) => {
+            error!("❌ Failed to start the stand-in: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    info!("✅ Stand-in ready; run the apps with NULLARY_ENV=test");
+
+    if let Err(e) = tokio::signal::ctrl_c().await {
+        error!("❌ Failed to wait for Ctrl-C: {}", e);
+    }
+    standin.shutdown().await;
+    ExitCode::SUCCESS
+}
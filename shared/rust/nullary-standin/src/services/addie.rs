@@ -0,0 +1,62 @@
+// Addie: payment users and stand-in payment intents
+//
+// No processor is contacted; an intent is a made-up id the app can show.
+
+use axum::extract::{Path, State};
+use axum::routing::put;
+use axum::{Json, Router};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tracing::info;
+
+use super::{create_user_route, get_user_route};
+use crate::auth::verify_user;
+use crate::error::ApiResult;
+use crate::state::{new_uuid, SharedState};
+
+const SERVICE: &str = "addie";
+
+pub fn routes() -> Router<SharedState> {
+    Router::new()
+        .route("/user/create", create_user_route(SERVICE, || json!({})))
+        .route("/user/:uuid", get_user_route(SERVICE))
+        .route("/user/:uuid/processor/:processor/intent", put(intent))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IntentBody {
+    timestamp: String,
+    amount: Value,
+    currency: String,
+    signature: String,
+}
+
+// Signs timestamp + uuid + amount + currency
+async fn intent(
+    State(state): State<SharedState>,
+    Path((uuid, processor)): Path<(String, String)>,
+    Json(body): Json<IntentBody>,
+) -> ApiResult<Json<Value>> {
+    let amount = match &body.amount {
+        Value::String(amount) => amount.clone(),
+        amount => amount.to_string(),
+    };
+    verify_user(
+        &state.store(),
+        SERVICE,
+        &uuid,
+        &body.timestamp,
+        &body.signature,
+        &[&amount, &body.currency],
+    )?;
+
+    let id = new_uuid().replace('-', "");
+    info!(%uuid, %processor, %amount, "💳 Payment intent");
+    Ok(Json(json!({
+        "paymentIntent": format!("pi_standin_{}_secret_{}", id, &id[..8]),
+        "ephemeralKey": format!("ek_standin_{}", id),
+        "customer": format!("cus_standin_{}", &uuid[..8.min(uuid.len())]),
+        "publishableKey": "pk_test_standin"
+    })))
+}
@@ -0,0 +1,210 @@
+// BDO: big dumb objects, bases and teleportation
+//
+// A BDO user is keyed by public key and hash, so one key can hold a BDO per
+// app. Everyone sees the seeded bases until they save their own.
+
+use axum::extract::{Path, Query, State};
+use axum::routing::{get, put};
+use axum::{Json, Router};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tracing::info;
+
+use crate::auth::{self, verify_user};
+use crate::error::{ApiError, ApiResult};
+use crate::state::SharedState;
+
+const SERVICE: &str = "bdo";
+
+pub fn routes() -> Router<SharedState> {
+    Router::new()
+        .route("/user/create", put(create_user))
+        .route("/user/:uuid/bdo", get(get_bdo).put(put_bdo))
+        .route("/user/:uuid/bases", get(get_bases).put(put_bases))
+        .route("/user/:uuid/teleport", get(teleport))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CreateBody {
+    timestamp: String,
+    pub_key: String,
+    hash: String,
+    #[serde(default)]
+    bdo: Value,
+    signature: String,
+}
+
+// Signs timestamp + pubKey + hash
+async fn create_user(
+    State(state): State<SharedState>,
+    Json(body): Json<CreateBody>,
+) -> ApiResult<Json<Value>> {
+    auth::verify(
+        &body.pub_key,
+        &auth::message(&body.timestamp, &[&body.pub_key, &body.hash]),
+        &body.signature,
+    )?;
+
+    let mut store = state.store();
+    let user = store.user_for_key(SERVICE, &body.pub_key, Some(&body.hash), json!({}));
+    let bdo = store
+        .bdos
+        .entry(user.uuid.clone())
+        .or_insert(body.bdo)
+        .clone();
+    info!(uuid = %user.uuid, hash = %body.hash, "👤 BDO user registered");
+    Ok(Json(
+        json!({ "uuid": user.uuid, "pubKey": user.pub_key, "hash": body.hash, "bdo": bdo }),
+    ))
+}
+
+/// Query of the signed BDO GETs
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HashQuery {
+    timestamp: String,
+    hash: String,
+    signature: String,
+    /// Someone else's public key, to read the BDO they made public
+    #[serde(default)]
+    pub_key: Option<String>,
+    #[serde(default)]
+    url: Option<String>,
+}
+
+// Signs timestamp + uuid + hash
+async fn get_bdo(
+    State(state): State<SharedState>,
+    Path(uuid): Path<String>,
+    Query(query): Query<HashQuery>,
+) -> ApiResult<Json<Value>> {
+    let store = state.store();
+    verify_user(
+        &store,
+        SERVICE,
+        &uuid,
+        &query.timestamp,
+        &query.signature,
+        &[&query.hash],
+    )?;
+
+    let bdo = match &query.pub_key {
+        Some(pub_key) => store
+            .public_bdos
+            .get(pub_key)
+            .cloned()
+            .ok_or_else(|| ApiError::not_found(format!("No public BDO for {}", pub_key)))?,
+        None => store.bdos.get(&uuid).cloned().unwrap_or_else(|| json!({})),
+    };
+    Ok(Json(json!({ "uuid": uuid, "bdo": bdo })))
+}
+
+#[derive(Deserialize)]
+struct PutBdoBody {
+    timestamp: String,
+    hash: String,
+    bdo: Value,
+    signature: String,
+    /// Also publish the BDO under the user's public key
+    #[serde(default, rename = "pub")]
+    public: bool,
+}
+
+// Signs timestamp + uuid + hash
+async fn put_bdo(
+    State(state): State<SharedState>,
+    Path(uuid): Path<String>,
+    Json(body): Json<PutBdoBody>,
+) -> ApiResult<Json<Value>> {
+    let mut store = state.store();
+    let user = verify_user(
+        &store,
+        SERVICE,
+        &uuid,
+        &body.timestamp,
+        &body.signature,
+        &[&body.hash],
+    )?;
+
+    if body.public {
+        store.public_bdos.insert(user.pub_key, body.bdo.clone());
+    }
+    store.bdos.insert(uuid.clone(), body.bdo.clone());
+    Ok(Json(json!({ "uuid": uuid, "bdo": body.bdo })))
+}
+
+// Signs timestamp + uuid + hash
+async fn get_bases(
+    State(state): State<SharedState>,
+    Path(uuid): Path<String>,
+    Query(query): Query<HashQuery>,
+) -> ApiResult<Json<Value>> {
+    let store = state.store();
+    verify_user(
+        &store,
+        SERVICE,
+        &uuid,
+        &query.timestamp,
+        &query.signature,
+        &[&query.hash],
+    )?;
+
+    let bases = store
+        .bases
+        .get(&uuid)
+        .unwrap_or(&store.default_bases)
+        .clone();
+    Ok(Json(bases))
+}
+
+#[derive(Deserialize)]
+struct PutBasesBody {
+    timestamp: String,
+    hash: String,
+    bases: Value,
+    signature: String,
+}
+
+// Signs timestamp + uuid + hash
+async fn put_bases(
+    State(state): State<SharedState>,
+    Path(uuid): Path<String>,
+    Json(body): Json<PutBasesBody>,
+) -> ApiResult<Json<Value>> {
+    let mut store = state.store();
+    verify_user(
+        &store,
+        SERVICE,
+        &uuid,
+        &body.timestamp,
+        &body.signature,
+        &[&body.hash],
+    )?;
+
+    store.bases.insert(uuid, body.bases.clone());
+    Ok(Json(body.bases))
+}
+
+// Signs timestamp + uuid + hash. The real service fetches the page and
+// checks its teleport tag; the stand-in never goes out, so nothing is
+// teleported.
+async fn teleport(
+    State(state): State<SharedState>,
+    Path(uuid): Path<String>,
+    Query(query): Query<HashQuery>,
+) -> ApiResult<Json<Value>> {
+    verify_user(
+        &state.store(),
+        SERVICE,
+        &uuid,
+        &query.timestamp,
+        &query.signature,
+        &[&query.hash],
+    )?;
+
+    let url = query
+        .url
+        .ok_or_else(|| ApiError::bad_request("Missing url to teleport"))?;
+    Ok(Json(json!({ "url": url, "valid": false, "html": "" })))
+}
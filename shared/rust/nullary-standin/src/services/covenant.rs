@@ -0,0 +1,288 @@
+// Covenant: contracts and step signatures
+//
+// Covenant requests carry `userUUID` and `pubKey` in the body rather than
+// going through `/user/create`. A step signature is stored but not checked:
+// the app signs it with a timestamp it doesn't send.
+
+use axum::extract::{Path, Query, State};
+use axum::http::header;
+use axum::response::IntoResponse;
+use axum::routing::{get, post, put};
+use axum::{Json, Router};
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use tracing::info;
+
+use crate::auth;
+use crate::error::{ApiError, ApiResult};
+use crate::state::{new_uuid, SharedState};
+
+pub fn routes() -> Router<SharedState> {
+    Router::new()
+        .route("/contract", post(create_contract))
+        .route("/contracts", get(list_contracts))
+        .route("/contract/:uuid", get(get_contract))
+        .route("/contract/:uuid/sign", put(sign_step))
+        .route("/contract/:uuid/svg", get(contract_svg))
+}
+
+fn now_rfc3339() -> String {
+    chrono::Utc::now().to_rfc3339()
+}
+
+#[derive(Deserialize)]
+struct NewStep {
+    description: String,
+    #[serde(default)]
+    order: u32,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CreateBody {
+    timestamp: String,
+    #[serde(rename = "userUUID")]
+    user_uuid: String,
+    pub_key: String,
+    signature: String,
+    title: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    participants: Vec<String>,
+    #[serde(default)]
+    steps: Vec<NewStep>,
+}
+
+// Signs timestamp + userUUID
+async fn create_contract(
+    State(state): State<SharedState>,
+    Json(body): Json<CreateBody>,
+) -> ApiResult<Json<Value>> {
+    auth::verify(
+        &body.pub_key,
+        &auth::message(&body.timestamp, &[&body.user_uuid]),
+        &body.signature,
+    )?;
+
+    let now = now_rfc3339();
+    let mut participants = body.participants;
+    if !participants.contains(&body.user_uuid) {
+        participants.insert(0, body.user_uuid.clone());
+    }
+    let signatures: Map<String, Value> = participants
+        .iter()
+        .map(|participant| (participant.clone(), Value::Null))
+        .collect();
+    let steps: Vec<Value> = body
+        .steps
+        .into_iter()
+        .map(|step| {
+            json!({
+                "id": new_uuid(),
+                "description": step.description,
+                "order": step.order,
+                "completed": false,
+                "signatures": signatures,
+                "created_at": now,
+                "completed_at": null
+            })
+        })
+        .collect();
+
+    let uuid = new_uuid();
+    let contract = json!({
+        "uuid": uuid,
+        "title": body.title,
+        "description": body.description,
+        "participants": participants,
+        "steps": steps,
+        "creator": body.user_uuid,
+        "pubKey": body.pub_key,
+        "status": "active",
+        "created_at": now,
+        "updated_at": now,
+        "bdoLocation": null
+    });
+    state.store().contracts.insert(uuid.clone(), contract);
+    info!(%uuid, "📜 Contract created");
+    Ok(Json(json!({ "success": true, "data": { "uuid": uuid } })))
+}
+
+/// A contract as `GET /contracts` lists it, with step counts instead of steps
+fn summary(contract: &Value) -> Value {
+    let steps = contract["steps"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or(&[]);
+    let completed = steps
+        .iter()
+        .filter(|step| step["completed"].as_bool().unwrap_or(false))
+        .count();
+    json!({
+        "uuid": contract["uuid"],
+        "title": contract["title"],
+        "description": contract["description"],
+        "participants": contract["participants"],
+        "creator": contract["creator"],
+        "status": contract["status"],
+        "createdAt": contract["created_at"],
+        "stepCount": steps.len(),
+        "completedSteps": completed,
+        "pubKey": contract["pubKey"]
+    })
+}
+
+// Public, like the real service
+async fn list_contracts(State(state): State<SharedState>) -> Json<Value> {
+    let contracts: Vec<Value> = state.store().contracts.values().map(summary).collect();
+    Json(json!({ "success": true, "data": contracts }))
+}
+
+// Public, like the real service
+async fn get_contract(
+    State(state): State<SharedState>,
+    Path(uuid): Path<String>,
+) -> ApiResult<Json<Value>> {
+    state
+        .store()
+        .contracts
+        .get(&uuid)
+        .cloned()
+        .map(Json)
+        .ok_or_else(|| ApiError::not_found(format!("No contract {}", uuid)))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SignBody {
+    timestamp: String,
+    #[serde(rename = "userUUID")]
+    user_uuid: String,
+    pub_key: String,
+    signature: String,
+    #[serde(rename = "step_id")]
+    step_id: String,
+    #[serde(rename = "step_signature")]
+    step_signature: String,
+}
+
+// Signs timestamp + userUUID + contract uuid
+async fn sign_step(
+    State(state): State<SharedState>,
+    Path(uuid): Path<String>,
+    Json(body): Json<SignBody>,
+) -> ApiResult<Json<Value>> {
+    auth::verify(
+        &body.pub_key,
+        &auth::message(&body.timestamp, &[&body.user_uuid, &uuid]),
+        &body.signature,
+    )?;
+
+    let mut store = state.store();
+    let contract = store
+        .contracts
+        .get_mut(&uuid)
+        .ok_or_else(|| ApiError::not_found(format!("No contract {}", uuid)))?;
+    if contract["participants"]
+        .as_array()
+        .is_none_or(|participants| !participants.contains(&json!(body.user_uuid)))
+    {
+        return Err(ApiError::auth("Not a participant of this contract"));
+    }
+
+    let now = now_rfc3339();
+    let step = contract["steps"]
+        .as_array_mut()
+        .and_then(|steps| {
+            steps
+                .iter_mut()
+                .find(|step| step["id"] == json!(body.step_id))
+        })
+        .ok_or_else(|| ApiError::not_found(format!("No step {}", body.step_id)))?;
+    step["signatures"][&body.user_uuid] = json!({
+        "signature": body.step_signature,
+        "timestamp": body.timestamp,
+        "pubKey": body.pub_key,
+        "message": "",
+        "signed_at": now
+    });
+    let all_signed = step["signatures"]
+        .as_object()
+        .is_some_and(|signatures| signatures.values().all(|s| !s.is_null()));
+    if all_signed {
+        step["completed"] = json!(true);
+        step["completed_at"] = json!(now);
+    }
+
+    let all_completed = contract["steps"]
+        .as_array()
+        .is_some_and(|steps| steps.iter().all(|step| step["completed"] == json!(true)));
+    if all_completed {
+        contract["status"] = json!("completed");
+    }
+    contract["updated_at"] = json!(now);
+
+    info!(%uuid, step = %body.step_id, user = %body.user_uuid, "✍️ Step signed");
+    Ok(Json(json!({
+        "success": true,
+        "data": { "stepCompleted": all_signed, "contractCompleted": all_completed }
+    })))
+}
+
+#[derive(Deserialize)]
+struct SvgQuery {
+    #[serde(default)]
+    theme: Option<String>,
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// Public, like the real service; one row per step, green once completed
+async fn contract_svg(
+    State(state): State<SharedState>,
+    Path(uuid): Path<String>,
+    Query(query): Query<SvgQuery>,
+) -> ApiResult<impl IntoResponse> {
+    let store = state.store();
+    let contract = store
+        .contracts
+        .get(&uuid)
+        .ok_or_else(|| ApiError::not_found(format!("No contract {}", uuid)))?;
+
+    let (background, foreground) = match query.theme.as_deref() {
+        Some("dark") => ("#1a1a2e", "#ffffff"),
+        _ => ("#ffffff", "#1a1a2e"),
+    };
+    let steps = contract["steps"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or(&[]);
+    let height = 60 + steps.len() * 40;
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="600" height="{height}"><rect width="600" height="{height}" fill="{background}"/><text x="20" y="35" fill="{foreground}" font-size="20">{}</text>"#,
+        escape(contract["title"].as_str().unwrap_or_default())
+    );
+    for (index, step) in steps.iter().enumerate() {
+        let y = 60 + index * 40;
+        let fill = if step["completed"] == json!(true) {
+            "#10b981"
+        } else {
+            "#9ca3af"
+        };
+        svg.push_str(&format!(
+            r#"<circle cx="30" cy="{}" r="8" fill="{fill}"/><text x="50" y="{}" fill="{foreground}" font-size="14">{}</text>"#,
+            y + 15,
+            y + 20,
+            escape(step["description"].as_str().unwrap_or_default())
+        ));
+    }
+    svg.push_str("</svg>");
+
+    Ok(([(header::CONTENT_TYPE, "image/svg+xml")], svg))
+}
@@ -0,0 +1,127 @@
+// Dolores: posts and tag feeds
+//
+// A feed is every post sharing a tag with the request, newest first, split
+// the way Dolores splits it.
+
+use axum::extract::{Path, Query, State};
+use axum::routing::{get, put};
+use axum::{Json, Router};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tracing::info;
+
+use super::create_user_route;
+use crate::auth::verify_user;
+use crate::error::ApiResult;
+use crate::state::{new_uuid, now_ms, SharedState};
+
+const SERVICE: &str = "dolores";
+
+pub fn routes() -> Router<SharedState> {
+    Router::new()
+        .route("/user/create", create_user_route(SERVICE, || json!({})))
+        .route("/user/:uuid/feed", get(feed))
+        .route("/user/:uuid/post", put(add_post))
+}
+
+#[derive(Deserialize)]
+struct FeedQuery {
+    timestamp: String,
+    #[serde(default)]
+    tags: String,
+    signature: String,
+}
+
+fn has_tag(post: &Value, tags: &[&str]) -> bool {
+    tags.is_empty()
+        || post["tags"].as_array().is_some_and(|post_tags| {
+            post_tags
+                .iter()
+                .filter_map(|tag| tag.as_str())
+                .any(|tag| tags.contains(&tag))
+        })
+}
+
+// Signs timestamp + uuid + tags
+async fn feed(
+    State(state): State<SharedState>,
+    Path(uuid): Path<String>,
+    Query(query): Query<FeedQuery>,
+) -> ApiResult<Json<Value>> {
+    let store = state.store();
+    verify_user(
+        &store,
+        SERVICE,
+        &uuid,
+        &query.timestamp,
+        &query.signature,
+        &[&query.tags],
+    )?;
+
+    let tags: Vec<&str> = query
+        .tags
+        .split(',')
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .collect();
+    let mut posts: Vec<Value> = store
+        .posts
+        .iter()
+        .filter(|post| has_tag(post, &tags))
+        .cloned()
+        .collect();
+    posts.sort_by_key(|post| std::cmp::Reverse(post["timestamp"].as_i64().unwrap_or(0)));
+
+    let is_video = |post: &&Value| post.get("url").is_some() || post.get("video_url").is_some();
+    let has_images = |post: &&Value| post["images"].as_array().is_some_and(|i| !i.is_empty());
+    let video_posts: Vec<&Value> = posts.iter().filter(is_video).collect();
+    let pic_posts: Vec<&Value> = posts
+        .iter()
+        .filter(|post| !is_video(post) && has_images(post))
+        .collect();
+    let generic_posts: Vec<&Value> = posts
+        .iter()
+        .filter(|post| !is_video(post) && !has_images(post))
+        .collect();
+
+    Ok(Json(json!({
+        "videoPosts": video_posts,
+        "picPosts": pic_posts,
+        "genericPosts": generic_posts,
+        "allPosts": posts
+    })))
+}
+
+#[derive(Deserialize)]
+struct PostBody {
+    timestamp: String,
+    post: Value,
+    signature: String,
+}
+
+// Signs timestamp + uuid
+async fn add_post(
+    State(state): State<SharedState>,
+    Path(uuid): Path<String>,
+    Json(body): Json<PostBody>,
+) -> ApiResult<Json<Value>> {
+    let mut store = state.store();
+    verify_user(
+        &store,
+        SERVICE,
+        &uuid,
+        &body.timestamp,
+        &body.signature,
+        &[],
+    )?;
+
+    let mut post = body.post;
+    if let Value::Object(fields) = &mut post {
+        fields.entry("uuid").or_insert_with(|| json!(new_uuid()));
+        fields.entry("author").or_insert_with(|| json!(uuid));
+        fields.entry("timestamp").or_insert_with(|| json!(now_ms()));
+    }
+    info!(%uuid, "📝 Post added");
+    store.posts.push(post.clone());
+    Ok(Json(post))
+}
@@ -0,0 +1,45 @@
+// Fount: MP and nineum users
+//
+// Users start with no nineum; nothing is granted or transferred.
+
+use axum::extract::{Path, Query, State};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde_json::{json, Value};
+
+use super::{create_user_route, get_user_route, SignedQuery};
+use crate::auth::verify_user;
+use crate::error::ApiResult;
+use crate::state::SharedState;
+
+const SERVICE: &str = "fount";
+
+pub fn routes() -> Router<SharedState> {
+    Router::new()
+        .route(
+            "/user/create",
+            create_user_route(
+                SERVICE,
+                || json!({ "mp": 0, "ordinal": 0, "nineumCount": 0, "experience": 0 }),
+            ),
+        )
+        .route("/user/:uuid", get_user_route(SERVICE))
+        .route("/user/:uuid/nineum", get(nineum))
+}
+
+// Signs timestamp + uuid
+async fn nineum(
+    State(state): State<SharedState>,
+    Path(uuid): Path<String>,
+    Query(query): Query<SignedQuery>,
+) -> ApiResult<Json<Value>> {
+    verify_user(
+        &state.store(),
+        SERVICE,
+        &uuid,
+        &query.timestamp,
+        &query.signature,
+        &[],
+    )?;
+    Ok(Json(json!({ "nineum": [] })))
+}
@@ -0,0 +1,298 @@
+// Julia: users, association prompts and messages
+//
+// Association follows the prompt flow stackchat uses: one user asks for a
+// prompt, signs it, and the other user associates with the signed prompt.
+// Both users then hold each other's key in `keys.interactingKeys`, and only
+// associated users can message each other.
+
+use axum::extract::{Path, Query, State};
+use axum::routing::{get, post, put};
+use axum::{Json, Router};
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tracing::info;
+
+use super::{get_user_route, SignedQuery};
+use crate::auth::{self, verify_user};
+use crate::error::{ApiError, ApiResult};
+use crate::state::{now_ms, SharedState, Store};
+
+const SERVICE: &str = "julia";
+
+pub fn routes() -> Router<SharedState> {
+    Router::new()
+        .route("/user/create", put(create_user))
+        .route("/user/:uuid", get_user_route(SERVICE))
+        .route("/user/:uuid/associate/prompt", get(get_prompt))
+        .route(
+            "/user/:uuid/associate/signedPrompt",
+            post(sign_prompt).put(sign_prompt),
+        )
+        .route("/user/:uuid/associate", post(associate).put(associate))
+        .route("/message", post(post_message))
+        .route("/messages/user/:uuid", get(get_messages))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CreateBody {
+    timestamp: String,
+    pub_key: String,
+    #[serde(default)]
+    user: Value,
+    signature: String,
+}
+
+// Signs timestamp + pubKey
+async fn create_user(
+    State(state): State<SharedState>,
+    Json(body): Json<CreateBody>,
+) -> ApiResult<Json<Value>> {
+    auth::verify(
+        &body.pub_key,
+        &auth::message(&body.timestamp, &[&body.pub_key]),
+        &body.signature,
+    )?;
+
+    let mut data = match body.user {
+        Value::Object(user) => user,
+        _ => Default::default(),
+    };
+    data.insert(
+        "keys".to_string(),
+        json!({ "interactingKeys": {}, "coordinatingKeys": {} }),
+    );
+    data.insert("pendingPrompts".to_string(), json!({}));
+
+    let user = state
+        .store()
+        .user_for_key(SERVICE, &body.pub_key, None, Value::Object(data));
+    info!(uuid = %user.uuid, "👤 Julia user registered");
+    Ok(Json(user.to_json()))
+}
+
+// Signs timestamp + uuid
+async fn get_prompt(
+    State(state): State<SharedState>,
+    Path(uuid): Path<String>,
+    Query(query): Query<SignedQuery>,
+) -> ApiResult<Json<Value>> {
+    let mut store = state.store();
+    verify_user(
+        &store,
+        SERVICE,
+        &uuid,
+        &query.timestamp,
+        &query.signature,
+        &[],
+    )?;
+
+    let prompt: String = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(4)
+        .map(char::from)
+        .collect::<String>()
+        .to_uppercase();
+    let timestamp = now_ms().to_string();
+
+    let user = store.user_mut(SERVICE, &uuid)?;
+    user.data["pendingPrompts"][&timestamp] =
+        json!({ "timestamp": timestamp, "prompter": uuid, "prompt": prompt });
+    Ok(Json(user.to_json()))
+}
+
+/// A prompt as Julia passes it around, signed by the user who will use it
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SignedPrompt {
+    timestamp: String,
+    prompter: Option<String>,
+    prompt: Option<String>,
+    new_timestamp: Option<String>,
+    #[serde(rename = "newUUID")]
+    new_uuid: Option<String>,
+    new_pub_key: Option<String>,
+    new_signature: Option<String>,
+}
+
+impl SignedPrompt {
+    /// Check newSignature over newTimestamp + newUUID + newPubKey + prompt
+    fn verify(&self) -> ApiResult<(String, String, String)> {
+        let missing = || ApiError::bad_request("Prompt is missing its signed fields");
+        let prompt = self.prompt.clone().ok_or_else(missing)?;
+        let new_timestamp = self.new_timestamp.as_deref().ok_or_else(missing)?;
+        let new_uuid = self.new_uuid.clone().ok_or_else(missing)?;
+        let new_pub_key = self.new_pub_key.clone().ok_or_else(missing)?;
+        let new_signature = self.new_signature.as_deref().ok_or_else(missing)?;
+
+        auth::verify(
+            &new_pub_key,
+            &auth::message(new_timestamp, &[&new_uuid, &new_pub_key, &prompt]),
+            new_signature,
+        )?;
+        Ok((prompt, new_uuid, new_pub_key))
+    }
+}
+
+#[derive(Deserialize)]
+struct PromptBody {
+    timestamp: String,
+    signature: String,
+    prompt: SignedPrompt,
+}
+
+fn pending_prompt<'a>(store: &'a Store, prompter: &str, id: &str) -> ApiResult<&'a Value> {
+    let pending = &store.user(SERVICE, prompter)?.data["pendingPrompts"][id];
+    if pending.is_null() {
+        return Err(ApiError::not_found(format!("No pending prompt {}", id)));
+    }
+    Ok(pending)
+}
+
+// Signs timestamp + uuid + prompt; stores the prompt's own signature so the
+// other user can associate with it
+async fn sign_prompt(
+    State(state): State<SharedState>,
+    Path(uuid): Path<String>,
+    Json(body): Json<PromptBody>,
+) -> ApiResult<Json<Value>> {
+    let mut store = state.store();
+    let prompt_text = body.prompt.prompt.clone().unwrap_or_default();
+    verify_user(
+        &store,
+        SERVICE,
+        &uuid,
+        &body.timestamp,
+        &body.signature,
+        &[&prompt_text],
+    )?;
+
+    let prompter = body.prompt.prompter.clone().unwrap_or_else(|| uuid.clone());
+    let mut pending = pending_prompt(&store, &prompter, &body.prompt.timestamp)?.clone();
+    if body.prompt.new_signature.is_some() {
+        let (_, new_uuid, new_pub_key) = body.prompt.verify()?;
+        pending["newTimestamp"] = json!(body.prompt.new_timestamp);
+        pending["newUUID"] = json!(new_uuid);
+        pending["newPubKey"] = json!(new_pub_key);
+        pending["newSignature"] = json!(body.prompt.new_signature);
+    }
+    store.user_mut(SERVICE, &prompter)?.data["pendingPrompts"][&body.prompt.timestamp] =
+        pending.clone();
+    Ok(Json(json!({ "success": true, "prompt": pending })))
+}
+
+fn add_interacting_key(store: &mut Store, uuid: &str, other: &str, pub_key: &str) -> ApiResult<()> {
+    let user = store.user_mut(SERVICE, uuid)?;
+    user.data["keys"]["interactingKeys"][other] = json!(pub_key);
+    Ok(())
+}
+
+// Signs timestamp + uuid; the prompt carries the prompter's signature
+async fn associate(
+    State(state): State<SharedState>,
+    Path(uuid): Path<String>,
+    Json(body): Json<PromptBody>,
+) -> ApiResult<Json<Value>> {
+    let mut store = state.store();
+    let user = verify_user(
+        &store,
+        SERVICE,
+        &uuid,
+        &body.timestamp,
+        &body.signature,
+        &[],
+    )?;
+    let (prompt, partner, partner_key) = body.prompt.verify()?;
+
+    let prompter = body
+        .prompt
+        .prompter
+        .clone()
+        .unwrap_or_else(|| partner.clone());
+    let pending = pending_prompt(&store, &prompter, &body.prompt.timestamp)?;
+    if pending["prompt"] != json!(prompt) {
+        return Err(ApiError::auth("Prompt does not match"));
+    }
+    store.user(SERVICE, &partner)?;
+
+    add_interacting_key(&mut store, &uuid, &partner, &partner_key)?;
+    add_interacting_key(&mut store, &partner, &uuid, &user.pub_key)?;
+    if let Some(pending) =
+        store.user_mut(SERVICE, &prompter)?.data["pendingPrompts"].as_object_mut()
+    {
+        pending.remove(&body.prompt.timestamp);
+    }
+    info!(%uuid, %partner, "🤝 Users associated");
+    Ok(Json(store.user(SERVICE, &uuid)?.to_json()))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MessageBody {
+    timestamp: String,
+    #[serde(rename = "senderUUID")]
+    sender_uuid: String,
+    #[serde(rename = "receiverUUID")]
+    receiver_uuid: String,
+    message: String,
+    signature: String,
+}
+
+// Signs timestamp + senderUUID + receiverUUID + message
+async fn post_message(
+    State(state): State<SharedState>,
+    Json(body): Json<MessageBody>,
+) -> ApiResult<Json<Value>> {
+    let mut store = state.store();
+    let sender = verify_user(
+        &store,
+        SERVICE,
+        &body.sender_uuid,
+        &body.timestamp,
+        &body.signature,
+        &[&body.receiver_uuid, &body.message],
+    )?;
+    let receiver = store.user(SERVICE, &body.receiver_uuid)?;
+    if receiver.data["keys"]["interactingKeys"][&body.sender_uuid].is_null() {
+        return Err(ApiError::auth("Sender is not associated with the receiver"));
+    }
+
+    let message = json!({
+        "timestamp": body.timestamp,
+        "senderUUID": body.sender_uuid,
+        "receiverUUID": body.receiver_uuid,
+        "message": body.message,
+        "senderPubKey": sender.pub_key,
+        "signature": body.signature
+    });
+    store.messages.push(message);
+    Ok(Json(json!({ "success": true })))
+}
+
+// Signs timestamp + uuid
+async fn get_messages(
+    State(state): State<SharedState>,
+    Path(uuid): Path<String>,
+    Query(query): Query<SignedQuery>,
+) -> ApiResult<Json<Value>> {
+    let store = state.store();
+    verify_user(
+        &store,
+        SERVICE,
+        &uuid,
+        &query.timestamp,
+        &query.signature,
+        &[],
+    )?;
+
+    let messages: Vec<&Value> = store
+        .messages
+        .iter()
+        .filter(|message| {
+            message["receiverUUID"] == json!(uuid) || message["senderUUID"] == json!(uuid)
+        })
+        .collect();
+    Ok(Json(json!({ "messages": messages })))
+}
@@ -0,0 +1,163 @@
+// Stood-in services
+//
+// Each service gets its own router on its own port, behind the fault
+// middleware and the ETag middleware. Routes follow the allyabase service that is stood in, with
+// the same signed message layouts; only what the Nullary clients call is
+// implemented.
+
+use axum::extract::{Path, Query, State};
+use axum::http::header::HeaderName;
+use axum::middleware;
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tracing::info;
+
+use crate::auth::{self, verify_user};
+use crate::conditional::etag;
+use crate::error::ApiResult;
+use crate::faults::{inject, FaultContext};
+use crate::state::SharedState;
+
+mod addie;
+mod bdo;
+mod covenant;
+mod dolores;
+mod fount;
+mod julia;
+mod prof;
+mod sanora;
+
+/// Services the stand-in implements, on the ports of the `test` environment
+/// in nullary-core's services.rs
+pub const SERVICES: [(&str, u16); 8] = [
+    ("julia", 5111),
+    ("bdo", 5114),
+    ("addie", 5116),
+    ("fount", 5117),
+    ("dolores", 5118),
+    ("sanora", 5121),
+    ("covenant", 5122),
+    ("prof", 5123),
+];
+
+const VERSION_HEADER: HeaderName = HeaderName::from_static("x-service-version");
+
+/// Router for `service`, or `None` if the stand-in doesn't implement it
+pub fn router(service: &str, state: SharedState) -> Option<Router> {
+    let (service, _) = SERVICES.iter().find(|(name, _)| *name == service)?;
+    let routes = match *service {
+        "addie" => addie::routes(),
+        "bdo" => bdo::routes(),
+        "covenant" => covenant::routes(),
+        "dolores" => dolores::routes(),
+        "fount" => fount::routes(),
+        "julia" => julia::routes(),
+        "prof" => prof::routes(),
+        "sanora" => sanora::routes(),
+        _ => return None,
+    };
+
+    let context = FaultContext {
+        state: state.clone(),
+        service,
+    };
+    Some(
+        routes
+            .route("/", get(move || root(service)))
+            .layer(middleware::from_fn(etag))
+            .layer(middleware::from_fn_with_state(context, inject))
+            .with_state(state),
+    )
+}
+
+// What the health probe reads: a version header and a JSON body
+async fn root(service: &'static str) -> impl IntoResponse {
+    let version = env!("CARGO_PKG_VERSION");
+    (
+        [(VERSION_HEADER, format!("stand-in-{}", version))],
+        Json(json!({ "service": service, "version": version, "standIn": true })),
+    )
+}
+
+// ===== SHARED ROUTES =====
+
+/// `PUT /user/create` body for services keyed only by public key
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateUser {
+    pub timestamp: String,
+    pub pub_key: String,
+    pub signature: String,
+}
+
+/// Query of a signed GET: `?timestamp=...&signature=...`
+#[derive(Deserialize)]
+pub struct SignedQuery {
+    pub timestamp: String,
+    pub signature: String,
+}
+
+/// Signs `timestamp + pubKey`; registering the same key again returns the
+/// existing user
+pub async fn create_user(
+    service: &'static str,
+    state: SharedState,
+    body: CreateUser,
+    data: Value,
+) -> ApiResult<Json<Value>> {
+    auth::verify(
+        &body.pub_key,
+        &auth::message(&body.timestamp, &[&body.pub_key]),
+        &body.signature,
+    )?;
+    let user = state
+        .store()
+        .user_for_key(service, &body.pub_key, None, data);
+    info!(service, uuid = %user.uuid, "👤 User registered");
+    Ok(Json(user.to_json()))
+}
+
+/// `GET /user/:uuid`, signed `timestamp + uuid`
+pub async fn get_user(
+    service: &'static str,
+    state: SharedState,
+    uuid: String,
+    query: SignedQuery,
+) -> ApiResult<Json<Value>> {
+    let store = state.store();
+    let user = verify_user(
+        &store,
+        service,
+        &uuid,
+        &query.timestamp,
+        &query.signature,
+        &[],
+    )?;
+    Ok(Json(user.to_json()))
+}
+
+/// Handler for `PUT /user/create` on a service keyed only by public key
+pub fn create_user_route(
+    service: &'static str,
+    data: fn() -> Value,
+) -> axum::routing::MethodRouter<SharedState> {
+    axum::routing::put(
+        move |State(state): State<SharedState>, Json(body): Json<CreateUser>| async move {
+            create_user(service, state, body, data()).await
+        },
+    )
+}
+
+/// Handler for `GET /user/:uuid`
+pub fn get_user_route(service: &'static str) -> axum::routing::MethodRouter<SharedState> {
+    get(
+        move |State(state): State<SharedState>,
+              Path(uuid): Path<String>,
+              Query(query): Query<SignedQuery>| async move {
+            get_user(service, state, uuid, query).await
+        },
+    )
+}
@@ -0,0 +1,208 @@
+// Prof: profiles
+//
+// Prof has no `/user/create`; a profile's uuid is bound to the public key
+// that first writes it, and every later request must be signed by that key.
+// Profiles arrive as multipart (`profile` or `profileData` JSON, `timestamp`,
+// `signature`, `pubKey`, optional `image`) or as the same fields in JSON.
+
+use axum::extract::{FromRequest, Multipart, Path, Query, Request, State};
+use axum::http::header;
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tracing::info;
+
+use crate::auth::{self, verify_user};
+use crate::error::{ApiError, ApiResult};
+use crate::state::{now_ms, SharedState, Store, User};
+
+const SERVICE: &str = "prof";
+
+pub fn routes() -> Router<SharedState> {
+    Router::new().route(
+        "/user/:uuid/profile",
+        get(get_profile).put(put_profile).delete(delete_profile),
+    )
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProfileWrite {
+    timestamp: String,
+    signature: String,
+    #[serde(default)]
+    pub_key: Option<String>,
+    #[serde(default, alias = "profileData")]
+    profile: Value,
+    #[serde(skip)]
+    image_bytes: Option<usize>,
+}
+
+async fn read_write(request: Request) -> ApiResult<ProfileWrite> {
+    let is_multipart = request
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("multipart/"));
+    if !is_multipart {
+        let Json(write) = Json::<ProfileWrite>::from_request(request, &())
+            .await
+            .map_err(|e| ApiError::bad_request(e.body_text()))?;
+        return Ok(write);
+    }
+
+    let mut multipart = Multipart::from_request(request, &())
+        .await
+        .map_err(|e| ApiError::bad_request(e.body_text()))?;
+    let mut write = ProfileWrite::default();
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| ApiError::bad_request(e.body_text()))?
+    {
+        let name = field.name().unwrap_or_default().to_string();
+        if name == "image" {
+            let bytes = field
+                .bytes()
+                .await
+                .map_err(|e| ApiError::bad_request(e.body_text()))?;
+            write.image_bytes = Some(bytes.len());
+            continue;
+        }
+        let text = field
+            .text()
+            .await
+            .map_err(|e| ApiError::bad_request(e.body_text()))?;
+        match name.as_str() {
+            "timestamp" => write.timestamp = text,
+            "signature" => write.signature = text,
+            "pubKey" => write.pub_key = Some(text),
+            "profile" | "profileData" => {
+                write.profile = serde_json::from_str(&text)
+                    .map_err(|e| ApiError::bad_request(format!("Invalid profile: {}", e)))?
+            }
+            _ => {}
+        }
+    }
+    Ok(write)
+}
+
+/// Verify a request for `uuid`'s profile, binding the uuid to `pub_key` the
+/// first time
+fn verify_profile_owner(
+    store: &mut Store,
+    uuid: &str,
+    timestamp: &str,
+    signature: &str,
+    pub_key: Option<&str>,
+) -> ApiResult<()> {
+    let known = store.user(SERVICE, uuid).is_ok();
+    if !known {
+        let pub_key =
+            pub_key.ok_or_else(|| ApiError::not_found(format!("No prof user {}", uuid)))?;
+        auth::verify(pub_key, &auth::message(timestamp, &[uuid]), signature)?;
+        store.users.entry(SERVICE.to_string()).or_default().insert(
+            uuid.to_string(),
+            User {
+                uuid: uuid.to_string(),
+                pub_key: pub_key.to_string(),
+                hash: None,
+                data: json!({}),
+            },
+        );
+        return Ok(());
+    }
+    verify_user(store, SERVICE, uuid, timestamp, signature, &[]).map(|_| ())
+}
+
+// Signs timestamp + uuid
+async fn put_profile(
+    State(state): State<SharedState>,
+    Path(uuid): Path<String>,
+    request: Request,
+) -> ApiResult<Json<Value>> {
+    let write = read_write(request).await?;
+    let mut store = state.store();
+    verify_profile_owner(
+        &mut store,
+        &uuid,
+        &write.timestamp,
+        &write.signature,
+        write.pub_key.as_deref(),
+    )?;
+
+    let now = now_ms();
+    let created_at = store
+        .profiles
+        .get(&uuid)
+        .and_then(|profile| profile.get("createdAt").cloned())
+        .unwrap_or_else(|| json!(now));
+    let mut profile = match write.profile {
+        Value::Object(fields) => fields,
+        _ => return Err(ApiError::bad_request("Profile must be an object")),
+    };
+    profile.insert("uuid".to_string(), json!(uuid));
+    profile.insert("createdAt".to_string(), created_at);
+    profile.insert("updatedAt".to_string(), json!(now));
+    if write.image_bytes.is_some() {
+        profile.insert(
+            "imageUrl".to_string(),
+            json!(format!("standin://prof/{}/image", uuid)),
+        );
+    }
+
+    let profile = Value::Object(profile);
+    store.profiles.insert(uuid.clone(), profile.clone());
+    info!(%uuid, "🪪 Profile saved");
+    Ok(Json(profile))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProfileQuery {
+    timestamp: String,
+    signature: String,
+    #[serde(default)]
+    pub_key: Option<String>,
+}
+
+// Signs timestamp + uuid
+async fn get_profile(
+    State(state): State<SharedState>,
+    Path(uuid): Path<String>,
+    Query(query): Query<ProfileQuery>,
+) -> ApiResult<Json<Value>> {
+    let mut store = state.store();
+    verify_profile_owner(
+        &mut store,
+        &uuid,
+        &query.timestamp,
+        &query.signature,
+        query.pub_key.as_deref(),
+    )?;
+    store
+        .profiles
+        .get(&uuid)
+        .cloned()
+        .map(Json)
+        .ok_or_else(|| ApiError::not_found(format!("No profile for {}", uuid)))
+}
+
+// Signs timestamp + uuid
+async fn delete_profile(
+    State(state): State<SharedState>,
+    Path(uuid): Path<String>,
+    Query(query): Query<ProfileQuery>,
+) -> ApiResult<Json<Value>> {
+    let mut store = state.store();
+    verify_profile_owner(
+        &mut store,
+        &uuid,
+        &query.timestamp,
+        &query.signature,
+        query.pub_key.as_deref(),
+    )?;
+    let deleted = store.profiles.remove(&uuid).is_some();
+    Ok(Json(json!({ "success": deleted })))
+}
@@ -0,0 +1,210 @@
+// Sanora: products, uploads and orders
+//
+// Uploads are signed with the `x-pn-timestamp`/`x-pn-signature` headers the
+// apps send; their bytes are counted and dropped.
+
+use axum::body::Bytes;
+use axum::extract::{DefaultBodyLimit, Path, Query, State};
+use axum::http::HeaderMap;
+use axum::routing::{get, put};
+use axum::{Json, Router};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tracing::info;
+
+use super::{create_user_route, get_user_route, SignedQuery};
+use crate::auth::verify_user;
+use crate::error::{ApiError, ApiResult};
+use crate::state::{new_uuid, now_ms, SharedState};
+
+const SERVICE: &str = "sanora";
+const UPLOAD_LIMIT: usize = 64 * 1024 * 1024;
+
+pub fn routes() -> Router<SharedState> {
+    Router::new()
+        .route("/user/create", create_user_route(SERVICE, || json!({})))
+        .route("/user/:uuid", get_user_route(SERVICE))
+        .route("/user/:uuid/product/:title", put(add_product))
+        .route(
+            "/user/:uuid/product/:title/:kind",
+            put(upload).layer(DefaultBodyLimit::max(UPLOAD_LIMIT)),
+        )
+        .route("/user/:uuid/products", get(user_products))
+        .route("/products/base", get(base_products))
+        .route("/user/:uuid/orders", put(add_order))
+        .route("/user/:uuid/orders/:product_id", get(orders_for_product))
+}
+
+fn text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[derive(Deserialize)]
+struct ProductBody {
+    timestamp: String,
+    description: String,
+    price: Value,
+    signature: String,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+// Signs timestamp + uuid + title + description + price
+async fn add_product(
+    State(state): State<SharedState>,
+    Path((uuid, title)): Path<(String, String)>,
+    Json(body): Json<ProductBody>,
+) -> ApiResult<Json<Value>> {
+    let price = text(&body.price);
+    let mut store = state.store();
+    verify_user(
+        &store,
+        SERVICE,
+        &uuid,
+        &body.timestamp,
+        &body.signature,
+        &[&title, &body.description, &price],
+    )?;
+
+    // Re-adding a title updates the product, as Sanora does
+    let existing = store
+        .products
+        .iter()
+        .position(|product| product["uuid"] == json!(uuid) && product["title"] == json!(title));
+    let product = json!({
+        "uuid": uuid,
+        "productId": existing
+            .map(|index| store.products[index]["productId"].clone())
+            .unwrap_or_else(|| json!(new_uuid())),
+        "author": uuid,
+        "title": title,
+        "description": body.description,
+        "price": price.parse::<u64>().map(Value::from).unwrap_or(body.price),
+        "tags": body.tags,
+        "createdAt": now_ms()
+    });
+    match existing {
+        Some(index) => store.products[index] = product.clone(),
+        None => store.products.push(product.clone()),
+    }
+    info!(%uuid, %title, "🛍️ Product saved");
+    Ok(Json(product))
+}
+
+// Signs x-pn-timestamp + uuid + title, in headers
+async fn upload(
+    State(state): State<SharedState>,
+    Path((uuid, title, kind)): Path<(String, String, String)>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> ApiResult<Json<Value>> {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.to_string())
+            .ok_or_else(|| ApiError::auth(format!("Missing {} header", name)))
+    };
+    let timestamp = header("x-pn-timestamp")?;
+    let signature = header("x-pn-signature")?;
+    if kind != "image" && kind != "artifact" {
+        return Err(ApiError::not_found(format!("Unknown upload kind {}", kind)));
+    }
+
+    let mut store = state.store();
+    verify_user(&store, SERVICE, &uuid, &timestamp, &signature, &[&title])?;
+    let product = store
+        .products
+        .iter_mut()
+        .find(|product| product["uuid"] == json!(uuid) && product["title"] == json!(title))
+        .ok_or_else(|| ApiError::not_found(format!("No product {}", title)))?;
+
+    let key = if kind == "image" {
+        "image"
+    } else {
+        "artifacts"
+    };
+    product[key] = json!(format!("standin://{}/{}/{}", uuid, title, kind));
+    info!(%uuid, %title, %kind, bytes = body.len(), "📦 Upload stored");
+    Ok(Json(json!({ "success": true, "bytes": body.len() })))
+}
+
+// Public, like the real service
+async fn user_products(
+    State(state): State<SharedState>,
+    Path(uuid): Path<String>,
+) -> ApiResult<Json<Value>> {
+    let store = state.store();
+    let products: serde_json::Map<String, Value> = store
+        .products
+        .iter()
+        .filter(|product| product["uuid"] == json!(uuid))
+        .map(|product| (text(&product["title"]), product.clone()))
+        .collect();
+    Ok(Json(Value::Object(products)))
+}
+
+async fn base_products(State(state): State<SharedState>) -> Json<Value> {
+    Json(json!(state.store().products))
+}
+
+#[derive(Deserialize)]
+struct OrderBody {
+    timestamp: String,
+    order: Value,
+    signature: String,
+}
+
+// Signs timestamp + uuid
+async fn add_order(
+    State(state): State<SharedState>,
+    Path(uuid): Path<String>,
+    Json(body): Json<OrderBody>,
+) -> ApiResult<Json<Value>> {
+    let mut store = state.store();
+    let user = verify_user(
+        &store,
+        SERVICE,
+        &uuid,
+        &body.timestamp,
+        &body.signature,
+        &[],
+    )?;
+
+    let mut order = body.order;
+    if let Value::Object(fields) = &mut order {
+        fields.entry("orderId").or_insert_with(|| json!(new_uuid()));
+        fields.insert("buyer".to_string(), json!(uuid));
+        fields.entry("createdAt").or_insert_with(|| json!(now_ms()));
+    }
+    info!(%uuid, "🧾 Order placed");
+    store.orders.push(order);
+    Ok(Json(user.to_json()))
+}
+
+// Signs timestamp + uuid
+async fn orders_for_product(
+    State(state): State<SharedState>,
+    Path((uuid, product_id)): Path<(String, String)>,
+    Query(query): Query<SignedQuery>,
+) -> ApiResult<Json<Value>> {
+    let store = state.store();
+    verify_user(
+        &store,
+        SERVICE,
+        &uuid,
+        &query.timestamp,
+        &query.signature,
+        &[],
+    )?;
+
+    let orders: Vec<&Value> = store
+        .orders
+        .iter()
+        .filter(|order| order["productId"] == json!(product_id))
+        .collect();
+    Ok(Json(json!({ "orders": orders })))
+}
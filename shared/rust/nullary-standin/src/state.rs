@@ -0,0 +1,245 @@
+// In-memory service state
+//
+// One `Store` holds everything every stood-in service knows: users per
+// service, BDOs, bases, posts, products, orders, contracts, messages and
+// profiles. It starts from a small seed (a base pointing back at the
+// stand-in, a few posts and products) so the apps have something to show,
+// and `reset` puts it back there.
+
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use sessionless::Sessionless;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::error::{ApiError, ApiResult};
+use crate::faults::Faults;
+
+pub type SharedState = Arc<StandinState>;
+
+pub struct StandinState {
+    store: Mutex<Store>,
+    pub faults: Faults,
+    /// Service name -> URL the stand-in serves it on, for the seeded base
+    dns: BTreeMap<String, String>,
+    seed: bool,
+}
+
+impl StandinState {
+    pub fn new(dns: BTreeMap<String, String>, faults: Faults, seed: bool) -> SharedState {
+        Arc::new(Self {
+            store: Mutex::new(Store::seeded(&dns, seed)),
+            faults,
+            dns,
+            seed,
+        })
+    }
+
+    pub fn store(&self) -> MutexGuard<'_, Store> {
+        self.store
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Forget everything and go back to the seed
+    pub fn reset(&self) {
+        *self.store() = Store::seeded(&self.dns, self.seed);
+    }
+}
+
+// ===== STORE =====
+
+/// A user of one service
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct User {
+    pub uuid: String,
+    pub pub_key: String,
+    /// BDO keeps a user per public key and hash
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+    /// Whatever else the service keeps on the user
+    pub data: Value,
+}
+
+impl User {
+    /// The user as the service returns it: its data plus uuid and pubKey
+    pub fn to_json(&self) -> Value {
+        let mut user = match &self.data {
+            Value::Object(data) => data.clone(),
+            _ => Default::default(),
+        };
+        user.insert("uuid".to_string(), json!(self.uuid));
+        user.insert("pubKey".to_string(), json!(self.pub_key));
+        if let Some(hash) = &self.hash {
+            user.insert("hash".to_string(), json!(hash));
+        }
+        Value::Object(user)
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Store {
+    /// Service -> uuid -> user
+    pub users: BTreeMap<String, BTreeMap<String, User>>,
+    /// BDO user uuid -> their BDO
+    pub bdos: BTreeMap<String, Value>,
+    /// Public key -> BDO shared with `pub: true`
+    pub public_bdos: BTreeMap<String, Value>,
+    /// BDO user uuid -> bases they saved; everyone else sees `default_bases`
+    pub bases: BTreeMap<String, Value>,
+    pub default_bases: Value,
+    pub posts: Vec<Value>,
+    pub products: Vec<Value>,
+    pub orders: Vec<Value>,
+    pub contracts: BTreeMap<String, Value>,
+    pub messages: Vec<Value>,
+    /// Prof user uuid -> profile
+    pub profiles: BTreeMap<String, Value>,
+}
+
+impl Store {
+    fn seeded(dns: &BTreeMap<String, String>, seed: bool) -> Self {
+        let mut store = Store {
+            default_bases: json!({}),
+            ..Default::default()
+        };
+        if seed {
+            store.seed(dns);
+        }
+        store
+    }
+
+    pub fn user(&self, service: &str, uuid: &str) -> ApiResult<&User> {
+        self.users
+            .get(service)
+            .and_then(|users| users.get(uuid))
+            .ok_or_else(|| ApiError::not_found(format!("No {} user {}", service, uuid)))
+    }
+
+    pub fn user_mut(&mut self, service: &str, uuid: &str) -> ApiResult<&mut User> {
+        self.users
+            .get_mut(service)
+            .and_then(|users| users.get_mut(uuid))
+            .ok_or_else(|| ApiError::not_found(format!("No {} user {}", service, uuid)))
+    }
+
+    /// The user registered with `pub_key` (and `hash`, for BDO), creating
+    /// them with `data` the first time
+    pub fn user_for_key(
+        &mut self,
+        service: &str,
+        pub_key: &str,
+        hash: Option<&str>,
+        data: Value,
+    ) -> User {
+        let users = self.users.entry(service.to_string()).or_default();
+        if let Some(user) = users
+            .values()
+            .find(|user| user.pub_key == pub_key && user.hash.as_deref() == hash)
+        {
+            return user.clone();
+        }
+
+        let user = User {
+            uuid: new_uuid(),
+            pub_key: pub_key.to_string(),
+            hash: hash.map(|hash| hash.to_string()),
+            data,
+        };
+        users.insert(user.uuid.clone(), user.clone());
+        user
+    }
+
+    fn seed(&mut self, dns: &BTreeMap<String, String>) {
+        let now = now_ms();
+        let base = new_uuid();
+        let mut bases = Map::new();
+        bases.insert(
+            base.clone(),
+            json!({
+                "uuid": base,
+                "name": "Stand-in Base",
+                "description": "Local allyabase stand-in for offline development",
+                "location": {
+                    "latitude": 37.7749,
+                    "longitude": -122.4194,
+                    "postalCode": "94103"
+                },
+                "soma": {
+                    "lexary": ["text", "blogs"],
+                    "photary": ["photos"],
+                    "viewary": ["videos"]
+                },
+                "dns": dns,
+                "joined": false
+            }),
+        );
+        self.default_bases = Value::Object(bases);
+
+        self.posts = vec![
+            json!({
+                "uuid": new_uuid(),
+                "title": "Stand-in photos",
+                "description": "Served by the local stand-in",
+                "images": ["https://images.unsplash.com/photo-1506905925346-21bda4d32df4?w=800"],
+                "tags": ["photos", "social", "photary"],
+                "author": "Stand-in",
+                "timestamp": now - 3_600_000
+            }),
+            json!({
+                "uuid": new_uuid(),
+                "title": "Working offline",
+                "description": "A text post from the local stand-in",
+                "content": "Everything here lives in memory and is gone when the stand-in stops.",
+                "images": [],
+                "tags": ["text", "blogs", "programming"],
+                "author": "Stand-in",
+                "timestamp": now - 7_200_000
+            }),
+            json!({
+                "uuid": new_uuid(),
+                "title": "Stand-in video",
+                "description": "A video post from the local stand-in",
+                "url": "https://www.w3schools.com/html/mov_bbb.mp4",
+                "thumbnail": "",
+                "duration": 10,
+                "tags": ["videos", "entertainment"],
+                "author": "Stand-in",
+                "timestamp": now - 10_800_000
+            }),
+        ];
+
+        self.products = vec![
+            json!({
+                "uuid": new_uuid(),
+                "productId": new_uuid(),
+                "author": null,
+                "title": "Stand-in Ebook",
+                "description": "A product served by the local stand-in",
+                "price": 500,
+                "tags": ["ebook"],
+                "createdAt": now - 3_600_000
+            }),
+            json!({
+                "uuid": new_uuid(),
+                "productId": new_uuid(),
+                "author": null,
+                "title": "Stand-in Blog",
+                "description": "A blog served by the local stand-in",
+                "price": 0,
+                "tags": ["blog"],
+                "createdAt": now - 7_200_000
+            }),
+        ];
+    }
+}
+
+pub fn new_uuid() -> String {
+    Sessionless::generate_uuid().to_string()
+}
+
+pub fn now_ms() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
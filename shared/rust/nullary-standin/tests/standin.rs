@@ -0,0 +1,205 @@
+// The stand-in started in-process on free ports, driven over HTTP the way
+// the service clients drive it
+
+use nullary_standin::{start, FaultRule, Standin, StandinConfig};
+use reqwest::header::{ETAG, IF_NONE_MATCH};
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use sessionless::hex::IntoHex;
+use sessionless::Sessionless;
+use std::time::{Duration, Instant};
+
+async fn standin() -> Standin {
+    start(StandinConfig::ephemeral()).await.unwrap()
+}
+
+fn timestamp() -> String {
+    chrono::Utc::now().timestamp_millis().to_string()
+}
+
+fn sign(keys: &Sessionless, parts: &[&str]) -> String {
+    keys.sign(parts.concat()).into_hex()
+}
+
+/// Register `keys` with Dolores and return the user's UUID
+async fn dolores_user(standin: &Standin, keys: &Sessionless) -> String {
+    let timestamp = timestamp();
+    let pub_key = keys.public_key().to_hex();
+    let user: Value = reqwest::Client::new()
+        .put(format!("{}user/create", standin.url("dolores").unwrap()))
+        .json(&json!({
+            "timestamp": timestamp,
+            "pubKey": pub_key,
+            "signature": sign(keys, &[&timestamp, &pub_key]),
+        }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    user["uuid"].as_str().unwrap().to_string()
+}
+
+async fn get_feed(standin: &Standin, uuid: &str, tags: &str, signature: &str, timestamp: &str) -> reqwest::Response {
+    reqwest::Client::new()
+        .get(format!("{}user/{}/feed", standin.url("dolores").unwrap(), uuid))
+        .query(&[("timestamp", timestamp), ("tags", tags), ("signature", signature)])
+        .send()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn signed_requests_are_verified() {
+    let standin = standin().await;
+    let keys = Sessionless::new();
+    let uuid = dolores_user(&standin, &keys).await;
+
+    let timestamp = timestamp();
+    let signature = sign(&keys, &[&timestamp, &uuid, "photos"]);
+    let response = get_feed(&standin, &uuid, "photos", &signature, &timestamp).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let feed: Value = response.json().await.unwrap();
+    assert_eq!(feed["allPosts"].as_array().unwrap().len(), 1);
+    assert_eq!(feed["picPosts"][0]["title"], "Stand-in photos");
+
+    // Signed over other tags, or by someone else
+    let wrong_tags = get_feed(&standin, &uuid, "videos", &signature, &timestamp).await;
+    assert_eq!(wrong_tags.status(), StatusCode::FORBIDDEN);
+    let other = sign(&Sessionless::new(), &[&timestamp, &uuid, "photos"]);
+    let wrong_key = get_feed(&standin, &uuid, "photos", &other, &timestamp).await;
+    assert_eq!(wrong_key.status(), StatusCode::FORBIDDEN);
+
+    standin.shutdown().await;
+}
+
+#[tokio::test]
+async fn registering_a_key_again_returns_the_same_user() {
+    let standin = standin().await;
+    let keys = Sessionless::new();
+
+    assert_eq!(dolores_user(&standin, &keys).await, dolores_user(&standin, &keys).await);
+    standin.shutdown().await;
+}
+
+#[tokio::test]
+async fn faults_are_injected_and_retired() {
+    let standin = standin().await;
+    let products = format!("{}products/base", standin.url("sanora").unwrap());
+    let client = reqwest::Client::new();
+
+    standin.state().faults.push(FaultRule {
+        service: Some("sanora".to_string()),
+        path: Some("/products".to_string()),
+        status: Some(503),
+        times: Some(2),
+        ..FaultRule::default()
+    });
+    for _ in 0..2 {
+        let response = client.get(&products).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+    assert_eq!(client.get(&products).send().await.unwrap().status(), StatusCode::OK);
+    assert!(standin.state().faults.list().is_empty());
+
+    standin.state().faults.push(FaultRule {
+        service: Some("sanora".to_string()),
+        malformed: true,
+        latency_ms: Some(200),
+        times: Some(1),
+        ..FaultRule::default()
+    });
+    let started = Instant::now();
+    let response = client.get(&products).send().await.unwrap();
+    assert!(started.elapsed() >= Duration::from_millis(200));
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.json::<Value>().await.is_err());
+
+    // Rules for one service leave the others alone
+    standin.state().faults.push(FaultRule {
+        service: Some("bdo".to_string()),
+        status: Some(500),
+        ..FaultRule::default()
+    });
+    assert_eq!(client.get(&products).send().await.unwrap().status(), StatusCode::OK);
+
+    standin.shutdown().await;
+}
+
+#[tokio::test]
+async fn unchanged_gets_are_not_modified() {
+    let standin = standin().await;
+    let products = format!("{}products/base", standin.url("sanora").unwrap());
+    let client = reqwest::Client::new();
+
+    let response = client.get(&products).send().await.unwrap();
+    let etag = response.headers().get(ETAG).cloned().unwrap();
+    assert_eq!(response.json::<Vec<Value>>().await.unwrap().len(), 2);
+
+    let response = client
+        .get(&products)
+        .header(IF_NONE_MATCH, etag.clone())
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+    assert!(response.bytes().await.unwrap().is_empty());
+
+    // A new product changes the page
+    standin.state().store().products.push(json!({ "title": "New", "tags": [] }));
+    let response = client
+        .get(&products)
+        .header(IF_NONE_MATCH, etag.clone())
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_ne!(response.headers().get(ETAG), Some(&etag));
+
+    standin.shutdown().await;
+}
+
+#[tokio::test]
+async fn control_api_scripts_faults_and_resets() {
+    let standin = start(StandinConfig {
+        control_port: Some(0),
+        ..StandinConfig::ephemeral()
+    })
+    .await
+    .unwrap();
+    let control = format!("http://{}", standin.control_addr().unwrap());
+    let client = reqwest::Client::new();
+
+    let rules: Vec<Value> = client
+        .post(format!("{}/faults", control))
+        .json(&json!({ "service": "dolores", "status": 502 }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(rules.len(), 1);
+    let dolores = client.get(standin.url("dolores").unwrap()).send().await.unwrap();
+    assert_eq!(dolores.status(), StatusCode::BAD_GATEWAY);
+
+    client.delete(format!("{}/faults", control)).send().await.unwrap();
+    assert!(standin.state().faults.list().is_empty());
+
+    dolores_user(&standin, &Sessionless::new()).await;
+    assert_eq!(standin.state().store().users["dolores"].len(), 1);
+    client.post(format!("{}/reset", control)).send().await.unwrap();
+    let state: Value = client
+        .get(format!("{}/state", control))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(state["users"], json!({}));
+    assert_eq!(state["posts"].as_array().unwrap().len(), 3);
+
+    standin.shutdown().await;
+}
@@ -4,7 +4,8 @@
  * Add User Persistence to Nullary Apps
  * 
  * This script automatically adds the user persistence system to existing
- * Nullary applications by updating their Cargo.toml and Rust backend files.
+ * Nullary applications by depending on the shared nullary-core crate and
+ * registering its commands in their Rust backend files.
  */
 
 const fs = require('fs');
@@ -29,14 +30,11 @@ const NULLARY_APPS = [
 
 // Required dependencies for user persistence
 const REQUIRED_DEPENDENCIES = `
-# User persistence dependencies
-sessionless = "0.1.1"
-hex = "0.4"
-uuid = { version = "1.0", features = ["v4"] }
-chrono = { version = "0.4", features = ["serde"] }
+# Shared identity, endpoints and service clients
+nullary-core = { path = "../../../shared/rust/nullary-core" }
 `;
 
-// User persistence Rust functions to add
+// User persistence Rust functions to add (exported from nullary-core)
 const RUST_FUNCTIONS = [
   'generate_sessionless_keys',
   'stronghold_init', 
@@ -45,7 +43,27 @@ const RUST_FUNCTIONS = [
   'stronghold_clear_vault',
   'read_user_data_file',
   'write_user_data_file',
-  'clear_user_data'
+  'clear_user_data',
+  'read_document',
+  'write_document',
+  'delete_document',
+  'list_documents',
+  'list_personas',
+  'create_persona',
+  'rename_persona',
+  'delete_persona',
+  'switch_persona',
+  'get_persona_service_uuids',
+  'set_persona_service_uuid',
+  'export_identity_backup',
+  'import_identity_backup',
+  'export_recovery_words',
+  'import_recovery_words',
+  'rotate_identity',
+  'retry_identity_rotation',
+  'get_key_rotations',
+  'get_environment_config',
+  'switch_environment'
 ];
 
 /**
@@ -79,7 +97,7 @@ async function main() {
   console.log('1. Review the updated files in each app');
   console.log('2. Test the user persistence functionality');
   console.log('3. Update frontend code to use the new user persistence system');
-  console.log('4. Consider implementing Tauri Stronghold for production security');
+  console.log('4. Replace the default Stronghold vault password with a user-provided one');
 }
 
 /**
@@ -105,9 +123,6 @@ async function updateApp(appPath, appName) {
   } else {
     console.warn(`⚠️  No Rust backend file found for ${appName}`);
   }
-  
-  // Step 3: Copy user persistence Rust module
-  await copyUserPersistenceModule(path.join(appPath, 'src-tauri', 'src'));
 }
 
 /**
@@ -119,7 +134,7 @@ async function updateCargoToml(cargoTomlPath) {
   let cargoContent = fs.readFileSync(cargoTomlPath, 'utf8');
   
   // Check if dependencies already exist
-  if (cargoContent.includes('sessionless =') && cargoContent.includes('hex =')) {
+  if (cargoContent.includes('nullary-core =')) {
     console.log('   Dependencies already present');
     return;
   }
@@ -160,29 +175,18 @@ async function updateRustBackend(rustFilePath, appName) {
   let rustContent = fs.readFileSync(rustFilePath, 'utf8');
   
   // Check if user persistence is already integrated
-  if (rustContent.includes('mod user_persistence') || rustContent.includes('generate_sessionless_keys')) {
+  if (rustContent.includes('nullary_core::') || rustContent.includes('generate_sessionless_keys')) {
     console.log('   User persistence already integrated');
     return;
   }
   
-  // Add user persistence module import
-  const moduleImport = '\n// User Persistence Module\nmod user_persistence;\nuse user_persistence::*;\n';
-  
-  // Find a good place to add the import (after existing imports)
-  const importIndex = rustContent.lastIndexOf('use ');
-  if (importIndex !== -1) {
-    const lineEnd = rustContent.indexOf('\n', importIndex);
-    rustContent = rustContent.slice(0, lineEnd) + moduleImport + rustContent.slice(lineEnd);
-  } else {
-    // No imports found, add at the beginning
-    rustContent = moduleImport + rustContent;
-  }
+  const crateName = appName.split('/').pop();
   
   // Add functions to invoke_handler
   const handlerMatch = rustContent.match(/\.invoke_handler\(tauri::generate_handler!\[([\s\S]*?)\]\)/);
   if (handlerMatch) {
     const existingFunctions = handlerMatch[1];
-    const userPersistenceFunctions = RUST_FUNCTIONS.map(fn => `            ${fn}`).join(',\n');
+    const userPersistenceFunctions = RUST_FUNCTIONS.map(fn => `            nullary_core::${fn}`).join(',\n');
     
     // Add user persistence functions to the handler
     const updatedFunctions = existingFunctions.trim() + ',\n\n            // User Persistence Functions\n' + userPersistenceFunctions;
@@ -195,26 +199,25 @@ async function updateRustBackend(rustFilePath, appName) {
     console.warn('   ⚠️  Could not find invoke_handler to update');
   }
   
-  fs.writeFileSync(rustFilePath, rustContent);
-  console.log('   ✅ Rust backend updated');
-}
-
-/**
- * Copy user persistence Rust module to app
- */
-async function copyUserPersistenceModule(srcPath) {
-  console.log('📁 Copying user persistence module...');
-  
-  const sourceModulePath = path.resolve(__dirname, '../rust/user-persistence.rs');
-  const targetModulePath = path.join(srcPath, 'user_persistence.rs');
-  
-  if (!fs.existsSync(sourceModulePath)) {
-    throw new Error('Source user persistence module not found');
+  // Initialize nullary-core (app name and identity) in the setup hook
+  const coreInit = `
+            // Register the app and load (or create on first launch) its identity
+            if let Err(e) = nullary_core::init(app.handle(), "${crateName}") {
+                eprintln!("❌ Failed to initialize nullary core: {}", e);
+            }
+`;
+  const setupMatch = rustContent.match(/\.setup\(\|app\| \{\n/);
+  if (setupMatch) {
+    rustContent = rustContent.replace(setupMatch[0], setupMatch[0] + coreInit);
+  } else {
+    rustContent = rustContent.replace(
+      /\.run\(tauri::generate_context!\(\)\)/,
+      `.setup(|app| {${coreInit}            Ok(())\n        })\n        .run(tauri::generate_context!())`
+    );
   }
   
-  // Copy the module
-  fs.copyFileSync(sourceModulePath, targetModulePath);
-  console.log('   ✅ User persistence module copied');
+  fs.writeFileSync(rustFilePath, rustContent);
+  console.log('   ✅ Rust backend updated');
 }
 
 /**
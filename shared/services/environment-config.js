@@ -107,6 +107,15 @@ function createEnvironmentControls(appName = 'nullary') {
         return false;
       }
       localStorage.setItem('nullary-env', env);
+
+      // The Rust backend switches immediately; only the frontend needs a refresh
+      if (window.__TAURI__ && window.__TAURI__.core) {
+        const { invoke } = window.__TAURI__.core;
+        invoke('switch_environment', { environment: env })
+          .then(info => console.log(`🦀 Backend now using ${info.environment} endpoints`))
+          .catch(error => console.warn(`⚠️ Backend environment switch failed: ${error}`));
+      }
+
       console.log(`🔄 ${appName} environment switched to ${env}. Refresh app to apply changes.`);
       console.log(`Run: location.reload() to refresh`);
       return true;
@@ -3,6 +3,7 @@
  * 
  * Provides visual sync status indicators instead of confusing error messages.
  * Shows last sync time and success status through color-coded banners and base indicators.
+ * Also tracks per-service health reported by the backend's `service-health` event.
  */
 
 import { color } from '../themes/simple-theme.js';
@@ -27,10 +28,14 @@ function createDefaultSyncStatus() {
     baseStatuses: {},  // baseId: { status, lastSuccess, lastAttempt, error }
     totalBases: 0,
     successfulBases: 0,
-    failedBases: 0
+    failedBases: 0,
+    serviceHealth: null  // last HealthReport from the backend
   };
 }
 
+// Clock skew beyond this makes signed requests likely to be rejected
+const CLOCK_SKEW_WARNING_MS = 30000;
+
 /**
  * Sync Status Manager
  */
@@ -170,6 +175,93 @@ export class SyncStatusManager {
     }
   }
 
+  /**
+   * Record a feed sync from the backend in place of per-base tracking
+   * @param {Object} sync - FeedSync ({ status, totalBases, successfulBases, failedBases, bases })
+   */
+  recordFeedSync(sync) {
+    const now = Date.now();
+
+    this.syncStatus.lastAttempt = now;
+    this.syncStatus.status = sync.status;
+    this.syncStatus.totalBases = sync.totalBases;
+    this.syncStatus.successfulBases = sync.successfulBases;
+    this.syncStatus.failedBases = sync.failedBases;
+    if (sync.successfulBases > 0) {
+      this.syncStatus.lastSuccess = now;
+    }
+
+    sync.bases.forEach(base => {
+      const previous = this.syncStatus.baseStatuses[base.baseId] || {};
+      const succeeded = base.status === SYNC_STATUS.SUCCESS;
+      this.syncStatus.baseStatuses[base.baseId] = {
+        ...previous,
+        status: base.status,
+        lastAttempt: now,
+        lastSuccess: succeeded ? now : previous.lastSuccess,
+        error: succeeded ? null : base.error,
+        errorKind: base.errorKind,
+        durationMs: base.durationMs,
+        data: { contentCount: base.postCount, stale: base.stale }
+      };
+    });
+
+    this.saveSyncStatus();
+    this.notifyCallbacks();
+  }
+
+  /**
+   * Record a service health report from the backend
+   * @param {Object} report - HealthReport ({ environment, checkedAt, services })
+   */
+  recordServiceHealth(report) {
+    this.syncStatus.serviceHealth = report;
+    this.saveSyncStatus();
+    this.notifyCallbacks();
+  }
+
+  /**
+   * Services that did not answer in the last health report
+   * @returns {Array} ServiceHealth entries
+   */
+  getUnreachableServices() {
+    const report = this.syncStatus.serviceHealth;
+    return report ? report.services.filter(service => !service.reachable) : [];
+  }
+
+  /**
+   * Services whose clock is far enough off to break signed requests
+   * @returns {Array} ServiceHealth entries
+   */
+  getSkewedServices() {
+    const report = this.syncStatus.serviceHealth;
+    if (!report) return [];
+    return report.services.filter(service =>
+      service.clockSkewMs !== null && Math.abs(service.clockSkewMs) > CLOCK_SKEW_WARNING_MS
+    );
+  }
+
+  /**
+   * Listen for `service-health` events and ask for a first report
+   */
+  async listenForServiceHealth() {
+    if (!window.__TAURI__ || !window.__TAURI__.event) {
+      console.warn('⚠️ Tauri not available, service health will not be tracked');
+      return;
+    }
+
+    window.__TAURI__.event.listen('service-health', event => {
+      this.recordServiceHealth(event.payload);
+    });
+
+    try {
+      const report = await window.__TAURI__.core.invoke('check_service_health', { refresh: false });
+      this.recordServiceHealth(report);
+    } catch (err) {
+      console.warn('Failed to check service health:', err);
+    }
+  }
+
   /**
    * Get sync status for a specific base
    * @param {Object} base - Base to check
@@ -323,10 +415,70 @@ export function createSyncStatusBanner(status = null) {
   `;
   
   banner.textContent = message;
+
+  const healthLine = formatServiceHealth();
+  if (healthLine) {
+    const health = document.createElement('div');
+    health.className = 'sync-status-service-health';
+    health.style.cssText = `
+      font-size: 12px;
+      font-weight: 400;
+      opacity: 0.9;
+    `;
+    health.textContent = healthLine;
+    health.title = describeServiceHealth();
+    banner.style.flexDirection = 'column';
+    banner.style.gap = '2px';
+    banner.appendChild(health);
+  }
   
   return banner;
 }
 
+/**
+ * One-line summary of service health for the banner, or null if there is no
+ * report yet
+ * @returns {string|null} Summary
+ */
+function formatServiceHealth() {
+  const report = syncStatusManager.getStatus().serviceHealth;
+  if (!report) return null;
+
+  const down = syncStatusManager.getUnreachableServices();
+  const skewed = syncStatusManager.getSkewedServices();
+  const parts = [];
+
+  if (down.length > 0) {
+    parts.push(`🔌 ${down.map(service => service.service).join(', ')} unreachable`);
+  } else {
+    parts.push(`🩺 ${report.services.length} services up`);
+  }
+  if (skewed.length > 0) {
+    parts.push(`🕒 clock skew on ${skewed.map(service => service.service).join(', ')}`);
+  }
+
+  return parts.join(' · ');
+}
+
+/**
+ * Per-service detail for the banner tooltip
+ * @returns {string} One line per service
+ */
+function describeServiceHealth() {
+  const report = syncStatusManager.getStatus().serviceHealth;
+  if (!report) return '';
+
+  return report.services.map(service => {
+    if (!service.reachable) {
+      return `${service.service}: unreachable (${service.error || 'no response'})`;
+    }
+    const details = [`HTTP ${service.status}`, `${service.latencyMs}ms`];
+    if (service.version) details.push(`v${service.version}`);
+    if (service.clockSkewMs !== null) details.push(`skew ${service.clockSkewMs}ms`);
+    return `${service.service}: ${details.join(', ')}`;
+  }).join('\n');
+}
+
 /**
  * Update base UI element with sync status indicators
  * @param {HTMLElement} baseElement - Base DOM element
@@ -32,9 +32,13 @@
 const DEFAULT_CONFIG = {
   // Stronghold settings
   stronghold: {
-    password: 'nullary-default-password', // In production, should be user-provided
+    // The backend unlocks the vault at startup with a password from the OS
+    // keychain; set one here only for a vault of your own
+    password: null,
     vault: 'nullary-vault',
-    keysRecord: 'sessionless-keys'
+    // The backend's identity records (master-seed, personas,
+    // sessionless-keys) are reserved and can't be read from here
+    keysRecord: 'webview-sessionless-keys'
   },
   
   // Filesystem settings
@@ -95,6 +99,9 @@ export class UserPersistence {
       // Load service users
       await this._loadServiceUsers();
       
+      // Reload everything when the backend switches persona
+      await this._listenForPersonaSwitch();
+      
       this.isInitialized = true;
       console.log('✅ User persistence system initialized');
       return true;
@@ -315,12 +322,185 @@ export class UserPersistence {
     }
   }
 
+  // ===== PERSONAS =====
+
+  /**
+   * List personas on this install
+   * @returns {Array} Personas with { name, active, createdAt, serviceUuids }
+   */
+  async listPersonas() {
+    return await window.__TAURI__.core.invoke('list_personas');
+  }
+
+  /**
+   * Create a persona with its own keys and data
+   * @param {string} name - Letters, digits, - and _
+   */
+  async createPersona(name) {
+    return await window.__TAURI__.core.invoke('create_persona', { name });
+  }
+
+  /**
+   * Rename a persona
+   * @param {string} name - Current name
+   * @param {string} newName - New name
+   */
+  async renamePersona(name, newName) {
+    return await window.__TAURI__.core.invoke('rename_persona', { name, newName });
+  }
+
+  /**
+   * Delete a persona and its data (not allowed for the active persona)
+   * @param {string} name - Persona to delete
+   */
+  async deletePersona(name) {
+    return await window.__TAURI__.core.invoke('delete_persona', { name });
+  }
+
+  /**
+   * Switch the active persona. Every window receives `persona-switched`
+   * and reloads its user data.
+   * @param {string} name - Persona to activate
+   */
+  async switchPersona(name) {
+    return await window.__TAURI__.core.invoke('switch_persona', { name });
+  }
+
+  // ===== BACKUP =====
+
+  /**
+   * Export a persona as an encrypted backup (JSON string)
+   * @param {string} passphrase - At least 8 characters
+   * @param {string} [persona] - Defaults to the active persona
+   */
+  async exportBackup(passphrase, persona = null) {
+    return await window.__TAURI__.core.invoke('export_identity_backup', { passphrase, persona });
+  }
+
+  /**
+   * Restore an encrypted backup as a new persona
+   * @param {string} backup - Backup JSON from exportBackup
+   * @param {string} passphrase - Passphrase used on export
+   * @param {string} [persona] - Defaults to the exported persona's name
+   */
+  async importBackup(backup, passphrase, persona = null) {
+    return await window.__TAURI__.core.invoke('import_identity_backup', { backup, passphrase, persona });
+  }
+
+  /**
+   * Get a persona's 24 recovery words
+   * @param {string} [persona] - Defaults to the active persona
+   */
+  async exportRecoveryWords(persona = null) {
+    return await window.__TAURI__.core.invoke('export_recovery_words', { persona });
+  }
+
+  /**
+   * Restore recovery words as a new persona
+   * @param {string} words - Space-separated recovery words
+   * @param {string} persona - Name for the restored persona
+   */
+  async importRecoveryWords(words, persona) {
+    return await window.__TAURI__.core.invoke('import_recovery_words', { words, persona });
+  }
+
+  // ===== KEY ROTATION =====
+
+  /**
+   * Replace the active persona's keys and re-register it on every service
+   * @returns {Object} Report with `succeeded` and `needsRetry` lists of `service@base`
+   */
+  async rotateIdentity() {
+    const report = await window.__TAURI__.core.invoke('rotate_identity');
+    if (report.committed) {
+      this.sessionlessKeys = null;
+      await this._loadServiceUsers();
+    }
+    return report;
+  }
+
+  /**
+   * Retry the services that failed in the last rotation
+   */
+  async retryRotation() {
+    return await window.__TAURI__.core.invoke('retry_identity_rotation');
+  }
+
+  /**
+   * Rotation history with the old-to-new UUID mapping per service
+   */
+  async getKeyRotations() {
+    return await window.__TAURI__.core.invoke('get_key_rotations');
+  }
+
+  // ===== DOCUMENTS =====
+
+  /**
+   * Read a document from the active persona's data dir
+   * @param {string} key - Relative key such as 'drafts/post-1.json'
+   * @returns {*} The stored data, or null if there is none
+   */
+  async readDocument(key) {
+    return await window.__TAURI__.core.invoke('read_document', { key });
+  }
+
+  /**
+   * Write a document atomically at its key's current schema version
+   * @returns {Object} `{ key, schemaVersion, updatedAt }`
+   */
+  async writeDocument(key, data) {
+    return await window.__TAURI__.core.invoke('write_document', { key, data });
+  }
+
+  /**
+   * Delete a document; resolves to whether it existed
+   */
+  async deleteDocument(key) {
+    return await window.__TAURI__.core.invoke('delete_document', { key });
+  }
+
+  /**
+   * Keys of every document in the active persona's data dir
+   */
+  async listDocuments() {
+    return await window.__TAURI__.core.invoke('list_documents');
+  }
+
   // ===== PRIVATE METHODS =====
 
+  /**
+   * Subscribe to backend persona switches
+   */
+  async _listenForPersonaSwitch() {
+    if (!window.__TAURI__.event) {
+      return;
+    }
+
+    await window.__TAURI__.event.listen('persona-switched', async (event) => {
+      console.log(`👤 Switched to persona: ${event.payload.name}`);
+
+      this.sessionlessKeys = null;
+      this.serviceUsers = new Map();
+      this.userData = {};
+
+      await this._loadUserData();
+      await this._loadServiceUsers();
+
+      if (typeof this.config.onPersonaSwitched === 'function') {
+        this.config.onPersonaSwitched(event.payload);
+      }
+    });
+  }
+
   /**
    * Initialize Stronghold for secure key storage
    */
   async _initializeStronghold() {
+    if (!this.config.stronghold.password) {
+      console.log('🔐 Using the vault the backend unlocked');
+      return;
+    }
+
     try {
       // Initialize Stronghold vault
       await window.__TAURI__.core.invoke('stronghold_init', {
@@ -404,12 +584,10 @@ export class UserPersistence {
    */
   async _loadUserData() {
     try {
-      const userDataJson = await window.__TAURI__.core.invoke('read_user_data_file', {
-        filename: this.config.userData.preferencesFile
-      });
+      const userData = await this.readDocument(this.config.userData.preferencesFile);
       
-      if (userDataJson) {
-        this.userData = JSON.parse(userDataJson);
+      if (userData) {
+        this.userData = userData;
         console.log('📁 User data loaded from filesystem');
       }
       
@@ -424,10 +602,7 @@ export class UserPersistence {
    */
   async _saveUserData() {
     try {
-      await window.__TAURI__.core.invoke('write_user_data_file', {
-        filename: this.config.userData.preferencesFile,
-        data: JSON.stringify(this.userData, null, 2)
-      });
+      await this.writeDocument(this.config.userData.preferencesFile, this.userData);
       
     } catch (error) {
       console.error('❌ Failed to save user data:', error);
@@ -439,13 +614,9 @@ export class UserPersistence {
    */
   async _loadServiceUsers() {
     try {
-      const serviceUsersJson = await window.__TAURI__.core.invoke('read_user_data_file', {
-        filename: this.config.userData.serviceUsersFile
-      });
+      const serviceUsersData = await this.readDocument(this.config.userData.serviceUsersFile);
       
-      if (serviceUsersJson) {
-        const serviceUsersData = JSON.parse(serviceUsersJson);
-        
+      if (serviceUsersData) {
         // Convert to Map
         for (const [key, user] of Object.entries(serviceUsersData)) {
           this.serviceUsers.set(key, user);
@@ -467,10 +638,7 @@ export class UserPersistence {
       // Convert Map to object for JSON
       const serviceUsersObj = Object.fromEntries(this.serviceUsers);
       
-      await window.__TAURI__.core.invoke('write_user_data_file', {
-        filename: this.config.userData.serviceUsersFile,
-        data: JSON.stringify(serviceUsersObj, null, 2)
-      });
+      await this.writeDocument(this.config.userData.serviceUsersFile, serviceUsersObj);
       
     } catch (error) {
       console.error('❌ Failed to save service users:', error);
@@ -1,12 +1,16 @@
 use julia_rs::{Julia, JuliaUser};
 use serde_json::json;
-use sessionless::hex::{FromHex, IntoHex};
-use sessionless::{PrivateKey, Sessionless};
-use std::env;
+use sessionless::hex::IntoHex;
 use std::sync::{Mutex, LazyLock};
 use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
+use tracing::{debug, error, info, warn};
+
+use nullary_core::{
+    forget_uuid, get_service_url, julia_client_at, moderation, register_uuid, registered_uuid,
+    set_user_blocked, sign_document_at, sign_operation_at, NullaryError, SigningPurpose,
+};
 
 // Data structures for julia-based connections
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -46,64 +50,22 @@ static CONNECTIONS: LazyLock<Mutex<HashMap<String, JuliaConnection>>> = LazyLock
     Mutex::new(HashMap::new())
 });
 
-// Cache the julia user UUID
-static JULIA_USER_UUID: LazyLock<Mutex<Option<String>>> = LazyLock::new(|| {
-    Mutex::new(None)
-});
-
-/// Get service URL based on environment and service name
-fn get_service_url(service: &str) -> String {
-    let env = env::var("STACKCHAT_ENV").unwrap_or_else(|_| "dev".to_string());
-    println!("🌍 Environment for {}: {}", service, env);
-    
-    let url = match (env.as_str(), service) {
-        // Test environment (127.0.0.1:5111-5122)
-        ("test", "julia") => "http://127.0.0.1:5111/".to_string(),
-        
-        // Local environment (127.0.0.1:3000-3011)
-        ("local", "julia") => "http://127.0.0.1:3000/".to_string(),
-        
-        // Dev environment (default)
-        (_, service) => format!("https://dev.{}.allyabase.com/", service),
-    };
-    
-    println!("📍 Service URL for {} in {} env: {}", service, env, url);
-    url
-}
-
 /// Create a Julia instance with our consistent sessionless keys
-async fn create_julia_client() -> Result<Julia, String> {
-    // Use the same sessionless instance logic as the main app
-    let private_key = match env::var("PRIVATE_KEY") {
-        Ok(env_key) => {
-            println!("🔑 Julia client using PRIVATE_KEY from environment: {}...", &env_key[..16]);
-            env_key
-        }
-        Err(_) => {
-            println!("🔑 Julia client: No PRIVATE_KEY environment variable found, generating unique key");
-            // Generate unique key per app instance to avoid conflicts
-            let unique_id = std::process::id();
-            let base_key = "b75011b167c5e3a6b0de97d8e1950cd9548f83bb67f47112bed6a082db795496";
-            format!("{}{:08x}", &base_key[..56], unique_id) // Mix in process ID for uniqueness
-        }
-    };
-    
-    let sessionless = Sessionless::from_private_key(PrivateKey::from_hex(private_key).expect("private key"));
-    
+async fn create_julia_client() -> Result<Julia, NullaryError> {
+    // Use the same persistent identity as the main app
     let julia_url = get_service_url("julia");
-    println!("🔧 Creating julia client with URL: {}", julia_url);
-    let mut julia = Julia::new(Some(julia_url.clone()));
-    julia.sessionless = sessionless;
-    println!("🔧 Julia client created with base_url: {}", julia_url);
+    info!("🔧 Creating julia client with URL: {}", julia_url);
+    let julia = julia_client_at(&julia_url).await?;
+    info!("🔧 Julia client created with base_url: {}", julia_url);
     
     Ok(julia)
 }
 
 /// Update local connections from julia user data
-fn update_connections_from_julia(julia_user: &JuliaUser) -> Result<(), String> {
+fn update_connections_from_julia(julia_user: &JuliaUser) -> Result<(), NullaryError> {
     // Parse associations from julia user's keys
     if let Some(interacting_keys) = julia_user.keys.get("interactingKeys") {
-        let mut connections_map = CONNECTIONS.lock().map_err(|e| format!("Failed to lock connections: {}", e))?;
+        let mut connections_map = CONNECTIONS.lock().map_err(|e| NullaryError::internal(format!("Failed to lock connections: {}", e)))?;
         
         for (partner_uuid, partner_data) in interacting_keys {
             // Check if we already have this connection
@@ -127,116 +89,117 @@ fn update_connections_from_julia(julia_user: &JuliaUser) -> Result<(), String> {
                 };
                 
                 connections_map.insert(connection.uuid.clone(), connection);
-                println!("  📥 Loaded julia connection: {}", partner_uuid);
+                info!("  📥 Loaded julia connection: {}", partner_uuid);
             }
         }
     }
     
     // Also check pending prompts for incoming connection requests
     for (prompt_id, prompt) in &julia_user.pending_prompts {
-        println!("  📨 Found pending prompt: {} from {}", prompt_id, prompt.prompter);
+        info!("  📨 Found pending prompt: {} from {}", prompt_id, prompt.prompter);
     }
     
     Ok(())
 }
 
 /// Get or create julia user
-pub async fn get_or_create_julia_user() -> Result<JuliaUser, String> {
-    println!("🔍 Getting or creating julia user...");
+pub async fn get_or_create_julia_user() -> Result<JuliaUser, NullaryError> {
+    debug!("🔍 Getting or creating julia user...");
     
-    // Check if we already have a UUID cached
-    let cached_uuid = {
-        let uuid_cache = JULIA_USER_UUID.lock().map_err(|e| format!("Failed to lock UUID cache: {}", e))?;
-        uuid_cache.clone()
-    };
+    // Check if we already have a UUID registered for this identity
+    let julia_url = get_service_url("julia");
+    let cached_uuid = registered_uuid(&julia_url, "julia", None)?;
     
     let julia = create_julia_client().await?;
     
     if let Some(uuid) = cached_uuid {
-        println!("🔍 Checking for existing julia user with UUID: {}", uuid);
+        debug!("🔍 Checking for existing julia user with UUID: {}", uuid);
         // Try to get existing user with our sessionless keys
         // The julia service expects the UUID to be the actual UUID, not the public key
         match julia.get_user(&uuid).await {
             Ok(julia_user) => {
-                println!("✅ Retrieved existing julia user: {}", julia_user.uuid);
+                info!("✅ Retrieved existing julia user: {}", julia_user.uuid);
                 update_connections_from_julia(&julia_user)?;
                 return Ok(julia_user);
             }
             Err(e) => {
-                let error_msg = format!("{}", e);
-                println!("⚠️ Failed to get existing user: {}", error_msg);
+                let error = NullaryError::service("julia", e);
+                warn!("⚠️ Failed to get existing user: {}", error);
                 
                 // Only clear the cache if it's a "not found" error
                 // Keep the cache for parsing errors or network issues
-                if error_msg.contains("not found") || error_msg.contains("404") {
-                    println!("   User not found - clearing cache");
-                    let mut uuid_cache = JULIA_USER_UUID.lock().map_err(|e| format!("Failed to lock UUID cache: {}", e))?;
-                    *uuid_cache = None;
+                if error.is_not_found() {
+                    info!("   User not found - clearing cache");
+                    forget_uuid(&julia_url, "julia", None)?;
                 } else {
-                    println!("   Keeping cached UUID - might be temporary issue");
+                    info!("   Keeping cached UUID - might be temporary issue");
                     // For parsing errors, try to create user but don't cache a new UUID yet
                     // This prevents creating multiple users for the same person
-                    return Err(format!("Julia service communication error: {}", error_msg));
+                    return Err(error);
                 }
             }
         }
     }
     
     // Create new user
-    println!("📝 Creating new julia user...");
+    info!("📝 Creating new julia user...");
     let pub_key = julia.sessionless.public_key().to_hex();
     let user = JuliaUser::new(pub_key.clone(), "StackChat User".to_string());
     
     match julia.create_user(user).await {
         Ok(julia_user) => {
-            println!("✅ Julia user created with UUID: {}", julia_user.uuid);
-            println!("   Public key: {}", julia_user.pub_key);
+            info!("✅ Julia user created with UUID: {}", julia_user.uuid);
+            info!("   Public key: {}", julia_user.pub_key);
             
             // Cache the UUID for future use
-            {
-                let mut uuid_cache = JULIA_USER_UUID.lock().map_err(|e| format!("Failed to lock UUID cache: {}", e))?;
-                *uuid_cache = Some(julia_user.uuid.clone());
-                println!("💾 Cached julia UUID: {}", julia_user.uuid);
-            }
+            register_uuid(&julia_url, "julia", None, &julia_user.uuid)?;
+            info!("💾 Cached julia UUID: {}", julia_user.uuid);
             
             update_connections_from_julia(&julia_user)?;
             Ok(julia_user)
         }
         Err(e) => {
-            println!("❌ Failed to create julia user: {}", e);
-            Err(format!("Julia service error: {}", e))
+            error!("❌ Failed to create julia user: {}", e);
+            Err(NullaryError::service("julia", e))
         }
     }
 }
 
 /// Get julia connections (real implementation)
-pub async fn get_connections() -> Result<Vec<JuliaConnection>, String> {
-    println!("🔍 Getting real julia connections...");
+pub async fn get_connections() -> Result<Vec<JuliaConnection>, NullaryError> {
+    debug!("🔍 Getting real julia connections...");
     
     // Try to refresh from julia service first
     let _ = get_or_create_julia_user().await;
     
-    // Get connections from storage
-    let connections_map = CONNECTIONS.lock().map_err(|e| format!("Failed to lock connections: {}", e))?;
+    // Get connections from storage, marking those whose partner is blocked
+    // by UUID or key, so a block from any Nullary app applies here too
+    let mut connections_map = CONNECTIONS.lock().map_err(|e| NullaryError::internal(format!("Failed to lock connections: {}", e)))?;
+    let moderation = moderation();
+    for connection in connections_map.values_mut() {
+        if moderation.blocks(&connection.partner_uuid) || moderation.blocks(&connection.partner_public_key) {
+            connection.status = "Blocked".to_string();
+        }
+    }
     let connections: Vec<JuliaConnection> = connections_map.values().cloned().collect();
     
-    println!("📋 Found {} julia connections", connections.len());
+    info!("📋 Found {} julia connections", connections.len());
     for conn in &connections {
-        println!("  - {} ({}): {}", conn.partner_name, conn.status, conn.uuid);
+        info!("  - {} ({}): {}", conn.partner_name, conn.status, conn.uuid);
     }
     
     Ok(connections)
 }
 
 /// Send a message in a julia association
-pub async fn send_message(association_uuid: String, content: String) -> Result<Message, String> {
-    println!("📤 Sending message to julia association: {}", association_uuid);
+pub async fn send_message(association_uuid: String, content: String) -> Result<Message, NullaryError> {
+    info!("📤 Sending message to julia association: {}", association_uuid);
     
     // Get the connection to find the partner's UUID
     let connection = {
-        let connections_map = CONNECTIONS.lock().map_err(|e| format!("Failed to lock connections: {}", e))?;
+        let connections_map = CONNECTIONS.lock().map_err(|e| NullaryError::internal(format!("Failed to lock connections: {}", e)))?;
         connections_map.get(&association_uuid)
-            .ok_or_else(|| format!("Connection {} not found", association_uuid))?
+            .ok_or_else(|| NullaryError::not_found("julia", format!("Connection {} not found", association_uuid)))?
             .clone()
     };
     
@@ -246,15 +209,15 @@ pub async fn send_message(association_uuid: String, content: String) -> Result<M
     // CRITICAL FIX: Check if partner_uuid is actually a UUID or a public key
     let receiver_uuid = if connection.partner_uuid.len() == 66 && connection.partner_uuid.starts_with("02") {
         // This is a public key, we need to find the actual UUID
-        println!("⚠️ Partner UUID appears to be a public key: {}", connection.partner_uuid);
-        println!("🔍 Searching julia interacting keys for real UUID...");
+        warn!("⚠️ Partner UUID appears to be a public key: {}", connection.partner_uuid);
+        debug!("🔍 Searching julia interacting keys for real UUID...");
         
         // Look up the partner's UUID from our interacting keys
         if let Some(interacting_keys) = julia_user.keys.get("interactingKeys") {
             let mut found_uuid = None;
             for (uuid, stored_key) in interacting_keys {
                 if stored_key == &connection.partner_public_key {
-                    println!("🎯 Found partner's real UUID: {} for public key: {}", uuid, stored_key);
+                    info!("🎯 Found partner's real UUID: {} for public key: {}", uuid, stored_key);
                     found_uuid = Some(uuid.clone());
                     break;
                 }
@@ -263,14 +226,14 @@ pub async fn send_message(association_uuid: String, content: String) -> Result<M
             if let Some(uuid) = found_uuid {
                 uuid
             } else {
-                return Err(format!("Partner UUID not found in julia associations. Partner may not be properly associated. Partner public key: {}", connection.partner_public_key));
+                return Err(NullaryError::not_found("julia", format!("Partner UUID not found in julia associations. Partner may not be properly associated. Partner public key: {}", connection.partner_public_key)));
             }
         } else {
-            return Err("No interacting keys found - partner is not associated".to_string());
+            return Err(NullaryError::not_found("julia", "No interacting keys found - partner is not associated"));
         }
     } else {
         // This looks like a proper UUID
-        println!("✅ Using partner UUID: {}", connection.partner_uuid);
+        info!("✅ Using partner UUID: {}", connection.partner_uuid);
         connection.partner_uuid.clone()
     };
     
@@ -279,11 +242,12 @@ pub async fn send_message(association_uuid: String, content: String) -> Result<M
     
     match julia.post_message(&julia_user.uuid, &receiver_uuid, content.clone()).await {
         Ok(result) => {
-            println!("✅ Message sent via julia service: {}", content);
-            println!("📊 Julia response: {:?}", result);
+            info!("✅ Message sent via julia service: {}", content);
+            info!("📊 Julia response: {:?}", result);
         }
         Err(e) => {
-            println!("⚠️ Failed to send via julia: {}", e);
+            warn!("⚠️ Failed to send via julia: {}", e);
+            return Err(NullaryError::service("julia", format!("Failed to send message: {}", e)));
         }
     }
     
@@ -303,49 +267,117 @@ pub async fn send_message(association_uuid: String, content: String) -> Result<M
 }
 
 /// Accept a julia connection
-pub async fn accept_connection(association_uuid: String) -> Result<bool, String> {
-    println!("🤝 Accepting julia association: {}", association_uuid);
+pub async fn accept_connection(association_uuid: String) -> Result<bool, NullaryError> {
+    info!("🤝 Accepting julia association: {}", association_uuid);
     
     // Update connection status to "Active"
     {
-        let mut connections_map = CONNECTIONS.lock().map_err(|e| format!("Failed to lock connections: {}", e))?;
+        let mut connections_map = CONNECTIONS.lock().map_err(|e| NullaryError::internal(format!("Failed to lock connections: {}", e)))?;
         if let Some(connection) = connections_map.get_mut(&association_uuid) {
             connection.status = "Active".to_string();
-            println!("✅ Connection {} status updated to Active", association_uuid);
+            info!("✅ Connection {} status updated to Active", association_uuid);
             
             // In a real system, the julia service would notify the other party
             // For now, we just log that this would happen
-            println!("📨 In production, julia service would notify partner at: {}", connection.partner_public_key);
-            println!("   Partner would create their corresponding connection");
+            info!("📨 In production, julia service would notify partner at: {}", connection.partner_public_key);
+            info!("   Partner would create their corresponding connection");
         } else {
-            return Err(format!("Connection {} not found", association_uuid));
+            return Err(NullaryError::not_found("julia", format!("Connection {} not found", association_uuid)));
         }
     }
     
     Ok(true)
 }
 
-/// Block a julia connection
-pub async fn block_connection(association_uuid: String) -> Result<bool, String> {
-    println!("🚫 Blocking julia association: {}", association_uuid);
+/// Block a julia connection, and its partner in every Nullary app
+pub async fn block_connection(association_uuid: String) -> Result<bool, NullaryError> {
+    warn!("🚫 Blocking julia association: {}", association_uuid);
     
     // Update connection status to "Blocked"
-    {
-        let mut connections_map = CONNECTIONS.lock().map_err(|e| format!("Failed to lock connections: {}", e))?;
+    let partner = {
+        let mut connections_map = CONNECTIONS.lock().map_err(|e| NullaryError::internal(format!("Failed to lock connections: {}", e)))?;
         if let Some(connection) = connections_map.get_mut(&association_uuid) {
             connection.status = "Blocked".to_string();
-            println!("❌ Connection {} status updated to Blocked", association_uuid);
+            error!("❌ Connection {} status updated to Blocked", association_uuid);
+            (connection.partner_uuid.clone(), connection.partner_public_key.clone())
         } else {
-            return Err(format!("Connection {} not found", association_uuid));
+            return Err(NullaryError::not_found("julia", format!("Connection {} not found", association_uuid)));
         }
-    }
+    };
+    
+    // Their julia UUID hides their messages, their key their posts elsewhere
+    set_user_blocked(partner.0, true)?;
+    set_user_blocked(partner.1, true)?;
     
     Ok(true)
 }
 
+/// A message as julia stores it
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JuliaMessage {
+    sender_uuid: String,
+    receiver_uuid: String,
+    message: String,
+    timestamp: String,
+}
+
+/// Messages exchanged with the partner of a julia association, without those
+/// sent by blocked users
+pub async fn get_messages(connection: &JuliaConnection) -> Result<Vec<Message>, NullaryError> {
+    info!("📥 Getting julia messages for association: {}", connection.uuid);
+    
+    let julia_user = get_or_create_julia_user().await?;
+    let julia = create_julia_client().await?;
+    
+    let response = julia.get_messages(&julia_user.uuid).await
+        .map_err(|e| NullaryError::service("julia", format!("Failed to get messages: {}", e)))?;
+    let julia_messages: Vec<JuliaMessage> = serde_json::from_value(
+        response.get("messages").cloned().unwrap_or_else(|| json!([]))
+    ).map_err(|e| NullaryError::service("julia", format!("Failed to parse messages: {}", e)))?;
+    
+    let messages = julia_messages.into_iter()
+        .filter(|message| {
+            message.sender_uuid == connection.partner_uuid || message.receiver_uuid == connection.partner_uuid
+        })
+        .map(|message| {
+            let from_us = message.sender_uuid == julia_user.uuid;
+            Message {
+                uuid: format!("msg-{}", uuid::Uuid::new_v4()),
+                sender_name: if from_us { "You".to_string() } else { connection.partner_name.clone() },
+                recipient_uuid: message.receiver_uuid,
+                content: message.message,
+                // Julia keeps milliseconds since the epoch as a string
+                timestamp: message.timestamp.parse::<i64>().ok()
+                    .and_then(DateTime::from_timestamp_millis)
+                    .unwrap_or_else(Utc::now),
+                association_uuid: connection.uuid.clone(),
+                read: from_us,
+                sender_uuid: message.sender_uuid,
+            }
+        })
+        .collect();
+    
+    Ok(without_blocked_messages(messages, connection))
+}
+
+/// Messages without those sent by blocked users, the partner included when
+/// they are blocked by key
+pub fn without_blocked_messages(messages: Vec<Message>, connection: &JuliaConnection) -> Vec<Message> {
+    let moderation = moderation();
+    let partner_blocked = moderation.blocks(&connection.partner_public_key);
+    messages
+        .into_iter()
+        .filter(|message| {
+            !moderation.blocks(&message.sender_uuid)
+                && !(partner_blocked && message.sender_uuid == connection.partner_uuid)
+        })
+        .collect()
+}
+
 /// Process a julia-based connection URL
-pub async fn process_connection_url(connection_url: String) -> Result<JuliaConnection, String> {
-    println!("🔗 Processing julia connection URL: {}", connection_url);
+pub async fn process_connection_url(connection_url: String) -> Result<JuliaConnection, NullaryError> {
+    info!("🔗 Processing julia connection URL: {}", connection_url);
     
     // Get our current user first to check for self-connection
     let julia_user = get_or_create_julia_user().await?;
@@ -354,7 +386,7 @@ pub async fn process_connection_url(connection_url: String) -> Result<JuliaConne
     // Parse URL to extract connection parameters
     let url_parts: Vec<&str> = connection_url.split('?').collect();
     if url_parts.len() != 2 {
-        return Err("Invalid connection URL format".to_string());
+        return Err(NullaryError::validation("Invalid connection URL format"));
     }
     
     let query_params: std::collections::HashMap<String, String> = url_parts[1]
@@ -373,48 +405,48 @@ pub async fn process_connection_url(connection_url: String) -> Result<JuliaConne
         .collect();
     
     let message = query_params.get("message")
-        .ok_or("Missing message parameter")?;
+        .ok_or_else(|| NullaryError::validation("Missing message parameter"))?;
     let partner_name = query_params.get("name")
-        .ok_or("Missing name parameter")?;
+        .ok_or_else(|| NullaryError::validation("Missing name parameter"))?;
     let julia_url = query_params.get("juliaUrl")
-        .ok_or("Missing juliaUrl parameter")?;
+        .ok_or_else(|| NullaryError::validation("Missing juliaUrl parameter"))?;
     let public_key_a = query_params.get("publicKey")
-        .ok_or("Missing publicKey parameter")?;
+        .ok_or_else(|| NullaryError::validation("Missing publicKey parameter"))?;
     let _signature = query_params.get("signature")
-        .ok_or("Missing signature parameter")?;
+        .ok_or_else(|| NullaryError::validation("Missing signature parameter"))?;
     let partner_julia_uuid = query_params.get("juliaUUID")
-        .ok_or("Missing juliaUUID parameter")?;
+        .ok_or_else(|| NullaryError::validation("Missing juliaUUID parameter"))?;
     let prompt_id = query_params.get("promptId")
-        .ok_or("Missing promptId parameter")?;
+        .ok_or_else(|| NullaryError::validation("Missing promptId parameter"))?;
     let prompt_text = query_params.get("promptText")
-        .ok_or("Missing promptText parameter")?;
+        .ok_or_else(|| NullaryError::validation("Missing promptText parameter"))?;
     let new_timestamp = query_params.get("newTimestamp")
-        .ok_or("Missing newTimestamp parameter")?;
+        .ok_or_else(|| NullaryError::validation("Missing newTimestamp parameter"))?;
     let new_signature = query_params.get("newSignature")
-        .ok_or("Missing newSignature parameter")?;
+        .ok_or_else(|| NullaryError::validation("Missing newSignature parameter"))?;
     
     // Check for self-connection attempt
     if public_key_a == &our_public_key {
-        return Err("❌ Cannot create connection to yourself! You're trying to connect to your own public key.".to_string());
+        return Err(NullaryError::validation("❌ Cannot create connection to yourself! You're trying to connect to your own public key."));
     }
     
-    println!("✅ Connection request valid: {} → {}", our_public_key, public_key_a);
-    println!("✅ Partner julia UUID: {}", partner_julia_uuid);
-    println!("✅ Prompt ID: {}", prompt_id);
-    println!("✅ Partner's signed timestamp: {}", new_timestamp);
+    info!("✅ Connection request valid: {} → {}", our_public_key, public_key_a);
+    info!("✅ Partner julia UUID: {}", partner_julia_uuid);
+    info!("✅ Prompt ID: {}", prompt_id);
+    info!("✅ Partner's signed timestamp: {}", new_timestamp);
     
     // Validate timestamp (check if not expired - 5 minute window)
     let message_parts: Vec<&str> = message.split('|').collect();
     if message_parts.len() != 8 {
-        return Err(format!("Invalid message format - should be timestamp|publicKey|name|juliaUrl|juliaUUID|promptId|newTimestamp|newSignature, got {} parts: {:?}", message_parts.len(), message_parts));
+        return Err(NullaryError::validation(format!("Invalid message format - should be timestamp|publicKey|name|juliaUrl|juliaUUID|promptId|newTimestamp|newSignature, got {} parts: {:?}", message_parts.len(), message_parts)));
     }
     
     let timestamp: i64 = message_parts[0].parse()
-        .map_err(|_| "Invalid timestamp in message")?;
+        .map_err(|_| NullaryError::validation("Invalid timestamp in message"))?;
     let current_timestamp = Utc::now().timestamp();
     
     if current_timestamp - timestamp > 300 { // 5 minutes expiration
-        return Err("Connection URL has expired".to_string());
+        return Err(NullaryError::validation("Connection URL has expired"));
     }
     
     // Julia user already retrieved above for self-connection check
@@ -424,7 +456,7 @@ pub async fn process_connection_url(connection_url: String) -> Result<JuliaConne
     
     // The partner (App 1) has already signed the prompt
     // We (App 2) just need to associate using their signed prompt data
-    println!("📝 Using partner's signed prompt for association...");
+    info!("📝 Using partner's signed prompt for association...");
     
     // Create the association prompt using the signed data from the URL
     let association_prompt = julia_rs::Prompt {
@@ -437,18 +469,18 @@ pub async fn process_connection_url(connection_url: String) -> Result<JuliaConne
         new_signature: Some(new_signature.clone()), // Partner's signature
     };
     
-    println!("🔗 Associating with partner's signed prompt...");
-    println!("   Partner UUID: {}", partner_julia_uuid);
-    println!("   Partner Public Key: {}", public_key_a);
-    println!("   Prompt: {}", prompt_text);
+    info!("🔗 Associating with partner's signed prompt...");
+    info!("   Partner UUID: {}", partner_julia_uuid);
+    info!("   Partner Public Key: {}", public_key_a);
+    info!("   Prompt: {}", prompt_text);
     
     // Associate using the partner's signed prompt
     // This should add the partner to our interacting keys
     let updated_user = julia.associate(&julia_user.uuid, &association_prompt).await
-        .map_err(|e| format!("Failed to complete julia association: {}", e))?;
+        .map_err(|e| NullaryError::service("julia", format!("Failed to complete julia association: {}", e)))?;
     
-    println!("✅ Julia association completed successfully!");
-    println!("📊 We now have {} associations", updated_user.keys.get("interactingKeys").map(|k| k.len()).unwrap_or(0));
+    info!("✅ Julia association completed successfully!");
+    info!("📊 We now have {} associations", updated_user.keys.get("interactingKeys").map(|k| k.len()).unwrap_or(0));
     
     let partner_uuid = partner_julia_uuid.clone();
     
@@ -465,22 +497,22 @@ pub async fn process_connection_url(connection_url: String) -> Result<JuliaConne
         status: "Pending".to_string(), // Pending until partner accepts
     };
     
-    println!("✅ Created julia association: {:?}", connection);
+    info!("✅ Created julia association: {:?}", connection);
     
     // Store the connection
     {
-        let mut connections_map = CONNECTIONS.lock().map_err(|e| format!("Failed to lock connections: {}", e))?;
+        let mut connections_map = CONNECTIONS.lock().map_err(|e| NullaryError::internal(format!("Failed to lock connections: {}", e)))?;
         connections_map.insert(connection.uuid.clone(), connection.clone());
-        println!("💾 Stored connection with UUID: {}", connection.uuid);
-        println!("📊 Total connections in storage: {}", connections_map.len());
+        info!("💾 Stored connection with UUID: {}", connection.uuid);
+        info!("📊 Total connections in storage: {}", connections_map.len());
     }
     
     Ok(connection)
 }
 
 /// Generate a julia-based connection URL
-pub async fn generate_connection_url() -> Result<String, String> {
-    println!("🔗 Generating julia connection URL...");
+pub async fn generate_connection_url() -> Result<String, NullaryError> {
+    info!("🔗 Generating julia connection URL...");
     
     // Get our julia user to include our UUID in the connection URL
     let julia_user = get_or_create_julia_user().await?;
@@ -489,35 +521,35 @@ pub async fn generate_connection_url() -> Result<String, String> {
     let julia_url = get_service_url("julia");
     
     // Step 1: Get a prompt from julia for association
-    println!("📝 Getting association prompt from julia...");
+    info!("📝 Getting association prompt from julia...");
     let prompt_user = julia.get_prompt(&julia_user.uuid).await
-        .map_err(|e| format!("Failed to get julia prompt: {}", e))?;
+        .map_err(|e| NullaryError::service("julia", format!("Failed to get julia prompt: {}", e)))?;
     
     // The prompt should be in the pending_prompts
     let prompt_id = prompt_user.pending_prompts.keys().next()
-        .ok_or("No prompt returned from julia")?;
+        .ok_or_else(|| NullaryError::service("julia", "No prompt returned from julia"))?;
     let prompt = prompt_user.pending_prompts.get(prompt_id)
-        .ok_or("Prompt not found")?;
+        .ok_or_else(|| NullaryError::not_found("julia", "Prompt not found"))?;
     
-    println!("✅ Got prompt from julia: {}", prompt_id);
-    println!("📝 Prompt details: {:?}", prompt);
+    info!("✅ Got prompt from julia: {}", prompt_id);
+    info!("📝 Prompt details: {:?}", prompt);
     
     // Step 2: Sign the prompt ourselves (App 1)
-    println!("✍️ Signing our own prompt...");
+    info!("✍️ Signing our own prompt...");
     let signed_result = julia.sign_prompt(&julia_user.uuid, &prompt).await
-        .map_err(|e| format!("Failed to sign prompt: {}", e))?;
-    println!("✅ Signed prompt successfully: {:?}", signed_result);
+        .map_err(|e| NullaryError::service("julia", format!("Failed to sign prompt: {}", e)))?;
+    info!("✅ Signed prompt successfully: {:?}", signed_result);
     
     // Now we need to prepare the signed prompt data for the connection URL
     // The second app will need: our UUID, our public key, the prompt text, and our signature
-    let new_timestamp = chrono::Utc::now().timestamp_millis().to_string();
-    let sign_message = format!("{}{}{}{}", 
-        new_timestamp,
-        julia_user.uuid.clone(),
-        julia_user.pub_key.clone(),
-        prompt.prompt.as_deref().unwrap_or("")
-    );
-    let new_signature = julia.sessionless.sign(&sign_message).into_hex();
+    let prompt_signed = sign_operation_at(
+        &julia_url,
+        "julia",
+        SigningPurpose::JuliaPrompt,
+        &[&julia_user.uuid, &julia_user.pub_key, prompt.prompt.as_deref().unwrap_or("")],
+    ).await?;
+    let new_timestamp = prompt_signed.timestamp;
+    let new_signature = prompt_signed.signature;
     
     // Create connection info including all signed prompt details
     // Format: timestamp|publicKey|name|juliaServerURL|juliaUUID|promptId|newTimestamp|newSignature
@@ -529,7 +561,7 @@ pub async fn generate_connection_url() -> Result<String, String> {
         timestamp, public_key, name, julia_url, julia_uuid, prompt_id, new_timestamp, new_signature);
     
     // Sign the entire connection message
-    let signature = julia.sessionless.sign(&message).into_hex();
+    let signature = sign_document_at(&julia_url, "julia", SigningPurpose::ConnectionInvite, &message).await?;
     
     // Create URL with all needed info for julia association including signed prompt
     let connection_url = format!(
@@ -562,12 +594,12 @@ pub async fn generate_connection_url() -> Result<String, String> {
     
     // Store the outgoing connection
     {
-        let mut connections_map = CONNECTIONS.lock().map_err(|e| format!("Failed to lock connections: {}", e))?;
+        let mut connections_map = CONNECTIONS.lock().map_err(|e| NullaryError::internal(format!("Failed to lock connections: {}", e)))?;
         connections_map.insert(outgoing_connection.uuid.clone(), outgoing_connection.clone());
-        println!("📤 Created outgoing connection request: {}", outgoing_connection.uuid);
-        println!("📊 Total connections in storage: {}", connections_map.len());
+        info!("📤 Created outgoing connection request: {}", outgoing_connection.uuid);
+        info!("📊 Total connections in storage: {}", connections_map.len());
     }
     
-    println!("✅ Generated connection URL: {}", connection_url);
+    info!("✅ Generated connection URL: {}", connection_url);
     Ok(connection_url)
 }
\ No newline at end of file
@@ -1,109 +1,34 @@
-use bdo_rs::BDO;
-use sanora_rs::Sanora;
 use serde_json::json;
 use serde_json::Value;
-use sessionless::hex::FromHex;
-use sessionless::hex::IntoHex;
-use sessionless::{PrivateKey, Sessionless};
-use std::env;
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
+use tracing::{error, info};
 
 mod julia_integration;
 use julia_integration::{JuliaConnection, Message, Conversation};
 
+// Shared identity, endpoints and service clients
+use nullary_core::{
+    bdo_client_at, bdo_user_uuid_at, current_env, forget_if_not_found, get_service_url, public_key,
+    queue_if_transient, sanora_client_at, NullaryError,
+};
+
 /// Debug logging command for development
 #[tauri::command]
 fn dbg(log: &str) {
-    dbg!(log);
+    info!(target: "frontend", "{}", log);
 }
 
-/// Get the public key from the sessionless instance
+/// Get the public key this install uses with julia
 #[tauri::command]
-async fn get_public_key() -> Result<String, String> {
-    let sessionless = get_sessionless().await?;
-    Ok(sessionless.public_key().to_hex())
+async fn get_public_key() -> Result<String, NullaryError> {
+    public_key("julia")
 }
 
 /// Get environment configuration from environment variables
 #[tauri::command]
 fn get_env_config() -> String {
-    env::var("STACKCHAT_ENV").unwrap_or_else(|_| "dev".to_string())
-}
-
-/// Get service URL based on environment and service name
-fn get_service_url(service: &str) -> String {
-    let env = env::var("STACKCHAT_ENV").unwrap_or_else(|_| "dev".to_string());
-    
-    let url = match (env.as_str(), service) {
-        // Test environment (127.0.0.1:5111-5122)
-        ("test", "julia") => "http://127.0.0.1:5111/".to_string(),
-        ("test", "continuebee") => "http://127.0.0.1:5112/".to_string(),
-        ("test", "pref") => "http://127.0.0.1:5113/".to_string(),
-        ("test", "bdo") => "http://127.0.0.1:5114/".to_string(),
-        ("test", "joan") => "http://127.0.0.1:5115/".to_string(),
-        ("test", "addie") => "http://127.0.0.1:5116/".to_string(),
-        ("test", "fount") => "http://127.0.0.1:5117/".to_string(),
-        ("test", "dolores") => "http://127.0.0.1:5118/".to_string(),
-        ("test", "minnie") => "http://127.0.0.1:5119/".to_string(),
-        ("test", "aretha") => "http://127.0.0.1:5120/".to_string(),
-        ("test", "sanora") => "http://127.0.0.1:5121/".to_string(),
-        ("test", "covenant") => "http://127.0.0.1:5122/".to_string(),
-        
-        // Local environment (127.0.0.1:3000-3011)
-        ("local", "julia") => "http://127.0.0.1:3000/".to_string(),
-        ("local", "continuebee") => "http://127.0.0.1:2999/".to_string(),
-        ("local", "fount") => "http://127.0.0.1:3002/".to_string(),
-        ("local", "bdo") => "http://127.0.0.1:3003/".to_string(),
-        ("local", "pref") => "http://127.0.0.1:3004/".to_string(),
-        ("local", "addie") => "http://127.0.0.1:3005/".to_string(),
-        ("local", "dolores") => "http://127.0.0.1:3005/".to_string(),
-        ("local", "joan") => "http://127.0.0.1:3004/".to_string(),
-        ("local", "aretha") => "http://127.0.0.1:7277/".to_string(),
-        ("local", "minnie") => "http://127.0.0.1:2525/".to_string(),
-        ("local", "sanora") => "http://127.0.0.1:7243/".to_string(),
-        ("local", "covenant") => "http://127.0.0.1:3011/".to_string(),
-        
-        // Dev environment (default)
-        (_, service) => format!("https://dev.{}.allyabase.com/", service),
-    };
-    
-    url
-}
-
-/// Get or create sessionless instance using environment variable or unique generated key
-async fn get_sessionless() -> Result<Sessionless, String> {
-    // Check for environment variable first
-    match env::var("PRIVATE_KEY") {
-        Ok(env_key) => {
-            println!("🔑 Using PRIVATE_KEY from environment: {}...", &env_key[..16]);
-            let sessionless = Sessionless::from_private_key(PrivateKey::from_hex(env_key).expect("private key"));
-            println!("🔑 Public key (from env): {}", sessionless.public_key().to_hex());
-            return Ok(sessionless);
-        }
-        Err(_) => {
-            println!("🔑 No PRIVATE_KEY environment variable found, generating unique key");
-        }
-    }
-    
-    // Generate unique key per app instance to avoid conflicts
-    // In production, this should be stored persistently per user
-    let unique_id = std::process::id();
-    let base_key = "b75011b167c5e3a6b0de97d8e1950cd9548f83bb67f47112bed6a082db795496";
-    let private_key = format!("{}{:08x}", &base_key[..56], unique_id); // Mix in process ID for uniqueness
-    
-    println!("🔑 Generated private key (process {}): {}...", unique_id, &private_key[..16]);
-    let sessionless =
-        Sessionless::from_private_key(PrivateKey::from_hex(private_key).expect("private key"));
-    println!("🔑 Public key: {}", sessionless.public_key().to_hex());
-    Ok(sessionless)
-}
-
-/// Sign a message using sessionless
-async fn sign_message(message: String) -> Result<String, String> {
-    let sessionless = get_sessionless().await?;
-    let signature = sessionless.sign(&message);
-    Ok(signature.into_hex())
+    current_env()
 }
 
 // ============================================================================
@@ -112,66 +37,80 @@ async fn sign_message(message: String) -> Result<String, String> {
 
 /// Get julia connections (delegates to julia_integration module)
 #[tauri::command]
-async fn get_connections() -> Result<Vec<JuliaConnection>, String> {
+async fn get_connections() -> Result<Vec<JuliaConnection>, NullaryError> {
     julia_integration::get_connections().await
 }
 
 /// Get conversation messages for a julia association
 #[tauri::command]
-async fn get_conversation(association_uuid: String) -> Result<Conversation, String> {
-    println!("💬 Getting conversation for association: {}", association_uuid);
+async fn get_conversation(association_uuid: String) -> Result<Conversation, NullaryError> {
+    info!("💬 Getting conversation for association: {}", association_uuid);
     
     let connections = julia_integration::get_connections().await?;
     let connection = connections.into_iter()
         .find(|c| c.uuid == association_uuid)
-        .ok_or_else(|| format!("Connection {} not found", association_uuid))?;
+        .ok_or_else(|| NullaryError::not_found("julia", format!("Connection {} not found", association_uuid)))?;
     
-    // No mock data - return empty conversation until real julia message retrieval is implemented
+    // Messages from blocked users are dropped as they come in
+    let messages = julia_integration::get_messages(&connection).await?;
     let conversation = Conversation {
         connection,
-        messages: vec![], // Empty - no mock data!
-        total_count: 0,
+        total_count: messages.len(),
+        messages,
     };
     
-    println!("📋 Retrieved conversation with {} real messages", conversation.messages.len());
+    info!("📋 Retrieved conversation with {} messages", conversation.messages.len());
     Ok(conversation)
 }
 
-/// Send a message in a julia association
+/// A message as the outbox stores and replays it
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct OutgoingMessage {
+    association_uuid: String,
+    content: String,
+}
+
+/// Send a message in a julia association, queued in the outbox while julia is
+/// unreachable
 #[tauri::command]
-async fn send_message(association_uuid: String, content: String) -> Result<Message, String> {
-    julia_integration::send_message(association_uuid, content).await
+async fn send_message(association_uuid: String, content: String) -> Result<Message, NullaryError> {
+    let outgoing = OutgoingMessage {
+        association_uuid,
+        content,
+    };
+    let result = julia_integration::send_message(outgoing.association_uuid.clone(), outgoing.content.clone()).await;
+    queue_if_transient(result, "send_message", "julia", &get_service_url("julia"), &outgoing).await
 }
 
 /// Mark messages as read for a julia association
 #[tauri::command]
-async fn mark_messages_read(association_uuid: String) -> Result<bool, String> {
-    println!("Marked messages as read for julia association: {}", association_uuid);
+async fn mark_messages_read(association_uuid: String) -> Result<bool, NullaryError> {
+    info!("Marked messages as read for julia association: {}", association_uuid);
     Ok(true)
 }
 
 /// Accept a julia connection
 #[tauri::command]
-async fn accept_connection(association_uuid: String) -> Result<bool, String> {
+async fn accept_connection(association_uuid: String) -> Result<bool, NullaryError> {
     julia_integration::accept_connection(association_uuid).await
 }
 
 /// Block a julia connection
 #[tauri::command]
-async fn block_connection(association_uuid: String) -> Result<bool, String> {
+async fn block_connection(association_uuid: String) -> Result<bool, NullaryError> {
     julia_integration::block_connection(association_uuid).await
 }
 
 /// Generate a julia-based connection URL
 #[tauri::command]
-async fn generate_connection_url() -> Result<String, String> {
+async fn generate_connection_url() -> Result<String, NullaryError> {
     julia_integration::generate_connection_url().await
 }
 
 /// Create a reciprocal connection for testing (simulates julia service behavior)
 #[tauri::command]
-async fn create_reciprocal_connection(partner_public_key: String, partner_name: String) -> Result<JuliaConnection, String> {
-    println!("🔄 Creating reciprocal connection for testing");
+async fn create_reciprocal_connection(partner_public_key: String, partner_name: String) -> Result<JuliaConnection, NullaryError> {
+    info!("🔄 Creating reciprocal connection for testing");
     
     let julia_url = get_service_url("julia");
     
@@ -188,13 +127,13 @@ async fn create_reciprocal_connection(partner_public_key: String, partner_name:
         status: "Active".to_string(), // Already accepted
     };
     
-    println!("✅ Created reciprocal connection: {}", connection.uuid);
+    info!("✅ Created reciprocal connection: {}", connection.uuid);
     Ok(connection)
 }
 
 /// Process a julia-based connection URL
 #[tauri::command]
-async fn process_connection_url(connection_url: String) -> Result<JuliaConnection, String> {
+async fn process_connection_url(connection_url: String) -> Result<JuliaConnection, NullaryError> {
     julia_integration::process_connection_url(connection_url).await
 }
 
@@ -204,104 +143,106 @@ async fn process_connection_url(connection_url: String) -> Result<JuliaConnectio
 
 /// Get sessionless info for the frontend
 #[tauri::command]
-async fn get_sessionless_info() -> Result<Value, String> {
-    let sessionless = get_sessionless().await?;
+async fn get_sessionless_info() -> Result<Value, NullaryError> {
+    let public_key = public_key("julia")?;
     Ok(json!({
-        "uuid": sessionless.public_key().to_hex(),
-        "public_key": sessionless.public_key().to_hex()
+        "uuid": public_key,
+        "public_key": public_key
     }))
 }
 
 /// Health check for the app
 #[tauri::command]
-async fn health_check() -> Result<Value, String> {
-    let health_info = json!({
+async fn health_check() -> Result<Value, NullaryError> {
+    let report = nullary_core::check_service_health(None).await?;
+    Ok(json!({
         "app": "stackchat",
         "version": "0.0.1",
         "environment": get_env_config(),
-        "services": {
-            "julia": get_service_url("julia"),
-            "bdo": get_service_url("bdo"),
-            "sanora": get_service_url("sanora")
-        },
-        "timestamp": Utc::now().to_rfc3339()
-    });
-
-    Ok(health_info)
+        "health": report
+    }))
 }
 
 /// Create Sanora user for teleportation (real implementation)
 #[tauri::command]
-async fn create_sanora_user(sanora_url: String) -> Result<Value, String> {
-    println!("🔧 Creating Sanora user at: {}", sanora_url);
+async fn create_sanora_user(sanora_url: String) -> Result<Value, NullaryError> {
+    info!("🔧 Creating Sanora user at: {}", sanora_url);
     
-    match get_sessionless().await {
-        Ok(sessionless) => {
-            let sanora = Sanora::new(Some(sanora_url), Some(sessionless));
+    match sanora_client_at(&sanora_url).await {
+        Ok(sanora) => {
             match sanora.create_user().await {
                 Ok(user) => {
-                    println!("✅ Got Sanora user: uuid={}, basePubKey={}", user.uuid, user.base_pub_key);
+                    info!("✅ Got Sanora user: uuid={}, basePubKey={}", user.uuid, user.base_pub_key);
                     Ok(json!({
                         "uuid": user.uuid,
                         "basePubKey": user.base_pub_key
                     }))
                 },
                 Err(e) => {
-                    println!("❌ Failed to create Sanora user: {}", e);
-                    Err(format!("Failed to create Sanora user: {}", e))
+                    error!("❌ Failed to create Sanora user: {}", e);
+                    Err(NullaryError::service("sanora", format!("Failed to create Sanora user: {}", e)))
                 }
             }
         }
         Err(e) => {
-            println!("❌ Failed to get sessionless: {}", e);
-            Err(format!("Failed to get sessionless: {}", e))
+            error!("❌ Failed to get sessionless: {}", e);
+            Err(e)
         }
     }
 }
 
 /// Teleport content via BDO (real implementation)
 #[tauri::command]
-async fn teleport_content(bdo_url: String, teleport_url: String) -> Result<Value, String> {
-    println!("🌐 Teleporting content from: {} via BDO: {}", teleport_url, bdo_url);
+async fn teleport_content(bdo_url: String, teleport_url: String) -> Result<Value, NullaryError> {
+    info!("🌐 Teleporting content from: {} via BDO: {}", teleport_url, bdo_url);
     
-    let s = get_sessionless().await;
-    match s {
-        Ok(sessionless) => {
-            let bdo = BDO::new(Some(bdo_url), Some(sessionless));
+    match bdo_client_at(&bdo_url).await {
+        Ok(bdo) => {
             
             // Create/get BDO user first
             let stackchat = "stackchat";
-            let bdo_user = match bdo.create_user(&stackchat, &json!({})).await {
-                Ok(user) => {
-                    println!("✅ BDO user ready for teleportation: {}", user.uuid);
-                    user
+            let bdo_uuid = match bdo_user_uuid_at(&bdo_url, stackchat).await {
+                Ok(uuid) => {
+                    info!("✅ BDO user ready for teleportation: {}", uuid);
+                    uuid
                 }
                 Err(e) => {
-                    println!("❌ Failed to create BDO user: {:?}", e);
-                    return Err(format!("Failed to create BDO user: {}", e));
+                    error!("❌ Failed to create BDO user: {:?}", e);
+                    return Err(e);
                 }
             };
             
             // Now teleport the content
-            println!("🚀 Starting teleportation with uuid: {}", bdo_user.uuid);
-            match bdo.teleport(&bdo_user.uuid, &stackchat, &teleport_url).await {
+            info!("🚀 Starting teleportation with uuid: {}", bdo_uuid);
+            match bdo.teleport(&bdo_uuid, &stackchat, &teleport_url).await {
                 Ok(teleported_content) => {
-                    println!("✅ Successfully teleported content: {:?}", teleported_content);
+                    info!("✅ Successfully teleported content: {:?}", teleported_content);
                     Ok(teleported_content)
                 }
                 Err(e) => {
-                    println!("❌ Teleportation failed: {:?}", e);
-                    Err(format!("Teleportation failed: {}", e))
+                    error!("❌ Teleportation failed: {:?}", e);
+                    let error = NullaryError::service("bdo", format!("Teleportation failed: {}", e));
+                    forget_if_not_found(&bdo_url, "bdo", Some(stackchat), &error);
+                    Err(error)
                 }
             }
         }
         Err(e) => {
-            println!("❌ Failed to get sessionless instance: {}", e);
-            Err(format!("Failed to get sessionless: {}", e))
+            error!("❌ Failed to get sessionless instance: {}", e);
+            Err(e)
         }
     }
 }
 
+/// Replay messages queued while julia was unreachable
+fn register_outbox_handlers() {
+    nullary_core::register_outbox_handler("send_message", |payload| async move {
+        let outgoing: OutgoingMessage = nullary_core::outbox_payload(payload)?;
+        let message = julia_integration::send_message(outgoing.association_uuid, outgoing.content).await?;
+        Ok(json!(message))
+    });
+}
+
 // Generate the handler
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -323,8 +264,67 @@ pub fn run() {
             get_sessionless_info,
             health_check,
             create_sanora_user,
-            teleport_content
+            teleport_content,
+            // User Persistence Functions
+            nullary_core::generate_sessionless_keys,
+            nullary_core::stronghold_init,
+            nullary_core::stronghold_get_record,
+            nullary_core::stronghold_set_record,
+            nullary_core::stronghold_clear_vault,
+            nullary_core::read_user_data_file,
+            nullary_core::write_user_data_file,
+            nullary_core::clear_user_data,
+            // Documents
+            nullary_core::read_document,
+            nullary_core::write_document,
+            nullary_core::delete_document,
+            nullary_core::list_documents,
+            // Personas
+            nullary_core::list_personas,
+            nullary_core::create_persona,
+            nullary_core::rename_persona,
+            nullary_core::delete_persona,
+            nullary_core::switch_persona,
+            nullary_core::get_persona_service_uuids,
+            nullary_core::set_persona_service_uuid,
+            // Identity backup
+            nullary_core::export_identity_backup,
+            nullary_core::import_identity_backup,
+            nullary_core::export_recovery_words,
+            nullary_core::import_recovery_words,
+            // Key rotation
+            nullary_core::rotate_identity,
+            nullary_core::retry_identity_rotation,
+            nullary_core::get_key_rotations,
+            // Signing
+            nullary_core::get_signing_journal,
+            // Logs
+            nullary_core::export_logs,
+            // Health
+            nullary_core::check_service_health,
+            // Outbox
+            nullary_core::list_outbox,
+            nullary_core::retry_outbox_item,
+            nullary_core::cancel_outbox_item,
+            // Moderation
+            nullary_core::get_moderation,
+            nullary_core::set_base_muted,
+            nullary_core::set_base_ignored,
+            nullary_core::set_user_blocked,
+            // Endpoint configuration
+            nullary_core::get_environment_config,
+            nullary_core::switch_environment
         ])
+        .setup(|app| {
+            info!("💬 StackChat backend is starting up...");
+
+            // Register the app and load (or create on first launch) its identity
+            if let Err(e) = nullary_core::init(app.handle(), "stackchat") {
+                error!("❌ Failed to initialize nullary core: {}", e);
+            }
+            register_outbox_handlers();
+            Ok(())
+        })
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
\ No newline at end of file
@@ -1,76 +1,44 @@
-use std::env;
 use reqwest::Client;
 use std::time::{SystemTime, UNIX_EPOCH};
 use serde_json::json;
 use serde_json::Value;
-use sessionless::hex::FromHex;
-use sessionless::hex::IntoHex;
-use sessionless::{Sessionless, PrivateKey};
-use fount_rs::{Fount, FountUser};
+use fount_rs::FountUser;
 use fount_rs::structs::{Gateway};
-use bdo_rs::{BDO, Spellbook};
-use dolores_rs::{Dolores, DoloresUser};
+use bdo_rs::Spellbook;
+use dolores_rs::DoloresUser;
 use dolores_rs::structs::{Feed};
+use tracing::{error, info};
+
+// Shared identity, endpoints and service clients
+use nullary_core::{dolores_client, NullaryError};
 
 #[tauri::command]
 fn dbg(log: &str) {
-    dbg!(log);
-}
-
-async fn get_sessionless() -> Result<Sessionless, String> {
-    let private_key = env::var("PRIVATE_KEY").unwrap_or_else(|_| String::from("b75011b167c5e3a6b0de97d8e1950cd9548f83bb67f47112bed6a082db795496"));
-    let sessionless = Sessionless::from_private_key(PrivateKey::from_hex(private_key).expect("private key"));
-    Ok(sessionless)
+    info!(target: "frontend", "{}", log);
 }
 
 #[tauri::command]
-async fn create_fount_user() -> Result<FountUser, String> {
-    let s = get_sessionless().await;
-    match s {
-        Ok(sessionless) => {
-            let fount = Fount::new(Some("https://dev.fount.allyabase.com/".to_string()), Some(sessionless));
-            let _user = fount.create_user().await;
-dbg!(&_user);
-            return match _user {
-                Ok(user) => Ok(user),
-                Err(_) => Err("no user".to_string())
-            }
-        },
-        Err(_) => Err("no user".to_string())
-    }
+async fn create_fount_user() -> Result<FountUser, NullaryError> {
+    nullary_core::create_fount_user().await
 }
 
 #[tauri::command]
-async fn create_dolores_user() -> Result<DoloresUser, String> {
-    let s = get_sessionless().await;
-    match s {
-        Ok(sessionless) => {
-            let dolores = Dolores::new(Some("https://dev.dolores.allyabase.com/".to_string()), Some(sessionless));
-            let _user = dolores.create_user().await;
-dbg!(&_user);
-            return match _user {
-                Ok(user) => Ok(user),
-                Err(_) => Err("no user".to_string())
-            }
-        },
-        Err(_) => Err("no user".to_string())
-    }
+async fn create_dolores_user() -> Result<DoloresUser, NullaryError> {
+    nullary_core::create_dolores_user().await
 }
 
 #[tauri::command]
-async fn get_feed(uuid: &str, tags: &str) -> Result<Feed, String> {
-    let s = get_sessionless().await;
-    match s {
-        Ok(sessionless) => {
-            let dolores = Dolores::new(Some("https://dev.dolores.allyabase.com/".to_string()), Some(sessionless));
+async fn get_feed(uuid: &str, tags: &str) -> Result<Feed, NullaryError> {
+    match dolores_client().await {
+        Ok(dolores) => {
             let feed_result = dolores.get_feed(&uuid, &tags).await;
     
             match feed_result {
                 Ok(feed) => Ok(feed),
-                Err(_) => Err("no feed".to_string())
+                Err(e) => Err(NullaryError::service("dolores", format!("Failed to get feed: {}", e)))
             }
         },
-        Err(_) => Err("no dolores".to_string())
+        Err(e) => Err(e)
     }
 }
 
@@ -79,7 +47,63 @@ pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_shell::init())
-        .invoke_handler(tauri::generate_handler![dbg, create_fount_user, create_dolores_user, get_feed])
+        .invoke_handler(tauri::generate_handler![
+            dbg,
+            create_fount_user,
+            create_dolores_user,
+            get_feed,
+            // User Persistence Functions
+            nullary_core::generate_sessionless_keys,
+            nullary_core::stronghold_init,
+            nullary_core::stronghold_get_record,
+            nullary_core::stronghold_set_record,
+            nullary_core::stronghold_clear_vault,
+            nullary_core::read_user_data_file,
+            nullary_core::write_user_data_file,
+            nullary_core::clear_user_data,
+            // Documents
+            nullary_core::read_document,
+            nullary_core::write_document,
+            nullary_core::delete_document,
+            nullary_core::list_documents,
+            // Personas
+            nullary_core::list_personas,
+            nullary_core::create_persona,
+            nullary_core::rename_persona,
+            nullary_core::delete_persona,
+            nullary_core::switch_persona,
+            nullary_core::get_persona_service_uuids,
+            nullary_core::set_persona_service_uuid,
+            // Identity backup
+            nullary_core::export_identity_backup,
+            nullary_core::import_identity_backup,
+            nullary_core::export_recovery_words,
+            nullary_core::import_recovery_words,
+            // Key rotation
+            nullary_core::rotate_identity,
+            nullary_core::retry_identity_rotation,
+            nullary_core::get_key_rotations,
+            // Signing
+            nullary_core::get_signing_journal,
+            // Logs
+            nullary_core::export_logs,
+            // Health
+            nullary_core::check_service_health,
+            // Outbox
+            nullary_core::list_outbox,
+            nullary_core::retry_outbox_item,
+            nullary_core::cancel_outbox_item,
+            // Endpoint configuration
+            nullary_core::get_environment_config,
+            nullary_core::switch_environment
+        ])
+        .setup(|app| {
+            // Register the app and load (or create on first launch) its identity
+            if let Err(e) = nullary_core::init(app.handle(), "viewaris") {
+                error!("❌ Failed to initialize nullary core: {}", e);
+            }
+            Ok(())
+        })
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
@@ -1,243 +1,110 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::env;
 use tauri::command;
+use tracing::{error, info};
 
-// Planet Nine service clients
-use bdo_rs::BdoClient;
-use dolores_rs::DoloresClient;
-use sessionless::{Sessionless, PrivateKey};
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct BaseData {
-    pub name: String,
-    pub description: String,
-    pub location: Option<LocationData>,
-    pub soma: Option<SomaData>,
-    pub dns: Option<DnsData>,
-    pub joined: bool,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct LocationData {
-    pub latitude: f64,
-    pub longitude: f64,
-    pub postal_code: Option<String>,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct SomaData {
-    pub lexary: Option<Vec<String>>,
-    pub photary: Option<Vec<String>>,
-    pub viewary: Option<Vec<String>>,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct DnsData {
-    pub bdo: String,
-    pub dolores: String,
-}
+// Shared identity, endpoints, service clients, base discovery and moderation
+use nullary_core::{
+    aggregate_feed, discover_bases, dolores_feed_at, feed_sources, public_key,
+    without_blocked_posts, BaseData, FeedFetch, FeedSync, NullaryError, ServiceResponse,
+    Validators,
+};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct VideoFeedData {
     pub video_posts: Vec<serde_json::Value>,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ServiceResponse<T> {
-    pub success: bool,
-    pub data: Option<T>,
-    pub error: Option<String>,
-}
-
-// Get sessionless instance with development key
-async fn get_sessionless() -> Result<Sessionless, String> {
-    let private_key = env::var("PRIVATE_KEY").unwrap_or_else(|_| {
-        // Development key - in production, this should be user-specific
-        String::from("b75011b167c5e3a6b0de97d8e1950cd9548f83bb67f47112bed6a082db795496")
-    });
-    
-    let sessionless = Sessionless::from_private_key(
-        PrivateKey::from_hex(private_key).map_err(|e| format!("Invalid private key: {}", e))?
-    );
-    
-    Ok(sessionless)
+    /// Served from the feed cache past its TTL or while Dolores is unreachable
+    pub stale: bool,
+    /// When the posts were fetched from Dolores; `None` for the mock feed
+    pub fetched_at: Option<i64>,
+    /// How each base answered, for the sync status banner
+    pub sync: Option<FeedSync>,
 }
 
 // Base management commands
 
+/// Bases discovered through BDO, the home base first, each marked joined or
+/// not
 #[command]
 pub async fn get_bases() -> ServiceResponse<Vec<BaseData>> {
-    match get_bases_internal().await {
-        Ok(bases) => ServiceResponse {
-            success: true,
-            data: Some(bases),
-            error: None,
-        },
-        Err(e) => ServiceResponse {
-            success: false,
-            data: None,
-            error: Some(e),
-        },
-    }
+    get_bases_internal().await.into()
 }
 
-async fn get_bases_internal() -> Result<Vec<BaseData>, String> {
-    // For now, return development bases
-    // In a full implementation, this would query base discovery services
-    
-    let dev_base = BaseData {
-        name: "DEV".to_string(),
-        description: "Development base for testing. May be unstable.".to_string(),
-        location: Some(LocationData {
-            latitude: 36.788,
-            longitude: -119.417,
-            postal_code: Some("94102".to_string()),
-        }),
-        soma: Some(SomaData {
-            lexary: Some(vec!["tech".to_string(), "programming".to_string()]),
-            photary: Some(vec!["cats".to_string(), "photography".to_string()]),
-            viewary: Some(vec!["comedy".to_string(), "education".to_string(), "entertainment".to_string()]),
-        }),
-        dns: Some(DnsData {
-            bdo: "https://dev.bdo.allyabase.com/".to_string(),
-            dolores: "https://dev.dolores.allyabase.com/".to_string(),
-        }),
-        joined: true,
-    };
-
-    let community_base = BaseData {
-        name: "COMMUNITY".to_string(),
-        description: "Community-run base with diverse content.".to_string(),
-        location: Some(LocationData {
-            latitude: 40.7128,
-            longitude: -74.0060,
-            postal_code: Some("10001".to_string()),
-        }),
-        soma: Some(SomaData {
-            lexary: Some(vec!["community".to_string(), "discussion".to_string()]),
-            photary: Some(vec!["art".to_string(), "street".to_string()]),
-            viewary: Some(vec!["tutorials".to_string(), "vlogs".to_string(), "music".to_string()]),
-        }),
-        dns: Some(DnsData {
-            bdo: "https://community.bdo.allyabase.com/".to_string(),
-            dolores: "https://community.dolores.allyabase.com/".to_string(),
-        }),
-        joined: false,
-    };
-
-    Ok(vec![dev_base, community_base])
+async fn get_bases_internal() -> Result<Vec<BaseData>, NullaryError> {
+    discover_bases(None).await.map(|page| page.data)
 }
 
+/// Join a base by id or name: register with its services and remember it
+/// for every Nullary app
 #[command]
-pub async fn join_base(base_name: String) -> ServiceResponse<bool> {
-    match join_base_internal(base_name).await {
-        Ok(success) => ServiceResponse {
-            success,
-            data: Some(success),
-            error: None,
-        },
-        Err(e) => ServiceResponse {
-            success: false,
-            data: None,
-            error: Some(e),
-        },
-    }
-}
-
-async fn join_base_internal(base_name: String) -> Result<bool, String> {
-    // In a full implementation, this would:
-    // 1. Connect to the base
-    // 2. Register with their services
-    // 3. Save the configuration locally
-    
-    println!("Joining base: {}", base_name);
-    Ok(true)
+pub async fn join_base(base_name: String) -> ServiceResponse<BaseData> {
+    nullary_core::join_base(&base_name).await.into()
 }
 
+/// Leave a base by id or name, deleting our users there if asked
 #[command]
-pub async fn leave_base(base_name: String) -> ServiceResponse<bool> {
-    match leave_base_internal(base_name).await {
-        Ok(success) => ServiceResponse {
-            success,
-            data: Some(success),
-            error: None,
-        },
-        Err(e) => ServiceResponse {
-            success: false,
-            data: None,
-            error: Some(e),
-        },
-    }
-}
-
-async fn leave_base_internal(base_name: String) -> Result<bool, String> {
-    println!("Leaving base: {}", base_name);
-    Ok(true)
+pub async fn leave_base(base_name: String, delete_users: Option<bool>) -> ServiceResponse<BaseData> {
+    nullary_core::leave_base(&base_name, delete_users.unwrap_or(false)).await.into()
 }
 
 // Video feed management commands
 
+// Video posts from Dolores, shaped for the feed
+async fn fetch_video_posts(dolores_url: &str, tags: &str, validators: Validators) -> Result<FeedFetch<Vec<serde_json::Value>>, NullaryError> {
+    let feed = dolores_feed_at(dolores_url, tags, &validators).await?;
+
+    Ok(feed.map(|posts| {
+        posts
+            .into_iter()
+            .filter(|item| {
+                // Filter for video-based posts
+                item.get("url").and_then(|u| u.as_str()).is_some() ||
+                item.get("video_url").and_then(|u| u.as_str()).is_some()
+            })
+            .map(|item| serde_json::json!({
+                "uuid": item.get("uuid").unwrap_or(&serde_json::Value::String("unknown".to_string())),
+                "title": item.get("title").unwrap_or(&serde_json::Value::String("".to_string())),
+                "description": item.get("description").unwrap_or(&serde_json::Value::String("".to_string())),
+                "url": item.get("url").or_else(|| item.get("video_url")).unwrap_or(&serde_json::Value::String("".to_string())),
+                "thumbnail": item.get("thumbnail").unwrap_or(&serde_json::Value::String("".to_string())),
+                "duration": item.get("duration").unwrap_or(&serde_json::Value::Number(serde_json::Number::from(0))),
+                "timestamp": item.get("timestamp").unwrap_or(&serde_json::Value::Number(serde_json::Number::from(chrono::Utc::now().timestamp_millis()))),
+                "author": item.get("author").unwrap_or(&serde_json::Value::String("Anonymous".to_string())),
+                "tags": item.get("tags").unwrap_or(&serde_json::Value::Array(vec![]))
+            }))
+            .collect()
+    }))
+}
+
 #[command]
 pub async fn get_video_feed(dolores_url: Option<String>, tags: Option<Vec<String>>) -> ServiceResponse<VideoFeedData> {
-    match get_video_feed_internal(dolores_url, tags).await {
-        Ok(feed) => ServiceResponse {
-            success: true,
-            data: Some(feed),
-            error: None,
-        },
-        Err(e) => ServiceResponse {
-            success: false,
-            data: None,
-            error: Some(e),
-        },
-    }
+    get_video_feed_internal(dolores_url, tags, false).await.into()
 }
 
-async fn get_video_feed_internal(dolores_url: Option<String>, tags: Option<Vec<String>>) -> Result<VideoFeedData, String> {
-    let sessionless = get_sessionless().await?;
-    let dolores_url = dolores_url.unwrap_or_else(|| "https://dev.dolores.allyabase.com/".to_string());
+async fn get_video_feed_internal(dolores_url: Option<String>, tags: Option<Vec<String>>, refresh: bool) -> Result<VideoFeedData, NullaryError> {
+    let tags = tags.unwrap_or_else(|| vec!["videos".to_string(), "entertainment".to_string()]).join(",");
     
-    // Try to get real feed from Dolores
-    match DoloresClient::new(dolores_url.clone(), sessionless.clone()) {
-        Ok(dolores_client) => {
-            match dolores_client.get_feed(sessionless.uuid.clone(), tags.unwrap_or_else(|| vec!["videos".to_string(), "entertainment".to_string()])).await {
-                Ok(feed_items) => {
-                    let video_posts: Vec<serde_json::Value> = feed_items
-                        .into_iter()
-                        .filter(|item| {
-                            // Filter for video-based posts
-                            item.get("url").and_then(|u| u.as_str()).is_some() ||
-                            item.get("video_url").and_then(|u| u.as_str()).is_some()
-                        })
-                        .map(|item| serde_json::json!({
-                            "uuid": item.get("uuid").unwrap_or(&serde_json::Value::String("unknown".to_string())),
-                            "title": item.get("title").unwrap_or(&serde_json::Value::String("".to_string())),
-                            "description": item.get("description").unwrap_or(&serde_json::Value::String("".to_string())),
-                            "url": item.get("url").or_else(|| item.get("video_url")).unwrap_or(&serde_json::Value::String("".to_string())),
-                            "thumbnail": item.get("thumbnail").unwrap_or(&serde_json::Value::String("".to_string())),
-                            "duration": item.get("duration").unwrap_or(&serde_json::Value::Number(serde_json::Number::from(0))),
-                            "timestamp": item.get("timestamp").unwrap_or(&serde_json::Value::Number(serde_json::Number::from(chrono::Utc::now().timestamp_millis()))),
-                            "author": item.get("author").unwrap_or(&serde_json::Value::String("Anonymous".to_string())),
-                            "tags": item.get("tags").unwrap_or(&serde_json::Value::Array(vec![]))
-                        }))
-                        .collect();
-
-                    return Ok(VideoFeedData {
-                        video_posts,
-                    });
-                }
-                Err(e) => {
-                    println!("Failed to get real feed: {}", e);
-                    // Fall through to mock data
-                }
-            }
-        }
-        Err(e) => {
-            println!("Failed to create Dolores client: {}", e);
-            // Fall through to mock data
-        }
+    // Without a URL the feed covers the home base and every joined base, all
+    // asked at once. Cached posts come back at once and are refreshed in the
+    // background once stale; refresh asks every base first
+    let sources = feed_sources("dolores", dolores_url.as_deref());
+    let feed_tags = tags.clone();
+    let feed = aggregate_feed(sources, "dolores", &tags, refresh, move |url, validators| {
+        let tags = feed_tags.clone();
+        async move { fetch_video_posts(&url, &tags, validators).await }
+    })
+    .await?;
+
+    if !feed.unavailable() {
+        return Ok(VideoFeedData {
+            video_posts: without_blocked_posts(feed.data),
+            stale: feed.stale,
+            fetched_at: Some(feed.fetched_at),
+            sync: Some(feed.sync),
+        });
     }
+    info!("No base could serve the feed");
+    // Fall through to mock data
 
     // Return mock video posts for development
     let mock_video_posts = vec![
@@ -300,119 +167,160 @@ async fn get_video_feed_internal(dolores_url: Option<String>, tags: Option<Vec<S
 
     Ok(VideoFeedData {
         video_posts: mock_video_posts,
+        stale: false,
+        fetched_at: None,
+        sync: Some(feed.sync),
     })
 }
 
 #[command]
 pub async fn refresh_video_feed(dolores_url: Option<String>, tags: Option<Vec<String>>) -> ServiceResponse<VideoFeedData> {
-    // Same as get_video_feed but could implement cache invalidation
-    get_video_feed(dolores_url, tags).await
+    get_video_feed_internal(dolores_url, tags, true).await.into()
 }
 
 // User management commands
 
 #[command]
 pub async fn create_bdo_user(bdo_url: String) -> ServiceResponse<serde_json::Value> {
-    match create_bdo_user_internal(bdo_url).await {
-        Ok(user) => ServiceResponse {
-            success: true,
-            data: Some(user),
-            error: None,
-        },
-        Err(e) => ServiceResponse {
-            success: false,
-            data: None,
-            error: Some(e),
-        },
-    }
+    create_bdo_user_internal(bdo_url).await.into()
 }
 
-async fn create_bdo_user_internal(bdo_url: String) -> Result<serde_json::Value, String> {
-    let sessionless = get_sessionless().await?;
-    
-    match BdoClient::new(bdo_url, sessionless.clone()) {
-        Ok(bdo_client) => {
-            match bdo_client.create_user().await {
-                Ok(user) => Ok(serde_json::to_value(user).unwrap()),
-                Err(e) => Err(format!("Failed to create BDO user: {}", e)),
-            }
-        }
-        Err(e) => Err(format!("Failed to create BDO client: {}", e)),
-    }
+async fn create_bdo_user_internal(bdo_url: String) -> Result<serde_json::Value, NullaryError> {
+    let user = nullary_core::create_bdo_user_at(&bdo_url, "viewary").await?;
+    serde_json::to_value(user).map_err(|e| NullaryError::internal(format!("Failed to serialize BDO user: {}", e)))
 }
 
 #[command]
 pub async fn create_dolores_user(dolores_url: String) -> ServiceResponse<serde_json::Value> {
-    match create_dolores_user_internal(dolores_url).await {
-        Ok(user) => ServiceResponse {
-            success: true,
-            data: Some(user),
-            error: None,
-        },
-        Err(e) => ServiceResponse {
-            success: false,
-            data: None,
-            error: Some(e),
-        },
-    }
+    create_dolores_user_internal(dolores_url).await.into()
 }
 
-async fn create_dolores_user_internal(dolores_url: String) -> Result<serde_json::Value, String> {
-    let sessionless = get_sessionless().await?;
-    
-    match DoloresClient::new(dolores_url, sessionless.clone()) {
-        Ok(dolores_client) => {
-            match dolores_client.create_user().await {
-                Ok(user) => Ok(serde_json::to_value(user).unwrap()),
-                Err(e) => Err(format!("Failed to create Dolores user: {}", e)),
-            }
-        }
-        Err(e) => Err(format!("Failed to create Dolores client: {}", e)),
-    }
+async fn create_dolores_user_internal(dolores_url: String) -> Result<serde_json::Value, NullaryError> {
+    let user = nullary_core::create_dolores_user_at(&dolores_url).await?;
+    serde_json::to_value(user).map_err(|e| NullaryError::internal(format!("Failed to serialize Dolores user: {}", e)))
 }
 
 // Utility commands
 
 #[command]
 pub async fn get_sessionless_info() -> ServiceResponse<serde_json::Value> {
-    match get_sessionless().await {
-        Ok(sessionless) => ServiceResponse {
+    match public_key("dolores") {
+        Ok(public_key) => ServiceResponse {
             success: true,
             data: Some(serde_json::json!({
-                "uuid": sessionless.uuid,
-                "public_key": sessionless.public_key.to_hex()
+                "uuid": public_key,
+                "public_key": public_key
             })),
             error: None,
+            details: None,
         },
-        Err(e) => ServiceResponse {
-            success: false,
-            data: None,
-            error: Some(e),
-        },
+        Err(e) => ServiceResponse::err(e),
     }
 }
 
 #[command]
 pub async fn health_check() -> ServiceResponse<serde_json::Value> {
-    let health_info = serde_json::json!({
-        "app": "viewary",
-        "version": "0.0.1",
-        "services": {
-            "bdo": "https://dev.bdo.allyabase.com/",
-            "dolores": "https://dev.dolores.allyabase.com/"
-        },
-        "timestamp": chrono::Utc::now().to_rfc3339()
-    });
-
-    ServiceResponse {
-        success: true,
-        data: Some(health_info),
-        error: None,
-    }
+    nullary_core::check_service_health(None)
+        .await
+        .map(|report| {
+            serde_json::json!({
+                "app": "viewary",
+                "version": "0.0.1",
+                "health": report
+            })
+        })
+        .into()
 }
 
 #[command]
 pub async fn dbg(message: String) -> String {
-    println!("Viewary Debug: {}", message);
+    info!("Viewary Debug: {}", message);
     format!("Debug logged: {}", message)
-}
\ No newline at end of file
+}
+
+#[cfg_attr(mobile, tauri::mobile_entry_point)]
+pub fn run() {
+    tauri::Builder::default()
+        .plugin(tauri_plugin_shell::init())
+        .invoke_handler(tauri::generate_handler![
+            // Base management
+            get_bases,
+            join_base,
+            leave_base,
+            
+            // Video feed management
+            get_video_feed,
+            refresh_video_feed,
+            
+            // User management
+            create_bdo_user,
+            create_dolores_user,
+            
+            // Utilities
+            get_sessionless_info,
+            health_check,
+            dbg,
+            
+            // User Persistence Functions
+            nullary_core::generate_sessionless_keys,
+            nullary_core::stronghold_init,
+            nullary_core::stronghold_get_record,
+            nullary_core::stronghold_set_record,
+            nullary_core::stronghold_clear_vault,
+            nullary_core::read_user_data_file,
+            nullary_core::write_user_data_file,
+            nullary_core::clear_user_data,
+            // Documents
+            nullary_core::read_document,
+            nullary_core::write_document,
+            nullary_core::delete_document,
+            nullary_core::list_documents,
+            // Personas
+            nullary_core::list_personas,
+            nullary_core::create_persona,
+            nullary_core::rename_persona,
+            nullary_core::delete_persona,
+            nullary_core::switch_persona,
+            nullary_core::get_persona_service_uuids,
+            nullary_core::set_persona_service_uuid,
+            // Identity backup
+            nullary_core::export_identity_backup,
+            nullary_core::import_identity_backup,
+            nullary_core::export_recovery_words,
+            nullary_core::import_recovery_words,
+            // Key rotation
+            nullary_core::rotate_identity,
+            nullary_core::retry_identity_rotation,
+            nullary_core::get_key_rotations,
+            // Signing
+            nullary_core::get_signing_journal,
+            // Logs
+            nullary_core::export_logs,
+            // Health
+            nullary_core::check_service_health,
+            // Outbox
+            nullary_core::list_outbox,
+            nullary_core::retry_outbox_item,
+            nullary_core::cancel_outbox_item,
+            // Moderation
+            nullary_core::get_moderation,
+            nullary_core::set_base_muted,
+            nullary_core::set_base_ignored,
+            nullary_core::set_user_blocked,
+            // Nearby bases
+            nullary_core::discover_bases_near,
+            nullary_core::bases_near_me,
+            // Endpoint configuration
+            nullary_core::get_environment_config,
+            nullary_core::switch_environment
+        ])
+        .setup(|app| {
+            // Register the app and load (or create on first launch) its identity
+            if let Err(e) = nullary_core::init(app.handle(), "viewary") {
+                error!("❌ Failed to initialize nullary core: {}", e);
+            }
+            Ok(())
+        })
+        .run(tauri::generate_context!())
+        .expect("error while running tauri application");
+}